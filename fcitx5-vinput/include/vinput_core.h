@@ -12,6 +12,11 @@
 #include <stdint.h>
 #include <stdlib.h>
 
+/**
+ * 命令载荷版本（`VInputCommandPayload` 布局变化时递增）
+ */
+#define VINPUT_COMMAND_PAYLOAD_VERSION 1
+
 /**
  * FFI 结果码
  */
@@ -59,7 +64,7 @@ typedef enum VInputVInputCommandType {
    */
   CommitText = 1,
   /**
-   * 显示候选
+   * 显示候选（text: 刚上屏、待替换的文本；载荷 candidates: 候选列表，候选 0 即 text）
    */
   ShowCandidate = 2,
   /**
@@ -67,7 +72,8 @@ typedef enum VInputVInputCommandType {
    */
   HideCandidate = 3,
   /**
-   * 错误消息
+   * 错误消息（录音会话无法自动恢复时 text 为 `code\tmessage`，code 为错误码如 E2003，
+   * 随后发出 RecordingStopped）
    */
   Error = 4,
   /**
@@ -86,6 +92,26 @@ typedef enum VInputVInputCommandType {
    * 清除 Preedit
    */
   ClearPreedit = 8,
+  /**
+   * 活动音频设备变化（text: `name\tdescription\treason`，reason 为 initial / preferred / fallback）
+   */
+  AudioDeviceChanged = 9,
+  /**
+   * 麦克风电平（text: `rms_dbfs\tpeak_dbfs\tclipping`，约每 100ms 一次）
+   */
+  AudioLevel = 10,
+  /**
+   * 输入诊断警告（text: `kind\tmessage`，kind 为 clipping / no_signal / low_gain）
+   */
+  AudioWarning = 11,
+  /**
+   * 自动停止了识别（AutoStop 整句上屏后 / HandsFree 静默超时；前端应随后发送 StopRecording 释放麦克风）
+   */
+  RecordingStopped = 12,
+  /**
+   * 检测到唤醒词（text: 唤醒词文本；前端应随后发送 StartRecording 开始录音）
+   */
+  WakeWordDetected = 13,
 } VInputVInputCommandType;
 
 /**
@@ -120,8 +146,152 @@ typedef enum VInputVInputEventType {
    * 重试请求
    */
   RedoRequest = 7,
+  /**
+   * 用户从候选列表中选择了替代结果（data: 选中的 UTF-8 文本，已由前端替换上屏）
+   */
+  CandidateSelected = 8,
+  /**
+   * 取消录音：丢弃当前句子，不上屏、不记入历史
+   */
+  CancelRecording = 9,
+  /**
+   * 用户撤销后在原位置重新输入的文本（data: UTF-8 文本，用于学习热词）
+   */
+  CorrectionText = 10,
+  /**
+   * 用户把候选手动加为热词（data: UTF-8 文本）
+   */
+  AddHotword = 11,
 } VInputVInputEventType;
 
+/**
+ * 触发键按法
+ */
+typedef enum VInputVInputActivationMode {
+  /**
+   * 按住触发键录音，松开停止
+   */
+  Hold = 0,
+  /**
+   * 按一下开始，再按一下停止
+   */
+  Toggle = 1,
+  /**
+   * 双击触发键开始，单击停止
+   */
+  DoubleTap = 2,
+} VInputVInputActivationMode;
+
+/**
+ * 前端快捷键动作（对应 config.toml 的 `[shortcuts]` 段）
+ */
+typedef enum VInputVInputShortcutAction {
+  /**
+   * 开始 / 停止录音
+   */
+  Trigger = 0,
+  /**
+   * 撤销上一次上屏
+   */
+  Undo = 1,
+  /**
+   * 重新上屏被撤销的文本
+   */
+  Redo = 2,
+} VInputVInputShortcutAction;
+
+/**
+ * 停止方式
+ */
+typedef enum VInputVInputStopMode {
+  /**
+   * 整句上屏后自动停止（发出 RecordingStopped 命令）
+   */
+  AutoStop = 0,
+  /**
+   * 连续听写，直到再次按触发键
+   */
+  Continuous = 1,
+  /**
+   * 免提听写，静默超时后自动停止（发出 RecordingStopped 命令）
+   */
+  HandsFree = 2,
+} VInputVInputStopMode;
+
+/**
+ * Preedit 片段属性
+ */
+typedef enum VInputVInputSegmentAttribute {
+  /**
+   * 稳定文本
+   */
+  Stable = 0,
+  /**
+   * 不稳定文本（可能随后续解码改变）
+   */
+  Unstable = 1,
+  /**
+   * 低置信度文本（保留：流式 Paraformer 不提供逐 Token 置信度，当前不会产生）
+   */
+  LowConfidence = 2,
+} VInputVInputSegmentAttribute;
+
+/**
+ * 不透明的 V-Input 引擎句柄
+ *
+ * 由 `vinput_engine_new` 创建、`vinput_engine_free` 释放；
+ * 每个句柄拥有独立的配置、管道、命令队列与回调，可在同一进程内并存
+ */
+typedef struct VInputHandle VInputHandle;
+
+/**
+ * Preedit 片段
+ */
+typedef struct VInputVInputPreeditSegment {
+  /**
+   * 片段文本（UTF-8，以 null 结尾）
+   */
+  char *text;
+  /**
+   * 文本长度（字节，不含 null）
+   */
+  uintptr_t text_len;
+  /**
+   * 片段属性
+   */
+  enum VInputVInputSegmentAttribute attribute;
+} VInputVInputPreeditSegment;
+
+/**
+ * 结构化命令载荷（随 VInputCommand 一起由 vinput_command_free 释放）
+ */
+typedef struct VInputVInputCommandPayload {
+  /**
+   * 载荷版本（VINPUT_COMMAND_PAYLOAD_VERSION）
+   */
+  uint32_t version;
+  /**
+   * Preedit 片段数组（按顺序拼接即为命令 text）
+   */
+  struct VInputVInputPreeditSegment *segments;
+  /**
+   * 片段数量
+   */
+  uintptr_t segment_count;
+  /**
+   * 光标位置（命令 text 中的字节偏移）
+   */
+  uintptr_t cursor;
+  /**
+   * 候选文本数组（UTF-8，以 null 结尾）
+   */
+  char **candidates;
+  /**
+   * 候选数量
+   */
+  uintptr_t candidate_count;
+} VInputVInputCommandPayload;
+
 /**
  * V-Input 命令（从 Rust Core -> Fcitx5）
  */
@@ -138,6 +308,10 @@ typedef struct VInputVInputCommand {
    * 文本长度（不含 null）
    */
   uintptr_t text_len;
+  /**
+   * 结构化载荷（可为 null，旧前端可只读取 text）
+   */
+  struct VInputVInputCommandPayload *payload;
 } VInputVInputCommand;
 
 /**
@@ -148,6 +322,15 @@ typedef struct VInputVInputCommand {
  */
 typedef void (*VInputVInputCommandCallback)(const struct VInputVInputCommand*);
 
+/**
+ * 句柄回调函数类型（带 user_data）
+ *
+ * 由 `vinput_engine_register_callback` 注册，`user_data` 原样传回，
+ * 便于同一进程内多个引擎实例把命令路由到各自的前端对象
+ * 参数: VInputCommand 指针（仅在回调期间有效）、注册时的 user_data
+ */
+typedef void (*VInputVInputHandleCommandCallback)(const struct VInputVInputCommand*, void*);
+
 /**
  * V-Input 事件（从 Fcitx5 -> Rust Core）
  */
@@ -166,6 +349,104 @@ typedef struct VInputVInputEvent {
   uintptr_t data_len;
 } VInputVInputEvent;
 
+/**
+ * 录音触发方式（对应 config.toml 的 `[activation]` 段）
+ */
+typedef struct VInputVInputActivation {
+  /**
+   * 触发键按法
+   */
+  enum VInputVInputActivationMode mode;
+  /**
+   * 停止方式
+   */
+  enum VInputVInputStopMode stop_mode;
+  /**
+   * 双击判定间隔（毫秒）
+   */
+  uint32_t double_tap_interval_ms;
+} VInputVInputActivation;
+
+/**
+ * 管道性能指标快照（时长单位均为毫秒）
+ */
+typedef struct VInputVInputMetrics {
+  /**
+   * 已处理的音频时长
+   */
+  double audio_ms;
+  /**
+   * 实时率（各阶段处理耗时合计 / 音频时长，< 1 表示快于实时）
+   */
+  double real_time_factor;
+  /**
+   * 语音增强平均耗时（每帧）
+   */
+  double enhancement_mean_ms;
+  /**
+   * VAD 平均耗时（每帧）
+   */
+  double vad_mean_ms;
+  /**
+   * ASR 送入音频与解码平均耗时（每次）
+   */
+  double asr_decode_mean_ms;
+  /**
+   * 标点平均耗时（每次部分 / 最终结果）
+   */
+  double punctuation_mean_ms;
+  /**
+   * ITN 平均耗时（每次）
+   */
+  double itn_mean_ms;
+  /**
+   * 首个部分结果延迟：最近一次
+   */
+  double first_partial_last_ms;
+  /**
+   * 首个部分结果延迟：平均
+   */
+  double first_partial_mean_ms;
+  /**
+   * 首个部分结果延迟：最大
+   */
+  double first_partial_max_ms;
+  /**
+   * 端点到上屏延迟：最近一次
+   */
+  double endpoint_to_commit_last_ms;
+  /**
+   * 端点到上屏延迟：平均
+   */
+  double endpoint_to_commit_mean_ms;
+  /**
+   * 端点到上屏延迟：最大
+   */
+  double endpoint_to_commit_max_ms;
+  /**
+   * 已上屏的句子数
+   */
+  uint64_t utterances;
+  /**
+   * Ring Buffer 溢出丢弃的样本数
+   */
+  uint64_t ring_overruns;
+} VInputVInputMetrics;
+
+/**
+ * 字符串列表（FFI 兼容）
+ */
+typedef struct VInputVInputStringList {
+  /**
+   * 字符串数组指针（UTF-8）
+   */
+  char **items;
+  /**
+   * 字符串数量
+   */
+  uintptr_t count;
+} VInputVInputStringList;
+
 /**
  * 音频设备信息（FFI 兼容）
  */
@@ -207,17 +488,111 @@ extern "C" {
 #endif // __cplusplus
 
 /**
- * 初始化 V-Input Core
+ * 创建 V-Input 引擎实例
+ *
+ * `config_path` 为 UTF-8 配置文件路径，传 null 使用 ~/.config/vinput/config.toml
+ *
+ * # 返回值
+ * 成功返回句柄，失败返回 null
+ * 调用者需要使用 vinput_engine_free 释放
+ */
+VInputHandle *vinput_engine_new(const char *config_path);
+
+/**
+ * 释放 V-Input 引擎实例（正在录音时先停止录音）
+ */
+void vinput_engine_free(VInputHandle *handle);
+
+/**
+ * 注册实例的命令回调函数
+ *
+ * `user_data` 在每次回调时原样传回；回调在音频线程中调用。
+ * 传入 null 回调可取消注册
+ */
+enum VInputVInputFFIResult vinput_engine_register_callback(VInputHandle *handle,
+                                                           VInputVInputHandleCommandCallback callback,
+                                                           void *user_data);
+
+/**
+ * 发送事件到 V-Input 引擎实例
+ */
+enum VInputVInputFFIResult vinput_engine_send_event(VInputHandle *handle,
+                                                    const struct VInputVInputEvent *event);
+
+/**
+ * 尝试从 V-Input 引擎实例接收命令（非阻塞）
+ */
+enum VInputVInputFFIResult vinput_engine_try_recv_command(VInputHandle *handle,
+                                                          struct VInputVInputCommand *command);
+
+/**
+ * 获取实例的识别历史（未撤销的记录，按时间顺序；每条记录一个字符串，可含换行）
+ *
+ * # 返回值
+ * 成功返回字符串列表指针（无历史时 count 为 0），失败返回 null
+ * 调用者需要使用 vinput_string_list_free 释放
+ */
+struct VInputVInputStringList *vinput_engine_get_history(const VInputHandle *handle);
+
+/**
+ * 释放字符串列表
+ */
+void vinput_string_list_free(struct VInputVInputStringList *list);
+
+/**
+ * 获取实例配置的前端快捷键（规范化的 `修饰键+键名` 写法，如 `Control+z`）
+ *
+ * 配置值无效时返回默认快捷键
+ *
+ * # 返回值
+ * 成功返回 UTF-8 字符串，失败返回 null
+ * 调用者需要使用 vinput_string_free 释放
+ */
+char *vinput_engine_get_shortcut(const VInputHandle *handle, enum VInputVInputShortcutAction action);
+
+/**
+ * 获取实例的录音触发方式
+ */
+enum VInputVInputFFIResult vinput_engine_get_activation(const VInputHandle *handle,
+                                                        struct VInputVInputActivation *activation);
+
+/**
+ * 获取实例的性能指标快照
+ */
+enum VInputVInputFFIResult vinput_engine_get_metrics(const VInputHandle *handle,
+                                                     struct VInputVInputMetrics *metrics);
+
+/**
+ * 获取实例的性能指标（Prometheus 文本格式）
+ *
+ * # 返回值
+ * 成功返回 UTF-8 字符串，失败返回 null
+ * 调用者需要使用 vinput_string_free 释放
+ */
+char *vinput_engine_get_metrics_text(const VInputHandle *handle);
+
+/**
+ * 清零实例的性能指标
+ */
+enum VInputVInputFFIResult vinput_engine_reset_metrics(const VInputHandle *handle);
+
+/**
+ * 释放由 V-Input 返回的字符串
+ */
+void vinput_string_free(char *string);
+
+/**
+ * 初始化 V-Input Core（默认实例）
  */
 enum VInputVInputFFIResult vinput_core_init(void);
 
 /**
- * 关闭 V-Input Core
+ * 关闭 V-Input Core（默认实例）
  */
 enum VInputVInputFFIResult vinput_core_shutdown(void);
 
 /**
- * 注册命令回调函数
+ * 注册命令回调函数（默认实例）
  *
  * C++ 插件在初始化时调用此函数，注册回调以接收实时命令
  * 当 Rust Core 检测到句子结束时，会直接调用此回调
@@ -225,20 +600,59 @@ enum VInputVInputFFIResult vinput_core_shutdown(void);
 enum VInputVInputFFIResult vinput_core_register_callback(VInputVInputCommandCallback callback);
 
 /**
- * 发送事件到 V-Input Core
+ * 发送事件到 V-Input Core（默认实例）
  */
 enum VInputVInputFFIResult vinput_core_send_event(const struct VInputVInputEvent *event);
 
 /**
- * 尝试接收命令（非阻塞）
+ * 尝试接收命令（非阻塞，默认实例）
  */
 enum VInputVInputFFIResult vinput_core_try_recv_command(struct VInputVInputCommand *command);
 
 /**
- * 释放命令资源
+ * 获取配置的前端快捷键（默认实例，写法同 `vinput_engine_get_shortcut`）
+ *
+ * # 返回值
+ * 成功返回 UTF-8 字符串，未初始化或失败返回 null
+ * 调用者需要使用 vinput_string_free 释放
+ */
+char *vinput_core_get_shortcut(enum VInputVInputShortcutAction action);
+
+/**
+ * 获取录音触发方式（默认实例）
+ */
+enum VInputVInputFFIResult vinput_core_get_activation(struct VInputVInputActivation *activation);
+
+/**
+ * 获取性能指标快照（默认实例）
+ */
+enum VInputVInputFFIResult vinput_core_get_metrics(struct VInputVInputMetrics *metrics);
+
+/**
+ * 释放命令资源（含结构化载荷）
  */
 void vinput_command_free(struct VInputVInputCommand *command);
 
+/**
+ * 获取命令的结构化载荷
+ *
+ * # 返回值
+ * 无载荷或载荷版本不受支持时返回 null（前端应回退到 text）
+ */
+const struct VInputVInputCommandPayload *vinput_command_payload(const struct VInputVInputCommand *command);
+
+/**
+ * 获取载荷中的第 index 个 Preedit 片段（越界返回 null）
+ */
+const struct VInputVInputPreeditSegment *vinput_payload_segment(const struct VInputVInputCommandPayload *payload,
+                                                                uintptr_t index);
+
+/**
+ * 获取载荷中的第 index 个候选文本（越界返回 null）
+ */
+const char *vinput_payload_candidate(const struct VInputVInputCommandPayload *payload,
+                                     uintptr_t index);
+
 /**
  * 获取版本字符串
  */
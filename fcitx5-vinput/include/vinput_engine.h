@@ -11,7 +11,11 @@
 #include <fcitx/addonmanager.h>
 #include <fcitx/instance.h>
 #include <fcitx/inputcontext.h>
+#include <fcitx-utils/key.h>
+#include <chrono>
 #include <memory>
+#include <optional>
+#include <string>
 
 extern "C" {
 #include "vinput_core.h"
@@ -44,16 +48,43 @@ public:
     // 命令回调处理（静态方法，供 FFI 回调）
     static void handleCommand(const VInputVInputCommand* command);
 
+    // 用候选列表中的替代结果替换刚上屏的文本
+    void selectAlternative(InputContext* ic, const std::string& text);
+
 private:
     Instance* instance_;
     bool vinput_core_initialized_;
     bool is_recording_;
+    // 候选列表对应的已上屏文本（为空表示未显示候选）
+    std::string alternative_source_;
+    // 快捷键与触发方式（来自 config.toml 的 [shortcuts] / [activation]）
+    Key trigger_key_;
+    Key undo_key_;
+    Key redo_key_;
+    VInputVInputActivation activation_;
+    // 触发键是否处于按下状态（过滤自动重复）
+    bool trigger_held_;
+    // 上一次单击触发键的时间（双击判定）
+    std::chrono::steady_clock::time_point last_trigger_press_;
+    // 撤销位置之前的文本（用于找出用户撤销后重新输入的内容）
+    std::optional<std::string> undo_anchor_;
 
+    void loadShortcuts();
+    void handleTrigger(bool isRelease);
     void startRecording();
     void stopRecording();
+    void cancelRecording();
     void processCommands(InputContext* ic);
     void requestUndo();
     void requestRedo();
+    void showAlternatives(InputContext* ic, const std::string& committed,
+                          const VInputVInputCommandPayload* payload);
+    void hideAlternatives(InputContext* ic);
+    void deleteCommitted(InputContext* ic, const std::string& text);
+    void addAlternativeHotword(InputContext* ic, int index);
+    void rememberUndoAnchor(InputContext* ic, const std::string& undone);
+    void reportCorrection(InputContext* ic);
+    void sendTextEvent(VInputVInputEventType type, const std::string& text);
 };
 
 /**
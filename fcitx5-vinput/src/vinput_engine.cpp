@@ -4,7 +4,10 @@
  */
 
 #include "vinput_engine.h"
+#include <fcitx-utils/eventdispatcher.h>
 #include <fcitx-utils/log.h>
+#include <fcitx-utils/utf8.h>
+#include <fcitx/candidatelist.h>
 #include <fcitx/inputcontext.h>
 #include <fcitx/inputpanel.h>
 #include <fcitx/text.h>
@@ -14,8 +17,42 @@ namespace fcitx {
 // 全局 VInputEngine 实例指针（用于回调）
 static VInputEngine* g_vinput_engine_instance = nullptr;
 
+namespace {
+
+// 识别候选：选中后替换刚上屏的文本
+class VInputAlternativeWord : public CandidateWord {
+public:
+    VInputAlternativeWord(VInputEngine* engine, std::string text)
+        : CandidateWord(Text(text)), engine_(engine), text_(std::move(text)) {}
+
+    void select(InputContext* ic) const override {
+        engine_->selectAlternative(ic, text_);
+    }
+
+private:
+    VInputEngine* engine_;
+    std::string text_;
+};
+
+// 读取快捷键配置（核心已规范化为 fcitx 同款写法，如 Control+z）
+Key loadShortcut(VInputVInputShortcutAction action, const char* fallback) {
+    char* spec = vinput_core_get_shortcut(action);
+    Key key(spec != nullptr ? spec : fallback);
+    if (!key.isValid()) {
+        FCITX_WARN() << "无法识别的快捷键 '" << (spec != nullptr ? spec : "") << "'，使用 " << fallback;
+        key = Key(fallback);
+    }
+    vinput_string_free(spec);
+    return key;
+}
+
+} // namespace
+
 VInputEngine::VInputEngine(Instance* instance)
-    : instance_(instance), vinput_core_initialized_(false), is_recording_(false) {
+    : instance_(instance), vinput_core_initialized_(false), is_recording_(false),
+      trigger_key_(FcitxKey_Control_R), undo_key_(FcitxKey_z, KeyState::Ctrl),
+      redo_key_(FcitxKey_y, KeyState::Ctrl), activation_{Toggle, Continuous, 400},
+      trigger_held_(false) {
 
     FCITX_INFO() << "V-Input Engine: 初始化";
 
@@ -36,6 +73,8 @@ VInputEngine::VInputEngine(Instance* instance)
         } else {
             FCITX_ERROR() << "❌ 命令回调注册失败: " << result;
         }
+
+        loadShortcuts();
     } else {
         FCITX_ERROR() << "V-Input Core 初始化失败: " << result;
     }
@@ -54,15 +93,23 @@ VInputEngine::~VInputEngine() {
         }
         vinput_core_initialized_ = false;
     }
+
+    if (g_vinput_engine_instance == this) {
+        g_vinput_engine_instance = nullptr;
+    }
 }
 
 void VInputEngine::activate(const InputMethodEntry& entry, InputContextEvent& event) {
     FCITX_DEBUG() << "V-Input: activate";
     is_recording_ = false;
+    trigger_held_ = false;
+    // 撤销后常切到其他输入法手动改正，切回来时检查改正的内容
+    reportCorrection(event.inputContext());
 }
 
 void VInputEngine::deactivate(const InputMethodEntry& entry, InputContextEvent& event) {
     FCITX_DEBUG() << "V-Input: deactivate";
+    alternative_source_.clear();
 
     // 失活时停止录音
     if (is_recording_) {
@@ -72,6 +119,7 @@ void VInputEngine::deactivate(const InputMethodEntry& entry, InputContextEvent&
 
 void VInputEngine::reset(const InputMethodEntry& entry, InputContextEvent& event) {
     FCITX_DEBUG() << "V-Input: reset";
+    alternative_source_.clear();
 
     // 重置时停止录音
     if (is_recording_) {
@@ -84,46 +132,62 @@ void VInputEngine::keyEvent(const InputMethodEntry& entry, KeyEvent& keyEvent) {
                   << ", isRelease=" << keyEvent.isRelease()
                   << ", recording=" << is_recording_;
 
-    // 右 Control 键触发语音输入（Push to Toggle）
-    // 第一次按下：开始录音
-    // 第二次按下：停止录音并识别
-    if (keyEvent.key().check(FcitxKey_Control_R)) {
-        // 只处理按下事件，忽略释放事件
-        if (keyEvent.isRelease()) {
-            FCITX_DEBUG() << "忽略右Control键释放事件";
+    // 候选列表显示中：数字键选择替代结果，Esc 关闭，其他按键关闭后照常处理
+    if (!alternative_source_.empty() && !keyEvent.isRelease()) {
+        auto* ic = keyEvent.inputContext();
+        auto candidateList = ic->inputPanel().candidateList();
+        int index = keyEvent.key().digitSelection();
+        if (candidateList && index >= 0 && index < candidateList->size()) {
             keyEvent.filterAndAccept();
+            candidateList->candidate(index).select(ic);
+            return;
+        }
+        // Ctrl+数字：把该候选加为热词，候选列表保持显示
+        index = keyEvent.key().digitSelection(KeyState::Ctrl);
+        if (candidateList && index >= 0 && index < candidateList->size()) {
+            keyEvent.filterAndAccept();
+            addAlternativeHotword(ic, index);
             return;
         }
 
-        // 右Control键按下：切换录音状态
-        if (is_recording_) {
-            // 当前正在录音 → 停止录音
-            FCITX_INFO() << "右Control键按下 - 停止录音并识别";
-            stopRecording();
-        } else {
-            // 当前未录音 → 开始录音
-            FCITX_INFO() << "右Control键按下 - 开始录音";
-            startRecording();
+        hideAlternatives(ic);
+        if (keyEvent.key().check(FcitxKey_Escape)) {
+            keyEvent.filterAndAccept();
+            return;
         }
+    }
 
+    // 录音中按 Esc：取消本句，不上屏
+    if (is_recording_ && keyEvent.key().check(FcitxKey_Escape)) {
+        if (!keyEvent.isRelease()) {
+            FCITX_INFO() << "Esc - 取消录音";
+            cancelRecording();
+        }
         keyEvent.filterAndAccept();
         return;
     }
 
-    // Ctrl+Z: 撤销
-    if (keyEvent.key().check(FcitxKey_z, KeyState::Ctrl)) {
+    // 触发键：按法由 [activation] 决定（按住 / 切换 / 双击）
+    if (keyEvent.key().check(trigger_key_)) {
+        handleTrigger(keyEvent.isRelease());
+        keyEvent.filterAndAccept();
+        return;
+    }
+
+    // 撤销（默认 Ctrl+Z）
+    if (keyEvent.key().check(undo_key_)) {
         if (!keyEvent.isRelease()) {
-            FCITX_INFO() << "Ctrl+Z - 撤销";
+            FCITX_INFO() << undo_key_.toString() << " - 撤销";
             requestUndo();
         }
         keyEvent.filterAndAccept();
         return;
     }
 
-    // Ctrl+Y: 重试
-    if (keyEvent.key().check(FcitxKey_y, KeyState::Ctrl)) {
+    // 重试（默认 Ctrl+Y）
+    if (keyEvent.key().check(redo_key_)) {
         if (!keyEvent.isRelease()) {
-            FCITX_INFO() << "Ctrl+Y - 重试";
+            FCITX_INFO() << redo_key_.toString() << " - 重试";
             requestRedo();
         }
         keyEvent.filterAndAccept();
@@ -131,6 +195,82 @@ void VInputEngine::keyEvent(const InputMethodEntry& entry, KeyEvent& keyEvent) {
     }
 }
 
+void VInputEngine::loadShortcuts() {
+    trigger_key_ = loadShortcut(Trigger, "Control_R");
+    undo_key_ = loadShortcut(Undo, "Control+z");
+    redo_key_ = loadShortcut(Redo, "Control+y");
+
+    VInputVInputFFIResult result = vinput_core_get_activation(&activation_);
+    if (result != VInputVInputFFIResult::Success) {
+        FCITX_ERROR() << "读取触发方式失败: " << result;
+    }
+
+    FCITX_INFO() << "触发键: " << trigger_key_.toString()
+                 << ", 按法: " << static_cast<int>(activation_.mode)
+                 << ", 停止方式: " << static_cast<int>(activation_.stop_mode)
+                 << ", 撤销: " << undo_key_.toString()
+                 << ", 重试: " << redo_key_.toString();
+}
+
+void VInputEngine::handleTrigger(bool isRelease) {
+    // 按住不放时的自动重复按下事件只算一次
+    const bool repeated = !isRelease && trigger_held_;
+    trigger_held_ = !isRelease;
+    if (repeated) {
+        return;
+    }
+
+    switch (activation_.mode) {
+        case VInputVInputActivationMode::Hold:
+            // 按下开始，松开停止
+            if (!isRelease && !is_recording_) {
+                FCITX_INFO() << "触发键按下 - 开始录音（按住说话）";
+                startRecording();
+            } else if (isRelease && is_recording_) {
+                FCITX_INFO() << "触发键松开 - 停止录音并识别";
+                stopRecording();
+            }
+            break;
+
+        case VInputVInputActivationMode::DoubleTap: {
+            if (isRelease) {
+                break;
+            }
+            // 录音中单击即停止；未录音时需在间隔内连按两次才开始
+            if (is_recording_) {
+                FCITX_INFO() << "触发键按下 - 停止录音并识别";
+                stopRecording();
+                break;
+            }
+            const auto now = std::chrono::steady_clock::now();
+            const auto interval = std::chrono::milliseconds(activation_.double_tap_interval_ms);
+            if (now - last_trigger_press_ <= interval) {
+                FCITX_INFO() << "触发键双击 - 开始录音";
+                last_trigger_press_ = {};
+                startRecording();
+            } else {
+                last_trigger_press_ = now;
+            }
+            break;
+        }
+
+        case VInputVInputActivationMode::Toggle:
+        default:
+            // 只处理按下事件：第一次按下开始录音，第二次按下停止录音并识别
+            if (isRelease) {
+                break;
+            }
+            if (is_recording_) {
+                FCITX_INFO() << "触发键按下 - 停止录音并识别";
+                stopRecording();
+            } else {
+                FCITX_INFO() << "触发键按下 - 开始录音";
+                startRecording();
+            }
+            break;
+    }
+}
+
 void VInputEngine::startRecording() {
     if (!vinput_core_initialized_) {
         FCITX_ERROR() << "V-Input Core 未初始化";
@@ -142,6 +282,8 @@ void VInputEngine::startRecording() {
         return;
     }
 
+    reportCorrection(instance_->mostRecentInputContext());
+
     // 发送 StartRecording 事件
     VInputVInputEvent event;
     event.event_type = StartRecording;
@@ -156,6 +298,7 @@ void VInputEngine::startRecording() {
         // 显示录音指示器
         auto* ic = instance_->mostRecentInputContext();
         if (ic) {
+            alternative_source_.clear();
             auto& inputPanel = ic->inputPanel();
             inputPanel.setAuxUp(Text("🎤 录音中..."));
             ic->updateUserInterface(UserInterfaceComponent::InputPanel);
@@ -196,15 +339,44 @@ void VInputEngine::stopRecording() {
 
             processCommands(ic);
 
-            // 识别完成后清除指示器
-            inputPanel.reset();
-            ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+            // 识别完成后清除指示器（显示候选时保留候选列表）
+            if (alternative_source_.empty()) {
+                inputPanel.reset();
+                ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+            }
         }
     } else {
         FCITX_ERROR() << "停止录音失败: " << result;
     }
 }
 
+void VInputEngine::cancelRecording() {
+    if (!vinput_core_initialized_ || !is_recording_) {
+        return;
+    }
+
+    // 发送 CancelRecording 事件：核心丢弃当前句子，只排队清除 Preedit（及删除已流式上屏部分）的命令
+    VInputVInputEvent event;
+    event.event_type = CancelRecording;
+    event.data = nullptr;
+    event.data_len = 0;
+
+    VInputVInputFFIResult result = vinput_core_send_event(&event);
+    if (result == VInputVInputFFIResult::Success) {
+        is_recording_ = false;
+        FCITX_INFO() << "已取消录音";
+
+        auto* ic = instance_->mostRecentInputContext();
+        if (ic) {
+            processCommands(ic);
+            ic->inputPanel().reset();
+            ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+        }
+    } else {
+        FCITX_ERROR() << "取消录音失败: " << result;
+    }
+}
+
 void VInputEngine::handleCommand(const VInputVInputCommand* command) {
     if (!g_vinput_engine_instance) {
         FCITX_ERROR() << "VInputEngine 实例不存在";
@@ -237,20 +409,24 @@ void VInputEngine::handleCommand(const VInputVInputCommand* command) {
 
         case VInputVInputCommandType::ShowCandidate:
             FCITX_DEBUG() << "ShowCandidate: " << text;
-            // TODO: 显示候选词列表
+            g_vinput_engine_instance->showAlternatives(ic, text, vinput_command_payload(command));
             break;
 
         case VInputVInputCommandType::HideCandidate:
             FCITX_DEBUG() << "HideCandidate";
-            // TODO: 隐藏候选词列表
+            g_vinput_engine_instance->hideAlternatives(ic);
             break;
 
         case VInputVInputCommandType::Error:
             FCITX_ERROR() << "Error: " << text;
-            // 显示错误消息
+            // 显示错误消息（载荷格式: code\tmessage，或仅 message）
             {
+                auto sep = text.find('\t');
+                std::string message = sep == std::string::npos
+                    ? text
+                    : text.substr(sep + 1) + "（" + text.substr(0, sep) + "）";
                 auto& inputPanel = ic->inputPanel();
-                inputPanel.setAuxUp(Text("❌ " + text));
+                inputPanel.setAuxUp(Text("❌ " + message));
                 ic->updateUserInterface(UserInterfaceComponent::InputPanel);
             }
             break;
@@ -259,8 +435,27 @@ void VInputEngine::handleCommand(const VInputVInputCommand* command) {
             FCITX_DEBUG() << "UpdatePreedit: " << text;
             // 更新 Preedit 显示
             {
-                Text preedit(text);
-                preedit.setCursor(text.length());
+                Text preedit;
+                int cursor = static_cast<int>(text.length());
+                const VInputVInputCommandPayload* payload = vinput_command_payload(command);
+                if (payload != nullptr && payload->segment_count > 0) {
+                    // 不稳定文本加下划线
+                    for (uintptr_t i = 0; i < payload->segment_count; ++i) {
+                        const VInputVInputPreeditSegment* segment = vinput_payload_segment(payload, i);
+                        if (segment == nullptr || segment->text == nullptr) {
+                            continue;
+                        }
+                        TextFormatFlags format = TextFormatFlag::NoFlag;
+                        if (segment->attribute == VInputVInputSegmentAttribute::Unstable) {
+                            format = TextFormatFlag::Underline;
+                        }
+                        preedit.append(std::string(segment->text, segment->text_len), format);
+                    }
+                    cursor = static_cast<int>(payload->cursor);
+                } else {
+                    preedit.append(text);
+                }
+                preedit.setCursor(cursor);
                 auto& inputPanel = ic->inputPanel();
                 inputPanel.setClientPreedit(preedit);
                 ic->updatePreedit();
@@ -269,14 +464,128 @@ void VInputEngine::handleCommand(const VInputVInputCommand* command) {
 
         case VInputVInputCommandType::ClearPreedit:
             FCITX_DEBUG() << "ClearPreedit";
-            // 清除 Preedit
+            // 清除 Preedit（新句子开始，上一句的候选一并失效）
             {
+                g_vinput_engine_instance->alternative_source_.clear();
                 auto& inputPanel = ic->inputPanel();
                 inputPanel.reset();
                 ic->updatePreedit();
             }
             break;
 
+        case VInputVInputCommandType::AudioDeviceChanged:
+            FCITX_INFO() << "AudioDeviceChanged: " << text;
+            // 载荷格式: name\tdescription\treason
+            {
+                std::string description = text;
+                std::string reason;
+                auto first = text.find('\t');
+                if (first != std::string::npos) {
+                    auto second = text.find('\t', first + 1);
+                    description = text.substr(first + 1, second == std::string::npos
+                                                             ? std::string::npos
+                                                             : second - first - 1);
+                    if (second != std::string::npos) {
+                        reason = text.substr(second + 1);
+                    }
+                }
+
+                std::string aux = "🎤 " + description;
+                if (reason == "fallback") {
+                    aux += "（首选设备不可用，已切换到默认设备）";
+                }
+                auto& inputPanel = ic->inputPanel();
+                inputPanel.setAuxUp(Text(aux));
+                ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+            }
+            break;
+
+        case VInputVInputCommandType::AudioLevel:
+            // 载荷格式: rms_dbfs\tpeak_dbfs\tclipping
+            {
+                double rmsDb = -120.0;
+                bool clipping = false;
+                auto first = text.find('\t');
+                try {
+                    rmsDb = std::stod(text.substr(0, first));
+                } catch (...) {
+                }
+                auto last = text.rfind('\t');
+                if (last != std::string::npos) {
+                    clipping = text.substr(last + 1) == "1";
+                }
+
+                // -60 dBFS ~ 0 dBFS 映射为 10 格电平条
+                int bars = static_cast<int>((rmsDb + 60.0) / 6.0);
+                bars = std::max(0, std::min(10, bars));
+                std::string meter = "🎤 ";
+                for (int i = 0; i < 10; ++i) {
+                    meter += i < bars ? "▮" : "▯";
+                }
+                if (clipping) {
+                    meter += " ⚠ 削波";
+                }
+
+                auto& inputPanel = ic->inputPanel();
+                inputPanel.setAuxDown(Text(meter));
+                ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+            }
+            break;
+
+        case VInputVInputCommandType::AudioWarning:
+            FCITX_WARN() << "AudioWarning: " << text;
+            // 载荷格式: kind\tmessage
+            {
+                auto sep = text.find('\t');
+                std::string message = sep == std::string::npos ? text : text.substr(sep + 1);
+                auto& inputPanel = ic->inputPanel();
+                inputPanel.setAuxUp(Text("⚠️ " + message));
+                ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+            }
+            break;
+
+        case VInputVInputCommandType::RecordingStopped:
+            FCITX_INFO() << "自动停止录音";
+            // 回调位于音频线程，回到主线程后再发送 StopRecording 释放麦克风
+            {
+                VInputEngine* engine = g_vinput_engine_instance;
+                engine->instance_->eventDispatcher().schedule([engine]() {
+                    if (g_vinput_engine_instance == engine && engine->is_recording_) {
+                        engine->stopRecording();
+                    }
+                });
+            }
+            break;
+
+        case VInputVInputCommandType::UndoText:
+            FCITX_INFO() << "回调撤销: " << text;
+            // 语音取消口令：删除本句已流式上屏的文本；与 RecordingStopped 相同，回到主线程后再操作
+            {
+                VInputEngine* engine = g_vinput_engine_instance;
+                engine->instance_->eventDispatcher().schedule([engine, text]() {
+                    if (g_vinput_engine_instance != engine) {
+                        return;
+                    }
+                    if (auto* ic = engine->instance_->mostRecentInputContext()) {
+                        engine->deleteCommitted(ic, text);
+                    }
+                });
+            }
+            break;
+
+        case VInputVInputCommandType::WakeWordDetected:
+            FCITX_INFO() << "检测到唤醒词: " << text;
+            // 与 RecordingStopped 相同，回到主线程后发送 StartRecording
+            {
+                VInputEngine* engine = g_vinput_engine_instance;
+                engine->instance_->eventDispatcher().schedule([engine]() {
+                    if (g_vinput_engine_instance == engine && !engine->is_recording_) {
+                        engine->startRecording();
+                    }
+                });
+            }
+            break;
+
         default:
             FCITX_WARN() << "Unknown command type: "
                         << static_cast<int>(command->command_type);
@@ -291,7 +600,7 @@ void VInputEngine::processCommands(InputContext* ic) {
 
     // 循环接收所有命令
     while (true) {
-        VInputVInputCommand command;
+        VInputVInputCommand command{};
         VInputVInputFFIResult result = vinput_core_try_recv_command(&command);
 
         if (result == VInputVInputFFIResult::Success) {
@@ -309,22 +618,18 @@ void VInputEngine::processCommands(InputContext* ic) {
 
                 case VInputVInputCommandType::ShowCandidate:
                     FCITX_INFO() << "ShowCandidate: " << text;
-                    // TODO: 显示候选词列表
-                    // ic->inputPanel().setCandidateList(...);
+                    showAlternatives(ic, text, vinput_command_payload(&command));
                     break;
 
                 case VInputVInputCommandType::HideCandidate:
                     FCITX_INFO() << "HideCandidate";
-                    // TODO: 隐藏候选词列表
-                    // ic->inputPanel().reset();
+                    hideAlternatives(ic);
                     break;
 
                 case VInputVInputCommandType::UndoText:
                     FCITX_INFO() << "UndoText: " << text;
-                    // 删除指定长度的文本
-                    for (size_t i = 0; i < text.length(); ++i) {
-                        ic->forwardKey(Key(FcitxKey_BackSpace));
-                    }
+                    rememberUndoAnchor(ic, text);
+                    deleteCommitted(ic, text);
                     break;
 
                 case VInputVInputCommandType::RedoText:
@@ -335,10 +640,14 @@ void VInputEngine::processCommands(InputContext* ic) {
 
                 case VInputVInputCommandType::Error:
                     FCITX_ERROR() << "Error: " << text;
-                    // 显示错误消息
+                    // 显示错误消息（载荷格式: code\tmessage，或仅 message）
                     {
+                        auto sep = text.find('\t');
+                        std::string message = sep == std::string::npos
+                            ? text
+                            : text.substr(sep + 1) + "（" + text.substr(0, sep) + "）";
                         auto& inputPanel = ic->inputPanel();
-                        inputPanel.setAuxUp(Text("❌ " + text));
+                        inputPanel.setAuxUp(Text("❌ " + message));
                         ic->updateUserInterface(UserInterfaceComponent::InputPanel);
                     }
                     break;
@@ -362,6 +671,137 @@ void VInputEngine::processCommands(InputContext* ic) {
     }
 }
 
+void VInputEngine::showAlternatives(InputContext* ic, const std::string& committed,
+                                    const VInputVInputCommandPayload* payload) {
+    if (payload == nullptr || payload->candidate_count < 2) {
+        return;
+    }
+
+    // 候选 0 为已上屏文本，选中即保持不变
+    auto candidateList = std::make_unique<CommonCandidateList>();
+    candidateList->setPageSize(9);
+    candidateList->setSelectionKey(Key::keyListFromString("1 2 3 4 5 6 7 8 9"));
+    for (uintptr_t i = 0; i < payload->candidate_count && i < 9; ++i) {
+        const char* candidate = vinput_payload_candidate(payload, i);
+        if (candidate != nullptr) {
+            candidateList->append<VInputAlternativeWord>(this, std::string(candidate));
+        }
+    }
+
+    alternative_source_ = committed;
+    auto& inputPanel = ic->inputPanel();
+    inputPanel.setCandidateList(std::move(candidateList));
+    inputPanel.setAuxUp(Text("按数字键替换刚输入的文本，Ctrl+数字加为热词，Esc 关闭"));
+    ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+}
+
+void VInputEngine::hideAlternatives(InputContext* ic) {
+    if (alternative_source_.empty()) {
+        return;
+    }
+    alternative_source_.clear();
+    auto& inputPanel = ic->inputPanel();
+    inputPanel.setCandidateList(nullptr);
+    inputPanel.setAuxUp(Text());
+    ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+}
+
+void VInputEngine::selectAlternative(InputContext* ic, const std::string& text) {
+    const std::string previous = alternative_source_;
+    hideAlternatives(ic);
+    if (previous.empty() || text == previous) {
+        return;
+    }
+
+    FCITX_INFO() << "替换候选: " << previous << " → " << text;
+
+    deleteCommitted(ic, previous);
+    ic->commitString(text);
+
+    // 通知 Core 更新识别历史（撤销时删除替换后的文本）
+    sendTextEvent(CandidateSelected, text);
+}
+
+void VInputEngine::deleteCommitted(InputContext* ic, const std::string& text) {
+    // 删除光标前刚上屏的文本（按字符计数）：优先使用 surrounding text，不支持时退回退格键
+    const size_t length = utf8::length(text);
+    if (ic->capabilityFlags().test(CapabilityFlag::SurroundingText)) {
+        ic->deleteSurroundingText(-static_cast<int>(length), static_cast<unsigned int>(length));
+    } else {
+        for (size_t i = 0; i < length; ++i) {
+            ic->forwardKey(Key(FcitxKey_BackSpace));
+        }
+    }
+}
+
+void VInputEngine::addAlternativeHotword(InputContext* ic, int index) {
+    auto candidateList = ic->inputPanel().candidateList();
+    const std::string text = candidateList->candidate(index).text().toString();
+    FCITX_INFO() << "加为热词: " << text;
+    sendTextEvent(AddHotword, text);
+
+    auto& inputPanel = ic->inputPanel();
+    inputPanel.setAuxUp(Text("⭐ 已加为热词：" + text));
+    ic->updateUserInterface(UserInterfaceComponent::InputPanel);
+}
+
+void VInputEngine::rememberUndoAnchor(InputContext* ic, const std::string& undone) {
+    undo_anchor_.reset();
+    if (!ic->capabilityFlags().test(CapabilityFlag::SurroundingText)) {
+        return;
+    }
+    const auto& surrounding = ic->surroundingText();
+    if (!surrounding.isValid()) {
+        return;
+    }
+
+    // 撤销位置 = 光标前的文本去掉被撤销的部分
+    const std::string& content = surrounding.text();
+    const size_t cursor = utf8::ncharByteLength(content.begin(), surrounding.cursor());
+    const std::string before = content.substr(0, cursor);
+    if (before.size() < undone.size()
+        || before.compare(before.size() - undone.size(), undone.size(), undone) != 0) {
+        return;
+    }
+    undo_anchor_ = before.substr(0, before.size() - undone.size());
+}
+
+void VInputEngine::reportCorrection(InputContext* ic) {
+    if (!undo_anchor_ || ic == nullptr) {
+        return;
+    }
+    const std::string anchor = std::move(*undo_anchor_);
+    undo_anchor_.reset();
+
+    const auto& surrounding = ic->surroundingText();
+    if (!surrounding.isValid()) {
+        return;
+    }
+    const std::string& content = surrounding.text();
+    const size_t cursor = utf8::ncharByteLength(content.begin(), surrounding.cursor());
+    const std::string before = content.substr(0, cursor);
+    if (before.size() <= anchor.size() || before.compare(0, anchor.size(), anchor) != 0) {
+        return;
+    }
+
+    // 撤销位置之后新输入的文本即为用户的改正（是否在时间窗口内由 Core 判断）
+    const std::string typed = before.substr(anchor.size());
+    FCITX_INFO() << "撤销后重新输入: " << typed;
+    sendTextEvent(CorrectionText, typed);
+}
+
+void VInputEngine::sendTextEvent(VInputVInputEventType type, const std::string& text) {
+    VInputVInputEvent event;
+    event.event_type = type;
+    event.data = reinterpret_cast<const uint8_t*>(text.data());
+    event.data_len = text.size();
+
+    VInputVInputFFIResult result = vinput_core_send_event(&event);
+    if (result != VInputVInputFFIResult::Success) {
+        FCITX_ERROR() << "发送事件失败 (" << static_cast<int>(type) << "): " << result;
+    }
+}
+
 void VInputEngine::requestUndo() {
     if (!vinput_core_initialized_) {
         FCITX_ERROR() << "V-Input Core 未初始化";
@@ -0,0 +1,39 @@
+/*
+ * V-Input Engine for IBus
+ * IBusEngine 子类：通过 vinput-core 句柄接口驱动 preedit / commit
+ */
+
+#ifndef VINPUT_IBUS_ENGINE_H
+#define VINPUT_IBUS_ENGINE_H
+
+#include <ibus.h>
+
+G_BEGIN_DECLS
+
+/* IBus 组件总线名称与引擎名称（与 vinput.xml 保持一致） */
+#define VINPUT_IBUS_COMPONENT_NAME "org.freedesktop.IBus.VInput"
+#define VINPUT_IBUS_ENGINE_NAME "vinput"
+
+#define VINPUT_TYPE_IBUS_ENGINE (vinput_ibus_engine_get_type())
+#define VINPUT_IBUS_ENGINE(obj) \
+    (G_TYPE_CHECK_INSTANCE_CAST((obj), VINPUT_TYPE_IBUS_ENGINE, VInputIBusEngine))
+
+typedef struct _VInputIBusEngine VInputIBusEngine;
+typedef struct _VInputIBusEngineClass VInputIBusEngineClass;
+
+GType vinput_ibus_engine_get_type(void);
+
+/*
+ * 初始化进程内共享的 vinput-core 引擎（模型只加载一次）
+ *
+ * config_path 为 NULL 时使用 ~/.config/vinput/config.toml
+ * 返回 FALSE 表示引擎创建失败
+ */
+gboolean vinput_ibus_engine_init_core(const gchar* config_path);
+
+/* 释放共享引擎（正在录音时先停止录音） */
+void vinput_ibus_engine_shutdown_core(void);
+
+G_END_DECLS
+
+#endif /* VINPUT_IBUS_ENGINE_H */
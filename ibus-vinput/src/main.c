@@ -0,0 +1,85 @@
+/*
+ * V-Input IBus 组件入口
+ *
+ * 由 ibus-daemon 按 vinput.xml 以 --ibus 参数启动时申请组件总线名称；
+ * 手动运行时自行注册组件，便于调试。
+ */
+
+#include "vinput_ibus_engine.h"
+#include <locale.h>
+
+static gboolean opt_ibus = FALSE;
+static gchar* opt_config = NULL;
+
+static const GOptionEntry option_entries[] = {
+    {"ibus", 'i', 0, G_OPTION_ARG_NONE, &opt_ibus, "由 ibus-daemon 启动", NULL},
+    {"config", 'c', 0, G_OPTION_ARG_FILENAME, &opt_config,
+     "配置文件路径（默认 ~/.config/vinput/config.toml）", "FILE"},
+    {NULL, 0, 0, 0, NULL, NULL, NULL},
+};
+
+static void on_bus_disconnected(IBusBus* bus, gpointer user_data) {
+    (void)bus;
+    (void)user_data;
+    g_message("IBus 连接已断开，退出");
+    ibus_quit();
+}
+
+static void register_component(IBusBus* bus) {
+    IBusComponent* component = ibus_component_new(
+        VINPUT_IBUS_COMPONENT_NAME, "水滴语音输入法", VINPUT_IBUS_VERSION, "MIT",
+        "V-Input Contributors", "", "", "ibus-vinput");
+    ibus_component_add_engine(component,
+                              ibus_engine_desc_new(VINPUT_IBUS_ENGINE_NAME, "水滴语音输入法",
+                                                   "离线中文语音输入", "zh_CN", "MIT",
+                                                   "V-Input Contributors",
+                                                   "audio-input-microphone", "us"));
+    ibus_bus_register_component(bus, component);
+    g_object_unref(component);
+}
+
+int main(int argc, char** argv) {
+    setlocale(LC_ALL, "");
+
+    GError* error = NULL;
+    GOptionContext* context = g_option_context_new("- V-Input IBus 语音输入引擎");
+    g_option_context_add_main_entries(context, option_entries, NULL);
+    if (!g_option_context_parse(context, &argc, &argv, &error)) {
+        g_printerr("%s\n", error->message);
+        g_error_free(error);
+        g_option_context_free(context);
+        return 1;
+    }
+    g_option_context_free(context);
+
+    ibus_init();
+    IBusBus* bus = ibus_bus_new();
+    if (!ibus_bus_is_connected(bus)) {
+        g_printerr("无法连接 IBus 守护进程\n");
+        g_object_unref(bus);
+        return 1;
+    }
+    g_signal_connect(bus, "disconnected", G_CALLBACK(on_bus_disconnected), NULL);
+
+    if (!vinput_ibus_engine_init_core(opt_config)) {
+        g_object_unref(bus);
+        return 1;
+    }
+
+    IBusFactory* factory = ibus_factory_new(ibus_bus_get_connection(bus));
+    ibus_factory_add_engine(factory, VINPUT_IBUS_ENGINE_NAME, VINPUT_TYPE_IBUS_ENGINE);
+
+    if (opt_ibus) {
+        ibus_bus_request_name(bus, VINPUT_IBUS_COMPONENT_NAME, 0);
+    } else {
+        register_component(bus);
+    }
+
+    ibus_main();
+
+    vinput_ibus_engine_shutdown_core();
+    g_object_unref(factory);
+    g_object_unref(bus);
+    g_free(opt_config);
+    return 0;
+}
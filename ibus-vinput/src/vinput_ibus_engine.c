@@ -0,0 +1,588 @@
+/*
+ * V-Input Engine for IBus
+ * IBusEngine 子类：通过 vinput-core 句柄接口驱动 preedit / commit
+ */
+
+#include "vinput_ibus_engine.h"
+#include "vinput_core.h"
+#include <string.h>
+
+/* 参与快捷键匹配的修饰键 */
+#define VINPUT_MODIFIER_MASK (IBUS_CONTROL_MASK | IBUS_SHIFT_MASK | IBUS_MOD1_MASK | IBUS_SUPER_MASK)
+
+/* evdev BackSpace 键码（IBus 键码不含 X11 的 +8 偏移） */
+#define VINPUT_KEYCODE_BACKSPACE 14
+
+struct _VInputIBusEngine {
+    IBusEngine parent_instance;
+};
+
+struct _VInputIBusEngineClass {
+    IBusEngineClass parent_class;
+};
+
+G_DEFINE_TYPE(VInputIBusEngine, vinput_ibus_engine, IBUS_TYPE_ENGINE)
+
+/* 快捷键（由 config.toml 的 [shortcuts] 段解析） */
+typedef struct {
+    guint keyval;
+    guint modifiers;
+} VInputShortcut;
+
+/* Preedit 片段在文本中的字符范围 */
+typedef struct {
+    guint start;
+    guint end;
+    VInputVInputSegmentAttribute attribute;
+} VInputSegmentRange;
+
+/* 从 vinput-core 命令复制出的数据（可跨线程传递到主循环） */
+typedef struct {
+    VInputVInputCommandType type;
+    gchar* text;
+    /* VInputSegmentRange 数组，无结构化载荷时为空 */
+    GArray* segments;
+    /* 光标位置（字符偏移） */
+    guint cursor;
+} VInputPendingCommand;
+
+/*
+ * 进程内共享状态
+ *
+ * IBus 为每个输入上下文创建一个引擎对象，但所有对象共用一个 vinput-core 句柄，
+ * 识别结果只发给当前获得焦点的引擎。以下变量只在主循环线程中访问。
+ */
+static VInputHandle* g_handle = NULL;
+static VInputIBusEngine* g_focused_engine = NULL;
+static gboolean g_recording = FALSE;
+static VInputShortcut g_trigger_shortcut;
+static VInputShortcut g_undo_shortcut;
+static VInputShortcut g_redo_shortcut;
+static VInputVInputActivation g_activation = {Toggle, Continuous, 400};
+/* 触发键是否处于按下状态（过滤自动重复） */
+static gboolean g_trigger_held = FALSE;
+/* 上一次单击触发键的时间（微秒，双击判定） */
+static gint64 g_last_trigger_press = 0;
+
+static VInputShortcut parse_shortcut(const gchar* spec) {
+    VInputShortcut shortcut = {IBUS_KEY_VoidSymbol, 0};
+    gchar** parts = g_strsplit(spec, "+", -1);
+    guint count = g_strv_length(parts);
+
+    for (guint i = 0; i + 1 < count; ++i) {
+        if (g_strcmp0(parts[i], "Control") == 0) {
+            shortcut.modifiers |= IBUS_CONTROL_MASK;
+        } else if (g_strcmp0(parts[i], "Shift") == 0) {
+            shortcut.modifiers |= IBUS_SHIFT_MASK;
+        } else if (g_strcmp0(parts[i], "Alt") == 0) {
+            shortcut.modifiers |= IBUS_MOD1_MASK;
+        } else if (g_strcmp0(parts[i], "Super") == 0) {
+            shortcut.modifiers |= IBUS_SUPER_MASK;
+        }
+    }
+    if (count > 0) {
+        shortcut.keyval = ibus_keyval_from_name(parts[count - 1]);
+    }
+
+    g_strfreev(parts);
+    return shortcut;
+}
+
+/* 读取快捷键配置（核心已规范化写法；keysym 无法识别时使用 fallback） */
+static VInputShortcut load_shortcut(VInputVInputShortcutAction action, const gchar* fallback) {
+    char* spec = vinput_engine_get_shortcut(g_handle, action);
+    VInputShortcut shortcut = parse_shortcut(spec != NULL ? spec : fallback);
+    if (shortcut.keyval == IBUS_KEY_VoidSymbol) {
+        g_warning("无法识别的快捷键 '%s'，使用 %s", spec != NULL ? spec : "", fallback);
+        shortcut = parse_shortcut(fallback);
+    } else {
+        g_message("快捷键 %d: %s", action, spec != NULL ? spec : fallback);
+    }
+    vinput_string_free(spec);
+    return shortcut;
+}
+
+static gboolean is_modifier_keyval(guint keyval) {
+    switch (keyval) {
+        case IBUS_KEY_Control_L:
+        case IBUS_KEY_Control_R:
+        case IBUS_KEY_Shift_L:
+        case IBUS_KEY_Shift_R:
+        case IBUS_KEY_Alt_L:
+        case IBUS_KEY_Alt_R:
+        case IBUS_KEY_Super_L:
+        case IBUS_KEY_Super_R:
+        case IBUS_KEY_Meta_L:
+        case IBUS_KEY_Meta_R:
+            return TRUE;
+        default:
+            return FALSE;
+    }
+}
+
+static gboolean shortcut_matches(const VInputShortcut* shortcut, guint keyval, guint state) {
+    if (shortcut->keyval == IBUS_KEY_VoidSymbol
+        || ibus_keyval_to_lower(keyval) != ibus_keyval_to_lower(shortcut->keyval)) {
+        return FALSE;
+    }
+
+    // 单独的修饰键（如 Control_R）释放时状态位包含其自身，不比较修饰键
+    if (shortcut->modifiers == 0 && is_modifier_keyval(keyval)) {
+        return TRUE;
+    }
+
+    guint modifiers = state & VINPUT_MODIFIER_MASK;
+    if (state & IBUS_MOD4_MASK) {
+        modifiers |= IBUS_SUPER_MASK;
+    }
+    return modifiers == shortcut->modifiers;
+}
+
+static VInputPendingCommand* pending_command_new(const VInputVInputCommand* command) {
+    VInputPendingCommand* pending = g_new0(VInputPendingCommand, 1);
+    pending->type = command->command_type;
+    pending->text = command->text != NULL ? g_strndup(command->text, command->text_len) : g_strdup("");
+    pending->segments = g_array_new(FALSE, FALSE, sizeof(VInputSegmentRange));
+    pending->cursor = g_utf8_strlen(pending->text, -1);
+
+    const VInputVInputCommandPayload* payload = vinput_command_payload(command);
+    if (payload == NULL || payload->segment_count == 0) {
+        return pending;
+    }
+
+    guint offset = 0;
+    for (uintptr_t i = 0; i < payload->segment_count; ++i) {
+        const VInputVInputPreeditSegment* segment = vinput_payload_segment(payload, i);
+        if (segment == NULL || segment->text == NULL) {
+            continue;
+        }
+        VInputSegmentRange range;
+        range.start = offset;
+        range.end = offset + g_utf8_strlen(segment->text, segment->text_len);
+        range.attribute = segment->attribute;
+        g_array_append_val(pending->segments, range);
+        offset = range.end;
+    }
+
+    // 载荷光标为字节偏移，IBus 需要字符偏移
+    size_t cursor = MIN(payload->cursor, strlen(pending->text));
+    pending->cursor = g_utf8_pointer_to_offset(pending->text, pending->text + cursor);
+    return pending;
+}
+
+static void pending_command_free(gpointer data) {
+    VInputPendingCommand* pending = data;
+    g_free(pending->text);
+    g_array_unref(pending->segments);
+    g_free(pending);
+}
+
+static void show_aux(VInputIBusEngine* self, const gchar* text) {
+    ibus_engine_update_auxiliary_text(IBUS_ENGINE(self), ibus_text_new_from_string(text), TRUE);
+}
+
+static void hide_aux(VInputIBusEngine* self) {
+    ibus_engine_hide_auxiliary_text(IBUS_ENGINE(self));
+}
+
+/* tab 分隔载荷中的第 index 个字段（不存在时返回整个文本） */
+static gchar* payload_field(const gchar* text, guint index) {
+    gchar** fields = g_strsplit(text, "\t", -1);
+    gchar* field = g_strdup(index < g_strv_length(fields) ? fields[index] : text);
+    g_strfreev(fields);
+    return field;
+}
+
+static void update_preedit(VInputIBusEngine* self, const VInputPendingCommand* command) {
+    IBusText* preedit = ibus_text_new_from_string(command->text);
+
+    // 不稳定文本加下划线
+    for (guint i = 0; i < command->segments->len; ++i) {
+        const VInputSegmentRange* range = &g_array_index(command->segments, VInputSegmentRange, i);
+        if (range->attribute == Unstable) {
+            ibus_text_append_attribute(preedit, IBUS_ATTR_TYPE_UNDERLINE, IBUS_ATTR_UNDERLINE_SINGLE,
+                                       range->start, range->end);
+        }
+    }
+
+    ibus_engine_update_preedit_text(IBUS_ENGINE(self), preedit, command->cursor, TRUE);
+}
+
+static void delete_before_cursor(VInputIBusEngine* self, guint length) {
+    IBusEngine* engine = IBUS_ENGINE(self);
+
+    // 优先使用 surrounding text，不支持时退回退格键
+    if (engine->client_capabilities & IBUS_CAP_SURROUNDING_TEXT) {
+        ibus_engine_delete_surrounding_text(engine, -(gint)length, length);
+        return;
+    }
+    for (guint i = 0; i < length; ++i) {
+        ibus_engine_forward_key_event(engine, IBUS_KEY_BackSpace, VINPUT_KEYCODE_BACKSPACE, 0);
+        ibus_engine_forward_key_event(engine, IBUS_KEY_BackSpace, VINPUT_KEYCODE_BACKSPACE,
+                                      IBUS_RELEASE_MASK);
+    }
+}
+
+static void start_recording(VInputIBusEngine* self);
+static void stop_recording(VInputIBusEngine* self);
+
+static void apply_command(VInputIBusEngine* self, const VInputPendingCommand* command) {
+    IBusEngine* engine = IBUS_ENGINE(self);
+
+    switch (command->type) {
+        case CommitText:
+            g_message("CommitText: %s", command->text);
+            ibus_engine_commit_text(engine, ibus_text_new_from_string(command->text));
+            break;
+
+        case UpdatePreedit:
+            g_debug("UpdatePreedit: %s", command->text);
+            update_preedit(self, command);
+            break;
+
+        case ClearPreedit:
+            g_debug("ClearPreedit");
+            ibus_engine_update_preedit_text(engine, ibus_text_new_from_static_string(""), 0, FALSE);
+            break;
+
+        case UndoText:
+            g_message("UndoText: %s", command->text);
+            delete_before_cursor(self, g_utf8_strlen(command->text, -1));
+            break;
+
+        case RedoText:
+            g_message("RedoText: %s", command->text);
+            ibus_engine_commit_text(engine, ibus_text_new_from_string(command->text));
+            break;
+
+        case Error: {
+            g_warning("Error: %s", command->text);
+            // 载荷格式: code\tmessage，或仅 message
+            const gchar* sep = strchr(command->text, '\t');
+            gchar* aux = sep != NULL
+                ? g_strdup_printf("❌ %s（%.*s）", sep + 1, (int)(sep - command->text), command->text)
+                : g_strconcat("❌ ", command->text, NULL);
+            show_aux(self, aux);
+            g_free(aux);
+            break;
+        }
+
+        case AudioDeviceChanged: {
+            // 载荷格式: name\tdescription\treason
+            gchar* description = payload_field(command->text, 1);
+            gchar* aux = g_strconcat("🎤 ", description, NULL);
+            show_aux(self, aux);
+            g_free(aux);
+            g_free(description);
+            break;
+        }
+
+        case AudioWarning: {
+            // 载荷格式: kind\tmessage
+            gchar* message = payload_field(command->text, 1);
+            gchar* aux = g_strconcat("⚠️ ", message, NULL);
+            show_aux(self, aux);
+            g_free(aux);
+            g_free(message);
+            break;
+        }
+
+        case AudioLevel:
+            break;
+
+        case RecordingStopped:
+            // 自动停止（整句已上屏 / 免提静默超时）：发送 StopRecording 释放麦克风
+            g_message("自动停止录音");
+            stop_recording(self);
+            break;
+
+        case WakeWordDetected:
+            g_message("检测到唤醒词: %s", command->text ? command->text : "");
+            start_recording(self);
+            break;
+
+        default:
+            // 候选列表暂只在 fcitx5 前端显示
+            g_debug("忽略命令: %d", command->type);
+            break;
+    }
+}
+
+/* 主循环中分发回调命令（发给当前获得焦点的引擎） */
+static gboolean dispatch_pending_command(gpointer data) {
+    if (g_focused_engine != NULL) {
+        apply_command(g_focused_engine, data);
+    } else {
+        g_debug("没有获得焦点的输入上下文，丢弃命令");
+    }
+    return G_SOURCE_REMOVE;
+}
+
+/* 命令回调（音频线程中调用）：复制命令后转交主循环 */
+static void on_core_command(const VInputVInputCommand* command, void* user_data) {
+    (void)user_data;
+    if (command == NULL) {
+        return;
+    }
+    g_idle_add_full(G_PRIORITY_DEFAULT, dispatch_pending_command, pending_command_new(command),
+                    pending_command_free);
+}
+
+/* 处理排队的命令（停止录音、撤销、重试后由 Core 放入队列） */
+static void process_commands(VInputIBusEngine* self) {
+    while (TRUE) {
+        VInputVInputCommand command;
+        memset(&command, 0, sizeof(command));
+        VInputVInputFFIResult result = vinput_engine_try_recv_command(g_handle, &command);
+        if (result == NoData) {
+            break;
+        }
+        if (result != Success) {
+            g_warning("接收命令失败: %d", result);
+            break;
+        }
+
+        VInputPendingCommand* pending = pending_command_new(&command);
+        apply_command(self, pending);
+        pending_command_free(pending);
+        vinput_command_free(&command);
+    }
+}
+
+static gboolean send_core_event(VInputVInputEventType type) {
+    VInputVInputEvent event;
+    event.event_type = type;
+    event.data = NULL;
+    event.data_len = 0;
+
+    VInputVInputFFIResult result = vinput_engine_send_event(g_handle, &event);
+    if (result != Success) {
+        g_warning("发送事件 %d 失败: %d", type, result);
+        return FALSE;
+    }
+    return TRUE;
+}
+
+static void start_recording(VInputIBusEngine* self) {
+    if (g_recording || !send_core_event(StartRecording)) {
+        return;
+    }
+    g_recording = TRUE;
+    g_message("开始录音");
+    show_aux(self, "🎤 录音中...");
+}
+
+static void stop_recording(VInputIBusEngine* self) {
+    if (!g_recording || !send_core_event(StopRecording)) {
+        return;
+    }
+    g_recording = FALSE;
+    g_message("停止录音");
+    show_aux(self, "🔵 识别中...");
+    process_commands(self);
+    hide_aux(self);
+}
+
+static void cancel_recording(VInputIBusEngine* self) {
+    if (!g_recording || !send_core_event(CancelRecording)) {
+        return;
+    }
+    g_recording = FALSE;
+    g_message("取消录音");
+    process_commands(self);
+    hide_aux(self);
+}
+
+static void request_history_action(VInputIBusEngine* self, VInputVInputEventType type) {
+    if (send_core_event(type)) {
+        process_commands(self);
+    }
+}
+
+/* 触发键按法由 [activation] 决定（按住 / 切换 / 双击） */
+static void handle_trigger(VInputIBusEngine* self, gboolean is_release) {
+    // 按住不放时的自动重复按下事件只算一次
+    gboolean repeated = !is_release && g_trigger_held;
+    g_trigger_held = !is_release;
+    if (repeated) {
+        return;
+    }
+
+    switch (g_activation.mode) {
+        case Hold:
+            if (!is_release) {
+                start_recording(self);
+            } else {
+                stop_recording(self);
+            }
+            break;
+
+        case DoubleTap: {
+            if (is_release) {
+                break;
+            }
+            // 录音中单击即停止；未录音时需在间隔内连按两次才开始
+            if (g_recording) {
+                stop_recording(self);
+                break;
+            }
+            gint64 now = g_get_monotonic_time();
+            if (g_last_trigger_press != 0
+                && now - g_last_trigger_press <= (gint64)g_activation.double_tap_interval_ms * 1000) {
+                g_last_trigger_press = 0;
+                start_recording(self);
+            } else {
+                g_last_trigger_press = now;
+            }
+            break;
+        }
+
+        case Toggle:
+        default:
+            if (is_release) {
+                break;
+            }
+            if (g_recording) {
+                stop_recording(self);
+            } else {
+                start_recording(self);
+            }
+            break;
+    }
+}
+
+static gboolean vinput_ibus_engine_process_key_event(IBusEngine* engine, guint keyval,
+                                                     guint keycode, guint state) {
+    VInputIBusEngine* self = VINPUT_IBUS_ENGINE(engine);
+    gboolean is_release = (state & IBUS_RELEASE_MASK) != 0;
+    (void)keycode;
+
+    if (g_handle == NULL) {
+        return FALSE;
+    }
+
+    // 录音中按 Esc：取消本句，不上屏
+    if (g_recording && keyval == IBUS_KEY_Escape) {
+        if (!is_release) {
+            cancel_recording(self);
+        }
+        return TRUE;
+    }
+
+    // 触发键：按下与释放事件都交给 handle_trigger，并一并吞掉
+    if (shortcut_matches(&g_trigger_shortcut, keyval, state)) {
+        handle_trigger(self, is_release);
+        return TRUE;
+    }
+
+    if (shortcut_matches(&g_undo_shortcut, keyval, state)) {
+        if (!is_release) {
+            request_history_action(self, UndoRequest);
+        }
+        return TRUE;
+    }
+
+    if (shortcut_matches(&g_redo_shortcut, keyval, state)) {
+        if (!is_release) {
+            request_history_action(self, RedoRequest);
+        }
+        return TRUE;
+    }
+
+    return FALSE;
+}
+
+static void vinput_ibus_engine_focus_in(IBusEngine* engine) {
+    g_focused_engine = VINPUT_IBUS_ENGINE(engine);
+    g_trigger_held = FALSE;
+    IBUS_ENGINE_CLASS(vinput_ibus_engine_parent_class)->focus_in(engine);
+}
+
+static void vinput_ibus_engine_focus_out(IBusEngine* engine) {
+    VInputIBusEngine* self = VINPUT_IBUS_ENGINE(engine);
+
+    // 失去焦点时停止录音，最终结果仍上屏到原输入上下文
+    if (g_focused_engine == self) {
+        stop_recording(self);
+        g_focused_engine = NULL;
+    }
+    IBUS_ENGINE_CLASS(vinput_ibus_engine_parent_class)->focus_out(engine);
+}
+
+static void vinput_ibus_engine_reset(IBusEngine* engine) {
+    VInputIBusEngine* self = VINPUT_IBUS_ENGINE(engine);
+    if (g_focused_engine == self) {
+        stop_recording(self);
+    }
+    IBUS_ENGINE_CLASS(vinput_ibus_engine_parent_class)->reset(engine);
+}
+
+static void vinput_ibus_engine_disable(IBusEngine* engine) {
+    VInputIBusEngine* self = VINPUT_IBUS_ENGINE(engine);
+    if (g_focused_engine == self) {
+        stop_recording(self);
+    }
+    IBUS_ENGINE_CLASS(vinput_ibus_engine_parent_class)->disable(engine);
+}
+
+static void vinput_ibus_engine_destroy(IBusObject* object) {
+    if (g_focused_engine == VINPUT_IBUS_ENGINE(object)) {
+        g_focused_engine = NULL;
+    }
+    IBUS_OBJECT_CLASS(vinput_ibus_engine_parent_class)->destroy(object);
+}
+
+static void vinput_ibus_engine_class_init(VInputIBusEngineClass* klass) {
+    IBusObjectClass* object_class = IBUS_OBJECT_CLASS(klass);
+    IBusEngineClass* engine_class = IBUS_ENGINE_CLASS(klass);
+
+    object_class->destroy = vinput_ibus_engine_destroy;
+    engine_class->process_key_event = vinput_ibus_engine_process_key_event;
+    engine_class->focus_in = vinput_ibus_engine_focus_in;
+    engine_class->focus_out = vinput_ibus_engine_focus_out;
+    engine_class->reset = vinput_ibus_engine_reset;
+    engine_class->disable = vinput_ibus_engine_disable;
+}
+
+static void vinput_ibus_engine_init(VInputIBusEngine* self) {
+    (void)self;
+}
+
+gboolean vinput_ibus_engine_init_core(const gchar* config_path) {
+    if (g_handle != NULL) {
+        return TRUE;
+    }
+
+    g_handle = vinput_engine_new(config_path);
+    if (g_handle == NULL) {
+        g_warning("V-Input Core 初始化失败");
+        return FALSE;
+    }
+    g_message("V-Input Core 初始化成功, version: %s", vinput_core_version());
+
+    VInputVInputFFIResult result = vinput_engine_register_callback(g_handle, on_core_command, NULL);
+    if (result != Success) {
+        g_warning("命令回调注册失败: %d", result);
+    }
+
+    g_trigger_shortcut = load_shortcut(Trigger, "Control_R");
+    g_undo_shortcut = load_shortcut(Undo, "Control+z");
+    g_redo_shortcut = load_shortcut(Redo, "Control+y");
+
+    result = vinput_engine_get_activation(g_handle, &g_activation);
+    if (result != Success) {
+        g_warning("读取触发方式失败: %d", result);
+    }
+    g_message("触发方式: 按法 %d, 停止方式 %d", g_activation.mode, g_activation.stop_mode);
+    return TRUE;
+}
+
+void vinput_ibus_engine_shutdown_core(void) {
+    if (g_handle == NULL) {
+        return;
+    }
+    vinput_engine_free(g_handle);
+    g_handle = NULL;
+    g_recording = FALSE;
+    g_focused_engine = NULL;
+}
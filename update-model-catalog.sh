@@ -0,0 +1,48 @@
+#!/bin/bash
+# 更新模型目录中的 sha256 与 size_bytes
+#
+# 逐个下载 models/catalog.toml 中的压缩包，计算 SHA-256 与大小后写回目录文件。
+# 用法: ./update-model-catalog.sh [catalog.toml]
+
+set -e
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+CATALOG="${1:-$SCRIPT_DIR/models/catalog.toml}"
+WORK_DIR="$(mktemp -d)"
+trap 'rm -rf "$WORK_DIR"' EXIT
+
+echo "📋 模型目录: $CATALOG"
+
+OUTPUT="$WORK_DIR/catalog.toml"
+SHA256=""
+SIZE=""
+
+while IFS= read -r line; do
+    case "$line" in
+        url\ =\ *)
+            URL="${line#url = \"}"
+            URL="${URL%\"}"
+            ARCHIVE="$WORK_DIR/$(basename "$URL")"
+            echo "📥 下载 $URL"
+            curl -fL --retry 3 -o "$ARCHIVE" "$URL"
+            SHA256="$(sha256sum "$ARCHIVE" | cut -d' ' -f1)"
+            SIZE="$(stat -c %s "$ARCHIVE")"
+            rm -f "$ARCHIVE"
+            echo "   sha256 = $SHA256"
+            echo "   size_bytes = $SIZE"
+            echo "$line" >> "$OUTPUT"
+            ;;
+        sha256\ =\ *)
+            echo "sha256 = \"$SHA256\"" >> "$OUTPUT"
+            ;;
+        size_bytes\ =\ *)
+            echo "size_bytes = $SIZE" >> "$OUTPUT"
+            ;;
+        *)
+            echo "$line" >> "$OUTPUT"
+            ;;
+    esac
+done < "$CATALOG"
+
+cp "$OUTPUT" "$CATALOG"
+echo "✅ 已更新 $CATALOG"
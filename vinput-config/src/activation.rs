@@ -0,0 +1,66 @@
+//! 录音触发方式配置
+//!
+//! config.toml 中的 `[activation]` 段：触发键按法（按住说话 / 单击切换 / 双击开始）
+//! 与停止方式（整句上屏后自动停止 / 连续听写 / 免提听写）。触发键本身见 `[shortcuts]` 段。
+
+use serde::{Deserialize, Serialize};
+
+/// 触发键按法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivationMode {
+    /// 按住触发键录音，松开停止
+    Hold,
+    /// 按一下开始，再按一下停止
+    Toggle,
+    /// 双击触发键开始，单击停止（避免单独使用修饰键时误触）
+    DoubleTap,
+}
+
+/// 停止方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StopMode {
+    /// 端点检测到整句结束并上屏后自动停止录音
+    AutoStop,
+    /// 连续听写，直到再次按触发键
+    Continuous,
+    /// 免提听写：逐句上屏并继续监听，使用抗背景人声的 VAD 配置，
+    /// 长时间无语音后自动停止
+    HandsFree,
+}
+
+/// 录音触发方式配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActivationConfig {
+    /// 触发键按法
+    pub mode: ActivationMode,
+    /// 停止方式
+    pub stop_mode: StopMode,
+    /// 双击判定间隔（毫秒，仅 DoubleTap）
+    pub double_tap_interval_ms: u32,
+    /// 免提听写的静默超时（毫秒，0 表示不自动停止，仅 HandsFree）
+    pub idle_timeout_ms: u64,
+}
+
+impl Default for ActivationConfig {
+    fn default() -> Self {
+        Self {
+            mode: ActivationMode::Toggle,
+            stop_mode: StopMode::Continuous,
+            double_tap_interval_ms: 400,
+            idle_timeout_ms: 30_000,
+        }
+    }
+}
+
+impl ActivationConfig {
+    /// 整句上屏后是否自动停止录音
+    pub fn auto_stop(&self) -> bool {
+        self.stop_mode == StopMode::AutoStop
+    }
+
+    /// 免提听写的静默超时；非免提模式或未启用时为 None
+    pub fn idle_timeout_ms(&self) -> Option<u64> {
+        (self.stop_mode == StopMode::HandsFree && self.idle_timeout_ms > 0).then_some(self.idle_timeout_ms)
+    }
+}
@@ -0,0 +1,59 @@
+//! ASR 配置
+//!
+//! sherpa-onnx 在线识别器参数（config.toml 中的 `[asr]` 段）
+
+use serde::{Deserialize, Serialize};
+
+/// 在线识别器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnlineRecognizerConfig {
+    /// 模型目录路径
+    pub model_dir: String,
+    /// 采样率 (Hz)
+    #[serde(default = "default_sample_rate")]
+    pub sample_rate: i32,
+    /// 特征维度
+    #[serde(default = "default_feat_dim")]
+    pub feat_dim: i32,
+    /// 解码方法 ("greedy_search" 或 "modified_beam_search")
+    #[serde(default = "default_decoding_method")]
+    pub decoding_method: String,
+    /// 最大活跃路径数
+    #[serde(default = "default_max_active_paths")]
+    pub max_active_paths: i32,
+    /// 推理线程数（传给 sherpa-onnx；解码运行在独立的 ASR 线程，多核机器可适当调高）
+    #[serde(default = "default_num_threads")]
+    pub num_threads: i32,
+    /// 热词文件路径（可选）
+    #[serde(default)]
+    pub hotwords_file: Option<String>,
+    /// 热词得分
+    #[serde(default = "default_hotwords_score")]
+    pub hotwords_score: f32,
+}
+
+// 默认值函数
+fn default_sample_rate() -> i32 { 16000 }
+fn default_feat_dim() -> i32 { 80 }
+fn default_decoding_method() -> String { "greedy_search".to_string() }
+fn default_max_active_paths() -> i32 { 2 }  // 降低到 2 以减少 CPU 占用（原来是 4）
+fn default_hotwords_score() -> f32 { 1.5 }
+fn default_num_threads() -> i32 { 1 }  // 1 线程 CPU 占用最低
+
+/// 支持的解码方法
+pub const DECODING_METHODS: [&str; 2] = ["greedy_search", "modified_beam_search"];
+
+impl Default for OnlineRecognizerConfig {
+    fn default() -> Self {
+        Self {
+            model_dir: String::new(),
+            sample_rate: 16000,
+            feat_dim: 80,
+            decoding_method: "greedy_search".to_string(),
+            max_active_paths: 2,  // 与 serde default 保持一致
+            num_threads: 1,
+            hotwords_file: None,
+            hotwords_score: 1.5,
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! 音频配置
+//!
+//! config.toml 中的 `[audio]` 段（捕获格式、输入源、电平表）与 `[enhancement]` 段（语音增强）
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 音频格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    /// 32-bit float, little-endian
+    F32LE,
+    /// 16-bit signed integer, little-endian
+    S16LE,
+}
+
+impl AudioFormat {
+    /// 每个样本的字节数
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            AudioFormat::F32LE => 4,
+            AudioFormat::S16LE => 2,
+        }
+    }
+}
+
+/// 多声道下混方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelMix {
+    /// 所有声道取平均（默认）
+    #[default]
+    Average,
+    /// 只取指定声道（从 0 开始，越界时回退到最后一个声道）
+    Select(u32),
+}
+
+/// 重采样质量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResampleQuality {
+    /// 16 抽头，低 CPU
+    Fast,
+    /// 32 抽头（默认）
+    #[default]
+    Balanced,
+    /// 64 抽头，阻带衰减最高
+    High,
+}
+
+/// 音频输入源配置（config.toml 中的 `[audio.source]` 段）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AudioSourceConfig {
+    /// PipeWire 麦克风（默认）
+    #[default]
+    #[serde(rename = "pipewire")]
+    PipeWire,
+    /// WAV 文件回放
+    WavFile {
+        /// 文件路径
+        path: PathBuf,
+        /// 按实时速度播放（false = 尽可能快，受 Ring Buffer 背压限制）
+        #[serde(default = "default_true")]
+        realtime: bool,
+        /// 播放结束后追加的静音时长（ms），便于触发端点检测
+        #[serde(default = "default_trailing_silence_ms")]
+        trailing_silence_ms: u64,
+    },
+    /// stdin 原始 PCM
+    Stdin {
+        #[serde(flatten)]
+        pcm: RawPcmFormat,
+    },
+    /// Unix socket 原始 PCM（逐个接受连接，每个连接发送一段音频流）
+    UnixSocket {
+        /// socket 路径
+        path: PathBuf,
+        #[serde(flatten)]
+        pcm: RawPcmFormat,
+    },
+}
+
+/// 原始 PCM 流格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RawPcmFormat {
+    /// 样本格式
+    pub format: AudioFormat,
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u32,
+}
+
+impl Default for RawPcmFormat {
+    fn default() -> Self {
+        Self {
+            format: AudioFormat::S16LE,
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_trailing_silence_ms() -> u64 {
+    1000
+}
+
+/// 电平表配置（config.toml 中的 `[audio.level_meter]` 段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelMeterConfig {
+    /// 启用电平表与诊断
+    pub enabled: bool,
+    /// 电平上报间隔 (ms)
+    pub report_interval_ms: u64,
+    /// 削波判定幅度（|sample| ≥ 该值视为削波）
+    pub clip_level: f32,
+    /// 削波指示保持时间 (ms)
+    pub clip_hold_ms: u64,
+    /// 低于该电平 (dBFS) 视为无信号
+    pub silence_dbfs: f32,
+    /// 连续无信号多少秒后警告
+    pub no_signal_secs: f32,
+    /// 说话（明显高于环境噪声的输入）始终低于该电平 (dBFS) 时视为增益过低；
+    /// Energy Gate 阈值更高时以阈值为准
+    pub low_gain_dbfs: f32,
+    /// 累计多少秒这样的弱语音后警告增益过低
+    pub low_gain_secs: f32,
+}
+
+impl Default for LevelMeterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            report_interval_ms: 100,
+            clip_level: 0.99,
+            clip_hold_ms: 1000,
+            silence_dbfs: -70.0,
+            no_signal_secs: 3.0,
+            low_gain_dbfs: -45.0,
+            low_gain_secs: 5.0,
+        }
+    }
+}
+
+/// 分阶段运行时的队列配置（config.toml 中的 `[audio.queue]` 段）
+///
+/// 录音时捕获、VAD、ASR 解码分别运行在独立线程，经两个有界队列传递音频；
+/// 队列使用率超过背压阈值时上游阶段暂停读取，积压最终只在麦克风输入处丢弃
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioQueueConfig {
+    /// 捕获到 VAD 的队列大小（采样点数）
+    pub capture_to_vad_capacity: usize,
+    /// VAD 到 ASR 的队列大小（采样点数）
+    pub vad_to_asr_capacity: usize,
+    /// 背压阈值（队列使用率百分比，0-100）
+    pub backpressure_threshold: u8,
+}
+
+impl Default for AudioQueueConfig {
+    fn default() -> Self {
+        Self {
+            // 捕获到 VAD：1 秒缓冲 @ 16kHz
+            capture_to_vad_capacity: 16000,
+            // VAD 到 ASR：2 秒缓冲 @ 16kHz（吸收解码耗时波动）
+            vad_to_asr_capacity: 32000,
+            // 背压阈值：80%
+            backpressure_threshold: 80,
+        }
+    }
+}
+
+/// 音频捕获配置（config.toml 中的 `[audio]` 段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioCaptureConfig {
+    /// 音频输入源（默认 PipeWire 麦克风）
+    pub source: AudioSourceConfig,
+    /// 首选设备列表（设备名称或 ID，按优先级排列；为空时使用默认音频源）
+    pub preferred_devices: Vec<String>,
+    /// 设备热插拔检测间隔 (ms)
+    pub device_poll_interval_ms: u64,
+    /// 按设备原生格式捕获，由 Rust 侧重采样（false 时交给 PipeWire 转换）
+    pub native_capture: bool,
+    /// 多声道下混方式：`"average"` 或 `{ select = 0 }`
+    pub channel_mix: ChannelMix,
+    /// 重采样质量：Fast / Balanced / High
+    pub resample_quality: ResampleQuality,
+    /// 电平表与输入诊断
+    pub level_meter: LevelMeterConfig,
+    /// 捕获 → VAD → ASR 队列
+    pub queue: AudioQueueConfig,
+}
+
+impl Default for AudioCaptureConfig {
+    fn default() -> Self {
+        Self {
+            source: AudioSourceConfig::default(),
+            preferred_devices: Vec::new(),
+            device_poll_interval_ms: 1000,
+            native_capture: true,
+            channel_mix: ChannelMix::default(),
+            resample_quality: ResampleQuality::default(),
+            level_meter: LevelMeterConfig::default(),
+            queue: AudioQueueConfig::default(),
+        }
+    }
+}
+
+/// 增强算法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EnhancementMethod {
+    /// 谱减法：简单、低 CPU，残留"音乐噪声"较多
+    SpectralSubtraction,
+    /// 维纳滤波（判决引导法）：噪声残留更平滑，推荐
+    #[default]
+    Wiener,
+    /// ONNX 增益模型（需要 `denoise-onnx` feature，否则回退到维纳滤波）
+    Onnx,
+}
+
+/// 语音增强配置（config.toml 中的 `[enhancement]` 段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnhancementConfig {
+    /// 是否启用语音增强
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 增强算法
+    #[serde(default)]
+    pub method: EnhancementMethod,
+
+    /// 谱减过减因子（仅谱减法）
+    #[serde(default = "default_over_subtraction")]
+    pub over_subtraction: f32,
+
+    /// 频谱增益下限（0.0-1.0，越大噪声残留越多、语音失真越少）
+    #[serde(default = "default_spectral_floor")]
+    pub spectral_floor: f32,
+
+    /// 用于初始噪声估计的跳步数（1 跳步 = 16ms）
+    #[serde(default = "default_noise_init_frames")]
+    pub noise_init_frames: usize,
+
+    /// 噪声谱更新平滑系数
+    #[serde(default = "default_noise_update_alpha")]
+    pub noise_update_alpha: f32,
+
+    /// ONNX 增益模型路径（仅 Onnx 算法）
+    #[serde(default)]
+    pub model_path: Option<String>,
+
+    /// 调试：增强前后音频转储目录（None = 不转储）
+    #[serde(default)]
+    pub dump_dir: Option<String>,
+}
+
+fn default_over_subtraction() -> f32 { 2.0 }
+fn default_spectral_floor() -> f32 { 0.1 }
+fn default_noise_init_frames() -> usize { 10 }  // 10 × 16ms = 160ms
+fn default_noise_update_alpha() -> f32 { 0.98 }
+
+impl Default for EnhancementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            method: EnhancementMethod::Wiener,
+            over_subtraction: 2.0,
+            spectral_floor: 0.1,
+            noise_init_frames: 10,
+            noise_update_alpha: 0.98,
+            model_path: None,
+            dump_dir: None,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! 语音取消
+//!
+//! 一句话的最后一个分句是取消口令（如 "取消"、"算了"）时，整句丢弃：
+//! 不上屏、不记入历史，已流式上屏的部分由前端删除。
+//!
+//! 只比较最后一个分句（以标点或停顿逗号分隔），避免 "这件事就算了"
+//! 这类正常句子被误判为取消。
+
+use serde::{Deserialize, Serialize};
+
+/// 语音取消配置（config.toml 中的 `[cancel]` 段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CancelConfig {
+    /// 启用语音取消口令
+    pub enabled: bool,
+    /// 取消口令
+    pub phrases: Vec<String>,
+}
+
+impl Default for CancelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            phrases: vec!["取消".to_string(), "算了".to_string()],
+        }
+    }
+}
+
+/// 分句分隔符
+fn is_clause_separator(c: char) -> bool {
+    matches!(c, '，' | '。' | '！' | '？' | '、' | '；' | '：' | ',' | '.' | '!' | '?' | ';' | ':')
+}
+
+impl CancelConfig {
+    /// 识别结果（含标点）是否以取消口令结束
+    pub fn is_cancel(&self, text: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        let trimmed = text.trim_end_matches(|c: char| is_clause_separator(c) || c.is_whitespace());
+        let last_clause = trimmed
+            .rsplit(is_clause_separator)
+            .next()
+            .unwrap_or("")
+            .trim();
+        if last_clause.is_empty() {
+            return false;
+        }
+
+        self.phrases.iter().any(|phrase| phrase.trim() == last_clause)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_phrase_detection() {
+        let config = CancelConfig::default();
+        assert!(config.is_cancel("取消。"));
+        assert!(config.is_cancel("算了"));
+        assert!(config.is_cancel("明天下午三点开会，算了。"));
+        assert!(!config.is_cancel("这件事就算了。"));
+        assert!(!config.is_cancel("取消明天的会议。"));
+        assert!(!config.is_cancel("。"));
+    }
+
+    #[test]
+    fn test_cancel_disabled() {
+        let config = CancelConfig {
+            enabled: false,
+            ..CancelConfig::default()
+        };
+        assert!(!config.is_cancel("取消。"));
+    }
+}
@@ -0,0 +1,214 @@
+//! 热词配置（config.toml 中的 `[hotwords]` 段）
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// 热词分组（如「项目名称」「同事姓名」，可整组启用 / 停用）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HotwordGroup {
+    /// 分组名
+    pub name: String,
+    /// 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 热词列表 (词汇 → 权重)
+    #[serde(default)]
+    pub words: HashMap<String, f32>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl HotwordGroup {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            enabled: true,
+            words: HashMap::new(),
+        }
+    }
+}
+
+/// 热词自动学习配置（config.toml 中的 `[hotwords.learning]` 段）
+///
+/// 撤销后重新输入、从候选列表替换或手动「加为热词」的词记入单独的学习文件，
+/// 权重按半衰期衰减，加载时与配置中的热词合并（配置中的权重优先）。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotwordLearningConfig {
+    /// 启用自动学习
+    pub enabled: bool,
+    /// 学习文件路径（为空时使用 `~/.local/share/vinput/learned_hotwords.toml`）
+    pub file: String,
+    /// 纠错学到的词的初始建议权重
+    pub correction_weight: f32,
+    /// 手动「加为热词」的初始权重
+    pub explicit_weight: f32,
+    /// 每次重复纠错增加的权重
+    pub reinforce_step: f32,
+    /// 纠错学到的词出现该次数后才加载（手动添加的词立即加载）
+    pub min_hits: u32,
+    /// 权重衰减半衰期（天）
+    pub half_life_days: f32,
+    /// 撤销后多长时间内重新输入的文本视为纠错（秒）
+    pub correction_window_secs: u64,
+    /// 学习文件最多保留的词数
+    pub max_entries: usize,
+}
+
+impl Default for HotwordLearningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            file: String::new(),
+            correction_weight: 2.0,
+            explicit_weight: 3.0,
+            reinforce_step: 0.5,
+            min_hits: 2,
+            half_life_days: 30.0,
+            correction_window_secs: 120,
+            max_entries: 500,
+        }
+    }
+}
+
+impl HotwordLearningConfig {
+    /// 学习文件路径（无法获取数据目录时为 None）
+    pub fn file_path(&self) -> Option<PathBuf> {
+        if !self.file.trim().is_empty() {
+            return Some(PathBuf::from(self.file.trim()));
+        }
+        dirs::data_dir().map(|dir| dir.join("vinput").join("learned_hotwords.toml"))
+    }
+}
+
+/// 热词配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotwordsConfig {
+    /// 未分组热词 (词汇 → 权重)，始终启用
+    pub words: HashMap<String, f32>,
+    /// 热词分组
+    #[serde(default)]
+    pub groups: Vec<HotwordGroup>,
+    /// 全局权重
+    pub global_weight: f32,
+    /// 最大热词数
+    pub max_words: usize,
+    /// 自动学习
+    #[serde(default)]
+    pub learning: HotwordLearningConfig,
+}
+
+impl Default for HotwordsConfig {
+    fn default() -> Self {
+        Self {
+            words: HashMap::new(),
+            groups: Vec::new(),
+            global_weight: 2.5,
+            max_words: 10000,
+            learning: HotwordLearningConfig::default(),
+        }
+    }
+}
+
+impl HotwordsConfig {
+    /// 实际生效的热词：未分组热词加上已启用分组的热词
+    ///
+    /// 同一个词出现在多处时取最大权重。
+    pub fn active_words(&self) -> HashMap<String, f32> {
+        let mut active = self.words.clone();
+        for group in self.groups.iter().filter(|g| g.enabled) {
+            for (word, &weight) in &group.words {
+                active
+                    .entry(word.clone())
+                    .and_modify(|w| *w = w.max(weight))
+                    .or_insert(weight);
+            }
+        }
+        active
+    }
+
+    /// 按名称查找分组
+    pub fn group(&self, name: &str) -> Option<&HotwordGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    /// 指定分组的热词表（`None` 为未分组热词）
+    pub fn words_mut(&mut self, group: Option<&str>) -> Option<&mut HashMap<String, f32>> {
+        match group {
+            None => Some(&mut self.words),
+            Some(name) => self.groups.iter_mut().find(|g| g.name == name).map(|g| &mut g.words),
+        }
+    }
+
+    /// 热词所在的位置（分组名，`None` 为未分组）及权重
+    pub fn locate(&self, word: &str) -> Vec<(Option<&str>, f32)> {
+        let ungrouped = self.words.get(word).map(|&w| (None, w));
+        let grouped = self
+            .groups
+            .iter()
+            .filter_map(|g| g.words.get(word).map(|&w| (Some(g.name.as_str()), w)));
+        ungrouped.into_iter().chain(grouped).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> HotwordsConfig {
+        let mut projects = HotwordGroup::new("项目");
+        projects.words.insert("深度操作系统".to_string(), 3.0);
+        projects.words.insert("深度学习".to_string(), 4.0);
+        let mut names = HotwordGroup::new("同事");
+        names.enabled = false;
+        names.words.insert("张三".to_string(), 3.0);
+
+        let mut config = HotwordsConfig::default();
+        config.words.insert("深度学习".to_string(), 2.5);
+        config.groups = vec![projects, names];
+        config
+    }
+
+    #[test]
+    fn test_active_words_skips_disabled_groups() {
+        let active = config().active_words();
+        assert_eq!(active.len(), 2);
+        assert_eq!(active["深度学习"], 4.0);
+        assert!(!active.contains_key("张三"));
+    }
+
+    #[test]
+    fn test_locate_and_words_mut() {
+        let mut config = config();
+        assert_eq!(config.locate("深度学习"), vec![(None, 2.5), (Some("项目"), 4.0)]);
+        assert!(config.locate("李四").is_empty());
+
+        config.words_mut(Some("同事")).unwrap().insert("李四".to_string(), 2.0);
+        assert_eq!(config.locate("李四"), vec![(Some("同事"), 2.0)]);
+        assert!(config.words_mut(Some("不存在")).is_none());
+    }
+
+    #[test]
+    fn test_groups_default_when_missing() {
+        let config: HotwordsConfig = toml::from_str(
+            r#"
+global_weight = 2.5
+max_words = 100
+
+[words]
+"深度学习" = 3.0
+
+[[groups]]
+name = "同事"
+words = { "张三" = 3.0 }
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.groups.len(), 1);
+        assert!(config.groups[0].enabled);
+        assert_eq!(config.active_words().len(), 2);
+    }
+}
@@ -0,0 +1,143 @@
+//! 唤醒词配置（config.toml 中的 `[wake_word]` 段）
+
+use serde::{Deserialize, Serialize};
+
+/// 唤醒词
+///
+/// sherpa-onnx 不在运行时做分词，`tokens` 需按模型建模单元预先切分
+/// （中文 KWS 模型为声母 / 带调韵母，如 `k āi sh ǐ t īng x iě`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WakeKeyword {
+    /// 显示文本（检测到时作为命令文本返回）
+    pub phrase: String,
+    /// 模型 token 序列（空格分隔）
+    pub tokens: String,
+    /// 单独的加分（None 使用全局 keywords_score）
+    #[serde(default)]
+    pub boost: Option<f32>,
+    /// 单独的触发阈值（None 使用全局 keywords_threshold）
+    #[serde(default)]
+    pub threshold: Option<f32>,
+}
+
+impl WakeKeyword {
+    /// 转为 sherpa-onnx 关键词行：`tokens :boost #threshold @phrase`
+    pub fn to_keyword_line(&self) -> Option<String> {
+        let tokens = self.tokens.split_whitespace().collect::<Vec<_>>().join(" ");
+        let phrase = self.phrase.trim();
+        if tokens.is_empty() || phrase.is_empty() || phrase.contains(char::is_whitespace) {
+            return None;
+        }
+
+        let mut line = tokens;
+        if let Some(boost) = self.boost {
+            line.push_str(&format!(" :{}", boost));
+        }
+        if let Some(threshold) = self.threshold {
+            line.push_str(&format!(" #{}", threshold));
+        }
+        line.push_str(&format!(" @{}", phrase));
+        Some(line)
+    }
+}
+
+/// 唤醒词配置（config.toml 中的 `[wake_word]` 段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeWordConfig {
+    /// 启用唤醒词（未录音时持续监听麦克风）
+    pub enabled: bool,
+    /// KWS 模型目录（encoder / decoder / joiner 的 int8 模型与 tokens.txt）
+    pub model_dir: String,
+    /// 唤醒词列表（为空时使用模型目录下的 keywords.txt）
+    pub keywords: Vec<WakeKeyword>,
+    /// 关键词加分（越大越容易触发）
+    pub keywords_score: f32,
+    /// 触发阈值（越大越不容易误触发）
+    pub keywords_threshold: f32,
+    /// 关键词末尾需要的 blank 数
+    pub num_trailing_blanks: i32,
+    /// 能量门限倍数（RMS 超过噪声基线该倍数才送入 KWS，降低空闲 CPU 占用）
+    pub gate_noise_multiplier: f32,
+}
+
+impl Default for WakeWordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model_dir: "/usr/share/droplet-voice-input/models/kws".to_string(),
+            keywords: vec![
+                WakeKeyword {
+                    phrase: "小V小V".to_string(),
+                    tokens: "x iǎo w ēi x iǎo w ēi".to_string(),
+                    boost: None,
+                    threshold: None,
+                },
+                WakeKeyword {
+                    phrase: "开始听写".to_string(),
+                    tokens: "k āi sh ǐ t īng x iě".to_string(),
+                    boost: None,
+                    threshold: None,
+                },
+            ],
+            keywords_score: 1.0,
+            keywords_threshold: 0.25,
+            num_trailing_blanks: 1,
+            gate_noise_multiplier: 3.0,
+        }
+    }
+}
+
+impl WakeWordConfig {
+    /// 生成 sherpa-onnx 关键词缓冲区（每行一个关键词，跳过无效项）
+    pub fn keywords_buffer(&self) -> String {
+        self.keywords
+            .iter()
+            .filter_map(|keyword| {
+                let line = keyword.to_keyword_line();
+                if line.is_none() {
+                    tracing::warn!("忽略无效唤醒词: {:?}", keyword);
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_line_format() {
+        let keyword = WakeKeyword {
+            phrase: "开始听写".to_string(),
+            tokens: "k āi  sh ǐ t īng x iě".to_string(),
+            boost: Some(1.5),
+            threshold: Some(0.3),
+        };
+        assert_eq!(
+            keyword.to_keyword_line().as_deref(),
+            Some("k āi sh ǐ t īng x iě :1.5 #0.3 @开始听写")
+        );
+
+        let invalid = WakeKeyword {
+            phrase: "小V".to_string(),
+            tokens: "  ".to_string(),
+            boost: None,
+            threshold: None,
+        };
+        assert_eq!(invalid.to_keyword_line(), None);
+    }
+
+    #[test]
+    fn test_default_keywords_buffer() {
+        let buffer = WakeWordConfig::default().keywords_buffer();
+        let lines: Vec<&str> = buffer.lines().collect();
+        assert_eq!(lines, vec![
+            "x iǎo w ēi x iǎo w ēi @小V小V",
+            "k āi sh ǐ t īng x iě @开始听写",
+        ]);
+    }
+}
@@ -0,0 +1,382 @@
+//! V-Input 配置结构
+//!
+//! vinput-core 与设置界面（vinput-gui）共用的 `~/.config/vinput/config.toml` 结构：
+//! - 各段配置类型（核心库在原模块路径重新导出）
+//! - 文件顶层 `schema_version` 与按版本顺序执行的迁移（`migrate`）
+//! - 字段级校验（`validate`），设置界面只写出校验通过的配置
+
+pub mod activation;
+pub mod asr;
+pub mod audio;
+pub mod cancel;
+pub mod endpoint;
+pub mod hotwords;
+pub mod kws;
+pub mod metrics;
+pub mod migrate;
+pub mod punctuation;
+pub mod recorder;
+pub mod recovery;
+pub mod shortcuts;
+pub mod streaming;
+pub mod vad;
+pub mod validate;
+
+pub use activation::{ActivationConfig, ActivationMode, StopMode};
+pub use asr::OnlineRecognizerConfig;
+pub use audio::{
+    AudioCaptureConfig, AudioFormat, AudioQueueConfig, AudioSourceConfig, ChannelMix,
+    EnhancementConfig, EnhancementMethod, LevelMeterConfig, RawPcmFormat, ResampleQuality,
+};
+pub use cancel::CancelConfig;
+pub use endpoint::EndpointDetectorConfig;
+pub use hotwords::{HotwordGroup, HotwordLearningConfig, HotwordsConfig};
+pub use kws::{WakeKeyword, WakeWordConfig};
+pub use metrics::MetricsConfig;
+pub use migrate::CURRENT_SCHEMA_VERSION;
+pub use punctuation::StyleProfile;
+pub use recorder::RecorderConfig;
+pub use recovery::RecoveryConfig;
+pub use shortcuts::{KeyCombo, ShortcutAction, ShortcutConfig};
+pub use streaming::{HypothesisVariantsConfig, StreamingCommitConfig};
+pub use vad::VadConfig;
+pub use validate::FieldError;
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// 标点配置
+pub type PunctuationConfig = StyleProfile;
+
+/// 配置读写错误
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("无法获取配置目录")]
+    NoConfigDir,
+
+    #[error("读写配置文件失败: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("配置文件格式错误: {0}")]
+    Parse(#[from] toml::de::Error),
+
+    #[error("配置序列化失败: {0}")]
+    Serialize(#[from] toml::ser::Error),
+
+    #[error("配置文件版本 {found} 高于当前支持的版本 {supported}，请升级 V-Input")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("配置迁移失败（v{from} → v{}）: {message}", .from + 1)]
+    Migration { from: u32, message: String },
+
+    #[error("配置校验失败: {}", validate::join_errors(.0))]
+    Invalid(Vec<FieldError>),
+}
+
+/// V-Input 完整配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VInputConfig {
+    /// 配置文件格式版本（旧文件缺省为 0，加载时迁移到 `CURRENT_SCHEMA_VERSION`）
+    pub schema_version: u32,
+    /// VAD 配置
+    pub vad: VadConfig,
+    /// ASR 配置
+    pub asr: OnlineRecognizerConfig,
+    /// 标点配置
+    pub punctuation: PunctuationConfig,
+    /// 热词配置
+    pub hotwords: HotwordsConfig,
+    /// 端点检测配置（自动断句上屏）
+    pub endpoint: EndpointDetectorConfig,
+    /// 语音增强（降噪）配置
+    pub enhancement: EnhancementConfig,
+    /// 音频捕获配置（原生格式捕获、下混、重采样）
+    pub audio: AudioCaptureConfig,
+    /// 长句流式上屏配置
+    pub streaming_commit: StreamingCommitConfig,
+    /// 部分假设变体（上屏后的候选列表）配置
+    pub hypothesis_variants: HypothesisVariantsConfig,
+    /// 前端快捷键配置（各输入法前端共用）
+    pub shortcuts: ShortcutConfig,
+    /// 录音触发方式（按住 / 切换 / 双击，自动停止 / 连续听写 / 免提听写）
+    pub activation: ActivationConfig,
+    /// 免提听写使用的 VAD 配置（麦克风常开，需拒绝背景人声）
+    ///
+    /// 文件中只写出部分字段时，其余字段取免提默认值（而不是按键说话的默认值）
+    #[serde(
+        default = "VadConfig::hands_free_default",
+        deserialize_with = "vad::deserialize_hands_free"
+    )]
+    pub hands_free_vad: VadConfig,
+    /// 唤醒词（未录音时监听，检测到后开始录音）
+    pub wake_word: WakeWordConfig,
+    /// 语音取消口令（整句以口令结束时丢弃）
+    pub cancel: CancelConfig,
+    /// 录音会话出错后的重试 / 降级 / 重建管道策略
+    pub recovery: RecoveryConfig,
+    /// 性能指标（Prometheus 导出）
+    pub metrics: MetricsConfig,
+    /// 会话录制（问题复现用）
+    pub recorder: RecorderConfig,
+}
+
+impl Default for VInputConfig {
+    fn default() -> Self {
+        // 默认模型路径优先级：
+        // 1. 环境变量 VINPUT_MODEL_DIR
+        // 2. 系统安装路径 /usr/share/droplet-voice-input/models
+        // 3. 开发路径 ./models/streaming
+        let default_model_dir = std::env::var("VINPUT_MODEL_DIR")
+            .unwrap_or_else(|_| {
+                // 检查系统安装路径
+                let system_path = "/usr/share/droplet-voice-input/models";
+                if std::path::Path::new(system_path).exists() {
+                    system_path.to_string()
+                } else {
+                    // 开发环境路径
+                    "./models/streaming".to_string()
+                }
+            });
+
+        let asr_config = OnlineRecognizerConfig {
+            model_dir: default_model_dir,
+            ..OnlineRecognizerConfig::default()
+        };
+
+        Self {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            vad: VadConfig::push_to_talk_default(),
+            asr: asr_config,
+            punctuation: PunctuationConfig::default(),
+            hotwords: HotwordsConfig::default(),
+            endpoint: EndpointDetectorConfig::default(),
+            enhancement: EnhancementConfig::default(),
+            audio: AudioCaptureConfig::default(),
+            streaming_commit: StreamingCommitConfig::default(),
+            hypothesis_variants: HypothesisVariantsConfig::default(),
+            shortcuts: ShortcutConfig::default(),
+            activation: ActivationConfig::default(),
+            hands_free_vad: VadConfig::hands_free_default(),
+            wake_word: WakeWordConfig::default(),
+            cancel: CancelConfig::default(),
+            recovery: RecoveryConfig::default(),
+            metrics: MetricsConfig::default(),
+            recorder: RecorderConfig::default(),
+        }
+    }
+}
+
+impl VInputConfig {
+    /// 加载配置文件
+    pub fn load() -> Result<Self, ConfigError> {
+        Self::load_from(&Self::config_path()?)
+    }
+
+    /// 从指定路径加载配置文件（文件不存在时使用默认配置）
+    ///
+    /// 旧版本文件只在内存中迁移，不回写；校验失败的字段仅记录警告
+    pub fn load_from(config_path: &Path) -> Result<Self, ConfigError> {
+        if !config_path.exists() {
+            tracing::info!("配置文件不存在，使用默认配置: {:?}", config_path);
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(config_path)?;
+        let (config, migrated_from) = Self::parse(&content)?;
+        if let Some(version) = migrated_from {
+            tracing::info!("配置文件为旧版本 v{}，已按 v{} 读取", version, CURRENT_SCHEMA_VERSION);
+        }
+        if let Err(errors) = config.validate() {
+            for error in &errors {
+                tracing::warn!("配置项无效: {}", error);
+            }
+        }
+
+        tracing::info!("📋 加载配置成功: {:?}", config_path);
+        tracing::info!("📊 标点配置: pause_ratio={}, min_tokens={}, allow_exclamation={}",
+            config.punctuation.streaming_pause_ratio,
+            config.punctuation.streaming_min_tokens,
+            config.punctuation.allow_exclamation
+        );
+        tracing::info!("🎯 端点检测: trailing_silence={}ms, min_speech={}ms, vad_frames={}",
+            config.endpoint.trailing_silence_ms,
+            config.endpoint.min_speech_duration_ms,
+            config.endpoint.vad_silence_confirm_frames
+        );
+        Ok(config)
+    }
+
+    /// 解析配置文本：先迁移到当前版本再反序列化，同时返回迁移前的版本（无需迁移时为 None）
+    pub fn parse(content: &str) -> Result<(Self, Option<u32>), ConfigError> {
+        let mut table: toml::Table = content.parse()?;
+        let migrated_from = migrate::migrate(&mut table)?;
+        let config: Self = toml::Value::Table(table).try_into()?;
+        Ok((config, migrated_from))
+    }
+
+    /// 序列化为 TOML 文本（先校验，校验失败时不输出）
+    pub fn to_toml_string(&self) -> Result<String, ConfigError> {
+        self.validate().map_err(ConfigError::Invalid)?;
+
+        let mut config = self.clone();
+        config.schema_version = CURRENT_SCHEMA_VERSION;
+        Ok(toml::to_string_pretty(&config)?)
+    }
+
+    /// 保存配置文件
+    pub fn save(&self) -> Result<(), ConfigError> {
+        self.save_to(&Self::config_path()?)
+    }
+
+    /// 保存到指定路径（校验失败时不写入）
+    pub fn save_to(&self, config_path: &Path) -> Result<(), ConfigError> {
+        let content = self.to_toml_string()?;
+
+        // 确保目录存在
+        if let Some(parent) = config_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(config_path, content)?;
+
+        tracing::info!("保存配置成功: {:?}", config_path);
+        Ok(())
+    }
+
+    /// 获取配置文件路径
+    pub fn config_path() -> Result<PathBuf, ConfigError> {
+        let config_dir = dirs::config_dir().ok_or(ConfigError::NoConfigDir)?;
+        Ok(config_dir.join("vinput").join("config.toml"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 旧版设置界面写出的完整配置（无 schema_version）
+    const LEGACY_GUI_CONFIG: &str = r#"
+[basic]
+hotkey = "Ctrl+Alt+V"
+
+[hotwords]
+global_weight = 2.5
+max_words = 10000
+
+[hotwords.words]
+"深度学习" = 3.0
+
+[punctuation]
+style = "Balanced"
+pause_ratio = 3.5
+min_tokens = 4
+allow_exclamation = false
+question_strict = false
+
+[vad]
+start_threshold = 0.7
+end_threshold = 0.35
+min_speech_duration = 100
+min_silence_duration = 700
+
+[asr]
+model_dir = "/usr/share/droplet-voice-input/models"
+sample_rate = 16000
+hotwords_score = 1.5
+
+[endpoint]
+min_speech_duration_ms = 300
+max_speech_duration_ms = 30000
+trailing_silence_ms = 1000
+force_timeout_ms = 60000
+vad_assisted = true
+vad_silence_confirm_frames = 8
+
+[audio]
+preferred_devices = ["USB Microphone"]
+"#;
+
+    #[test]
+    fn test_default_config_roundtrip() {
+        let original = VInputConfig::default();
+        let toml_str = original.to_toml_string().expect("默认配置必须可序列化");
+        assert!(toml_str.starts_with("schema_version = 1"));
+
+        let (loaded, migrated_from) = VInputConfig::parse(&toml_str).expect("默认配置必须可加载");
+        assert_eq!(migrated_from, None);
+        assert_eq!(loaded.asr.model_dir, original.asr.model_dir);
+        assert_eq!(loaded.vad.hysteresis.start_threshold, original.vad.hysteresis.start_threshold);
+        assert_eq!(loaded.hands_free_vad.hysteresis.start_threshold, original.hands_free_vad.hysteresis.start_threshold);
+        assert_eq!(loaded.punctuation.style, original.punctuation.style);
+        assert_eq!(loaded.wake_word.keywords, original.wake_word.keywords);
+        assert_eq!(loaded.audio.source, original.audio.source);
+        assert_eq!(loaded.to_toml_string().unwrap(), toml_str);
+    }
+
+    #[test]
+    fn test_legacy_gui_config_migrates() {
+        let (config, migrated_from) = VInputConfig::parse(LEGACY_GUI_CONFIG).unwrap();
+        assert_eq!(migrated_from, Some(0));
+        assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+
+        // 旧版平铺的 VAD 字段
+        assert_eq!(config.vad.hysteresis.start_threshold, 0.7);
+        assert_eq!(config.vad.hysteresis.min_silence_duration_ms, 700);
+        // 旧版标点字段名
+        assert_eq!(config.punctuation.style, "Balanced");
+        assert_eq!(config.punctuation.streaming_pause_ratio, 3.5);
+        assert_eq!(config.punctuation.streaming_min_tokens, 4);
+        assert!(!config.punctuation.question_strict_mode);
+        // 旧版热键
+        assert_eq!(config.shortcuts.trigger, "Control+Alt+v");
+
+        assert_eq!(config.endpoint.vad_silence_confirm_frames, 8);
+        assert_eq!(config.audio.preferred_devices, vec!["USB Microphone".to_string()]);
+        assert!(config.validate().is_ok());
+
+        // 迁移后写出的文件不再含旧字段，且无需再次迁移
+        let toml_str = config.to_toml_string().unwrap();
+        assert!(!toml_str.contains("hotkey"));
+        assert!(!toml_str.contains("min_silence_duration ="));
+        assert_eq!(VInputConfig::parse(&toml_str).unwrap().1, None);
+    }
+
+    #[test]
+    fn test_example_config_loads() {
+        let example = include_str!("../../config.toml.example");
+        let (config, migrated_from) = VInputConfig::parse(example).expect("示例配置必须可加载");
+        assert_eq!(migrated_from, None);
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.endpoint.trailing_silence_ms, 1000);
+    }
+
+    #[test]
+    fn test_newer_schema_version_rejected() {
+        let content = format!("schema_version = {}\n", CURRENT_SCHEMA_VERSION + 1);
+        assert!(matches!(
+            VInputConfig::parse(&content),
+            Err(ConfigError::UnsupportedVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_config_not_written() {
+        let dir = std::env::temp_dir().join(format!("vinput-config-test-{}", std::process::id()));
+        let path = dir.join("config.toml");
+
+        let mut config = VInputConfig::default();
+        config.vad.hysteresis.end_threshold = 0.9;
+        match config.save_to(&path) {
+            Err(ConfigError::Invalid(errors)) => {
+                assert_eq!(errors[0].field, "vad.hysteresis.end_threshold");
+            }
+            other => panic!("应拒绝写入无效配置: {:?}", other),
+        }
+        assert!(!path.exists());
+
+        VInputConfig::default().save_to(&path).unwrap();
+        assert!(VInputConfig::load_from(&path).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,27 @@
+//! 性能指标配置（config.toml 中的 `[metrics]` 段）
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 性能指标配置
+///
+/// 指标始终在内存中统计（可通过 FFI 读取快照）；开启 `prometheus` 后
+/// 另在本地 Unix socket 上以 Prometheus 文本格式提供。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// 在本地 Unix socket 上提供 Prometheus 文本格式的指标
+    pub prometheus: bool,
+    /// socket 路径（为空时使用 `$XDG_RUNTIME_DIR/vinput/metrics.sock`）
+    pub socket_path: String,
+}
+
+impl MetricsConfig {
+    /// socket 路径（无法获取运行时目录时为 None）
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        if !self.socket_path.trim().is_empty() {
+            return Some(PathBuf::from(self.socket_path.trim()));
+        }
+        dirs::runtime_dir().map(|dir| dir.join("vinput").join("metrics.sock"))
+    }
+}
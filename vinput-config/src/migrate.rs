@@ -0,0 +1,219 @@
+//! 配置文件版本迁移
+//!
+//! 配置文件顶层的 `schema_version` 记录文件格式版本（缺省视为 0）。
+//! 加载时在 TOML 表上依次执行从文件版本到当前版本的迁移，再反序列化为
+//! `VInputConfig`；新增迁移时在 `MIGRATIONS` 末尾追加并递增 `CURRENT_SCHEMA_VERSION`。
+
+use crate::shortcuts::{legacy_hotkey_to_keysym, ShortcutConfig};
+use crate::ConfigError;
+use toml::{Table, Value};
+
+/// 当前配置文件格式版本
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// 单步迁移：把版本 N 的配置表升级到 N + 1
+type Migration = fn(&mut Table) -> Result<(), String>;
+
+/// 按版本顺序排列的迁移，第 N 项把版本 N 升级到 N + 1
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [v0_to_v1];
+
+/// 读取配置表的格式版本（缺省为 0）
+pub fn schema_version(table: &Table) -> Result<u32, ConfigError> {
+    match table.get("schema_version") {
+        None => Ok(0),
+        Some(Value::Integer(version)) => u32::try_from(*version).map_err(|_| ConfigError::Migration {
+            from: 0,
+            message: format!("schema_version 无效: {}", version),
+        }),
+        Some(other) => Err(ConfigError::Migration {
+            from: 0,
+            message: format!("schema_version 必须是整数: {}", other),
+        }),
+    }
+}
+
+/// 将配置表迁移到当前版本，返回迁移前的版本（已是当前版本时为 None）
+pub fn migrate(table: &mut Table) -> Result<Option<u32>, ConfigError> {
+    let version = schema_version(table)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(ConfigError::UnsupportedVersion {
+            found: version,
+            supported: CURRENT_SCHEMA_VERSION,
+        });
+    }
+    if version == CURRENT_SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let from = from as u32;
+        migration(table).map_err(|message| ConfigError::Migration { from, message })?;
+        tracing::debug!("配置迁移: v{} → v{}", from, from + 1);
+    }
+    table.insert(
+        "schema_version".to_string(),
+        Value::Integer(i64::from(CURRENT_SCHEMA_VERSION)),
+    );
+    Ok(Some(version))
+}
+
+/// 取出顶层的段（不存在时返回 None，存在但不是表时报错）
+fn section_mut<'a>(table: &'a mut Table, name: &str) -> Result<Option<&'a mut Table>, String> {
+    match table.get_mut(name) {
+        None => Ok(None),
+        Some(Value::Table(section)) => Ok(Some(section)),
+        Some(_) => Err(format!("[{}] 必须是表", name)),
+    }
+}
+
+/// 取出段内的子表，不存在时创建
+fn subsection_mut<'a>(section: &'a mut Table, name: &str, path: &str) -> Result<&'a mut Table, String> {
+    match section
+        .entry(name.to_string())
+        .or_insert_with(|| Value::Table(Table::new()))
+    {
+        Value::Table(subsection) => Ok(subsection),
+        _ => Err(format!("[{}] 必须是表", path)),
+    }
+}
+
+/// 字段改名：旧字段移到新字段名下（新字段已存在时以新字段为准，丢弃旧字段）
+fn rename_key(section: &mut Table, from: &str, to: &str) {
+    if let Some(value) = section.remove(from) {
+        section.entry(to.to_string()).or_insert(value);
+    }
+}
+
+/// v0 → v1：合并旧版设置界面独立维护的字段
+///
+/// - `[basic].hotkey` → `[shortcuts].trigger`（已单独配置触发键时以新配置为准）
+/// - `[vad]` 顶层的阈值与时长 → `[vad.hysteresis]`（时长字段加 `_ms` 后缀），删除无作用的 `mode`
+/// - `[punctuation]` 的 `pause_ratio` / `min_tokens` / `question_strict` → 核心字段名
+fn v0_to_v1(table: &mut Table) -> Result<(), String> {
+    if let Some(basic) = section_mut(table, "basic")? {
+        let hotkey = basic.remove("hotkey");
+        let basic_empty = basic.is_empty();
+        if basic_empty {
+            table.remove("basic");
+        }
+
+        if let Some(hotkey) = hotkey {
+            let hotkey = hotkey
+                .as_str()
+                .ok_or("[basic].hotkey 必须是字符串")?
+                .to_string();
+            let shortcuts = subsection_mut(table, "shortcuts", "shortcuts")?;
+            let default_trigger = ShortcutConfig::default().trigger;
+            let trigger_is_default = shortcuts
+                .get("trigger")
+                .is_none_or(|trigger| trigger.as_str() == Some(default_trigger.as_str()));
+            if trigger_is_default {
+                match legacy_hotkey_to_keysym(&hotkey) {
+                    Some(trigger) => {
+                        shortcuts.insert("trigger".to_string(), Value::String(trigger));
+                    }
+                    None => tracing::warn!("无法迁移旧版热键: {}", hotkey),
+                }
+            }
+        }
+    }
+
+    if let Some(vad) = section_mut(table, "vad")? {
+        vad.remove("mode");
+        let legacy_fields = [
+            ("start_threshold", "start_threshold"),
+            ("end_threshold", "end_threshold"),
+            ("min_speech_duration", "min_speech_duration_ms"),
+            ("min_silence_duration", "min_silence_duration_ms"),
+        ];
+        let moved: Vec<(&str, Value)> = legacy_fields
+            .iter()
+            .filter_map(|(from, to)| vad.remove(*from).map(|value| (*to, value)))
+            .collect();
+        if !moved.is_empty() {
+            let hysteresis = subsection_mut(vad, "hysteresis", "vad.hysteresis")?;
+            for (to, value) in moved {
+                hysteresis.entry(to.to_string()).or_insert(value);
+            }
+        }
+    }
+
+    if let Some(punctuation) = section_mut(table, "punctuation")? {
+        rename_key(punctuation, "pause_ratio", "streaming_pause_ratio");
+        rename_key(punctuation, "min_tokens", "streaming_min_tokens");
+        rename_key(punctuation, "question_strict", "question_strict_mode");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Table {
+        content.parse().unwrap()
+    }
+
+    #[test]
+    fn test_migrate_v0_moves_legacy_fields() {
+        let mut table = parse(
+            r#"
+[basic]
+hotkey = "RCtrl"
+
+[vad]
+mode = "PushToTalk"
+start_threshold = 0.7
+min_silence_duration = 700
+
+[vad.hysteresis]
+start_threshold = 0.65
+
+[punctuation]
+pause_ratio = 1.8
+question_strict = true
+"#,
+        );
+
+        assert_eq!(migrate(&mut table).unwrap(), Some(0));
+        assert_eq!(schema_version(&table).unwrap(), CURRENT_SCHEMA_VERSION);
+        assert!(!table.contains_key("basic"));
+        assert_eq!(table["shortcuts"]["trigger"].as_str(), Some("Control_R"));
+
+        let vad = table["vad"].as_table().unwrap();
+        assert!(!vad.contains_key("mode"));
+        assert!(!vad.contains_key("start_threshold"));
+        // 已有的新字段优先
+        assert_eq!(vad["hysteresis"]["start_threshold"].as_float(), Some(0.65));
+        assert_eq!(vad["hysteresis"]["min_silence_duration_ms"].as_integer(), Some(700));
+
+        let punctuation = table["punctuation"].as_table().unwrap();
+        assert_eq!(punctuation["streaming_pause_ratio"].as_float(), Some(1.8));
+        assert_eq!(punctuation["question_strict_mode"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn test_legacy_hotkey_keeps_custom_trigger() {
+        let mut table = parse("[basic]\nhotkey = \"Ctrl+Alt+V\"\n\n[shortcuts]\ntrigger = \"F9\"\n");
+        migrate(&mut table).unwrap();
+        assert_eq!(table["shortcuts"]["trigger"].as_str(), Some("F9"));
+    }
+
+    #[test]
+    fn test_current_version_untouched() {
+        let content = format!("schema_version = {}\n\n[vad]\nstart_threshold = 0.7\n", CURRENT_SCHEMA_VERSION);
+        let mut table = parse(&content);
+        assert_eq!(migrate(&mut table).unwrap(), None);
+        assert_eq!(table, parse(&content));
+    }
+
+    #[test]
+    fn test_invalid_schema_version() {
+        let mut table = parse("schema_version = \"1\"\n");
+        assert!(matches!(migrate(&mut table), Err(ConfigError::Migration { .. })));
+
+        let mut table = parse("[basic]\nhotkey = 1\n");
+        assert!(matches!(migrate(&mut table), Err(ConfigError::Migration { from: 0, .. })));
+    }
+}
@@ -0,0 +1,167 @@
+//! 标点系统配置模块
+//!
+//! 定义 StyleProfile 和标点系统参数
+
+use serde::{Deserialize, Serialize};
+
+/// 标点风格配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StyleProfile {
+    /// 风格预设名称（Professional / Balanced / Expressive / Custom，供设置界面显示）
+    #[serde(default = "default_style")]
+    pub style: String,
+
+    /// Streaming 阶段停顿比例阈值
+    #[serde(alias = "pause_ratio")]
+    pub streaming_pause_ratio: f32,
+
+    /// Streaming 阶段最小 token 数
+    #[serde(alias = "min_tokens")]
+    pub streaming_min_tokens: usize,
+
+    /// 距离上次逗号的最小 token 数
+    #[serde(default = "default_min_tokens_between_commas")]
+    pub min_tokens_between_commas: usize,
+
+    /// 最小停顿时长（毫秒）
+    #[serde(default = "default_min_pause_duration_ms")]
+    pub min_pause_duration_ms: u64,
+
+    /// 是否允许感叹号
+    pub allow_exclamation: bool,
+
+    /// 问号严格模式
+    #[serde(alias = "question_strict")]
+    pub question_strict_mode: bool,
+
+    /// 逻辑连接词插入强度 (0.0 - 2.0)
+    #[serde(default = "default_logic_word_strength")]
+    pub logic_word_strength: f32,
+
+    /// 逻辑连接词插入最小 token 数
+    #[serde(default = "default_logic_word_min_tokens")]
+    pub logic_word_min_tokens: usize,
+}
+
+// 默认值函数（用于 serde）
+fn default_style() -> String { "Professional".to_string() }
+fn default_min_tokens_between_commas() -> usize { 4 }
+fn default_min_pause_duration_ms() -> u64 { 500 }
+fn default_logic_word_strength() -> f32 { 0.8 }
+fn default_logic_word_min_tokens() -> usize { 8 }
+
+impl StyleProfile {
+    /// 从预设名称创建（忽略配置文件，仅用于测试）
+    pub fn from_preset(preset: &str) -> Self {
+        match preset {
+            "Balanced" => Self::balanced_preset(),
+            "Expressive" => Self::expressive_preset(),
+            _ => Self::professional_preset(),
+        }
+    }
+
+    /// Professional 风格（默认，推荐）- 预设值
+    ///
+    /// 特点：
+    /// - 稳重克制，适合办公、技术文档、会议记录
+    /// - 标点精简，避免过度标注
+    /// - 逻辑连接词谨慎插入
+    /// - 问号需严格匹配
+    fn professional_preset() -> Self {
+        Self {
+            style: "Professional".to_string(),
+            streaming_pause_ratio: 1.8,  // 降低阈值，更容易检测到停顿（原 2.5 太高）
+            streaming_min_tokens: 5,     // 降低最小 token 数，更早开始检测（原 6）
+            min_tokens_between_commas: 3, // 降低逗号间隔，允许更密集的逗号（原 4）
+            min_pause_duration_ms: 400,  // 降低最小停顿时长（原 500ms）
+            allow_exclamation: false,
+            question_strict_mode: true,
+            logic_word_strength: 0.8,
+            logic_word_min_tokens: 8,
+        }
+    }
+
+    /// Balanced 风格（可选）- 预设值
+    ///
+    /// 特点：
+    /// - 更自然，标点略多
+    /// - 接近人工书写习惯
+    /// - 问号检测宽松
+    fn balanced_preset() -> Self {
+        Self {
+            style: "Balanced".to_string(),
+            streaming_pause_ratio: 1.6,  // 更敏感的停顿检测（原 2.8）
+            streaming_min_tokens: 4,
+            min_tokens_between_commas: 3,
+            min_pause_duration_ms: 350,  // 更短的最小停顿（原 400ms）
+            allow_exclamation: false,
+            question_strict_mode: false,
+            logic_word_strength: 1.0,
+            logic_word_min_tokens: 6,
+        }
+    }
+
+    /// Expressive 风格（可选）- 预设值
+    ///
+    /// 特点：
+    /// - 情绪表达明显
+    /// - 接近口语化
+    /// - 允许感叹号
+    /// - 标点丰富
+    fn expressive_preset() -> Self {
+        Self {
+            style: "Expressive".to_string(),
+            streaming_pause_ratio: 1.5,  // 最敏感的停顿检测（原 2.2）
+            streaming_min_tokens: 3,
+            min_tokens_between_commas: 2,
+            min_pause_duration_ms: 300,
+            allow_exclamation: true,
+            question_strict_mode: false,
+            logic_word_strength: 1.2,
+            logic_word_min_tokens: 5,
+        }
+    }
+}
+
+impl Default for StyleProfile {
+    fn default() -> Self {
+        Self::professional_preset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_professional_profile() {
+        let profile = StyleProfile::professional_preset();
+        assert_eq!(profile.streaming_pause_ratio, 1.8);
+        assert_eq!(profile.streaming_min_tokens, 5);
+        assert!(!profile.allow_exclamation);
+        assert!(profile.question_strict_mode);
+    }
+
+    #[test]
+    fn test_balanced_profile() {
+        let profile = StyleProfile::balanced_preset();
+        assert_eq!(profile.streaming_pause_ratio, 1.6);
+        assert_eq!(profile.streaming_min_tokens, 4);
+    }
+
+    #[test]
+    fn test_expressive_profile() {
+        let profile = StyleProfile::expressive_preset();
+        assert_eq!(profile.streaming_pause_ratio, 1.5);
+        assert!(profile.allow_exclamation);
+    }
+
+    #[test]
+    fn test_default_is_professional() {
+        let default_profile = StyleProfile::default();
+        let professional = StyleProfile::professional_preset();
+
+        assert_eq!(default_profile.streaming_pause_ratio, professional.streaming_pause_ratio);
+        assert_eq!(default_profile.streaming_min_tokens, professional.streaming_min_tokens);
+    }
+}
@@ -0,0 +1,43 @@
+//! 会话录制配置（config.toml 中的 `[recorder]` 段）
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 会话录制配置
+///
+/// 开启后每次录音的原始音频、VAD 判决、部分 / 最终结果、标点决策与配置快照
+/// 写入一个会话包（`.vinput-session`），可附在问题报告中用 `replay_session` 复现。
+/// 会话包包含录音原文，默认关闭。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderConfig {
+    /// 启用会话录制
+    pub enabled: bool,
+    /// 会话包目录（为空时使用 `~/.local/share/vinput/sessions`）
+    pub dir: String,
+    /// 最多保留的会话包数（超出时删除最旧的）
+    pub max_bundles: usize,
+    /// 单个会话最多录制的音频时长（秒），超出部分不再录制
+    pub max_duration_secs: u64,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: String::new(),
+            max_bundles: 20,
+            max_duration_secs: 300,
+        }
+    }
+}
+
+impl RecorderConfig {
+    /// 会话包目录（无法获取数据目录时为 None）
+    pub fn dir_path(&self) -> Option<PathBuf> {
+        if !self.dir.trim().is_empty() {
+            return Some(PathBuf::from(self.dir.trim()));
+        }
+        dirs::data_dir().map(|dir| dir.join("vinput").join("sessions"))
+    }
+}
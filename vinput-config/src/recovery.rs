@@ -0,0 +1,28 @@
+//! 错误恢复配置（config.toml 中的 `[recovery]` 段）
+
+use serde::{Deserialize, Serialize};
+
+/// 错误恢复配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecoveryConfig {
+    /// 连续重试次数上限
+    pub max_retries: u32,
+    /// 首次重试前的等待时长（ms），之后每次翻倍
+    pub initial_backoff_ms: u64,
+    /// 重试等待时长上限（ms）
+    pub max_backoff_ms: u64,
+    /// 单次录音会话内重建管道的次数上限
+    pub max_restarts: u32,
+}
+
+impl Default for RecoveryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 200,
+            max_backoff_ms: 3000,
+            max_restarts: 2,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! 前端快捷键配置
+//!
+//! 各输入法前端共用 config.toml 中的 `[shortcuts]` 段。
+//! 按键写法为 `修饰键+键名`，如 `Control_R`、`Control+z`、`Control+Shift+F9`：
+//! 键名为 X11 keysym 名称，修饰键为 Control / Shift / Alt / Super（Ctrl、Meta 等别名会被规范化）。
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 快捷键动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    /// 开始 / 停止录音
+    Trigger,
+    /// 撤销上一次上屏
+    Undo,
+    /// 重新上屏被撤销的文本
+    Redo,
+}
+
+/// 修饰键（规范名称）
+const MODIFIERS: [&str; 4] = ["Control", "Shift", "Alt", "Super"];
+
+/// 解析后的组合键
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    /// 修饰键（规范名称，按 Control / Shift / Alt / Super 顺序）
+    pub modifiers: Vec<&'static str>,
+    /// 主键的 keysym 名称
+    pub key: String,
+}
+
+impl KeyCombo {
+    /// 解析 `修饰键+键名` 写法
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+        let (key, modifier_parts) = parts.split_last().ok_or("快捷键为空")?;
+        if key.is_empty() {
+            return Err(format!("快捷键缺少键名: '{}'", spec));
+        }
+        if key.chars().any(char::is_whitespace) {
+            return Err(format!("键名不能包含空白: '{}'", key));
+        }
+
+        let mut modifiers = Vec::new();
+        for part in modifier_parts {
+            let modifier = normalize_modifier(part)
+                .ok_or_else(|| format!("未知的修饰键: '{}'", part))?;
+            if !modifiers.contains(&modifier) {
+                modifiers.push(modifier);
+            }
+        }
+        modifiers.sort_by_key(|m| MODIFIERS.iter().position(|name| name == m));
+
+        Ok(Self {
+            modifiers,
+            key: key.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", modifier)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+fn normalize_modifier(name: &str) -> Option<&'static str> {
+    match name.to_ascii_lowercase().as_str() {
+        "control" | "ctrl" => Some("Control"),
+        "shift" => Some("Shift"),
+        "alt" | "mod1" => Some("Alt"),
+        "super" | "meta" | "win" | "mod4" => Some("Super"),
+        _ => None,
+    }
+}
+
+/// 快捷键配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutConfig {
+    /// 开始 / 停止录音
+    pub trigger: String,
+    /// 撤销上一次上屏
+    pub undo: String,
+    /// 重新上屏被撤销的文本
+    pub redo: String,
+}
+
+impl Default for ShortcutConfig {
+    fn default() -> Self {
+        Self {
+            trigger: "Control_R".to_string(),
+            undo: "Control+z".to_string(),
+            redo: "Control+y".to_string(),
+        }
+    }
+}
+
+impl ShortcutConfig {
+    /// 动作对应的原始配置值
+    pub fn get(&self, action: ShortcutAction) -> &str {
+        match action {
+            ShortcutAction::Trigger => &self.trigger,
+            ShortcutAction::Undo => &self.undo,
+            ShortcutAction::Redo => &self.redo,
+        }
+    }
+
+    /// 动作对应的组合键（配置无效时回退到默认值）
+    pub fn resolve(&self, action: ShortcutAction) -> KeyCombo {
+        KeyCombo::parse(self.get(action)).unwrap_or_else(|e| {
+            tracing::warn!("快捷键配置无效 ({:?}): {}，使用默认值", action, e);
+            KeyCombo::parse(Self::default().get(action)).expect("默认快捷键必须有效")
+        })
+    }
+}
+
+/// 旧版 `[basic].hotkey` 写法（如 `RCtrl`、`Ctrl+Alt+V`）转为 keysym 写法
+pub fn legacy_hotkey_to_keysym(hotkey: &str) -> Option<String> {
+    let converted = match hotkey.trim() {
+        "RCtrl" => "Control_R".to_string(),
+        "LCtrl" => "Control_L".to_string(),
+        "Ctrl" => "Control_L".to_string(),
+        "Alt" => "Alt_L".to_string(),
+        "Shift" => "Shift_L".to_string(),
+        other => other.replace("Space", "space"),
+    };
+    let mut combo = KeyCombo::parse(&converted).ok()?;
+    // 单字符键名（字母）统一小写，与 keysym 名称一致
+    if combo.key.chars().count() == 1 {
+        combo.key = combo.key.to_lowercase();
+    }
+    Some(combo.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key_combo() {
+        let combo = KeyCombo::parse("Control_R").unwrap();
+        assert!(combo.modifiers.is_empty());
+        assert_eq!(combo.key, "Control_R");
+
+        let combo = KeyCombo::parse("shift + ctrl+F9").unwrap();
+        assert_eq!(combo.modifiers, vec!["Control", "Shift"]);
+        assert_eq!(combo.to_string(), "Control+Shift+F9");
+    }
+
+    #[test]
+    fn test_parse_key_combo_rejects_invalid() {
+        assert!(KeyCombo::parse("").is_err());
+        assert!(KeyCombo::parse("Control+").is_err());
+        assert!(KeyCombo::parse("Hyper+z").is_err());
+        assert!(KeyCombo::parse("Control+page up").is_err());
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_default() {
+        let config = ShortcutConfig {
+            trigger: "F9".to_string(),
+            undo: "Foo+z".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.resolve(ShortcutAction::Trigger).to_string(), "F9");
+        assert_eq!(config.resolve(ShortcutAction::Undo).to_string(), "Control+z");
+        assert_eq!(config.resolve(ShortcutAction::Redo).to_string(), "Control+y");
+    }
+
+    #[test]
+    fn test_legacy_hotkey_to_keysym() {
+        assert_eq!(legacy_hotkey_to_keysym("RCtrl").as_deref(), Some("Control_R"));
+        assert_eq!(legacy_hotkey_to_keysym("Ctrl+Alt+V").as_deref(), Some("Control+Alt+v"));
+        assert_eq!(legacy_hotkey_to_keysym("Ctrl+Space").as_deref(), Some("Control+space"));
+        assert_eq!(legacy_hotkey_to_keysym("Hyper+V"), None);
+    }
+}
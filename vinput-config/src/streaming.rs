@@ -0,0 +1,52 @@
+//! 流式上屏与部分假设变体配置
+//!
+//! config.toml 中的 `[streaming_commit]` 与 `[hypothesis_variants]` 段
+
+use serde::{Deserialize, Serialize};
+
+/// 流式上屏配置（config.toml 中的 `[streaming_commit]` 段）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StreamingCommitConfig {
+    /// 启用流式上屏
+    pub enabled: bool,
+    /// 候选前缀需连续保持不变的 Preedit 更新次数（每次约 160ms）
+    pub stable_updates: u32,
+    /// 单次上屏的最少字数（不含标点）
+    pub min_commit_chars: usize,
+}
+
+impl Default for StreamingCommitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stable_updates: 6,
+            min_commit_chars: 4,
+        }
+    }
+}
+
+/// 部分假设变体配置（config.toml 中的 `[hypothesis_variants]` 段）
+///
+/// 不是解码器的 N-best：sherpa-onnx C API 只返回最优路径，
+/// 变体来自解码过程中被推翻的等长部分假设，默认关闭。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HypothesisVariantsConfig {
+    /// 上屏后显示候选列表
+    pub enabled: bool,
+    /// 候选数量上限（含已上屏文本）
+    pub max_candidates: usize,
+    /// 保留的部分假设数量
+    pub hypothesis_history: usize,
+}
+
+impl Default for HypothesisVariantsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_candidates: 5,
+            hypothesis_history: 32,
+        }
+    }
+}
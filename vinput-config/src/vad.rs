@@ -0,0 +1,271 @@
+//! VAD 配置模块
+//!
+//! 定义 VAD 系统的所有配置参数
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// VAD 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VadConfig {
+    /// Silero VAD 配置
+    #[serde(default = "default_silero_config")]
+    pub silero: SileroConfig,
+
+    /// Energy Gate 配置
+    #[serde(default = "default_energy_gate_config")]
+    pub energy_gate: EnergyGateConfig,
+
+    /// 迟滞控制器配置
+    #[serde(default = "default_hysteresis_config")]
+    pub hysteresis: HysteresisConfig,
+
+    /// Pre-roll Buffer 配置
+    #[serde(default = "default_pre_roll_config")]
+    pub pre_roll: PreRollConfig,
+
+    /// 短爆发过滤器配置
+    #[serde(default = "default_transient_filter_config")]
+    pub transient_filter: TransientFilterConfig,
+}
+
+// 默认值函数
+fn default_silero_config() -> SileroConfig {
+    SileroConfig {
+        model_path: "models/silero-vad/silero_vad.onnx".to_string(),
+        sample_rate: 16000,
+        frame_size: 512,
+    }
+}
+
+fn default_energy_gate_config() -> EnergyGateConfig {
+    EnergyGateConfig {
+        enabled: true,
+        noise_multiplier: 2.5,
+        baseline_alpha: 0.95,
+        initial_baseline: 0.001,
+    }
+}
+
+fn default_hysteresis_config() -> HysteresisConfig {
+    HysteresisConfig {
+        start_threshold: 0.6,
+        end_threshold: 0.35,
+        min_speech_duration_ms: 100,
+        min_silence_duration_ms: 500,
+    }
+}
+
+fn default_pre_roll_config() -> PreRollConfig {
+    PreRollConfig {
+        enabled: true,
+        duration_ms: 250,
+        capacity: 4000,
+    }
+}
+
+fn default_transient_filter_config() -> TransientFilterConfig {
+    TransientFilterConfig {
+        enabled: true,
+        max_duration_ms: 80,
+        rms_threshold: 0.05,
+    }
+}
+
+/// Silero VAD 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SileroConfig {
+    /// 模型文件路径
+    pub model_path: String,
+
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+
+    /// 帧大小（样本数）
+    pub frame_size: usize,
+}
+
+/// Energy Gate 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergyGateConfig {
+    /// 启用 Energy Gate
+    pub enabled: bool,
+
+    /// 噪声基线倍数（RMS > noise_floor × multiplier 才通过）
+    pub noise_multiplier: f32,
+
+    /// 噪声基线更新系数（平滑因子）
+    pub baseline_alpha: f32,
+
+    /// 初始噪声基线
+    pub initial_baseline: f32,
+}
+
+/// 迟滞控制器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HysteresisConfig {
+    /// 启动阈值（Silence → Speech）
+    pub start_threshold: f32,
+
+    /// 结束阈值（Speech → Silence）
+    pub end_threshold: f32,
+
+    /// 最小语音持续时间 (ms)
+    pub min_speech_duration_ms: u64,
+
+    /// 最小静音持续时间 (ms)
+    pub min_silence_duration_ms: u64,
+}
+
+/// Pre-roll Buffer 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreRollConfig {
+    /// 启用 Pre-roll Buffer
+    pub enabled: bool,
+
+    /// Pre-roll 时长 (ms)
+    pub duration_ms: u64,
+
+    /// Buffer 容量（样本数）
+    pub capacity: usize,
+}
+
+/// 短爆发过滤器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransientFilterConfig {
+    /// 启用过滤器
+    pub enabled: bool,
+
+    /// 最大允许的短爆发持续时间 (ms)
+    pub max_duration_ms: u64,
+
+    /// RMS 阈值（超过此值视为可能的短爆发）
+    pub rms_threshold: f32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self::push_to_talk_default()
+    }
+}
+
+impl VadConfig {
+    /// PushToTalk 模式的默认配置
+    pub fn push_to_talk_default() -> Self {
+        Self {
+            silero: SileroConfig {
+                model_path: "models/silero-vad/silero_vad.onnx".to_string(),
+                sample_rate: 16000,
+                frame_size: 512, // 32ms @ 16kHz
+            },
+            energy_gate: EnergyGateConfig {
+                enabled: true,
+                noise_multiplier: 2.5,
+                baseline_alpha: 0.95,
+                initial_baseline: 0.001,
+            },
+            hysteresis: HysteresisConfig {
+                start_threshold: 0.6,
+                end_threshold: 0.35,
+                min_speech_duration_ms: 100,
+                min_silence_duration_ms: 500,
+            },
+            pre_roll: PreRollConfig {
+                enabled: true,
+                duration_ms: 250,
+                capacity: 4000, // 250ms @ 16kHz
+            },
+            transient_filter: TransientFilterConfig {
+                enabled: true,
+                max_duration_ms: 80,
+                rms_threshold: 0.05,
+            },
+        }
+    }
+
+    /// 免提连续听写的默认配置
+    ///
+    /// 麦克风长时间常开，需要拒绝背景人声与环境噪声：提高启动阈值与能量门限，
+    /// 要求更长的连续语音才进入 Speech，并过滤更长的短爆发（咳嗽、键盘声）
+    pub fn hands_free_default() -> Self {
+        let mut config = Self::push_to_talk_default();
+        config.energy_gate.noise_multiplier = 4.0;
+        config.energy_gate.baseline_alpha = 0.98;
+        config.hysteresis = HysteresisConfig {
+            start_threshold: 0.75,
+            end_threshold: 0.4,
+            min_speech_duration_ms: 250,
+            min_silence_duration_ms: 600,
+        };
+        config.pre_roll = PreRollConfig {
+            enabled: true,
+            duration_ms: 400,
+            capacity: 6400, // 400ms @ 16kHz（补偿更长的启动确认时间）
+        };
+        config.transient_filter = TransientFilterConfig {
+            enabled: true,
+            max_duration_ms: 200,
+            rms_threshold: 0.03,
+        };
+        config
+    }
+}
+
+/// 反序列化 `[hands_free_vad]`：文件中的字段覆盖到免提默认配置上
+///
+/// 逐字段派生的默认值是按键说话的配置，只写了 `[hands_free_vad.hysteresis]`
+/// 等部分段落时，其余段落会被换成按键说话的参数，因此这里按表合并。
+pub fn deserialize_hands_free<'de, D: Deserializer<'de>>(deserializer: D) -> Result<VadConfig, D::Error> {
+    let overrides = toml::Table::deserialize(deserializer)?;
+    let mut merged = toml::Table::try_from(VadConfig::hands_free_default()).map_err(D::Error::custom)?;
+    merge_table(&mut merged, overrides);
+    VadConfig::deserialize(toml::Value::Table(merged)).map_err(D::Error::custom)
+}
+
+/// 将 `overrides` 递归合并到 `base`（子表逐项合并，其余值直接覆盖）
+fn merge_table(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => merge_table(base_table, table),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hands_free_profile_is_stricter() {
+        let ptt = VadConfig::push_to_talk_default();
+        let hands_free = VadConfig::hands_free_default();
+        assert!(hands_free.hysteresis.start_threshold > ptt.hysteresis.start_threshold);
+        assert!(hands_free.energy_gate.noise_multiplier > ptt.energy_gate.noise_multiplier);
+        assert!(hands_free.hysteresis.min_speech_duration_ms > ptt.hysteresis.min_speech_duration_ms);
+        assert!(hands_free.transient_filter.max_duration_ms > ptt.transient_filter.max_duration_ms);
+        // Pre-roll 容量与时长一致
+        assert_eq!(hands_free.pre_roll.capacity as u64, hands_free.pre_roll.duration_ms * 16);
+    }
+
+    #[test]
+    fn test_partial_hands_free_table_keeps_hands_free_defaults() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(deserialize_with = "deserialize_hands_free")]
+            hands_free_vad: VadConfig,
+        }
+
+        let wrapper: Wrapper = toml::from_str("[hands_free_vad.hysteresis]\nstart_threshold = 0.8\n").unwrap();
+        let config = wrapper.hands_free_vad;
+        let defaults = VadConfig::hands_free_default();
+        assert_eq!(config.hysteresis.start_threshold, 0.8);
+        assert_eq!(config.hysteresis.end_threshold, defaults.hysteresis.end_threshold);
+        assert_eq!(config.hysteresis.min_speech_duration_ms, defaults.hysteresis.min_speech_duration_ms);
+        assert_eq!(config.energy_gate.noise_multiplier, defaults.energy_gate.noise_multiplier);
+        assert_eq!(config.pre_roll.duration_ms, defaults.pre_roll.duration_ms);
+        assert_eq!(config.transient_filter.max_duration_ms, defaults.transient_filter.max_duration_ms);
+    }
+}
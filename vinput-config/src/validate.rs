@@ -0,0 +1,322 @@
+//! 配置校验
+//!
+//! 反序列化只保证类型正确，这里检查取值范围与字段间约束，
+//! 每个错误带字段路径（如 `vad.hysteresis.start_threshold`），便于设置界面定位。
+
+use crate::asr::DECODING_METHODS;
+use crate::audio::AudioSourceConfig;
+use crate::shortcuts::{KeyCombo, ShortcutAction};
+use crate::vad::VadConfig;
+use crate::VInputConfig;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 管道每帧采样点数（队列余量校验用）
+const AUDIO_FRAME_SIZE: usize = 512;
+
+/// 设置界面可选的标点风格
+pub const PUNCTUATION_STYLES: [&str; 4] = ["Professional", "Balanced", "Expressive", "Custom"];
+
+/// 字段级校验错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// 字段路径
+    pub field: String,
+    /// 错误说明
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// 多个错误合并为一行（用于错误消息）
+pub(crate) fn join_errors(errors: &[FieldError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+/// 校验错误收集器
+#[derive(Default)]
+struct Validator {
+    errors: Vec<FieldError>,
+}
+
+impl Validator {
+    /// 条件不成立时记录错误
+    fn check(&mut self, field: impl Into<String>, ok: bool, message: impl Into<String>) {
+        if !ok {
+            self.errors.push(FieldError {
+                field: field.into(),
+                message: message.into(),
+            });
+        }
+    }
+
+    /// 检查闭区间
+    fn range<T: PartialOrd + fmt::Display>(&mut self, field: impl Into<String>, value: T, min: T, max: T) {
+        let ok = value >= min && value <= max;
+        self.check(field, ok, format!("应在 {} 到 {} 之间，当前为 {}", min, max, value));
+    }
+
+    /// 检查正数
+    fn positive<T: PartialOrd + Default + fmt::Display>(&mut self, field: impl Into<String>, value: T) {
+        let ok = value > T::default();
+        self.check(field, ok, format!("应大于 0，当前为 {}", value));
+    }
+
+    fn vad(&mut self, prefix: &str, vad: &VadConfig) {
+        self.positive(format!("{}.silero.sample_rate", prefix), vad.silero.sample_rate);
+        self.positive(format!("{}.silero.frame_size", prefix), vad.silero.frame_size);
+        self.positive(format!("{}.energy_gate.noise_multiplier", prefix), vad.energy_gate.noise_multiplier);
+        self.range(format!("{}.energy_gate.baseline_alpha", prefix), vad.energy_gate.baseline_alpha, 0.0, 1.0);
+
+        let hysteresis = &vad.hysteresis;
+        self.range(format!("{}.hysteresis.start_threshold", prefix), hysteresis.start_threshold, 0.0, 1.0);
+        self.range(format!("{}.hysteresis.end_threshold", prefix), hysteresis.end_threshold, 0.0, 1.0);
+        self.check(
+            format!("{}.hysteresis.end_threshold", prefix),
+            hysteresis.end_threshold < hysteresis.start_threshold,
+            format!(
+                "结束阈值 {} 应小于启动阈值 {}",
+                hysteresis.end_threshold, hysteresis.start_threshold
+            ),
+        );
+
+        if vad.pre_roll.enabled {
+            self.positive(format!("{}.pre_roll.capacity", prefix), vad.pre_roll.capacity);
+        }
+        self.range(format!("{}.transient_filter.rms_threshold", prefix), vad.transient_filter.rms_threshold, 0.0, 1.0);
+    }
+
+    fn hotwords(&mut self, prefix: &str, words: &HashMap<String, f32>) {
+        let mut words: Vec<_> = words.iter().collect();
+        words.sort_by(|a, b| a.0.cmp(b.0));
+        for (word, weight) in words {
+            let field = format!("{}.{}", prefix, word);
+            self.check(field.clone(), !word.trim().is_empty(), "热词不能为空");
+            self.positive(field, *weight);
+        }
+    }
+}
+
+impl VInputConfig {
+    /// 校验配置，返回全部字段错误
+    pub fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut v = Validator::default();
+
+        v.vad("vad", &self.vad);
+        v.vad("hands_free_vad", &self.hands_free_vad);
+
+        let asr = &self.asr;
+        v.check("asr.model_dir", !asr.model_dir.trim().is_empty(), "模型目录不能为空");
+        v.positive("asr.sample_rate", asr.sample_rate);
+        v.positive("asr.feat_dim", asr.feat_dim);
+        v.check(
+            "asr.decoding_method",
+            DECODING_METHODS.contains(&asr.decoding_method.as_str()),
+            format!("应为 {}，当前为 '{}'", DECODING_METHODS.join(" / "), asr.decoding_method),
+        );
+        v.positive("asr.max_active_paths", asr.max_active_paths);
+        v.range("asr.num_threads", asr.num_threads, 1, 16);
+        v.range("asr.hotwords_score", asr.hotwords_score, 0.0, 10.0);
+
+        let punctuation = &self.punctuation;
+        v.check(
+            "punctuation.style",
+            PUNCTUATION_STYLES.contains(&punctuation.style.as_str()),
+            format!("应为 {}，当前为 '{}'", PUNCTUATION_STYLES.join(" / "), punctuation.style),
+        );
+        v.positive("punctuation.streaming_pause_ratio", punctuation.streaming_pause_ratio);
+        v.positive("punctuation.streaming_min_tokens", punctuation.streaming_min_tokens);
+        v.range("punctuation.logic_word_strength", punctuation.logic_word_strength, 0.0, 2.0);
+
+        let hotwords = &self.hotwords;
+        v.positive("hotwords.global_weight", hotwords.global_weight);
+        let active = hotwords.active_words().len();
+        v.check(
+            "hotwords.words",
+            active <= hotwords.max_words,
+            format!("启用的热词数 {} 超过上限 {}", active, hotwords.max_words),
+        );
+        v.hotwords("hotwords.words", &hotwords.words);
+        for (i, group) in hotwords.groups.iter().enumerate() {
+            let name = group.name.trim();
+            v.check(format!("hotwords.groups.{}", i), !name.is_empty(), "分组名不能为空");
+            v.check(
+                format!("hotwords.groups.{}", name),
+                hotwords.groups[..i].iter().all(|g| g.name.trim() != name),
+                "分组名重复",
+            );
+            v.hotwords(&format!("hotwords.groups.{}", name), &group.words);
+        }
+        let learning = &hotwords.learning;
+        v.range("hotwords.learning.correction_weight", learning.correction_weight, 1.0, 5.0);
+        v.range("hotwords.learning.explicit_weight", learning.explicit_weight, 1.0, 5.0);
+        v.positive("hotwords.learning.half_life_days", learning.half_life_days);
+        v.positive("hotwords.learning.min_hits", learning.min_hits);
+
+        let endpoint = &self.endpoint;
+        v.check(
+            "endpoint.max_speech_duration_ms",
+            endpoint.max_speech_duration_ms > endpoint.min_speech_duration_ms,
+            "最大语音长度应大于最小语音长度",
+        );
+        v.check(
+            "endpoint.force_timeout_ms",
+            endpoint.force_timeout_ms >= endpoint.max_speech_duration_ms,
+            "强制超时不应小于最大语音长度",
+        );
+        v.positive("endpoint.trailing_silence_ms", endpoint.trailing_silence_ms);
+        v.positive("endpoint.vad_silence_confirm_frames", endpoint.vad_silence_confirm_frames);
+
+        let enhancement = &self.enhancement;
+        v.positive("enhancement.over_subtraction", enhancement.over_subtraction);
+        v.range("enhancement.spectral_floor", enhancement.spectral_floor, 0.0, 1.0);
+        v.range("enhancement.noise_update_alpha", enhancement.noise_update_alpha, 0.0, 1.0);
+
+        let audio = &self.audio;
+        v.positive("audio.device_poll_interval_ms", audio.device_poll_interval_ms);
+        v.positive("audio.level_meter.report_interval_ms", audio.level_meter.report_interval_ms);
+        v.range("audio.level_meter.clip_level", audio.level_meter.clip_level, 0.01, 1.0);
+        if let AudioSourceConfig::Stdin { pcm } | AudioSourceConfig::UnixSocket { pcm, .. } = &audio.source {
+            v.positive("audio.source.sample_rate", pcm.sample_rate);
+            v.positive("audio.source.channels", pcm.channels);
+        }
+        let queue = &audio.queue;
+        v.range("audio.queue.backpressure_threshold", queue.backpressure_threshold, 1, 99);
+        for (field, capacity) in [
+            ("audio.queue.capture_to_vad_capacity", queue.capture_to_vad_capacity),
+            ("audio.queue.vad_to_asr_capacity", queue.vad_to_asr_capacity),
+        ] {
+            // 背压阈值以下至少容纳一帧，否则下游阶段永远等不到完整的一帧
+            let limit = capacity * queue.backpressure_threshold as usize / 100;
+            v.check(
+                field,
+                limit >= AUDIO_FRAME_SIZE,
+                format!(
+                    "背压阈值以下应至少容纳一帧（{} 个采样点），当前容量 {} × {}% = {}",
+                    AUDIO_FRAME_SIZE, capacity, queue.backpressure_threshold, limit
+                ),
+            );
+        }
+
+        v.positive("streaming_commit.stable_updates", self.streaming_commit.stable_updates);
+        v.positive("hypothesis_variants.max_candidates", self.hypothesis_variants.max_candidates);
+
+        for (action, name) in [
+            (ShortcutAction::Trigger, "trigger"),
+            (ShortcutAction::Undo, "undo"),
+            (ShortcutAction::Redo, "redo"),
+        ] {
+            if let Err(e) = KeyCombo::parse(self.shortcuts.get(action)) {
+                v.check(format!("shortcuts.{}", name), false, e);
+            }
+        }
+
+        v.range("activation.double_tap_interval_ms", self.activation.double_tap_interval_ms, 100, 1000);
+
+        let wake_word = &self.wake_word;
+        if wake_word.enabled {
+            v.check("wake_word.model_dir", !wake_word.model_dir.trim().is_empty(), "模型目录不能为空");
+        }
+        v.range("wake_word.keywords_threshold", wake_word.keywords_threshold, 0.0, 1.0);
+        for (i, keyword) in wake_word.keywords.iter().enumerate() {
+            v.check(
+                format!("wake_word.keywords[{}]", i),
+                keyword.to_keyword_line().is_some(),
+                "唤醒词需要非空的 tokens 与不含空白的 phrase",
+            );
+        }
+
+        for (i, phrase) in self.cancel.phrases.iter().enumerate() {
+            v.check(format!("cancel.phrases[{}]", i), !phrase.trim().is_empty(), "取消口令不能为空");
+        }
+
+        v.check(
+            "recovery.max_backoff_ms",
+            self.recovery.max_backoff_ms >= self.recovery.initial_backoff_ms,
+            "重试等待上限不应小于首次等待时长",
+        );
+
+        if self.recorder.enabled {
+            v.positive("recorder.max_bundles", self.recorder.max_bundles);
+            v.positive("recorder.max_duration_secs", self.recorder.max_duration_secs);
+        }
+
+        if v.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(v.errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotwords::HotwordGroup;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(VInputConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_field_errors_report_paths() {
+        let mut config = VInputConfig::default();
+        config.hands_free_vad.hysteresis.start_threshold = 1.5;
+        config.asr.decoding_method = "beam".to_string();
+        config.shortcuts.undo = "Hyper+z".to_string();
+        config.hotwords.words.insert("深度学习".to_string(), 0.0);
+
+        let errors = config.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec![
+            "hands_free_vad.hysteresis.start_threshold",
+            "asr.decoding_method",
+            "hotwords.words.深度学习",
+            "shortcuts.undo",
+        ]);
+        assert!(errors[3].message.contains("Hyper"));
+    }
+
+    #[test]
+    fn test_queue_and_thread_limits() {
+        let mut config = VInputConfig::default();
+        config.asr.num_threads = 0;
+        config.audio.queue.capture_to_vad_capacity = 600;
+        config.audio.queue.backpressure_threshold = 80;
+
+        let errors = config.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["asr.num_threads", "audio.queue.capture_to_vad_capacity"]);
+
+        config.asr.num_threads = 4;
+        config.audio.queue.capture_to_vad_capacity = 2560;
+        assert_eq!(config.validate(), Ok(()));
+
+        // 阈值过低：默认容量下阈值以下只剩 320 个采样点，不足一帧
+        config.audio.queue.backpressure_threshold = 1;
+        let errors = config.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["audio.queue.capture_to_vad_capacity", "audio.queue.vad_to_asr_capacity"]);
+    }
+
+    #[test]
+    fn test_hotword_groups_validated() {
+        let mut config = VInputConfig::default();
+        let mut group = HotwordGroup::new("同事");
+        group.words.insert("张三".to_string(), -1.0);
+        config.hotwords.groups = vec![group, HotwordGroup::new("同事"), HotwordGroup::new(" ")];
+
+        let errors = config.validate().unwrap_err();
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec![
+            "hotwords.groups.同事.张三",
+            "hotwords.groups.同事",
+            "hotwords.groups.2",
+        ]);
+    }
+}
@@ -225,6 +225,7 @@ fn benchmark_asr(sample_rate: u32, samples: &[f32]) -> VInputResult<()> {
         feat_dim: 80,
         decoding_method: "greedy_search".to_string(),
         max_active_paths: 4,
+        num_threads: 1,
         hotwords_file: None,
         hotwords_score: 1.5,
     };
@@ -334,6 +335,7 @@ fn comprehensive_benchmark(sample_rate: u32, samples: &[f32]) -> VInputResult<()
         feat_dim: 80,
         decoding_method: "greedy_search".to_string(),
         max_active_paths: 4,
+        num_threads: 1,
         hotwords_file: None,
         hotwords_score: 1.5,
     };
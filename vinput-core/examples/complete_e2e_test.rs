@@ -112,6 +112,7 @@ fn main() -> VInputResult<()> {
             feat_dim: 80,
             decoding_method: "greedy_search".to_string(),
             max_active_paths: 4,
+            num_threads: 1,
             hotwords_file: None,
             hotwords_score: 1.5,
         },
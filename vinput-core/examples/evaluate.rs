@@ -0,0 +1,104 @@
+//! 识别准确率评测
+//!
+//! 用一组或多组配置识别语料目录（`<名称>.wav` + `<名称>.expected`，可选 `<名称>.spoken`），
+//! 输出字错误率、标点 F1、ITN 完全匹配与实时率的对比报告
+//!
+//! 用法：
+//!   cargo run --release --example evaluate -- tests/testdata
+//!   cargo run --release --example evaluate -- <语料目录> --config beam4.toml --config beam8.toml
+//!   cargo run --release --example evaluate -- <语料目录> --model models/a --model models/b
+//!   cargo run --release --example evaluate -- <语料目录> --config a.toml --json report.json --markdown report.md
+//!
+//! `--config` 每个配置文件为一组；`--model` 以基准配置（`--base`，默认 ~/.config/vinput/config.toml）
+//! 替换模型目录后为一组；都未指定时只评测基准配置。Markdown 报告同时输出到终端。
+
+use std::env;
+use std::path::{Path, PathBuf};
+use vinput_core::config::VInputConfig;
+use vinput_core::eval::{evaluate, load_corpus, EvalReport};
+use vinput_core::{VInputError, VInputResult};
+
+fn main() -> VInputResult<()> {
+    vinput_core::init_logging();
+
+    let mut corpus = None;
+    let mut base = None;
+    let mut configs = Vec::new();
+    let mut models = Vec::new();
+    let mut json_path = None;
+    let mut markdown_path = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--base" => base = args.next().map(PathBuf::from),
+            "--config" => configs.extend(args.next().map(PathBuf::from)),
+            "--model" => models.extend(args.next()),
+            "--json" => json_path = args.next().map(PathBuf::from),
+            "--markdown" => markdown_path = args.next().map(PathBuf::from),
+            _ => corpus = Some(PathBuf::from(arg)),
+        }
+    }
+    let Some(corpus) = corpus else {
+        eprintln!(
+            "用法: evaluate <语料目录> [--config <配置>]... [--model <模型目录>]... [--base <配置>] [--json <文件>] [--markdown <文件>]"
+        );
+        std::process::exit(2);
+    };
+
+    let cases = load_corpus(&corpus)?;
+    if cases.is_empty() {
+        eprintln!("❌ {} 中没有带参考文本的语料", corpus.display());
+        std::process::exit(1);
+    }
+    eprintln!("📁 语料: {}（{} 条）", corpus.display(), cases.len());
+
+    let load = |path: &Path| {
+        VInputConfig::load_from(path)
+            .map_err(|e| VInputError::Generic(format!("加载配置 {} 失败: {}", path.display(), e)))
+    };
+    let base_config = match &base {
+        Some(path) => load(path)?,
+        None => VInputConfig::load().unwrap_or_default(),
+    };
+
+    let mut runs: Vec<(String, VInputConfig)> = Vec::new();
+    for path in &configs {
+        runs.push((file_label(path), load(path)?));
+    }
+    for model in &models {
+        let mut config = base_config.clone();
+        config.asr.model_dir = model.clone();
+        runs.push((file_label(Path::new(model)), config));
+    }
+    if runs.is_empty() {
+        runs.push(("当前配置".to_string(), base_config));
+    }
+
+    let mut report = EvalReport {
+        corpus: corpus.display().to_string(),
+        runs: Vec::with_capacity(runs.len()),
+    };
+    for (label, config) in &runs {
+        eprintln!("🔧 评测 {}（模型: {}）", label, config.asr.model_dir);
+        report.runs.push(evaluate(label, config, &cases)?);
+    }
+
+    let markdown = report.to_markdown();
+    println!("{}", markdown);
+    if let Some(path) = markdown_path {
+        std::fs::write(&path, &markdown)?;
+        eprintln!("💾 Markdown 报告: {}", path.display());
+    }
+    if let Some(path) = json_path {
+        std::fs::write(&path, report.to_json()?)?;
+        eprintln!("💾 JSON 报告: {}", path.display());
+    }
+    Ok(())
+}
+
+/// 配置文件名或模型目录名（去掉扩展名）
+fn file_label(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
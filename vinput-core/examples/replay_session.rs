@@ -0,0 +1,81 @@
+//! 会话包回放
+//!
+//! 用会话包中的音频与配置重新运行识别管道，与现场记录比对（会话包由 `[recorder]` 生成）
+//!
+//! 用法：
+//!   cargo run --example replay_session -- session-1760000000.vinput-session
+//!   cargo run --example replay_session -- <会话包> --model-dir models/streaming
+//!   cargo run --example replay_session -- <会话包> --events       # 同时列出回放事件
+//!   cargo run --example replay_session -- <会话包> --wav out.wav  # 导出录制的音频
+//!
+//! 与现场一致时退出码为 0，有差异时为 1。
+
+use std::env;
+use std::path::PathBuf;
+use vinput_core::audio::WavWriter;
+use vinput_core::recorder::{replay, SessionBundle};
+use vinput_core::VInputResult;
+
+fn main() -> VInputResult<()> {
+    vinput_core::init_logging();
+
+    let mut bundle_path = None;
+    let mut model_dir = None;
+    let mut wav_path = None;
+    let mut show_events = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--model-dir" => model_dir = args.next(),
+            "--wav" => wav_path = args.next().map(PathBuf::from),
+            "--events" => show_events = true,
+            _ => bundle_path = Some(PathBuf::from(arg)),
+        }
+    }
+    let Some(bundle_path) = bundle_path else {
+        eprintln!("用法: replay_session <会话包> [--model-dir <模型目录>] [--events] [--wav <输出.wav>]");
+        std::process::exit(2);
+    };
+
+    println!("=== V-Input 会话回放 ===\n");
+    let bundle = SessionBundle::read_from(&bundle_path)?;
+    let manifest = &bundle.manifest;
+    println!("📁 会话包: {}", bundle_path.display());
+    println!("   - V-Input 版本: {}", manifest.vinput_version);
+    println!(
+        "   - 音频: {} 帧 × {} 样本 @ {} Hz（{:.1} 秒）",
+        manifest.frames,
+        manifest.frame_size,
+        manifest.sample_rate,
+        bundle.samples.len() as f64 / manifest.sample_rate as f64
+    );
+    println!("   - 事件: {} 条", bundle.events.len());
+
+    if let Some(path) = wav_path {
+        let mut writer = WavWriter::create(&path, manifest.sample_rate)?;
+        writer.write_samples(&bundle.samples)?;
+        writer.finish()?;
+        println!("💾 已导出音频: {}", path.display());
+    }
+
+    let mut config = bundle.config()?;
+    if let Some(dir) = model_dir {
+        config.asr.model_dir = dir;
+    }
+    println!("🔧 模型目录: {}\n", config.asr.model_dir);
+
+    let report = replay(&bundle, &config)?;
+    if show_events {
+        println!("📋 回放事件:");
+        for event in &report.events {
+            println!("  {:?}", event);
+        }
+        println!();
+    }
+    print!("{}", report);
+
+    if !report.is_identical() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
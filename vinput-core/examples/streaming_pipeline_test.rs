@@ -27,6 +27,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             feat_dim: 80,
             decoding_method: "greedy_search".to_string(),
             max_active_paths: 4,
+            num_threads: 1,
             hotwords_file: None,
             hotwords_score: 1.5,
         },
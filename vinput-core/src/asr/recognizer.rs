@@ -1,11 +1,12 @@
 //! sherpa-onnx 在线识别器安全封装
 
 use crate::error::{VInputError, VInputResult};
-use serde::{Deserialize, Serialize};
 use std::ffi::{CStr, CString};
 use std::path::Path;
 use std::ptr;
 
+pub use vinput_config::asr::OnlineRecognizerConfig;
+
 // 引入 bindgen 生成的绑定
 include!(concat!(env!("OUT_DIR"), "/sherpa_bindings.rs"));
 
@@ -62,52 +63,6 @@ impl RecognitionResult {
     }
 }
 
-/// 在线识别器配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OnlineRecognizerConfig {
-    /// 模型目录路径
-    pub model_dir: String,
-    /// 采样率 (Hz)
-    #[serde(default = "default_sample_rate")]
-    pub sample_rate: i32,
-    /// 特征维度
-    #[serde(default = "default_feat_dim")]
-    pub feat_dim: i32,
-    /// 解码方法 ("greedy_search" 或 "modified_beam_search")
-    #[serde(default = "default_decoding_method")]
-    pub decoding_method: String,
-    /// 最大活跃路径数
-    #[serde(default = "default_max_active_paths")]
-    pub max_active_paths: i32,
-    /// 热词文件路径（可选）
-    #[serde(default)]
-    pub hotwords_file: Option<String>,
-    /// 热词得分
-    #[serde(default = "default_hotwords_score")]
-    pub hotwords_score: f32,
-}
-
-// 默认值函数
-fn default_sample_rate() -> i32 { 16000 }
-fn default_feat_dim() -> i32 { 80 }
-fn default_decoding_method() -> String { "greedy_search".to_string() }
-fn default_max_active_paths() -> i32 { 2 }  // 降低到 2 以减少 CPU 占用（原来是 4）
-fn default_hotwords_score() -> f32 { 1.5 }
-
-impl Default for OnlineRecognizerConfig {
-    fn default() -> Self {
-        Self {
-            model_dir: String::new(),
-            sample_rate: 16000,
-            feat_dim: 80,
-            decoding_method: "greedy_search".to_string(),
-            max_active_paths: 2,  // 与 serde default 保持一致
-            hotwords_file: None,
-            hotwords_score: 1.5,
-        }
-    }
-}
-
 /// 在线识别器（线程安全）
 pub struct OnlineRecognizer {
     inner: *const SherpaOnnxOnlineRecognizer,
@@ -139,6 +94,7 @@ impl OnlineRecognizer {
         tracing::info!("  Encoder: {:?}", encoder_path);
         tracing::info!("  Decoder: {:?}", decoder_path);
         tracing::info!("  Tokens: {:?}", tokens_path);
+        tracing::info!("  推理线程数: {}", config.num_threads);
 
         // 验证文件存在
         if !encoder_path.exists() {
@@ -196,7 +152,7 @@ impl OnlineRecognizer {
             paraformer: paraformer_config,
             zipformer2_ctc: unsafe { std::mem::zeroed() },
             tokens: tokens_cstr.as_ptr(),
-            num_threads: 1,  // 降低到 1 以最小化 CPU 占用
+            num_threads: config.num_threads,
             provider: provider_cstr.as_ptr(),
             debug: 0,
             model_type: ptr::null(),
@@ -5,29 +5,8 @@
 use crate::audio::ring_buffer::{AudioRingBuffer, AudioRingBufferConfig, AudioRingConsumer, AudioRingProducer};
 use crate::error::VInputResult;
 
-/// 音频队列管理器配置
-#[derive(Debug, Clone)]
-pub struct AudioQueueConfig {
-    /// 捕获到 VAD 的队列大小（采样点数）
-    pub capture_to_vad_capacity: usize,
-    /// VAD 到 ASR 的队列大小（采样点数）
-    pub vad_to_asr_capacity: usize,
-    /// 背压阈值（队列使用率百分比，0-100）
-    pub backpressure_threshold: u8,
-}
-
-impl Default for AudioQueueConfig {
-    fn default() -> Self {
-        Self {
-            // 捕获到 VAD：1 秒缓冲 @ 16kHz
-            capture_to_vad_capacity: 16000,
-            // VAD 到 ASR：2 秒缓冲 @ 16kHz
-            vad_to_asr_capacity: 32000,
-            // 背压阈值：80%
-            backpressure_threshold: 80,
-        }
-    }
-}
+/// 音频队列管理器配置（config.toml 中的 `[audio.queue]` 段）
+pub use vinput_config::audio::AudioQueueConfig;
 
 /// 音频队列管理器
 ///
@@ -136,6 +115,41 @@ impl AudioQueueManager {
         ((used as f32 / capacity as f32) * 100.0) as u8
     }
 
+    /// 获取 Capture → VAD 队列中可读的样本数
+    pub fn capture_to_vad_available(&self) -> usize {
+        self.capture_to_vad_consumer.available_samples()
+    }
+
+    /// 获取 VAD → ASR 队列中可读的样本数
+    pub fn vad_to_asr_available(&self) -> usize {
+        self.vad_to_asr_consumer.available_samples()
+    }
+
+    /// Capture → VAD 队列达到背压阈值前还能写入的样本数
+    ///
+    /// 上游阶段按此限制读取量，写入不会被背压丢弃
+    pub fn capture_to_vad_headroom(&self) -> usize {
+        self.headroom(&self.capture_to_vad_consumer)
+    }
+
+    /// VAD → ASR 队列达到背压阈值前还能写入的样本数
+    pub fn vad_to_asr_headroom(&self) -> usize {
+        self.headroom(&self.vad_to_asr_consumer)
+    }
+
+    /// 两个队列达到背压阈值时的样本数（Capture → VAD, VAD → ASR）
+    pub fn backpressure_limits(&self) -> (usize, usize) {
+        (self.limit(&self.capture_to_vad_consumer), self.limit(&self.vad_to_asr_consumer))
+    }
+
+    fn limit(&self, consumer: &AudioRingConsumer) -> usize {
+        consumer.capacity() * self.config.backpressure_threshold as usize / 100
+    }
+
+    fn headroom(&self, consumer: &AudioRingConsumer) -> usize {
+        self.limit(consumer).saturating_sub(consumer.available_samples())
+    }
+
     /// 是否正在应用背压
     pub fn is_backpressure_active(&self) -> bool {
         self.backpressure_active
@@ -239,6 +253,29 @@ mod tests {
         assert!(manager.is_backpressure_active());
     }
 
+    #[test]
+    fn test_headroom_stops_below_threshold() {
+        let config = AudioQueueConfig {
+            capture_to_vad_capacity: 1000,
+            vad_to_asr_capacity: 2000,
+            backpressure_threshold: 80,
+        };
+        let mut manager = AudioQueueManager::new(config);
+        assert_eq!(manager.capture_to_vad_headroom(), 800);
+        assert_eq!(manager.vad_to_asr_headroom(), 1600);
+
+        // 写满余量不会触发背压
+        let samples = vec![1.0; manager.capture_to_vad_headroom()];
+        assert_eq!(manager.write_from_capture(&samples).unwrap(), 800);
+        assert_eq!(manager.capture_to_vad_headroom(), 0);
+        assert_eq!(manager.capture_to_vad_available(), 800);
+        assert!(!manager.is_backpressure_active());
+
+        manager.write_from_vad(&[2.0; 100]).unwrap();
+        assert_eq!(manager.vad_to_asr_available(), 100);
+        assert_eq!(manager.vad_to_asr_headroom(), 1500);
+    }
+
     #[test]
     fn test_usage_percent() {
         let config = AudioQueueConfig {
@@ -0,0 +1,202 @@
+//! 捕获格式转换
+//!
+//! 将设备原生格式（任意采样率 / 声道数 / F32LE 或 S16LE）的字节流
+//! 转换为识别管线需要的单声道 f32 样本：
+//! 字节解码 → 声道下混 → 重采样
+
+use crate::audio::pipewire_stream::AudioFormat;
+use crate::audio::resampler::{ResampleQuality, Resampler};
+
+pub use vinput_config::audio::ChannelMix;
+
+/// 将小端字节解码为 f32 样本（S16LE 归一化到 [-1.0, 1.0)）
+pub fn decode_samples(bytes: &[u8], format: AudioFormat) -> Vec<f32> {
+    match format {
+        AudioFormat::F32LE => bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        AudioFormat::S16LE => bytes
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+            .collect(),
+    }
+}
+
+/// 将交错多声道样本下混为单声道
+pub fn downmix(interleaved: &[f32], channels: usize, mix: ChannelMix) -> Vec<f32> {
+    if channels <= 1 {
+        return interleaved.to_vec();
+    }
+
+    match mix {
+        ChannelMix::Average => {
+            let scale = 1.0 / channels as f32;
+            interleaved
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() * scale)
+                .collect()
+        }
+        ChannelMix::Select(channel) => {
+            let channel = (channel as usize).min(channels - 1);
+            interleaved
+                .chunks_exact(channels)
+                .map(|frame| frame[channel])
+                .collect()
+        }
+    }
+}
+
+/// 捕获格式转换器
+///
+/// 按帧对齐缓存不完整的字节，保证跨 `read()` 调用的数据连续
+pub struct CaptureConverter {
+    format: AudioFormat,
+    channels: usize,
+    mix: ChannelMix,
+    resampler: Option<Resampler>,
+    /// 未凑满一帧的尾部字节
+    leftover: Vec<u8>,
+}
+
+impl CaptureConverter {
+    /// 创建转换器
+    ///
+    /// `input_rate` / `channels` / `format` 为设备实际提供的格式，
+    /// `output_rate` 为管线采样率；采样率相同时不做重采样
+    pub fn new(
+        format: AudioFormat,
+        input_rate: u32,
+        channels: u32,
+        output_rate: u32,
+        mix: ChannelMix,
+        quality: ResampleQuality,
+    ) -> Self {
+        let resampler = (input_rate != output_rate)
+            .then(|| Resampler::new(input_rate, output_rate, quality));
+
+        tracing::info!(
+            "捕获格式转换: {:?} {} Hz {} 声道 → f32 {} Hz 单声道 ({:?})",
+            format,
+            input_rate,
+            channels,
+            output_rate,
+            mix
+        );
+
+        Self {
+            format,
+            channels: channels.max(1) as usize,
+            mix,
+            resampler,
+            leftover: Vec::new(),
+        }
+    }
+
+    /// 输入一段原始字节，返回转换后的单声道样本
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> Vec<f32> {
+        self.leftover.extend_from_slice(bytes);
+
+        let frame_bytes = self.format.bytes_per_sample() * self.channels;
+        let aligned = self.leftover.len() / frame_bytes * frame_bytes;
+        if aligned == 0 {
+            return Vec::new();
+        }
+
+        let samples = decode_samples(&self.leftover[..aligned], self.format);
+        self.leftover.drain(..aligned);
+
+        self.push_samples(&samples)
+    }
+
+    /// 输入已解码的交错样本，返回转换后的单声道样本
+    pub fn push_samples(&mut self, interleaved: &[f32]) -> Vec<f32> {
+        let mono = downmix(interleaved, self.channels, self.mix);
+        match &mut self.resampler {
+            Some(resampler) => resampler.process(&mono),
+            None => mono,
+        }
+    }
+
+    /// 重置内部状态
+    pub fn reset(&mut self) {
+        self.leftover.clear();
+        if let Some(resampler) = &mut self.resampler {
+            resampler.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_s16le() {
+        let bytes: Vec<u8> = [0i16, 16384, -32768, 32767]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        let samples = decode_samples(&bytes, AudioFormat::S16LE);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[0], 0.0);
+        assert!((samples[1] - 0.5).abs() < 1e-6);
+        assert_eq!(samples[2], -1.0);
+        assert!(samples[3] < 1.0 && samples[3] > 0.999);
+    }
+
+    #[test]
+    fn test_downmix_average_and_select() {
+        let stereo = [1.0, 0.0, 0.5, -0.5, -1.0, 1.0];
+        assert_eq!(downmix(&stereo, 2, ChannelMix::Average), vec![0.5, 0.0, 0.0]);
+        assert_eq!(downmix(&stereo, 2, ChannelMix::Select(1)), vec![0.0, -0.5, 1.0]);
+        // 越界声道回退到最后一个
+        assert_eq!(downmix(&stereo, 2, ChannelMix::Select(5)), vec![0.0, -0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_converter_handles_unaligned_reads() {
+        // 48kHz 立体声 S16LE，左声道 1kHz 正弦，右声道静音
+        let frames = 4800;
+        let bytes: Vec<u8> = (0..frames)
+            .flat_map(|i| {
+                let t = i as f32 / 48000.0;
+                let left = ((2.0 * std::f32::consts::PI * 1000.0 * t).sin() * 16000.0) as i16;
+                [left, 0i16]
+            })
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+
+        let mut converter = CaptureConverter::new(
+            AudioFormat::S16LE,
+            48000,
+            2,
+            16000,
+            ChannelMix::Select(0),
+            ResampleQuality::Balanced,
+        );
+
+        // 以奇数字节块喂入，模拟 stdout.read() 的不对齐返回
+        let output: Vec<f32> = bytes.chunks(333).flat_map(|c| converter.push_bytes(c)).collect();
+
+        assert!((1580..=1600).contains(&output.len()), "输出长度: {}", output.len());
+        let peak = output[100..].iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 16000.0 / 32768.0).abs() < 0.03, "峰值: {}", peak);
+    }
+
+    #[test]
+    fn test_converter_passthrough_when_native_matches() {
+        let samples = [0.1f32, -0.2, 0.3, -0.4];
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+        let mut converter = CaptureConverter::new(
+            AudioFormat::F32LE,
+            16000,
+            1,
+            16000,
+            ChannelMix::Average,
+            ResampleQuality::Balanced,
+        );
+        assert_eq!(converter.push_bytes(&bytes), samples.to_vec());
+    }
+}
@@ -0,0 +1,108 @@
+//! ONNX 频谱增益模型（RNNoise 类降噪）
+//!
+//! 模型接口约定：
+//! - Input:  input[f32, (1, num_bins)]  对数功率谱 ln(|X|² + 1e-10)
+//! - Output: gains[f32, (1, num_bins)]  每个频点的增益 (0.0-1.0)
+//!
+//! 若模型带有循环状态（输入 "state" / 输出 "stateN"），状态在帧间自动传递。
+//!
+//! 需要启用 `denoise-onnx` feature
+
+#![cfg(feature = "denoise-onnx")]
+
+use crate::error::{VInputError, VInputResult};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Value;
+use std::path::Path;
+
+/// ONNX 频谱增益模型
+pub struct OnnxGainModel {
+    session: Session,
+    num_bins: usize,
+    /// 循环状态（模型无状态时为 None）
+    state: Option<(Vec<usize>, Vec<f32>)>,
+}
+
+impl OnnxGainModel {
+    /// 加载 ONNX 增益模型
+    pub fn new(model_path: &str, num_bins: usize) -> VInputResult<Self> {
+        if !Path::new(model_path).exists() {
+            return Err(VInputError::ModelLoad {
+                path: model_path.to_string(),
+                reason: "Denoise model file not found".to_string(),
+            });
+        }
+
+        let model_err = |reason: String| VInputError::ModelLoad {
+            path: model_path.to_string(),
+            reason,
+        };
+
+        let model_bytes = std::fs::read(model_path)
+            .map_err(|e| model_err(format!("Failed to read model file: {}", e)))?;
+
+        let session = Session::builder()
+            .map_err(|e| model_err(format!("Failed to create session builder: {}", e)))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| model_err(format!("Failed to set optimization level: {}", e)))?
+            .with_intra_threads(1)
+            .map_err(|e| model_err(format!("Failed to set intra threads: {}", e)))?
+            .commit_from_memory(&model_bytes)
+            .map_err(|e| model_err(format!("Failed to load model: {}", e)))?;
+
+        // 带循环状态的模型：第二个输入为 state，初始为全零
+        let state = session.inputs().get(1).map(|input| {
+            tracing::info!("降噪模型带循环状态输入: '{}'", input.name());
+            (vec![1usize, num_bins], vec![0.0f32; num_bins])
+        });
+
+        tracing::info!("✅ 加载 ONNX 降噪模型: {} ({} 频点)", model_path, num_bins);
+
+        Ok(Self {
+            session,
+            num_bins,
+            state,
+        })
+    }
+
+    /// 根据对数功率谱预测每个频点的增益
+    pub fn predict_gains(&mut self, log_power: &[f32]) -> VInputResult<Vec<f32>> {
+        use ort::inputs;
+
+        let input_tensor = Value::from_array((vec![1usize, self.num_bins], log_power.to_vec()))
+            .map_err(|e| VInputError::Generic(format!("Failed to create denoise input tensor: {}", e)))?;
+
+        let outputs = if let Some((shape, data)) = &self.state {
+            let state_tensor = Value::from_array((shape.clone(), data.clone()))
+                .map_err(|e| VInputError::Generic(format!("Failed to create denoise state tensor: {}", e)))?;
+            self.session.run(inputs![input_tensor, state_tensor])
+        } else {
+            self.session.run(inputs![input_tensor])
+        }
+        .map_err(|e| VInputError::Generic(format!("Denoise inference failed: {}", e)))?;
+
+        let (_shape, gains) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| VInputError::Generic(format!("Failed to extract gains: {}", e)))?;
+        let gains = gains.to_vec();
+
+        if self.state.is_some() && outputs.len() > 1 {
+            let (shape, new_state) = outputs[1]
+                .try_extract_tensor::<f32>()
+                .map_err(|e| VInputError::Generic(format!("Failed to extract stateN: {}", e)))?;
+            let dims = shape.iter().map(|&d| d as usize).collect();
+            self.state = Some((dims, new_state.to_vec()));
+        }
+
+        if gains.len() != self.num_bins {
+            return Err(VInputError::Generic(format!(
+                "Denoise model output size mismatch: {} != {}",
+                gains.len(),
+                self.num_bins
+            )));
+        }
+
+        Ok(gains)
+    }
+}
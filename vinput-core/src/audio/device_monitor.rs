@@ -0,0 +1,280 @@
+//! 音频设备选择与热插拔监控
+//!
+//! 按配置的首选设备列表（优先级从高到低）选择捕获设备：
+//! - 首选设备全部不可用时回退到系统默认音频源
+//! - 后台线程定期枚举设备，首选设备拔出/重新插入时请求切换
+
+use crate::audio::pipewire_stream::{enumerate_audio_devices, AudioDevice};
+use crate::error::VInputResult;
+use std::process::Child;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// 设备切换原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceSwitchReason {
+    /// 开始录音时选定
+    Initial,
+    /// 首选设备（重新）可用，切回首选设备
+    PreferredAvailable,
+    /// 首选设备不可用，回退到默认音频源
+    Fallback,
+}
+
+impl DeviceSwitchReason {
+    /// FFI 载荷中使用的标识
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeviceSwitchReason::Initial => "initial",
+            DeviceSwitchReason::PreferredAvailable => "preferred",
+            DeviceSwitchReason::Fallback => "fallback",
+        }
+    }
+}
+
+/// 活动设备变化事件
+#[derive(Debug, Clone)]
+pub struct DeviceEvent {
+    /// 当前使用的设备（None = 无法识别的默认音频源）
+    pub device: Option<AudioDevice>,
+    /// 切换原因
+    pub reason: DeviceSwitchReason,
+}
+
+impl DeviceEvent {
+    /// 编码为 FFI 载荷：`name\tdescription\treason`
+    pub fn to_payload(&self) -> String {
+        let (name, description) = match &self.device {
+            Some(device) => (device.name.as_str(), device.description.as_str()),
+            None => ("", "默认音频源"),
+        };
+        format!("{}\t{}\t{}", name, description, self.reason.as_str())
+    }
+}
+
+/// 设备事件监听器
+pub type DeviceEventListener = Arc<dyn Fn(&DeviceEvent) + Send + Sync>;
+
+/// 设备选择结果
+#[derive(Debug, Clone)]
+pub struct DeviceSelection {
+    /// 选中的设备（None = 默认音频源，且未能在列表中找到）
+    pub device: Option<AudioDevice>,
+    /// 是否命中首选设备列表
+    pub is_preferred: bool,
+}
+
+impl DeviceSelection {
+    /// 传给 pw-record `--target` 的节点名（None = 默认音频源）
+    pub fn target(&self) -> Option<&str> {
+        self.device.as_ref().map(|d| d.name.as_str())
+    }
+}
+
+/// 从可用设备中按首选列表选择设备
+///
+/// 首选项既可以是设备名称也可以是设备 ID
+pub fn select_device(preferred: &[String], devices: &[AudioDevice]) -> DeviceSelection {
+    for wanted in preferred {
+        if let Some(device) = devices.iter().find(|d| &d.name == wanted || &d.id == wanted) {
+            return DeviceSelection {
+                device: Some(device.clone()),
+                is_preferred: true,
+            };
+        }
+    }
+
+    DeviceSelection {
+        device: devices.iter().find(|d| d.is_default).cloned(),
+        is_preferred: false,
+    }
+}
+
+/// 枚举当前设备并选择（枚举失败时视为默认音频源）
+pub fn select_current_device(preferred: &[String]) -> DeviceSelection {
+    match enumerate_audio_devices() {
+        Ok(devices) => select_device(preferred, &devices),
+        Err(e) => {
+            tracing::warn!("枚举音频设备失败: {}，使用默认音频源", e);
+            DeviceSelection {
+                device: None,
+                is_preferred: false,
+            }
+        }
+    }
+}
+
+/// 监控轮询时期望的捕获目标
+///
+/// 返回 None 表示本轮枚举失败、应跳过比较：一次 pactl 失败不代表首选设备
+/// 已拔出，回退到默认音频源会无谓地重启捕获。只有枚举成功且找不到首选
+/// 设备时才回退。
+pub fn poll_desired_target(
+    preferred: &[String],
+    devices: VInputResult<Vec<AudioDevice>>,
+) -> Option<Option<String>> {
+    match devices {
+        Ok(devices) => Some(select_device(preferred, &devices).target().map(str::to_string)),
+        Err(e) => {
+            tracing::debug!("枚举音频设备失败: {}，跳过本轮设备检查", e);
+            None
+        }
+    }
+}
+
+/// 设备热插拔监控线程
+///
+/// 发现期望设备与当前捕获设备不一致时结束当前 pw-record 子进程，
+/// 由捕获循环重新选择设备并重启捕获
+pub struct DeviceMonitor {
+    quit: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl DeviceMonitor {
+    /// 启动监控线程
+    ///
+    /// - `active_target`: 捕获循环当前使用的节点名
+    /// - `child`: 当前 pw-record 子进程
+    pub fn spawn(
+        preferred: Vec<String>,
+        poll_interval: Duration,
+        active_target: Arc<Mutex<Option<String>>>,
+        child: Arc<Mutex<Option<Child>>>,
+    ) -> Self {
+        let quit = Arc::new(AtomicBool::new(false));
+        let quit_clone = quit.clone();
+
+        let handle = thread::spawn(move || {
+            tracing::info!("音频设备监控线程启动 (间隔 {:?})", poll_interval);
+            let step = Duration::from_millis(100);
+
+            while !quit_clone.load(Ordering::Acquire) {
+                // 分段休眠，保证停止请求能及时响应
+                let mut waited = Duration::ZERO;
+                while waited < poll_interval && !quit_clone.load(Ordering::Acquire) {
+                    thread::sleep(step);
+                    waited += step;
+                }
+                if quit_clone.load(Ordering::Acquire) {
+                    break;
+                }
+
+                let Some(desired_target) =
+                    poll_desired_target(&preferred, enumerate_audio_devices())
+                else {
+                    continue;
+                };
+                let current = active_target.lock().unwrap().clone();
+
+                if desired_target != current {
+                    tracing::info!(
+                        "检测到音频设备变化: {:?} → {:?}，重启捕获",
+                        current,
+                        desired_target
+                    );
+                    if let Some(child) = child.lock().unwrap().as_mut() {
+                        let _ = child.kill();
+                    }
+                }
+            }
+
+            tracing::info!("音频设备监控线程退出");
+        });
+
+        Self {
+            quit,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for DeviceMonitor {
+    fn drop(&mut self) {
+        self.quit.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str, name: &str, is_default: bool) -> AudioDevice {
+        AudioDevice {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: name.to_string(),
+            is_default,
+            sample_spec: None,
+        }
+    }
+
+    #[test]
+    fn test_select_first_available_preferred() {
+        let devices = vec![
+            device("1", "builtin", true),
+            device("2", "usb-mic", false),
+        ];
+        let preferred = vec!["headset".to_string(), "usb-mic".to_string()];
+
+        let selection = select_device(&preferred, &devices);
+        assert!(selection.is_preferred);
+        assert_eq!(selection.target(), Some("usb-mic"));
+    }
+
+    #[test]
+    fn test_fallback_to_default_when_unplugged() {
+        let devices = vec![device("1", "builtin", true)];
+        let preferred = vec!["usb-mic".to_string()];
+
+        let selection = select_device(&preferred, &devices);
+        assert!(!selection.is_preferred);
+        assert_eq!(selection.target(), Some("builtin"));
+
+        // 没有默认设备信息时交给 PipeWire 选择
+        let selection = select_device(&preferred, &[device("1", "builtin", false)]);
+        assert_eq!(selection.target(), None);
+    }
+
+    #[test]
+    fn test_select_by_id_and_payload() {
+        let devices = vec![device("1", "builtin", true), device("42", "usb-mic", false)];
+        let selection = select_device(&["42".to_string()], &devices);
+        assert_eq!(selection.target(), Some("usb-mic"));
+
+        let event = DeviceEvent {
+            device: selection.device,
+            reason: DeviceSwitchReason::PreferredAvailable,
+        };
+        assert_eq!(event.to_payload(), "usb-mic\tusb-mic\tpreferred");
+    }
+
+    #[test]
+    fn test_poll_skips_failed_enumeration() {
+        let preferred = vec!["usb-mic".to_string()];
+
+        // 枚举失败：跳过比较，不回退到默认音频源
+        let failed = Err(crate::error::VInputError::PipeWire("pactl 超时".to_string()));
+        assert_eq!(poll_desired_target(&preferred, failed), None);
+
+        // 枚举成功但首选设备不在：回退到默认音频源
+        let devices = Ok(vec![device("1", "builtin", true)]);
+        assert_eq!(
+            poll_desired_target(&preferred, devices),
+            Some(Some("builtin".to_string()))
+        );
+
+        let devices = Ok(vec![device("1", "builtin", true), device("2", "usb-mic", false)]);
+        assert_eq!(
+            poll_desired_target(&preferred, devices),
+            Some(Some("usb-mic".to_string()))
+        );
+    }
+}
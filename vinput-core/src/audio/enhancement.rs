@@ -0,0 +1,516 @@
+//! 语音增强（降噪）
+//!
+//! 位于 PipeWire 捕获与 VAD/ASR 之间的可选处理阶段：
+//! - 谱减法 (Spectral Subtraction)
+//! - 维纳滤波 (Wiener, 判决引导先验信噪比)
+//! - ONNX 增益模型（RNNoise 类，需要 `denoise-onnx` feature）
+//!
+//! 采用 512 点 STFT、256 样本跳步（sqrt-Hann 窗，重叠相加），
+//! 固定延迟 256 样本（16ms @ 16kHz），与 32ms 的处理帧对齐。
+
+use crate::audio::wav::WavWriter;
+use crate::error::VInputResult;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+pub use vinput_config::audio::{EnhancementConfig, EnhancementMethod};
+
+/// FFT 窗长（样本数）
+const FFT_SIZE: usize = 512;
+/// 跳步（样本数）
+const HOP_SIZE: usize = FFT_SIZE / 2;
+/// 频点数（含直流与奈奎斯特）
+const NUM_BINS: usize = FFT_SIZE / 2 + 1;
+
+/// 复数（FFT 内部使用）
+#[derive(Debug, Clone, Copy, Default)]
+struct Complex {
+    re: f32,
+    im: f32,
+}
+
+/// 原地基 2 FFT（`inverse = true` 时为未归一化的逆变换）
+fn fft_in_place(buf: &mut [Complex], inverse: bool) {
+    let n = buf.len();
+    debug_assert!(n.is_power_of_two());
+
+    // 位反转置换
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            buf.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let angle = sign * 2.0 * std::f32::consts::PI / len as f32;
+        let (w_re, w_im) = (angle.cos(), angle.sin());
+        for start in (0..n).step_by(len) {
+            let (mut cur_re, mut cur_im) = (1.0f32, 0.0f32);
+            for k in 0..len / 2 {
+                let a = buf[start + k];
+                let b = buf[start + k + len / 2];
+                let t = Complex {
+                    re: b.re * cur_re - b.im * cur_im,
+                    im: b.re * cur_im + b.im * cur_re,
+                };
+                buf[start + k] = Complex { re: a.re + t.re, im: a.im + t.im };
+                buf[start + k + len / 2] = Complex { re: a.re - t.re, im: a.im - t.im };
+                let next_re = cur_re * w_re - cur_im * w_im;
+                cur_im = cur_re * w_im + cur_im * w_re;
+                cur_re = next_re;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// 增强前后音频转储（用于参数调优）
+struct EnhancementDump {
+    before: WavWriter,
+    after: WavWriter,
+}
+
+impl EnhancementDump {
+    fn open(dir: &str) -> VInputResult<Self> {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        let dir = PathBuf::from(dir);
+        let before_path = dir.join(format!("enhance-{}-before.wav", stamp));
+        let after_path = dir.join(format!("enhance-{}-after.wav", stamp));
+        tracing::info!("🎙️ 语音增强转储: {:?} / {:?}", before_path, after_path);
+
+        Ok(Self {
+            before: WavWriter::create(&before_path, 16000)?,
+            after: WavWriter::create(&after_path, 16000)?,
+        })
+    }
+}
+
+/// 语音增强器
+///
+/// 输入任意长度的 16kHz 单声道样本，按跳步输出增强后的样本。
+/// 输入长度为 256 的整数倍时（如 512 样本帧），输出长度与输入相同。
+pub struct SpeechEnhancer {
+    config: EnhancementConfig,
+    method: EnhancementMethod,
+
+    /// sqrt-Hann 窗（分析与合成共用）
+    window: Vec<f32>,
+    /// 分析历史（最近 FFT_SIZE 个输入样本）
+    analysis: Vec<f32>,
+    /// 重叠相加缓冲
+    overlap: Vec<f32>,
+    /// 尚未凑满一个跳步的输入
+    pending: VecDeque<f32>,
+    /// FFT 工作缓冲
+    spectrum: Vec<Complex>,
+
+    /// 噪声功率谱估计
+    noise_psd: Vec<f32>,
+    /// 已累计的噪声估计帧数
+    noise_frames: usize,
+    /// 上一帧增益（维纳判决引导法）
+    prev_gain: Vec<f32>,
+    /// 上一帧后验信噪比（维纳判决引导法）
+    prev_post_snr: Vec<f32>,
+
+    #[cfg(feature = "denoise-onnx")]
+    onnx_model: Option<crate::audio::denoise_onnx::OnnxGainModel>,
+
+    dump: Option<EnhancementDump>,
+}
+
+impl SpeechEnhancer {
+    /// 创建语音增强器
+    pub fn new(config: EnhancementConfig) -> VInputResult<Self> {
+        // 周期 Hann 窗的平方根：50% 重叠时分析×合成之和恒为 1
+        let window = (0..FFT_SIZE)
+            .map(|i| {
+                let hann = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos();
+                hann.sqrt()
+            })
+            .collect();
+
+        #[cfg(feature = "denoise-onnx")]
+        let onnx_model = if config.method == EnhancementMethod::Onnx {
+            match &config.model_path {
+                Some(path) => Some(crate::audio::denoise_onnx::OnnxGainModel::new(path, NUM_BINS)?),
+                None => {
+                    tracing::warn!("语音增强: Onnx 算法未配置 model_path，回退到维纳滤波");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        #[cfg(feature = "denoise-onnx")]
+        let method = if config.method == EnhancementMethod::Onnx && onnx_model.is_none() {
+            EnhancementMethod::Wiener
+        } else {
+            config.method
+        };
+
+        #[cfg(not(feature = "denoise-onnx"))]
+        let method = if config.method == EnhancementMethod::Onnx {
+            tracing::warn!("语音增强: 未启用 denoise-onnx feature，回退到维纳滤波");
+            EnhancementMethod::Wiener
+        } else {
+            config.method
+        };
+
+        tracing::info!(
+            "创建语音增强器: {:?}, floor={}, 延迟={}ms",
+            method,
+            config.spectral_floor,
+            HOP_SIZE * 1000 / 16000
+        );
+
+        Ok(Self {
+            method,
+            window,
+            analysis: vec![0.0; FFT_SIZE],
+            overlap: vec![0.0; FFT_SIZE],
+            pending: VecDeque::with_capacity(FFT_SIZE),
+            spectrum: vec![Complex::default(); FFT_SIZE],
+            noise_psd: vec![0.0; NUM_BINS],
+            noise_frames: 0,
+            prev_gain: vec![1.0; NUM_BINS],
+            prev_post_snr: vec![1.0; NUM_BINS],
+            #[cfg(feature = "denoise-onnx")]
+            onnx_model,
+            dump: None,
+            config,
+        })
+    }
+
+    /// 处理音频样本，返回增强后的样本（固定延迟 `latency_samples()`）
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.pending.extend(samples.iter().copied());
+
+        let mut output = Vec::with_capacity(samples.len());
+        while self.pending.len() >= HOP_SIZE {
+            let hop: Vec<f32> = self.pending.drain(..HOP_SIZE).collect();
+            self.process_hop(&hop, &mut output);
+        }
+
+        if let Some(dir) = self.config.dump_dir.clone() {
+            if self.dump.is_none() {
+                match EnhancementDump::open(&dir) {
+                    Ok(dump) => self.dump = Some(dump),
+                    Err(e) => {
+                        tracing::warn!("语音增强转储打开失败，已禁用: {}", e);
+                        self.config.dump_dir = None;
+                    }
+                }
+            }
+            if let Some(dump) = &mut self.dump {
+                let _ = dump.before.write_samples(samples);
+                let _ = dump.after.write_samples(&output);
+            }
+        }
+
+        output
+    }
+
+    /// 处理一个跳步（HOP_SIZE 个新样本），输出 HOP_SIZE 个增强样本
+    fn process_hop(&mut self, hop: &[f32], output: &mut Vec<f32>) {
+        // 1. 更新分析窗口
+        self.analysis.copy_within(HOP_SIZE.., 0);
+        self.analysis[FFT_SIZE - HOP_SIZE..].copy_from_slice(hop);
+
+        // 2. 加窗 + FFT
+        for (i, bin) in self.spectrum.iter_mut().enumerate() {
+            *bin = Complex {
+                re: self.analysis[i] * self.window[i],
+                im: 0.0,
+            };
+        }
+        fft_in_place(&mut self.spectrum, false);
+
+        // 3. 计算功率谱并更新噪声估计
+        let power: Vec<f32> = self.spectrum[..NUM_BINS]
+            .iter()
+            .map(|c| c.re * c.re + c.im * c.im)
+            .collect();
+        self.update_noise(&power);
+
+        // 4. 计算并应用增益（对称频点共用增益，保证时域输出为实数）
+        let gains = self.compute_gains(&power);
+        for (k, &g) in gains.iter().enumerate() {
+            self.spectrum[k].re *= g;
+            self.spectrum[k].im *= g;
+            if k > 0 && k < FFT_SIZE - k {
+                self.spectrum[FFT_SIZE - k].re *= g;
+                self.spectrum[FFT_SIZE - k].im *= g;
+            }
+        }
+
+        // 5. IFFT + 合成窗 + 重叠相加
+        fft_in_place(&mut self.spectrum, true);
+        let scale = 1.0 / FFT_SIZE as f32;
+        for i in 0..FFT_SIZE {
+            self.overlap[i] += self.spectrum[i].re * scale * self.window[i];
+        }
+
+        output.extend_from_slice(&self.overlap[..HOP_SIZE]);
+        self.overlap.copy_within(HOP_SIZE.., 0);
+        self.overlap[FFT_SIZE - HOP_SIZE..].fill(0.0);
+    }
+
+    /// 更新噪声功率谱估计
+    ///
+    /// 前 `noise_init_frames` 帧直接平均；之后仅在整帧能量接近噪声基线时平滑更新，
+    /// 并允许噪声估计随更低的功率快速下降（防止被语音锁死在高位）
+    fn update_noise(&mut self, power: &[f32]) {
+        if self.noise_frames < self.config.noise_init_frames {
+            let n = self.noise_frames as f32;
+            for (noise, &p) in self.noise_psd.iter_mut().zip(power) {
+                *noise = (*noise * n + p) / (n + 1.0);
+            }
+            self.noise_frames += 1;
+            return;
+        }
+
+        let frame_power: f32 = power.iter().sum();
+        let noise_power: f32 = self.noise_psd.iter().sum();
+        let alpha = self.config.noise_update_alpha;
+        let is_noise_frame = frame_power < noise_power * 2.0;
+
+        for (noise, &p) in self.noise_psd.iter_mut().zip(power) {
+            if is_noise_frame {
+                *noise = alpha * *noise + (1.0 - alpha) * p;
+            } else if p < *noise {
+                *noise = 0.8 * *noise + 0.2 * p;
+            }
+        }
+    }
+
+    /// 计算每个频点的增益
+    fn compute_gains(&mut self, power: &[f32]) -> Vec<f32> {
+        let floor = self.config.spectral_floor.clamp(0.0, 1.0);
+
+        // 噪声估计尚未建立：透传
+        if self.noise_frames < self.config.noise_init_frames {
+            return vec![1.0; NUM_BINS];
+        }
+
+        match self.method {
+            EnhancementMethod::SpectralSubtraction => {
+                let beta = self.config.over_subtraction;
+                power
+                    .iter()
+                    .zip(&self.noise_psd)
+                    .map(|(&p, &n)| {
+                        let g2 = 1.0 - beta * n / p.max(f32::EPSILON);
+                        g2.max(floor * floor).sqrt()
+                    })
+                    .collect()
+            }
+            EnhancementMethod::Wiener => self.wiener_gains(power, floor),
+            EnhancementMethod::Onnx => {
+                #[cfg(feature = "denoise-onnx")]
+                if let Some(model) = &mut self.onnx_model {
+                    let log_power: Vec<f32> = power.iter().map(|&p| (p + 1e-10).ln()).collect();
+                    match model.predict_gains(&log_power) {
+                        Ok(gains) => return gains.into_iter().map(|g| g.clamp(floor, 1.0)).collect(),
+                        Err(e) => tracing::warn!("ONNX 降噪推理失败，本帧使用维纳滤波: {}", e),
+                    }
+                }
+                self.wiener_gains(power, floor)
+            }
+        }
+    }
+
+    /// 维纳滤波增益（判决引导法估计先验信噪比）
+    fn wiener_gains(&mut self, power: &[f32], floor: f32) -> Vec<f32> {
+        const DD_ALPHA: f32 = 0.98;
+        let mut gains = Vec::with_capacity(NUM_BINS);
+
+        for (k, &p) in power.iter().enumerate().take(NUM_BINS) {
+            let post_snr = p / self.noise_psd[k].max(f32::EPSILON);
+            let prior_snr = DD_ALPHA * self.prev_gain[k] * self.prev_gain[k] * self.prev_post_snr[k]
+                + (1.0 - DD_ALPHA) * (post_snr - 1.0).max(0.0);
+            let gain = (prior_snr / (1.0 + prior_snr)).max(floor);
+
+            self.prev_gain[k] = gain;
+            self.prev_post_snr[k] = post_snr;
+            gains.push(gain);
+        }
+
+        gains
+    }
+
+    /// 重置流式缓冲（保留噪声估计），并结束当前转储文件
+    pub fn reset(&mut self) {
+        self.analysis.fill(0.0);
+        self.overlap.fill(0.0);
+        self.pending.clear();
+        self.prev_gain.fill(1.0);
+        self.prev_post_snr.fill(1.0);
+        if let Some(mut dump) = self.dump.take() {
+            let _ = dump.before.finish();
+            let _ = dump.after.finish();
+        }
+    }
+
+    /// 处理延迟（样本数）
+    pub fn latency_samples(&self) -> usize {
+        FFT_SIZE - HOP_SIZE
+    }
+
+    /// 实际使用的增强算法（Onnx 不可用时为回退后的算法）
+    pub fn method(&self) -> EnhancementMethod {
+        self.method
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &EnhancementConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 确定性伪随机白噪声（LCG）
+    fn white_noise(len: usize, amplitude: f32, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                ((state >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0) * amplitude
+            })
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn test_fft_roundtrip() {
+        let input: Vec<f32> = (0..FFT_SIZE).map(|i| (i as f32 * 0.1).sin()).collect();
+        let mut buf: Vec<Complex> = input.iter().map(|&re| Complex { re, im: 0.0 }).collect();
+
+        fft_in_place(&mut buf, false);
+        fft_in_place(&mut buf, true);
+
+        for (orig, c) in input.iter().zip(&buf) {
+            assert!((orig - c.re / FFT_SIZE as f32).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_output_length_matches_frames() {
+        let mut enhancer = SpeechEnhancer::new(EnhancementConfig::default()).unwrap();
+        for _ in 0..5 {
+            assert_eq!(enhancer.process(&[0.0; 512]).len(), 512);
+        }
+        // 不足一个跳步的输入暂存，不输出
+        assert_eq!(enhancer.process(&[0.0; 100]).len(), 0);
+        assert_eq!(enhancer.process(&[0.0; 156]).len(), HOP_SIZE);
+    }
+
+    #[test]
+    fn test_passthrough_reconstruction() {
+        // spectral_floor = 1.0 时增益恒为 1，输出应为延迟后的输入
+        let config = EnhancementConfig {
+            spectral_floor: 1.0,
+            ..Default::default()
+        };
+        let mut enhancer = SpeechEnhancer::new(config).unwrap();
+        let latency = enhancer.latency_samples();
+
+        let input: Vec<f32> = (0..16384)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin() * 0.5)
+            .collect();
+        let output: Vec<f32> = input.chunks(512).flat_map(|c| enhancer.process(c)).collect();
+
+        for i in FFT_SIZE..input.len() - latency {
+            assert!(
+                (output[i + latency] - input[i]).abs() < 1e-3,
+                "样本 {} 重建误差过大",
+                i
+            );
+        }
+    }
+
+    #[test]
+    fn test_noise_reduction_wiener() {
+        let mut enhancer = SpeechEnhancer::new(EnhancementConfig::default()).unwrap();
+        let noise = white_noise(32000, 0.05, 42);
+
+        let output: Vec<f32> = noise.chunks(512).flat_map(|c| enhancer.process(c)).collect();
+
+        // 跳过噪声估计建立阶段，比较稳态噪声能量
+        let in_rms = rms(&noise[8000..]);
+        let out_rms = rms(&output[8000..]);
+        assert!(out_rms < in_rms * 0.5, "降噪不足: in={} out={}", in_rms, out_rms);
+    }
+
+    #[test]
+    fn test_tone_preserved_spectral_subtraction() {
+        let config = EnhancementConfig {
+            method: EnhancementMethod::SpectralSubtraction,
+            ..Default::default()
+        };
+        let mut enhancer = SpeechEnhancer::new(config).unwrap();
+
+        // 先给 0.5 秒纯噪声建立噪声估计，再叠加强音调
+        let mut input = white_noise(8000, 0.01, 7);
+        let tone_noise = white_noise(8000, 0.01, 8);
+        input.extend((0..8000).map(|i| {
+            (2.0 * std::f32::consts::PI * 500.0 * i as f32 / 16000.0).sin() * 0.5 + tone_noise[i]
+        }));
+
+        let output: Vec<f32> = input.chunks(512).flat_map(|c| enhancer.process(c)).collect();
+
+        let tone_in = rms(&input[10000..15000]);
+        let tone_out = rms(&output[10000..15000]);
+        assert!(tone_out > tone_in * 0.8, "语音被过度抑制: in={} out={}", tone_in, tone_out);
+    }
+
+    #[test]
+    fn test_onnx_falls_back_without_model() {
+        let config = EnhancementConfig {
+            method: EnhancementMethod::Onnx,
+            ..Default::default()
+        };
+        let enhancer = SpeechEnhancer::new(config).unwrap();
+        assert_eq!(enhancer.method(), EnhancementMethod::Wiener);
+    }
+
+    #[test]
+    fn test_dump_writes_wav_pair() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = EnhancementConfig {
+            enabled: true,
+            dump_dir: Some(dir.path().to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let mut enhancer = SpeechEnhancer::new(config).unwrap();
+        enhancer.process(&[0.1; 512]);
+        enhancer.reset();
+
+        let names: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.iter().any(|n| n.ends_with("-before.wav")));
+        assert!(names.iter().any(|n| n.ends_with("-after.wav")));
+    }
+}
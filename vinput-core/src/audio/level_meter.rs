@@ -0,0 +1,382 @@
+//! 麦克风电平表与输入诊断
+//!
+//! 逐帧计算 RMS / 峰值，并检测：
+//! - 削波（样本接近满幅）
+//! - 长时间无信号（麦克风静音或设备无输出）
+//! - 输入增益过低（有类似语音的输入，但始终达不到电平下限：配置的下限与 Energy Gate 阈值中较高者）
+//!
+//! 增益诊断只统计明显高于环境噪声的帧：说话停顿期间只有稳定的环境噪声，不会误报。
+
+pub use vinput_config::audio::LevelMeterConfig;
+
+/// 高于噪声基底多少 dB 视为有类似语音的输入
+const ACTIVITY_MARGIN_DB: f32 = 6.0;
+
+/// 噪声基底每帧最多上升的 dB（下降立即跟随）
+const NOISE_FLOOR_RISE_DB: f32 = 0.05;
+
+/// 一个上报周期内的电平读数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelReading {
+    /// RMS (线性)
+    pub rms: f32,
+    /// 峰值 (线性)
+    pub peak: f32,
+    /// RMS (dBFS)
+    pub rms_dbfs: f32,
+    /// 峰值 (dBFS)
+    pub peak_dbfs: f32,
+    /// 削波指示（含保持时间）
+    pub clipping: bool,
+}
+
+impl LevelReading {
+    /// 编码为 FFI 载荷：`rms_dbfs\tpeak_dbfs\tclipping`
+    pub fn to_payload(&self) -> String {
+        format!(
+            "{:.1}\t{:.1}\t{}",
+            self.rms_dbfs,
+            self.peak_dbfs,
+            u8::from(self.clipping)
+        )
+    }
+}
+
+/// 输入诊断警告
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelWarning {
+    /// 检测到削波
+    Clipping {
+        /// 本帧削波样本比例
+        ratio: f32,
+    },
+    /// 长时间无信号
+    NoSignal {
+        /// 已持续的秒数
+        seconds: f32,
+    },
+    /// 输入增益过低：说话的电平始终低于下限（语音无法通过 Energy Gate 或过于微弱）
+    LowGain {
+        /// 观察期间语音帧的最高 RMS (dBFS)
+        max_rms_dbfs: f32,
+        /// 增益过低判定下限 (dBFS)
+        floor_dbfs: f32,
+    },
+}
+
+impl LevelWarning {
+    /// 警告类型标识
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LevelWarning::Clipping { .. } => "clipping",
+            LevelWarning::NoSignal { .. } => "no_signal",
+            LevelWarning::LowGain { .. } => "low_gain",
+        }
+    }
+
+    /// 面向用户的提示
+    pub fn message(&self) -> String {
+        match self {
+            LevelWarning::Clipping { ratio } => {
+                format!("麦克风输入削波（{:.1}% 样本满幅），请降低输入增益", ratio * 100.0)
+            }
+            LevelWarning::NoSignal { seconds } => {
+                format!("已有 {:.0} 秒没有检测到麦克风信号，请检查麦克风是否静音", seconds)
+            }
+            LevelWarning::LowGain {
+                max_rms_dbfs,
+                floor_dbfs,
+            } => format!(
+                "麦克风音量过低（说话最高 {:.0} dBFS，低于 {:.0} dBFS），请提高输入增益",
+                max_rms_dbfs, floor_dbfs
+            ),
+        }
+    }
+
+    /// 编码为 FFI 载荷：`kind\tmessage`
+    pub fn to_payload(&self) -> String {
+        format!("{}\t{}", self.kind(), self.message())
+    }
+}
+
+/// 单次处理的输出
+#[derive(Debug, Clone, Default)]
+pub struct LevelUpdate {
+    /// 到达上报间隔时的电平读数
+    pub reading: Option<LevelReading>,
+    /// 新产生的警告
+    pub warnings: Vec<LevelWarning>,
+}
+
+/// 线性幅度转 dBFS（下限 -120 dB）
+pub fn to_dbfs(amplitude: f32) -> f32 {
+    if amplitude <= 1e-6 {
+        -120.0
+    } else {
+        20.0 * amplitude.log10()
+    }
+}
+
+/// 电平表
+pub struct LevelMeter {
+    config: LevelMeterConfig,
+    sample_rate: u32,
+
+    // 当前上报周期的累积
+    period_samples: usize,
+    period_sum_squares: f64,
+    period_peak: f32,
+
+    /// 削波指示剩余保持样本数
+    clip_hold_remaining: usize,
+
+    /// 连续无信号样本数
+    silent_samples: usize,
+    no_signal_warned: bool,
+
+    /// 噪声基底估计 (dBFS，尚无输入时为 None)
+    noise_floor_dbfs: Option<f32>,
+    /// 累计的弱语音样本数（类似语音但低于电平下限）
+    low_gain_samples: usize,
+    low_gain_max_rms: f32,
+    low_gain_warned: bool,
+}
+
+impl LevelMeter {
+    /// 创建电平表
+    pub fn new(config: LevelMeterConfig, sample_rate: u32) -> Self {
+        Self {
+            config,
+            sample_rate,
+            period_samples: 0,
+            period_sum_squares: 0.0,
+            period_peak: 0.0,
+            clip_hold_remaining: 0,
+            silent_samples: 0,
+            no_signal_warned: false,
+            noise_floor_dbfs: None,
+            low_gain_samples: 0,
+            low_gain_max_rms: 0.0,
+            low_gain_warned: false,
+        }
+    }
+
+    /// 处理一帧音频
+    ///
+    /// `gate_threshold` 为 Energy Gate 当前阈值（线性 RMS，禁用时为 None）；
+    /// 增益判定下限取 `low_gain_dbfs` 与该阈值中较高者
+    pub fn process(&mut self, samples: &[f32], gate_threshold: Option<f32>) -> LevelUpdate {
+        let mut update = LevelUpdate::default();
+        if !self.config.enabled || samples.is_empty() {
+            return update;
+        }
+
+        let sum_squares: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        let rms = (sum_squares / samples.len() as f64).sqrt() as f32;
+
+        // 削波：新的削波段开始时警告一次
+        let clipped = samples.iter().filter(|s| s.abs() >= self.config.clip_level).count();
+        if clipped > 0 {
+            if self.clip_hold_remaining == 0 {
+                update.warnings.push(LevelWarning::Clipping {
+                    ratio: clipped as f32 / samples.len() as f32,
+                });
+            }
+            self.clip_hold_remaining = self.ms_to_samples(self.config.clip_hold_ms);
+        } else {
+            self.clip_hold_remaining = self.clip_hold_remaining.saturating_sub(samples.len());
+        }
+
+        // 无信号
+        let silent = to_dbfs(rms) < self.config.silence_dbfs;
+        if silent {
+            self.silent_samples += samples.len();
+            let seconds = self.silent_samples as f32 / self.sample_rate as f32;
+            if !self.no_signal_warned && seconds >= self.config.no_signal_secs {
+                self.no_signal_warned = true;
+                update.warnings.push(LevelWarning::NoSignal { seconds });
+            }
+        } else {
+            self.silent_samples = 0;
+            self.no_signal_warned = false;
+        }
+
+        // 增益过低：累计足够长的类似语音输入，且没有一帧达到电平下限
+        let rms_dbfs = to_dbfs(rms);
+        let noise_floor = self.track_noise_floor(rms_dbfs);
+        let floor_dbfs = gate_threshold.map_or(self.config.low_gain_dbfs, |threshold| {
+            self.config.low_gain_dbfs.max(to_dbfs(threshold))
+        });
+        if !silent && rms_dbfs >= noise_floor + ACTIVITY_MARGIN_DB {
+            if rms_dbfs >= floor_dbfs {
+                self.low_gain_samples = 0;
+                self.low_gain_max_rms = 0.0;
+                self.low_gain_warned = false;
+            } else {
+                self.low_gain_samples += samples.len();
+                self.low_gain_max_rms = self.low_gain_max_rms.max(rms);
+                let seconds = self.low_gain_samples as f32 / self.sample_rate as f32;
+                if !self.low_gain_warned && seconds >= self.config.low_gain_secs {
+                    self.low_gain_warned = true;
+                    update.warnings.push(LevelWarning::LowGain {
+                        max_rms_dbfs: to_dbfs(self.low_gain_max_rms),
+                        floor_dbfs,
+                    });
+                }
+            }
+        }
+
+        // 电平上报
+        self.period_samples += samples.len();
+        self.period_sum_squares += sum_squares;
+        self.period_peak = self.period_peak.max(peak);
+
+        if self.period_samples >= self.ms_to_samples(self.config.report_interval_ms) {
+            let rms = (self.period_sum_squares / self.period_samples as f64).sqrt() as f32;
+            update.reading = Some(LevelReading {
+                rms,
+                peak: self.period_peak,
+                rms_dbfs: to_dbfs(rms),
+                peak_dbfs: to_dbfs(self.period_peak),
+                clipping: self.clip_hold_remaining > 0,
+            });
+            self.period_samples = 0;
+            self.period_sum_squares = 0.0;
+            self.period_peak = 0.0;
+        }
+
+        update
+    }
+
+    /// 重置诊断状态
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config.clone(), self.sample_rate);
+    }
+
+    /// 更新并返回噪声基底：电平低于基底时立即跟随，高于时缓慢上升
+    fn track_noise_floor(&mut self, rms_dbfs: f32) -> f32 {
+        let floor = match self.noise_floor_dbfs {
+            Some(floor) if rms_dbfs > floor => (floor + NOISE_FLOOR_RISE_DB).min(rms_dbfs),
+            _ => rms_dbfs,
+        };
+        self.noise_floor_dbfs = Some(floor);
+        floor
+    }
+
+    fn ms_to_samples(&self, ms: u64) -> usize {
+        (self.sample_rate as u64 * ms / 1000) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: usize = 512;
+
+    fn sine_frame(amplitude: f32) -> Vec<f32> {
+        (0..FRAME)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin() * amplitude)
+            .collect()
+    }
+
+    #[test]
+    fn test_reading_levels() {
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        let frame = sine_frame(0.5);
+
+        let reading = (0..4)
+            .find_map(|_| meter.process(&frame, None).reading)
+            .expect("100ms 内应产生读数");
+
+        // 正弦 RMS = A / √2 ≈ -9 dBFS，峰值 ≈ -6 dBFS
+        assert!((reading.rms_dbfs - -9.03).abs() < 0.2, "rms: {}", reading.rms_dbfs);
+        assert!((reading.peak_dbfs - -6.02).abs() < 0.2, "peak: {}", reading.peak_dbfs);
+        assert!(!reading.clipping);
+    }
+
+    #[test]
+    fn test_clipping_warned_once_per_episode() {
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        let clipped = sine_frame(1.5).iter().map(|s| s.clamp(-1.0, 1.0)).collect::<Vec<_>>();
+
+        let first = meter.process(&clipped, None);
+        assert!(matches!(first.warnings.as_slice(), [LevelWarning::Clipping { .. }]));
+        assert!(meter.process(&clipped, None).warnings.is_empty());
+
+        let reading = (0..4).find_map(|_| meter.process(&sine_frame(0.1), None).reading).unwrap();
+        assert!(reading.clipping, "削波指示应保持");
+    }
+
+    #[test]
+    fn test_no_signal_detection() {
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        let silence = vec![0.0f32; FRAME];
+
+        // 3 秒 ≈ 94 帧
+        let warnings: Vec<_> = (0..100)
+            .flat_map(|_| meter.process(&silence, None).warnings)
+            .collect();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind(), "no_signal");
+
+        // 信号恢复后重新计时
+        meter.process(&sine_frame(0.3), None);
+        assert!(meter.process(&silence, None).warnings.is_empty());
+    }
+
+    /// 环境噪声上交替出现的语音（各约 0.5 秒，共约 13 秒）
+    fn speech_over_noise(meter: &mut LevelMeter, amplitude: f32, gate: Option<f32>) -> Vec<LevelWarning> {
+        let noise = sine_frame(0.0005); // ≈ -69 dBFS
+        let speech = sine_frame(amplitude);
+        (0..400)
+            .flat_map(|i| {
+                let frame = if (i / 16) % 2 == 1 { &speech } else { &noise };
+                meter.process(frame, gate).warnings
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_low_gain_on_quiet_speech() {
+        // 语音 ≈ -53 dBFS：高于噪声，但低于 -45 dBFS 下限；累计 5 秒语音 ≈ 10 秒输入
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        let warnings = speech_over_noise(&mut meter, 0.003, None);
+        assert_eq!(warnings.len(), 1);
+        match &warnings[0] {
+            LevelWarning::LowGain { max_rms_dbfs, floor_dbfs } => {
+                assert!(max_rms_dbfs < floor_dbfs);
+            }
+            other => panic!("unexpected warning: {:?}", other),
+        }
+
+        // 正常音量说话不警告
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        assert!(speech_over_noise(&mut meter, 0.1, None).is_empty());
+    }
+
+    #[test]
+    fn test_low_gain_follows_raised_gate_threshold() {
+        // 语音 ≈ -23 dBFS 高于配置下限，但 Energy Gate 阈值调高到 ≈ -14 dBFS，语音过不了门
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        let warnings = speech_over_noise(&mut meter, 0.1, Some(0.2));
+        match warnings.as_slice() {
+            [LevelWarning::LowGain { floor_dbfs, .. }] => assert!((floor_dbfs - to_dbfs(0.2)).abs() < 1e-3),
+            other => panic!("unexpected warnings: {:?}", other),
+        }
+
+        // 阈值低于配置下限时仍以配置下限为准
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        assert!(speech_over_noise(&mut meter, 0.1, Some(0.001)).is_empty());
+    }
+
+    #[test]
+    fn test_no_low_gain_during_pause_with_steady_noise() {
+        // 说话停顿：只有稳定的环境噪声（高于静音线），不视为增益过低
+        let mut meter = LevelMeter::new(LevelMeterConfig::default(), 16000);
+        let ambient = sine_frame(0.003);
+        let warnings: Vec<_> = (0..400).flat_map(|_| meter.process(&ambient, None).warnings).collect();
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+}
@@ -1,11 +1,32 @@
 //! Audio 音频捕获模块
 //!
-//! 基于 PipeWire 的实时音频录制和 Ring Buffer 传输
+//! 基于 PipeWire 的实时音频录制（或文件 / stdin / socket 输入）和 Ring Buffer 传输
 
 pub mod ring_buffer;
 pub mod pipewire_stream;
 pub mod audio_queue;
+pub mod convert;
+pub mod device_monitor;
+pub mod level_meter;
+pub mod resampler;
+pub mod source;
+pub mod enhancement;
+pub mod wav;
+
+// ONNX 降噪模型（需要 ONNX Runtime）
+#[cfg(feature = "denoise-onnx")]
+pub mod denoise_onnx;
 
 pub use ring_buffer::{AudioRingBuffer, AudioRingBufferConfig, AudioRingConsumer, AudioRingProducer};
-pub use pipewire_stream::{PipeWireStream, PipeWireStreamConfig, AudioDevice, enumerate_audio_devices};
+pub use pipewire_stream::{
+    PipeWireStream, PipeWireStreamConfig, AudioCaptureConfig, AudioDevice, AudioFormat, CaptureFormat,
+    enumerate_audio_devices, probe_device_format,
+};
+pub use convert::{CaptureConverter, ChannelMix};
+pub use level_meter::{LevelMeter, LevelMeterConfig, LevelReading, LevelUpdate, LevelWarning};
+pub use device_monitor::{DeviceEvent, DeviceEventListener, DeviceSwitchReason, select_device};
+pub use resampler::{ResampleQuality, Resampler};
+pub use source::{open_audio_source, AudioSource, AudioSourceConfig, RawPcmFormat, WavFileSource};
 pub use audio_queue::{AudioQueueManager, AudioQueueConfig, AudioQueueStats};
+pub use enhancement::{EnhancementConfig, EnhancementMethod, SpeechEnhancer};
+pub use wav::{decode_wav, encode_wav_f32, read_wav, WavData, WavWriter};
@@ -2,23 +2,32 @@
 //!
 //! Phase 1: 完整的 PipeWire 集成实现
 
+use crate::audio::convert::{CaptureConverter, ChannelMix};
+use crate::audio::device_monitor::{
+    select_current_device, DeviceEvent, DeviceEventListener, DeviceMonitor, DeviceSelection,
+    DeviceSwitchReason,
+};
+use crate::audio::resampler::ResampleQuality;
 use crate::audio::ring_buffer::AudioRingProducer;
 use crate::error::{VInputError, VInputResult};
+use std::process::Child;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, Mutex,
 };
 use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+pub use vinput_config::audio::{AudioCaptureConfig, AudioFormat};
+
 /// PipeWire 音频流配置
 #[derive(Debug, Clone)]
 pub struct PipeWireStreamConfig {
-    /// 目标采样率 (Hz)
+    /// 目标采样率 (Hz)，写入 Ring Buffer 的样本始终为该采样率的单声道 f32
     pub sample_rate: u32,
-    /// 声道数
+    /// 声道数（未启用原生捕获或探测失败时向 PipeWire 请求的声道数）
     pub channels: u32,
-    /// 音频格式（默认 F32LE）
+    /// 音频格式（未启用原生捕获或探测失败时向 PipeWire 请求的格式，默认 F32LE）
     pub format: AudioFormat,
     /// 流名称
     pub stream_name: String,
@@ -26,15 +35,16 @@ pub struct PipeWireStreamConfig {
     pub app_name: String,
     /// 目标节点（None = 默认音频源）
     pub target_node: Option<String>,
-}
-
-/// 音频格式
-#[derive(Debug, Clone, Copy)]
-pub enum AudioFormat {
-    /// 32-bit float, little-endian
-    F32LE,
-    /// 16-bit signed integer, little-endian
-    S16LE,
+    /// 首选设备列表（按优先级，非空时优先于 `target_node` 并跟随热插拔）
+    pub preferred_devices: Vec<String>,
+    /// 设备热插拔检测间隔
+    pub device_poll_interval: Duration,
+    /// 按设备原生采样率/声道捕获，由 Rust 侧完成下混与重采样
+    pub native_capture: bool,
+    /// 多声道下混方式
+    pub channel_mix: ChannelMix,
+    /// 重采样质量
+    pub resample_quality: ResampleQuality,
 }
 
 impl Default for PipeWireStreamConfig {
@@ -46,6 +56,59 @@ impl Default for PipeWireStreamConfig {
             stream_name: "V-Input Audio Capture".to_string(),
             app_name: "vinput-core".to_string(),
             target_node: None,
+            preferred_devices: Vec::new(),
+            device_poll_interval: Duration::from_millis(1000),
+            native_capture: true,
+            channel_mix: ChannelMix::default(),
+            resample_quality: ResampleQuality::default(),
+        }
+    }
+}
+
+impl PipeWireStreamConfig {
+    /// 按 `[audio]` 配置设置首选设备、下混与重采样（输出 16kHz 单声道）
+    pub fn from_capture_config(config: &AudioCaptureConfig) -> Self {
+        Self {
+            preferred_devices: config.preferred_devices.clone(),
+            device_poll_interval: Duration::from_millis(config.device_poll_interval_ms),
+            native_capture: config.native_capture,
+            channel_mix: config.channel_mix,
+            resample_quality: config.resample_quality,
+            ..Default::default()
+        }
+    }
+}
+
+/// 实际向 PipeWire 请求的捕获格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureFormat {
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u32,
+    /// 样本格式
+    pub format: AudioFormat,
+}
+
+impl PipeWireStreamConfig {
+    /// 确定捕获格式：原生模式下使用设备原生格式，未知时回退到配置的格式
+    fn resolve_capture_format(&self, device: Option<&AudioDevice>) -> CaptureFormat {
+        let fallback = CaptureFormat {
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            format: self.format,
+        };
+
+        if !self.native_capture {
+            return fallback;
+        }
+
+        match device.and_then(|d| d.sample_spec) {
+            Some(native) => native,
+            None => {
+                tracing::warn!("无法探测设备原生格式，回退到 {:?}", fallback);
+                fallback
+            }
         }
     }
 }
@@ -55,6 +118,10 @@ pub struct PipeWireStream {
     config: PipeWireStreamConfig,
     running: Arc<AtomicBool>,
     quit_signal: Arc<AtomicBool>,
+    /// 当前捕获的节点名（None = 默认音频源）
+    active_target: Arc<Mutex<Option<String>>>,
+    /// 捕获线程非正常退出的原因（未请求停止时 pw-record 启动失败或意外结束）
+    failure: Arc<Mutex<Option<String>>>,
     thread_handle: Option<JoinHandle<VInputResult<()>>>,
 }
 
@@ -63,6 +130,15 @@ impl PipeWireStream {
     pub fn new(
         config: PipeWireStreamConfig,
         producer: AudioRingProducer,
+    ) -> VInputResult<Self> {
+        Self::with_device_listener(config, producer, Arc::new(|_| {}))
+    }
+
+    /// 创建 PipeWire 音频流，并在活动设备变化时通知监听器
+    pub fn with_device_listener(
+        config: PipeWireStreamConfig,
+        producer: AudioRingProducer,
+        listener: DeviceEventListener,
     ) -> VInputResult<Self> {
         tracing::info!(
             "创建 PipeWire 流: {} Hz, {} 声道, {:?}",
@@ -73,20 +149,41 @@ impl PipeWireStream {
 
         let running = Arc::new(AtomicBool::new(false));
         let quit_signal = Arc::new(AtomicBool::new(false));
+        let active_target = Arc::new(Mutex::new(None));
+        let failure = Arc::new(Mutex::new(None));
 
         // 在单独的线程中运行 PipeWire 主循环
         let running_clone = running.clone();
         let quit_clone = quit_signal.clone();
+        let target_clone = active_target.clone();
+        let failure_clone = failure.clone();
         let config_clone = config.clone();
 
         let thread_handle = thread::spawn(move || {
-            run_pipewire_loop(config_clone, producer, running_clone, quit_clone)
+            let result = run_pipewire_loop(
+                config_clone,
+                producer,
+                running_clone,
+                quit_clone.clone(),
+                target_clone,
+                listener,
+            );
+            if !quit_clone.load(Ordering::Acquire) {
+                let reason = match &result {
+                    Err(e) => e.to_string(),
+                    Ok(()) => "pw-record 进程意外结束".to_string(),
+                };
+                *failure_clone.lock().unwrap() = Some(reason);
+            }
+            result
         });
 
         Ok(Self {
             config,
             running,
             quit_signal,
+            active_target,
+            failure,
             thread_handle: Some(thread_handle),
         })
     }
@@ -101,6 +198,16 @@ impl PipeWireStream {
         &self.config
     }
 
+    /// 当前捕获的节点名（None = 默认音频源）
+    pub fn active_target(&self) -> Option<String> {
+        self.active_target.lock().unwrap().clone()
+    }
+
+    /// 捕获是否已非正常结束（返回错误，可重新创建流恢复）
+    pub fn failure(&self) -> Option<VInputError> {
+        self.failure.lock().unwrap().clone().map(VInputError::PipeWire)
+    }
+
     /// 等待流结束（阻塞）
     pub fn join(&mut self) -> VInputResult<()> {
         if let Some(handle) = self.thread_handle.take() {
@@ -130,12 +237,50 @@ impl Drop for PipeWireStream {
     }
 }
 
+/// 选择本次捕获使用的设备
+///
+/// 配置了首选设备列表时按列表选择（不可用则回退到默认源），
+/// 否则使用 `target_node`
+fn choose_device(config: &PipeWireStreamConfig) -> DeviceSelection {
+    if !config.preferred_devices.is_empty() {
+        return select_current_device(&config.preferred_devices);
+    }
+
+    let devices = enumerate_audio_devices().unwrap_or_default();
+    let device = match &config.target_node {
+        Some(target) => devices
+            .into_iter()
+            .find(|d| &d.name == target || &d.id == target)
+            .or_else(|| {
+                // 设备未出现在列表中，仍按配置的节点名尝试
+                Some(AudioDevice {
+                    id: String::new(),
+                    name: target.clone(),
+                    description: target.clone(),
+                    is_default: false,
+                    sample_spec: None,
+                })
+            }),
+        None => devices.into_iter().find(|d| d.is_default),
+    };
+
+    DeviceSelection {
+        device,
+        is_preferred: config.target_node.is_some(),
+    }
+}
+
 /// PipeWire 主循环（运行在独立线程）
+///
+/// 配置了首选设备时，设备监控线程会在设备变化时结束当前 pw-record，
+/// 本循环随即重新选择设备并重启捕获
 fn run_pipewire_loop(
     config: PipeWireStreamConfig,
     mut producer: AudioRingProducer,
     running: Arc<AtomicBool>,
     quit_signal: Arc<AtomicBool>,
+    active_target: Arc<Mutex<Option<String>>>,
+    listener: DeviceEventListener,
 ) -> VInputResult<()> {
     use std::process::{Command, Stdio};
     use std::io::Read;
@@ -143,91 +288,153 @@ fn run_pipewire_loop(
     tracing::info!("PipeWire 流线程启动");
     tracing::info!("PipeWire 真实音频捕获模式 (pw-record)");
 
-    // 启动 pw-record 子进程
-    let mut child = Command::new("pw-record")
-        .arg("--rate").arg(config.sample_rate.to_string())
-        .arg("--channels").arg(config.channels.to_string())
-        .arg("--format").arg(match config.format {
-            AudioFormat::F32LE => "f32",
-            AudioFormat::S16LE => "s16",
-        })
-        .arg("--quality").arg("8")  // 重采样质量：8（高质量，平衡性能）
-        .arg("-")  // 输出到 stdout
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .spawn()
-        .map_err(|e| VInputError::PipeWire(format!("启动 pw-record 失败: {}", e)))?;
-
-    tracing::info!("pw-record 子进程已启动 (PID: {})", child.id());
-    running.store(true, Ordering::Release);
-
-    // 从 stdout 读取音频数据
-    let mut stdout = child.stdout.take()
-        .ok_or_else(|| VInputError::PipeWire("无法获取 pw-record stdout".to_string()))?;
-
-    let frame_size = 1024; // 每次读取 1024 个样本
-    let buffer_size = frame_size * std::mem::size_of::<f32>();
-    let mut buffer = vec![0u8; buffer_size];
-    // 跨读取的字节余量缓冲：stdout.read() 可能返回非 4 字节对齐的数据
-    let mut leftover: Vec<u8> = Vec::with_capacity(3);
+    let follow_devices = !config.preferred_devices.is_empty();
+    let child_slot: Arc<Mutex<Option<Child>>> = Arc::new(Mutex::new(None));
+    let _monitor = follow_devices.then(|| {
+        DeviceMonitor::spawn(
+            config.preferred_devices.clone(),
+            config.device_poll_interval,
+            active_target.clone(),
+            child_slot.clone(),
+        )
+    });
+
     let mut total_samples = 0usize;
+    let mut first_start = true;
 
     while !quit_signal.load(Ordering::Acquire) {
-        // 读取音频数据
-        match stdout.read(&mut buffer) {
-            Ok(0) => {
-                // EOF - pw-record 进程结束
-                tracing::warn!("pw-record 进程意外结束");
-                break;
-            }
-            Ok(bytes_read) => {
-                // 将本次读取与上次余量拼接后再转换为 f32 样本
-                leftover.extend_from_slice(&buffer[..bytes_read]);
-                let sample_count = leftover.len() / std::mem::size_of::<f32>();
-                if sample_count == 0 {
-                    continue; // 还不足 4 字节，继续读
+        let selection = choose_device(&config);
+        let target = selection.target().map(str::to_string);
+
+        // 报告活动设备（首次启动或设备发生变化时）
+        let previous = active_target.lock().unwrap().clone();
+        if first_start || previous != target {
+            let reason = if first_start {
+                DeviceSwitchReason::Initial
+            } else if selection.is_preferred {
+                DeviceSwitchReason::PreferredAvailable
+            } else {
+                DeviceSwitchReason::Fallback
+            };
+            tracing::info!(
+                "🎤 活动音频设备: {} ({:?})",
+                target.as_deref().unwrap_or("默认音频源"),
+                reason
+            );
+            listener(&DeviceEvent {
+                device: selection.device.clone(),
+                reason,
+            });
+        }
+        *active_target.lock().unwrap() = target.clone();
+
+        let capture = config.resolve_capture_format(selection.device.as_ref());
+        tracing::info!(
+            "捕获格式: {} Hz, {} 声道, {:?}",
+            capture.sample_rate,
+            capture.channels,
+            capture.format
+        );
+
+        // 启动 pw-record 子进程
+        let mut command = Command::new("pw-record");
+        command
+            .arg("--rate").arg(capture.sample_rate.to_string())
+            .arg("--channels").arg(capture.channels.to_string())
+            .arg("--format").arg(match capture.format {
+                AudioFormat::F32LE => "f32",
+                AudioFormat::S16LE => "s16",
+            })
+            .arg("--quality").arg("8");  // PipeWire 侧重采样质量（仅回退模式下生效）
+        if let Some(target) = &target {
+            command.arg("--target").arg(target);
+        }
+        let mut child = command
+            .arg("-")  // 输出到 stdout
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| VInputError::PipeWire(format!("启动 pw-record 失败: {}", e)))?;
+
+        tracing::info!("pw-record 子进程已启动 (PID: {})", child.id());
+        running.store(true, Ordering::Release);
+        first_start = false;
+
+        // 从 stdout 读取音频数据
+        let mut stdout = child.stdout.take()
+            .ok_or_else(|| VInputError::PipeWire("无法获取 pw-record stdout".to_string()))?;
+        *child_slot.lock().unwrap() = Some(child);
+
+        // 设备格式 → 单声道 f32 @ sample_rate（内部处理跨读取的字节对齐）
+        let mut converter = CaptureConverter::new(
+            capture.format,
+            capture.sample_rate,
+            capture.channels,
+            config.sample_rate,
+            config.channel_mix,
+            config.resample_quality,
+        );
+
+        let frame_size = 1024; // 每次读取 1024 帧
+        let buffer_size = frame_size * capture.format.bytes_per_sample() * capture.channels.max(1) as usize;
+        let mut buffer = vec![0u8; buffer_size];
+
+        while !quit_signal.load(Ordering::Acquire) {
+            // 读取音频数据
+            match stdout.read(&mut buffer) {
+                Ok(0) => {
+                    // EOF - pw-record 进程结束（设备切换或设备断开）
+                    if follow_devices {
+                        tracing::info!("pw-record 进程结束，重新选择音频设备");
+                    } else {
+                        tracing::warn!("pw-record 进程意外结束");
+                    }
+                    break;
                 }
-                let aligned_bytes = sample_count * std::mem::size_of::<f32>();
-                let samples: &[f32] = unsafe {
-                    std::slice::from_raw_parts(
-                        leftover.as_ptr() as *const f32,
-                        sample_count,
-                    )
-                };
+                Ok(bytes_read) => {
+                    let samples = converter.push_bytes(&buffer[..bytes_read]);
+                    if samples.is_empty() {
+                        continue; // 还不足一帧，继续读
+                    }
 
-                // 写入 Ring Buffer
-                match producer.write(samples) {
-                    Ok(written) => {
-                        total_samples += written;
-                        if total_samples % (config.sample_rate as usize) == 0 {
-                            tracing::trace!("已捕获 {} 秒真实音频",
-                                total_samples / config.sample_rate as usize);
+                    // 写入 Ring Buffer
+                    match producer.write(&samples) {
+                        Ok(written) => {
+                            total_samples += written;
+                            if total_samples % (config.sample_rate as usize) == 0 {
+                                tracing::trace!("已捕获 {} 秒真实音频",
+                                    total_samples / config.sample_rate as usize);
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!("Ring Buffer 写入失败: {:?}", e);
                         }
-                    }
-                    Err(e) => {
-                        tracing::warn!("Ring Buffer 写入失败: {:?}", e);
                     }
                 }
-
-                // 保留未对齐的尾部字节（0~3 字节）
-                let remainder = leftover[aligned_bytes..].to_vec();
-                leftover = remainder;
-            }
-            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                // 非阻塞模式下无数据，等待一小会儿
-                thread::sleep(Duration::from_millis(1));
-            }
-            Err(e) => {
-                tracing::error!("读取 pw-record 输出失败: {}", e);
-                break;
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    // 非阻塞模式下无数据，等待一小会儿
+                    thread::sleep(Duration::from_millis(1));
+                }
+                Err(e) => {
+                    tracing::error!("读取 pw-record 输出失败: {}", e);
+                    break;
+                }
             }
         }
-    }
 
-    // 停止 pw-record
-    tracing::info!("停止 pw-record 进程");
-    let _ = child.kill();
-    let _ = child.wait();
+        // 停止 pw-record
+        tracing::info!("停止 pw-record 进程");
+        if let Some(mut child) = child_slot.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+
+        if !follow_devices {
+            break;
+        }
+        // 等待设备状态稳定后重新选择
+        thread::sleep(Duration::from_millis(200));
+    }
 
     running.store(false, Ordering::Release);
     tracing::info!("PipeWire 流线程停止，共捕获 {:.2} 秒真实音频",
@@ -240,7 +447,7 @@ fn run_pipewire_loop(
 pub fn enumerate_audio_devices() -> VInputResult<Vec<AudioDevice>> {
     use std::process::Command;
 
-    tracing::info!("开始枚举音频输入设备");
+    tracing::debug!("开始枚举音频输入设备");
 
     // 使用 pactl 枚举音频源
     let output = Command::new("pactl")
@@ -277,12 +484,20 @@ pub fn enumerate_audio_devices() -> VInputResult<Vec<AudioDevice>> {
 
         // 生成友好的描述
         let description = generate_device_description(name);
+        let sample_spec = parts.get(3).and_then(|spec| match parse_sample_spec(spec) {
+            Ok(format) => Some(format),
+            Err(e) => {
+                tracing::warn!("设备 {}: {}", name, e);
+                None
+            }
+        });
 
         devices.push(AudioDevice {
             id: id.to_string(),
             name: name.to_string(),
             description,
             is_default: false, // 稍后标记默认设备
+            sample_spec,
         });
     }
 
@@ -302,7 +517,7 @@ pub fn enumerate_audio_devices() -> VInputResult<Vec<AudioDevice>> {
         }
     }
 
-    tracing::info!("找到 {} 个音频输入设备", devices.len());
+    tracing::debug!("找到 {} 个音频输入设备", devices.len());
     for device in &devices {
         tracing::debug!("  - {} ({}){}",
             device.description,
@@ -314,6 +529,47 @@ pub fn enumerate_audio_devices() -> VInputResult<Vec<AudioDevice>> {
     Ok(devices)
 }
 
+/// 探测目标设备（None = 默认音频源）的原生捕获格式
+pub fn probe_device_format(target_node: Option<&str>) -> Option<CaptureFormat> {
+    let devices = enumerate_audio_devices().ok()?;
+    let device = match target_node {
+        Some(target) => devices.iter().find(|d| d.name == target || d.id == target),
+        None => devices.iter().find(|d| d.is_default),
+    }?;
+    device.sample_spec
+}
+
+/// 解析 pactl 的采样规格，如 "s16le 2ch 48000Hz"
+///
+/// 非 s16le 的格式（s24le、s32le、float32le 等）统一请求 F32LE，
+/// 样本格式转换由 PipeWire 无损完成，采样率与声道保持原生。
+/// 无法解析或采样率 / 声道数为 0 时返回错误（重采样器不接受 0 采样率）
+fn parse_sample_spec(spec: &str) -> VInputResult<CaptureFormat> {
+    let invalid = || VInputError::PipeWire(format!("无效的采样规格: {:?}", spec));
+
+    let mut parts = spec.split_whitespace();
+    let format = match parts.next().ok_or_else(invalid)? {
+        "s16le" => AudioFormat::S16LE,
+        _ => AudioFormat::F32LE,
+    };
+    let mut field = |suffix: &str| -> VInputResult<u32> {
+        parts
+            .next()
+            .and_then(|part| part.strip_suffix(suffix))
+            .and_then(|value| value.parse().ok())
+            .filter(|&value| value > 0)
+            .ok_or_else(invalid)
+    };
+    let channels = field("ch")?;
+    let sample_rate = field("Hz")?;
+
+    Ok(CaptureFormat {
+        sample_rate,
+        channels,
+        format,
+    })
+}
+
 /// 生成友好的设备描述
 fn generate_device_description(name: &str) -> String {
     // 尝试从设备名称提取友好描述
@@ -352,6 +608,32 @@ pub struct AudioDevice {
     pub description: String,
     /// 是否为默认设备
     pub is_default: bool,
+    /// 设备原生采样规格（无法解析时为 None）
+    pub sample_spec: Option<CaptureFormat>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sample_spec() {
+        assert_eq!(
+            parse_sample_spec("s16le 2ch 48000Hz").unwrap(),
+            CaptureFormat { sample_rate: 48000, channels: 2, format: AudioFormat::S16LE }
+        );
+        assert_eq!(
+            parse_sample_spec("float32le 1ch 44100Hz").unwrap(),
+            CaptureFormat { sample_rate: 44100, channels: 1, format: AudioFormat::F32LE }
+        );
+        assert!(parse_sample_spec("garbage").is_err());
+    }
+
+    #[test]
+    fn test_parse_sample_spec_rejects_zero() {
+        assert!(parse_sample_spec("s16le 2ch 0Hz").is_err());
+        assert!(parse_sample_spec("s16le 0ch 48000Hz").is_err());
+    }
 }
 
 // Phase 1.1 实施说明：
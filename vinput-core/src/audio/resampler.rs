@@ -0,0 +1,295 @@
+//! 多相加窗 sinc 重采样器
+//!
+//! 将任意采样率转换为目标采样率（通常为 16kHz），用于设备只提供
+//! 44.1kHz / 48kHz 时在 Rust 侧完成高质量重采样，而不依赖 PipeWire 协商。
+//!
+//! 实现：有理比 L/M（按最大公约数约简），每个相位预计算一组
+//! Kaiser 窗 sinc 系数，截止频率取输入/输出奈奎斯特频率的较小者。
+
+pub use vinput_config::audio::ResampleQuality;
+
+/// 单侧抽头数
+fn half_taps(quality: ResampleQuality) -> usize {
+    match quality {
+        ResampleQuality::Fast => 8,
+        ResampleQuality::Balanced => 16,
+        ResampleQuality::High => 32,
+    }
+}
+
+/// Kaiser 窗 beta 参数
+fn kaiser_beta(quality: ResampleQuality) -> f64 {
+    match quality {
+        ResampleQuality::Fast => 6.0,
+        ResampleQuality::Balanced => 8.0,
+        ResampleQuality::High => 10.0,
+    }
+}
+
+/// 流式多相重采样器（单声道）
+pub struct Resampler {
+    input_rate: u32,
+    output_rate: u32,
+    /// 上采样因子
+    up: usize,
+    /// 下采样因子
+    down: usize,
+    /// 单侧抽头数
+    half_taps: usize,
+    /// 多相系数表：`up` 个相位 × `2 * half_taps` 个系数
+    coeffs: Vec<f32>,
+    /// 输入历史
+    history: Vec<f32>,
+    /// 下一个输出样本对应的输入整数位置（相对 history 起点）
+    position: usize,
+    /// 下一个输出样本的相位
+    phase: usize,
+}
+
+impl Resampler {
+    /// 创建重采样器
+    pub fn new(input_rate: u32, output_rate: u32, quality: ResampleQuality) -> Self {
+        assert!(input_rate > 0 && output_rate > 0, "采样率必须大于 0");
+
+        let g = gcd(input_rate, output_rate);
+        let up = (output_rate / g) as usize;
+        let down = (input_rate / g) as usize;
+        let half_taps = half_taps(quality);
+        let taps = 2 * half_taps;
+
+        // 截止频率（相对输入采样率），留 5% 过渡带
+        let cutoff = (output_rate as f64 / input_rate as f64).min(1.0) * 0.95;
+        let beta = kaiser_beta(quality);
+        let i0_beta = bessel_i0(beta);
+
+        let mut coeffs = Vec::with_capacity(up * taps);
+        for phase in 0..up {
+            let frac = phase as f64 / up as f64;
+            let start = coeffs.len();
+            for j in 0..taps {
+                // 输入样本 x[i - half_taps + 1 + j] 相对输出时刻的距离
+                let tau = frac + (half_taps - 1) as f64 - j as f64;
+                let x = tau / half_taps as f64;
+                let window = if x.abs() <= 1.0 {
+                    bessel_i0(beta * (1.0 - x * x).sqrt()) / i0_beta
+                } else {
+                    0.0
+                };
+                coeffs.push((cutoff * sinc(cutoff * tau) * window) as f32);
+            }
+            // 每个相位归一化为单位直流增益
+            let sum: f32 = coeffs[start..].iter().sum();
+            if sum.abs() > f32::EPSILON {
+                coeffs[start..].iter_mut().for_each(|c| *c /= sum);
+            }
+        }
+
+        tracing::debug!(
+            "创建重采样器: {} Hz → {} Hz (L={}, M={}, taps={})",
+            input_rate,
+            output_rate,
+            up,
+            down,
+            taps
+        );
+
+        Self {
+            input_rate,
+            output_rate,
+            up,
+            down,
+            half_taps,
+            coeffs,
+            // 前置零填充，使第一个输出样本的左侧抽头有数据
+            history: vec![0.0; half_taps],
+            position: half_taps - 1,
+            phase: 0,
+        }
+    }
+
+    /// 处理输入样本，返回重采样后的样本
+    pub fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        self.history.extend_from_slice(input);
+
+        let taps = 2 * self.half_taps;
+        let expected = input.len() * self.up / self.down + 1;
+        let mut output = Vec::with_capacity(expected);
+
+        // 需要 x[position + half_taps] 可用
+        while self.position + self.half_taps < self.history.len() {
+            let start = self.position + 1 - self.half_taps;
+            let window = &self.history[start..start + taps];
+            let phase_coeffs = &self.coeffs[self.phase * taps..(self.phase + 1) * taps];
+            let y: f32 = window.iter().zip(phase_coeffs).map(|(x, c)| x * c).sum();
+            output.push(y);
+
+            self.phase += self.down;
+            self.position += self.phase / self.up;
+            self.phase %= self.up;
+        }
+
+        // 丢弃不再需要的历史样本
+        let keep_from = (self.position + 1).saturating_sub(self.half_taps);
+        if keep_from > 0 {
+            self.history.drain(..keep_from);
+            self.position -= keep_from;
+        }
+
+        output
+    }
+
+    /// 重置内部状态
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.history.resize(self.half_taps, 0.0);
+        self.position = self.half_taps - 1;
+        self.phase = 0;
+    }
+
+    /// 输入采样率
+    pub fn input_rate(&self) -> u32 {
+        self.input_rate
+    }
+
+    /// 输出采样率
+    pub fn output_rate(&self) -> u32 {
+        self.output_rate
+    }
+
+    /// 引入的延迟（输入样本数）
+    pub fn latency_input_samples(&self) -> usize {
+        self.half_taps
+    }
+}
+
+fn gcd(mut a: u32, mut b: u32) -> u32 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+    a
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        let px = std::f64::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+/// 第一类零阶修正贝塞尔函数（级数展开）
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = x / 2.0;
+    for k in 1..50 {
+        term *= (half_x / k as f64) * (half_x / k as f64);
+        sum += term;
+        if term < sum * 1e-12 {
+            break;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, rate: u32, len: usize, amplitude: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin() * amplitude)
+            .collect()
+    }
+
+    fn rms(samples: &[f32]) -> f32 {
+        (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+    }
+
+    /// Goertzel 算法：估计指定频率的幅度
+    fn goertzel(samples: &[f32], freq: f32, rate: u32) -> f32 {
+        let w = 2.0 * std::f32::consts::PI * freq / rate as f32;
+        let coeff = 2.0 * w.cos();
+        let (mut s1, mut s2) = (0.0f32, 0.0f32);
+        for &x in samples {
+            let s0 = x + coeff * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+        2.0 * power.sqrt() / samples.len() as f32
+    }
+
+    #[test]
+    fn test_output_length_48k_to_16k() {
+        let mut resampler = Resampler::new(48000, 16000, ResampleQuality::Balanced);
+        let mut total = 0;
+        for _ in 0..100 {
+            total += resampler.process(&[0.0; 480]).len();
+        }
+        // 48000 输入样本 → 约 16000 输出样本（减去滤波器延迟）
+        assert!((15980..=16000).contains(&total), "输出长度: {}", total);
+    }
+
+    #[test]
+    fn test_tone_preserved_48k_to_16k() {
+        let input = tone(1000.0, 48000, 48000, 0.5);
+        let mut resampler = Resampler::new(48000, 16000, ResampleQuality::Balanced);
+        let output: Vec<f32> = input.chunks(1024).flat_map(|c| resampler.process(c)).collect();
+
+        let steady = &output[1000..15000];
+        let amplitude = goertzel(steady, 1000.0, 16000);
+        assert!((amplitude - 0.5).abs() < 0.02, "1kHz 幅度: {}", amplitude);
+    }
+
+    #[test]
+    fn test_alias_rejected_48k_to_16k() {
+        // 10kHz 高于 16kHz 输出的奈奎斯特频率，应被抗混叠滤波器抑制
+        let input = tone(10000.0, 48000, 48000, 0.5);
+        let mut resampler = Resampler::new(48000, 16000, ResampleQuality::High);
+        let output: Vec<f32> = input.chunks(1024).flat_map(|c| resampler.process(c)).collect();
+
+        let residual = rms(&output[1000..15000]);
+        assert!(residual < 0.005, "混叠残留过大: {}", residual);
+    }
+
+    #[test]
+    fn test_tone_preserved_44100_to_16k() {
+        let input = tone(440.0, 44100, 44100, 0.3);
+        let mut resampler = Resampler::new(44100, 16000, ResampleQuality::Balanced);
+        let output: Vec<f32> = input.chunks(441).flat_map(|c| resampler.process(c)).collect();
+
+        assert!((15900..=16000).contains(&output.len()), "输出长度: {}", output.len());
+        let amplitude = goertzel(&output[1000..15000], 440.0, 16000);
+        assert!((amplitude - 0.3).abs() < 0.02, "440Hz 幅度: {}", amplitude);
+    }
+
+    #[test]
+    fn test_upsample_8k_to_16k() {
+        let input = tone(500.0, 8000, 8000, 0.4);
+        let mut resampler = Resampler::new(8000, 16000, ResampleQuality::Balanced);
+        let output = resampler.process(&input);
+
+        let amplitude = goertzel(&output[1000..15000], 500.0, 16000);
+        assert!((amplitude - 0.4).abs() < 0.02, "500Hz 幅度: {}", amplitude);
+    }
+
+    #[test]
+    fn test_chunking_independent() {
+        let input = tone(700.0, 48000, 9600, 0.5);
+
+        let mut a = Resampler::new(48000, 16000, ResampleQuality::Fast);
+        let whole = a.process(&input);
+
+        let mut b = Resampler::new(48000, 16000, ResampleQuality::Fast);
+        let chunked: Vec<f32> = input.chunks(37).flat_map(|c| b.process(c)).collect();
+
+        assert_eq!(whole.len(), chunked.len());
+        for (x, y) in whole.iter().zip(&chunked) {
+            assert!((x - y).abs() < 1e-6);
+        }
+    }
+}
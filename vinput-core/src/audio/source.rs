@@ -0,0 +1,568 @@
+//! 音频输入源抽象
+//!
+//! 统一 PipeWire 麦克风、WAV 文件回放、stdin 原始 PCM 与 Unix socket
+//! 四种输入，全部输出 16kHz（管线采样率）单声道 f32 到 `AudioRingProducer`。
+//! 非 PipeWire 输入用于无声音服务器的 CI 以及远程喂音频。
+
+use crate::audio::convert::{CaptureConverter, ChannelMix};
+use crate::audio::device_monitor::DeviceEventListener;
+use crate::audio::pipewire_stream::{AudioFormat, PipeWireStream, PipeWireStreamConfig};
+use crate::audio::resampler::ResampleQuality;
+use crate::audio::ring_buffer::AudioRingProducer;
+use crate::audio::wav::read_wav;
+use crate::error::{VInputError, VInputResult};
+use std::io::Read;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, Once,
+};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+pub use vinput_config::audio::{AudioSourceConfig, RawPcmFormat};
+
+/// 音频输入源
+///
+/// 创建即开始向 Ring Buffer 写入；Drop 时停止并回收线程
+pub trait AudioSource: Send {
+    /// 输入源名称（用于日志）
+    fn name(&self) -> &str;
+
+    /// 是否仍在产生音频（文件播放完毕后返回 false）
+    fn is_running(&self) -> bool;
+
+    /// 请求停止
+    fn stop(&self);
+
+    /// 输入源是否已非正常结束（断流等，返回错误）；正常结束（如文件播放完毕）返回 None
+    fn failure(&self) -> Option<VInputError> {
+        None
+    }
+}
+
+impl AudioSource for PipeWireStream {
+    fn name(&self) -> &str {
+        "pipewire"
+    }
+
+    fn is_running(&self) -> bool {
+        PipeWireStream::is_running(self)
+    }
+
+    fn stop(&self) {
+        PipeWireStream::stop(self)
+    }
+
+    fn failure(&self) -> Option<VInputError> {
+        PipeWireStream::failure(self)
+    }
+}
+
+/// 按配置打开音频输入源
+///
+/// `pipewire` 提供输出采样率、下混与重采样设置；`listener` 仅对 PipeWire 输入生效
+pub fn open_audio_source(
+    source: &AudioSourceConfig,
+    pipewire: PipeWireStreamConfig,
+    listener: DeviceEventListener,
+    producer: AudioRingProducer,
+) -> VInputResult<Box<dyn AudioSource>> {
+    let output_rate = pipewire.sample_rate;
+    let mix = pipewire.channel_mix;
+    let quality = pipewire.resample_quality;
+
+    let source: Box<dyn AudioSource> = match source {
+        AudioSourceConfig::PipeWire => {
+            Box::new(PipeWireStream::with_device_listener(pipewire, producer, listener)?)
+        }
+        AudioSourceConfig::WavFile {
+            path,
+            realtime,
+            trailing_silence_ms,
+        } => Box::new(WavFileSource::new(
+            path,
+            *realtime,
+            *trailing_silence_ms,
+            output_rate,
+            mix,
+            quality,
+            producer,
+        )?),
+        AudioSourceConfig::Stdin { pcm } => {
+            let converter = pcm_converter(pcm, output_rate, mix, quality);
+            let mut chunks = StdinChunks::acquire()?;
+            Box::new(ReaderSource::spawn("stdin", producer, move |quit, producer| {
+                let result = pump_reader(&mut chunks, converter, producer, quit);
+                chunks.release();
+                result
+            }))
+        }
+        AudioSourceConfig::UnixSocket { path, pcm } => {
+            Box::new(unix_socket_source(path, *pcm, output_rate, mix, quality, producer)?)
+        }
+    };
+
+    tracing::info!("音频输入源: {}", source.name());
+    Ok(source)
+}
+
+/// 原始 PCM 流到管线格式的转换器
+fn pcm_converter(pcm: &RawPcmFormat, output_rate: u32, mix: ChannelMix, quality: ResampleQuality) -> CaptureConverter {
+    CaptureConverter::new(pcm.format, pcm.sample_rate, pcm.channels, output_rate, mix, quality)
+}
+
+/// 写入 Ring Buffer，空间不足时等待消费（背压），收到停止信号时放弃剩余数据
+fn write_with_backpressure(producer: &mut AudioRingProducer, mut samples: &[f32], quit: &AtomicBool) {
+    while !samples.is_empty() && !quit.load(Ordering::Acquire) {
+        let n = producer.free_space().min(samples.len());
+        if n == 0 {
+            thread::sleep(Duration::from_millis(2));
+            continue;
+        }
+        let _ = producer.write(&samples[..n]);
+        samples = &samples[n..];
+    }
+}
+
+/// 从字节流读取 PCM 并写入 Ring Buffer，直到 EOF 或停止
+fn pump_reader<R: Read>(
+    mut reader: R,
+    mut converter: CaptureConverter,
+    producer: &mut AudioRingProducer,
+    quit: &AtomicBool,
+) -> VInputResult<()> {
+    let mut buffer = vec![0u8; 4096];
+    while !quit.load(Ordering::Acquire) {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let samples = converter.push_bytes(&buffer[..n]);
+        write_with_backpressure(producer, &samples, quit);
+    }
+    Ok(())
+}
+
+/// 进程内唯一的 stdin 读取线程产生的数据块（未被借出时存放于此）
+static STDIN_CHUNKS: Mutex<Option<StdinChunks>> = Mutex::new(None);
+static STDIN_READER: Once = Once::new();
+
+/// stdin 数据块的接收端
+///
+/// stdin 的阻塞读取无法中断，因此只由一个后台线程读取；stdin 输入源打开时借出接收端、
+/// 结束时归还，同一时间只允许一个 stdin 输入源（录音会话与唤醒词监听不会争抢 stdin）
+struct StdinChunks {
+    receiver: Receiver<Vec<u8>>,
+    /// 上次读取未取完的数据
+    pending: Vec<u8>,
+}
+
+impl StdinChunks {
+    /// 借出接收端（首次调用时启动读取线程）；已被其他输入源借出时返回错误
+    fn acquire() -> VInputResult<Self> {
+        STDIN_READER.call_once(|| {
+            // 有界队列：无输入源消费时读取线程暂停，数据留给下一个输入源
+            let (sender, receiver) = mpsc::sync_channel(16);
+            *STDIN_CHUNKS.lock().unwrap() = Some(StdinChunks {
+                receiver,
+                pending: Vec::new(),
+            });
+            thread::spawn(move || {
+                let mut stdin = std::io::stdin().lock();
+                let mut buffer = vec![0u8; 4096];
+                loop {
+                    match stdin.read(&mut buffer) {
+                        Ok(0) => break,
+                        Ok(n) => {
+                            if sender.send(buffer[..n].to_vec()).is_err() {
+                                break;
+                            }
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(e) => {
+                            tracing::warn!("读取 stdin 失败: {}", e);
+                            break;
+                        }
+                    }
+                }
+                tracing::info!("stdin 已结束");
+            });
+        });
+
+        STDIN_CHUNKS
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| VInputError::AudioSource("stdin 已被另一个音频输入源占用".to_string()))
+    }
+
+    /// 归还接收端，供下一个 stdin 输入源使用
+    fn release(self) {
+        *STDIN_CHUNKS.lock().unwrap() = Some(self);
+    }
+}
+
+impl Read for StdinChunks {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            // 周期性返回 Interrupted，使 `pump_reader` 能检查停止信号
+            match self.receiver.recv_timeout(Duration::from_millis(200)) {
+                Ok(chunk) => self.pending = chunk,
+                Err(RecvTimeoutError::Timeout) => return Err(std::io::ErrorKind::Interrupted.into()),
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(..n);
+        Ok(n)
+    }
+}
+
+/// 运行在独立线程中的输入源
+struct ReaderSource {
+    name: String,
+    running: Arc<AtomicBool>,
+    quit: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ReaderSource {
+    fn spawn<F>(name: &str, mut producer: AudioRingProducer, body: F) -> Self
+    where
+        F: FnOnce(&AtomicBool, &mut AudioRingProducer) -> VInputResult<()> + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let quit = Arc::new(AtomicBool::new(false));
+
+        let running_clone = running.clone();
+        let quit_clone = quit.clone();
+        let thread_name = name.to_string();
+        let handle = thread::spawn(move || {
+            tracing::info!("音频输入线程启动: {}", thread_name);
+            if let Err(e) = body(&quit_clone, &mut producer) {
+                e.log();
+            }
+            running_clone.store(false, Ordering::Release);
+            tracing::info!("音频输入线程结束: {}", thread_name);
+        });
+
+        Self {
+            name: name.to_string(),
+            running,
+            quit,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl AudioSource for ReaderSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    fn stop(&self) {
+        self.quit.store(true, Ordering::Release);
+    }
+}
+
+impl Drop for ReaderSource {
+    fn drop(&mut self) {
+        self.stop();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// WAV 文件回放输入源
+pub struct WavFileSource {
+    inner: ReaderSource,
+}
+
+impl WavFileSource {
+    /// 读取并转换整个文件，随后在后台线程中回放
+    pub fn new(
+        path: &Path,
+        realtime: bool,
+        trailing_silence_ms: u64,
+        output_rate: u32,
+        mix: ChannelMix,
+        quality: ResampleQuality,
+        producer: AudioRingProducer,
+    ) -> VInputResult<Self> {
+        let wav = read_wav(path)?;
+        tracing::info!(
+            "WAV 回放: {} ({} Hz, {} 声道, {:.2} 秒, {})",
+            path.display(),
+            wav.sample_rate,
+            wav.channels,
+            wav.samples.len() as f32 / (wav.sample_rate * wav.channels) as f32,
+            if realtime { "实时" } else { "快速" }
+        );
+
+        let mut converter =
+            CaptureConverter::new(AudioFormat::F32LE, wav.sample_rate, wav.channels, output_rate, mix, quality);
+        let mut samples = converter.push_samples(&wav.samples);
+        samples.resize(samples.len() + (output_rate as u64 * trailing_silence_ms / 1000) as usize, 0.0);
+
+        let inner = ReaderSource::spawn("wav_file", producer, move |quit, producer| {
+            // 每 32ms 写入一块，实时模式按墙钟节流
+            let chunk = (output_rate / 1000 * 32) as usize;
+            let started = Instant::now();
+            for (i, block) in samples.chunks(chunk.max(1)).enumerate() {
+                if quit.load(Ordering::Acquire) {
+                    break;
+                }
+                if realtime {
+                    let due = Duration::from_millis(i as u64 * 32);
+                    if let Some(wait) = due.checked_sub(started.elapsed()) {
+                        thread::sleep(wait);
+                    }
+                }
+                write_with_backpressure(producer, block, quit);
+            }
+            Ok(())
+        });
+
+        Ok(Self { inner })
+    }
+}
+
+impl AudioSource for WavFileSource {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn is_running(&self) -> bool {
+        self.inner.is_running()
+    }
+
+    fn stop(&self) {
+        self.inner.stop()
+    }
+}
+
+/// Unix socket 输入源：监听 `path`，依次接受连接并读取原始 PCM
+fn unix_socket_source(
+    path: &Path,
+    pcm: RawPcmFormat,
+    output_rate: u32,
+    mix: ChannelMix,
+    quality: ResampleQuality,
+    producer: AudioRingProducer,
+) -> VInputResult<ReaderSource> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    // 清理上次遗留的 socket 文件；路径上是其他文件时报错，不删除
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(VInputError::AudioSource(format!(
+                "{} 已存在且不是 socket 文件",
+                path.display()
+            )));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)
+        .map_err(|e| VInputError::AudioSource(format!("绑定 {} 失败: {}", path.display(), e)))?;
+    listener.set_nonblocking(true)?;
+    tracing::info!("等待音频连接: {}", path.display());
+
+    let socket_path = path.to_path_buf();
+    Ok(ReaderSource::spawn("unix_socket", producer, move |quit, producer| {
+        while !quit.load(Ordering::Acquire) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    tracing::info!("音频连接已建立");
+                    stream.set_nonblocking(false)?;
+                    stream.set_read_timeout(Some(Duration::from_millis(200)))?;
+                    let converter = pcm_converter(&pcm, output_rate, mix, quality);
+                    if let Err(e) = pump_reader(TimeoutReader(stream), converter, producer, quit) {
+                        tracing::warn!("音频连接读取失败: {}", e);
+                    }
+                    tracing::info!("音频连接已关闭");
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let _ = std::fs::remove_file(&socket_path);
+        Ok(())
+    }))
+}
+
+/// 将读取超时转换为 `Interrupted`，使 `pump_reader` 能周期性检查停止信号
+struct TimeoutReader<R>(R);
+
+impl<R: Read> Read for TimeoutReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.0.read(buf) {
+            Err(e)
+                if e.kind() == std::io::ErrorKind::WouldBlock
+                    || e.kind() == std::io::ErrorKind::TimedOut =>
+            {
+                Err(std::io::ErrorKind::Interrupted.into())
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::ring_buffer::{AudioRingBuffer, AudioRingBufferConfig};
+    use crate::audio::wav::WavWriter;
+    use std::path::PathBuf;
+
+    fn drain_until_stopped(source: &dyn AudioSource, consumer: &mut crate::audio::AudioRingConsumer) -> Vec<f32> {
+        let mut collected = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            collected.extend(consumer.read_available(4096));
+            if !source.is_running() {
+                collected.extend(consumer.read_available(usize::MAX));
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        collected
+    }
+
+    #[test]
+    fn test_source_config_from_toml() {
+        let config: AudioSourceConfig = toml::from_str(
+            r#"
+type = "unix_socket"
+path = "/tmp/vinput.sock"
+format = "f32le"
+sample_rate = 48000
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            AudioSourceConfig::UnixSocket {
+                path: PathBuf::from("/tmp/vinput.sock"),
+                pcm: RawPcmFormat {
+                    format: AudioFormat::F32LE,
+                    sample_rate: 48000,
+                    channels: 1,
+                },
+            }
+        );
+
+        let config: AudioSourceConfig = toml::from_str("type = \"wav_file\"\npath = \"a.wav\"").unwrap();
+        assert!(matches!(config, AudioSourceConfig::WavFile { realtime: true, trailing_silence_ms: 1000, .. }));
+    }
+
+    #[test]
+    fn test_wav_file_source_fast_playback() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tone.wav");
+        {
+            // 0.5 秒 8kHz，大于 Ring Buffer 容量以验证背压
+            let mut writer = WavWriter::create(&path, 8000).unwrap();
+            writer.write_samples(&vec![0.25; 4000]).unwrap();
+        }
+
+        let (producer, mut consumer) =
+            AudioRingBuffer::new(AudioRingBufferConfig { capacity: 1024 }).split();
+        let source = open_audio_source(
+            &AudioSourceConfig::WavFile {
+                path: path.clone(),
+                realtime: false,
+                trailing_silence_ms: 100,
+            },
+            PipeWireStreamConfig::default(),
+            Arc::new(|_| {}),
+            producer,
+        )
+        .unwrap();
+
+        let samples = drain_until_stopped(source.as_ref(), &mut consumer);
+        // 0.5 秒 @ 16kHz + 100ms 静音
+        assert!((9550..=9600).contains(&samples.len()), "样本数: {}", samples.len());
+        assert!((samples[4000] - 0.25).abs() < 0.01);
+        assert_eq!(*samples.last().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_unix_socket_source() {
+        use std::io::Write;
+        use std::os::unix::net::UnixStream;
+
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("audio.sock");
+
+        let (producer, mut consumer) = AudioRingBuffer::new(AudioRingBufferConfig::default()).split();
+        let source = open_audio_source(
+            &AudioSourceConfig::UnixSocket {
+                path: socket.clone(),
+                pcm: RawPcmFormat::default(),
+            },
+            PipeWireStreamConfig::default(),
+            Arc::new(|_| {}),
+            producer,
+        )
+        .unwrap();
+
+        let mut client = UnixStream::connect(&socket).unwrap();
+        let pcm: Vec<u8> = (0..1600).flat_map(|_| 8192i16.to_le_bytes()).collect();
+        client.write_all(&pcm).unwrap();
+        drop(client);
+
+        let mut collected = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while collected.len() < 1600 && Instant::now() < deadline {
+            collected.extend(consumer.read_available(4096));
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(collected.len(), 1600);
+        assert!((collected[0] - 0.25).abs() < 1e-6);
+
+        source.stop();
+        drop(source);
+        assert!(!socket.exists());
+    }
+
+    #[test]
+    fn test_unix_socket_source_keeps_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audio.sock");
+        std::fs::write(&path, b"not a socket").unwrap();
+
+        let (producer, _consumer) = AudioRingBuffer::new(AudioRingBufferConfig::default()).split();
+        let result = open_audio_source(
+            &AudioSourceConfig::UnixSocket {
+                path: path.clone(),
+                pcm: RawPcmFormat::default(),
+            },
+            PipeWireStreamConfig::default(),
+            Arc::new(|_| {}),
+            producer,
+        );
+        assert!(matches!(result, Err(VInputError::AudioSource(_))));
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+    }
+
+    #[test]
+    fn test_stdin_single_owner() {
+        let chunks = StdinChunks::acquire().unwrap();
+        assert!(StdinChunks::acquire().is_err());
+        chunks.release();
+        StdinChunks::acquire().unwrap().release();
+    }
+}
@@ -0,0 +1,261 @@
+//! 最小 WAV 文件读写
+//!
+//! 写入仅支持 PCM 16-bit 单声道（调试音频转储），另可在内存中编码
+//! 32-bit float 单声道（会话录制，样本无损）；读取支持 PCM 16-bit 与 32-bit float 的任意声道数（不依赖 hound）
+
+use crate::error::{VInputError, VInputResult};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// 解码后的 WAV 音频
+#[derive(Debug, Clone)]
+pub struct WavData {
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u32,
+    /// 交错排列的 f32 样本
+    pub samples: Vec<f32>,
+}
+
+/// 读取 WAV 文件
+pub fn read_wav(path: &Path) -> VInputResult<WavData> {
+    let bytes = std::fs::read(path)?;
+    parse_wav(&bytes).map_err(|reason| {
+        VInputError::AudioSource(format!("{}: {}", path.display(), reason))
+    })
+}
+
+/// 解码内存中的 WAV 字节流
+pub fn decode_wav(bytes: &[u8]) -> VInputResult<WavData> {
+    parse_wav(bytes).map_err(VInputError::AudioSource)
+}
+
+/// 编码为 32-bit float 单声道 WAV（样本原样保存，不截断、不量化）
+pub fn encode_wav_f32(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    const BYTES_PER_SAMPLE: u32 = 4;
+    let data_len = samples.len() as u32 * BYTES_PER_SAMPLE;
+
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * BYTES_PER_SAMPLE).to_le_bytes());
+    bytes.extend_from_slice(&(BYTES_PER_SAMPLE as u16).to_le_bytes());
+    bytes.extend_from_slice(&32u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        bytes.extend_from_slice(&s.to_le_bytes());
+    }
+    bytes
+}
+
+/// 解析 WAV 字节流（跳过 LIST 等无关块）
+fn parse_wav(bytes: &[u8]) -> Result<WavData, String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("不是 RIFF/WAVE 文件".to_string());
+    }
+
+    let u16_at = |pos: usize| u16::from_le_bytes([bytes[pos], bytes[pos + 1]]);
+    let u32_at = |pos: usize| {
+        u32::from_le_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]])
+    };
+
+    let mut fmt: Option<(u16, u32, u32, u16)> = None;
+    let mut pos = 12;
+
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32_at(pos + 4) as usize;
+        let body = pos + 8;
+        let end = (body + len).min(bytes.len());
+
+        if id == b"fmt " && len >= 16 {
+            let mut tag = u16_at(body);
+            // WAVE_FORMAT_EXTENSIBLE：真实格式位于子格式 GUID 的前 2 字节
+            if tag == 0xFFFE && len >= 26 {
+                tag = u16_at(body + 24);
+            }
+            fmt = Some((tag, u16_at(body + 2) as u32, u32_at(body + 4), u16_at(body + 14)));
+        } else if id == b"data" {
+            let (tag, channels, sample_rate, bits) = fmt.ok_or("data 块前缺少 fmt 块")?;
+            let data = &bytes[body..end];
+            let samples = match (tag, bits) {
+                (1, 16) => data
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0)
+                    .collect(),
+                (3, 32) => data
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+                _ => return Err(format!("不支持的 WAV 格式 (tag={}, bits={})", tag, bits)),
+            };
+            if channels == 0 || sample_rate == 0 {
+                return Err("无效的声道数或采样率".to_string());
+            }
+            return Ok(WavData {
+                sample_rate,
+                channels,
+                samples,
+            });
+        }
+
+        // 块按偶数字节对齐
+        pos = body + len + (len & 1);
+    }
+
+    Err("缺少 data 块".to_string())
+}
+
+/// WAV 文件写入器（PCM 16-bit 单声道）
+///
+/// 数据块长度在 `finish()` 或 Drop 时回填到文件头
+pub struct WavWriter {
+    writer: BufWriter<File>,
+    sample_rate: u32,
+    samples_written: u32,
+    finished: bool,
+}
+
+impl WavWriter {
+    /// 创建 WAV 文件并写入文件头
+    pub fn create(path: &Path, sample_rate: u32) -> VInputResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        write_header(&mut writer, sample_rate, 0)?;
+
+        Ok(Self {
+            writer,
+            sample_rate,
+            samples_written: 0,
+            finished: false,
+        })
+    }
+
+    /// 写入 f32 样本（[-1.0, 1.0]，超出范围会被截断）
+    pub fn write_samples(&mut self, samples: &[f32]) -> VInputResult<()> {
+        for &s in samples {
+            let v = (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            self.writer.write_all(&v.to_le_bytes())?;
+        }
+        self.samples_written += samples.len() as u32;
+        Ok(())
+    }
+
+    /// 已写入样本数
+    pub fn samples_written(&self) -> u32 {
+        self.samples_written
+    }
+
+    /// 回填文件头并刷新
+    pub fn finish(&mut self) -> VInputResult<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.writer.seek(SeekFrom::Start(0))?;
+        write_header(&mut self.writer, self.sample_rate, self.samples_written)?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for WavWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish() {
+            tracing::warn!("WAV 文件头回填失败: {}", e);
+        }
+    }
+}
+
+/// 写入 44 字节的标准 RIFF/WAVE 文件头
+fn write_header<W: Write>(w: &mut W, sample_rate: u32, num_samples: u32) -> std::io::Result<()> {
+    const CHANNELS: u16 = 1;
+    const BITS_PER_SAMPLE: u16 = 16;
+    let block_align = CHANNELS * BITS_PER_SAMPLE / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let data_len = num_samples * block_align as u32;
+
+    w.write_all(b"RIFF")?;
+    w.write_all(&(36 + data_len).to_le_bytes())?;
+    w.write_all(b"WAVE")?;
+    w.write_all(b"fmt ")?;
+    w.write_all(&16u32.to_le_bytes())?;
+    w.write_all(&1u16.to_le_bytes())?; // PCM
+    w.write_all(&CHANNELS.to_le_bytes())?;
+    w.write_all(&sample_rate.to_le_bytes())?;
+    w.write_all(&byte_rate.to_le_bytes())?;
+    w.write_all(&block_align.to_le_bytes())?;
+    w.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+    w.write_all(b"data")?;
+    w.write_all(&data_len.to_le_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_writer_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.wav");
+
+        {
+            let mut writer = WavWriter::create(&path, 16000).unwrap();
+            writer.write_samples(&[0.0, 0.5, -0.5, 1.0]).unwrap();
+            assert_eq!(writer.samples_written(), 4);
+        }
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), 44 + 8);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 16000);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 8);
+    }
+
+    #[test]
+    fn test_read_wav_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("roundtrip.wav");
+
+        {
+            let mut writer = WavWriter::create(&path, 22050).unwrap();
+            writer.write_samples(&[0.0, 0.5, -0.5]).unwrap();
+        }
+
+        let wav = read_wav(&path).unwrap();
+        assert_eq!(wav.sample_rate, 22050);
+        assert_eq!(wav.channels, 1);
+        assert_eq!(wav.samples.len(), 3);
+        assert!((wav.samples[1] - 0.5).abs() < 1e-3);
+
+        std::fs::write(&path, b"not a wav file").unwrap();
+        assert!(read_wav(&path).is_err());
+    }
+
+    #[test]
+    fn test_encode_wav_f32_is_lossless() {
+        let samples = [0.0, 0.123_456_79, -0.987_654_3, 1.5];
+        let bytes = encode_wav_f32(&samples, 16000);
+        assert_eq!(bytes.len(), 44 + samples.len() * 4);
+
+        let wav = decode_wav(&bytes).unwrap();
+        assert_eq!(wav.sample_rate, 16000);
+        assert_eq!(wav.channels, 1);
+        assert_eq!(wav.samples, samples);
+    }
+}
@@ -3,61 +3,9 @@
 //! 基于 VAD 和 ASR 端点的智能语音边界检测
 //! Phase 1.5: 端点检测优化
 
-use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 
-/// 端点检测配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EndpointDetectorConfig {
-    /// 最小语音长度（毫秒）
-    /// 低于此长度的音频段会被忽略（过滤点击音等）
-    #[serde(default = "default_min_speech_ms")]
-    pub min_speech_duration_ms: u64,
-
-    /// 最大语音长度（毫秒）
-    /// 超过此长度会自动分段
-    #[serde(default = "default_max_speech_ms")]
-    pub max_speech_duration_ms: u64,
-
-    /// 语音结束后的静音等待时间（毫秒）
-    /// 用于确认用户说话已结束
-    #[serde(default = "default_trailing_silence_ms")]
-    pub trailing_silence_ms: u64,
-
-    /// 强制超时（毫秒）
-    /// 即使没有检测到端点，超时后也会强制结束
-    #[serde(default = "default_force_timeout_ms")]
-    pub force_timeout_ms: u64,
-
-    /// 是否启用 VAD 辅助端点检测
-    #[serde(default = "default_true")]
-    pub vad_assisted: bool,
-
-    /// VAD 检测到静音后的确认帧数
-    /// 连续 N 帧静音才确认语音结束
-    #[serde(default = "default_vad_silence_frames")]
-    pub vad_silence_confirm_frames: usize,
-}
-
-fn default_min_speech_ms() -> u64 { 300 }
-fn default_max_speech_ms() -> u64 { 30_000 }
-fn default_trailing_silence_ms() -> u64 { 600 }
-fn default_force_timeout_ms() -> u64 { 60_000 }
-fn default_true() -> bool { true }
-fn default_vad_silence_frames() -> usize { 5 }  // 5 帧 × 32ms = 160ms
-
-impl Default for EndpointDetectorConfig {
-    fn default() -> Self {
-        Self {
-            min_speech_duration_ms: 300,        // 300ms 最小语音
-            max_speech_duration_ms: 30_000,     // 30s 最大语音（自动分段）
-            trailing_silence_ms: 600,           // 600ms 尾部静音
-            force_timeout_ms: 60_000,           // 60s 强制超时
-            vad_assisted: true,                 // 启用 VAD 辅助
-            vad_silence_confirm_frames: 5,      // 5 帧静音确认（约 160ms @ 32ms/frame）
-        }
-    }
-}
+pub use vinput_config::endpoint::EndpointDetectorConfig;
 
 /// 端点检测结果
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
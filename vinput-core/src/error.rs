@@ -13,6 +13,9 @@ pub enum VInputError {
     #[error("Ring buffer overrun: {lost_frames} frames lost")]
     RingBufferOverrun { lost_frames: u64 },
 
+    #[error("Audio source error: {0}")]
+    AudioSource(String),
+
     // ASR 错误
     #[error("Model load failed: {path} - {reason}")]
     ModelLoad { path: String, reason: String },
@@ -124,7 +127,7 @@ impl VInputError {
             }
 
             // 高严重度：核心功能受影响
-            VInputError::PipeWire(_) => ErrorSeverity::High,
+            VInputError::PipeWire(_) | VInputError::AudioSource(_) => ErrorSeverity::High,
             VInputError::AsrInference(_) => ErrorSeverity::High,
             VInputError::RecognizerNotReady => ErrorSeverity::High,
             VInputError::InvalidTransition { .. } | VInputError::NotAllowedInState { .. } => {
@@ -147,7 +150,7 @@ impl VInputError {
     pub fn recovery_strategy(&self) -> RecoveryStrategy {
         match self {
             // 可重试的错误
-            VInputError::PipeWire(_) => RecoveryStrategy::Retry,
+            VInputError::PipeWire(_) | VInputError::AudioSource(_) => RecoveryStrategy::Retry,
             VInputError::ChannelSend | VInputError::ChannelRecv => RecoveryStrategy::Retry,
             VInputError::AsrInference(_) | VInputError::VadInference(_) => RecoveryStrategy::Retry,
 
@@ -180,6 +183,9 @@ impl VInputError {
             VInputError::AudioDeviceNotFound(device) => {
                 format!("未找到音频设备 \"{}\"，请检查麦克风连接", device)
             }
+            VInputError::AudioSource(msg) => {
+                format!("音频输入源错误：{}", simplify_technical_message(msg))
+            }
             VInputError::RingBufferOverrun { lost_frames } => {
                 format!("音频缓冲区溢出，丢失了 {} 个音频帧。建议关闭其他占用 CPU 的程序", lost_frames)
             }
@@ -246,6 +252,7 @@ impl VInputError {
             VInputError::PipeWire(_) => "E1001",
             VInputError::AudioDeviceNotFound(_) => "E1002",
             VInputError::RingBufferOverrun { .. } => "E1003",
+            VInputError::AudioSource(_) => "E1004",
             VInputError::ModelLoad { .. } => "E2001",
             VInputError::AsrInference(_) => "E2002",
             VInputError::RecognizerNotReady => "E2003",
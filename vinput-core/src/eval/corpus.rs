@@ -0,0 +1,131 @@
+//! 评测语料
+//!
+//! 目录下每条语料由同名文件组成（与 `tests/testdata` 相同）：
+//!
+//! ```text
+//! 001_天气.wav        16kHz WAV（多声道时取平均）
+//! 001_天气.expected   书面形式参考文本（含标点与 ITN，如「今天是2003年3月5日。」）
+//! 001_天气.spoken     可选，口语形式参考文本（如「今天是二零零三年三月五日」），用于单独评测 ITN
+//! ```
+
+use crate::audio::read_wav;
+use crate::error::{VInputError, VInputResult};
+use std::path::{Path, PathBuf};
+
+/// 评测采样率（与管道一致）
+pub const SAMPLE_RATE: u32 = 16000;
+
+/// 一条评测语料
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalCase {
+    /// 名称（文件名去掉扩展名）
+    pub name: String,
+    /// 音频文件
+    pub audio: PathBuf,
+    /// 书面形式参考文本
+    pub expected: String,
+    /// 口语形式参考文本
+    pub spoken: Option<String>,
+}
+
+impl EvalCase {
+    /// 读取音频（16kHz 单声道 f32）
+    pub fn load_audio(&self) -> VInputResult<Vec<f32>> {
+        let wav = read_wav(&self.audio)?;
+        if wav.sample_rate != SAMPLE_RATE {
+            return Err(VInputError::AudioSource(format!(
+                "{}: 需要 {} Hz 音频，实际为 {} Hz",
+                self.audio.display(),
+                SAMPLE_RATE,
+                wav.sample_rate
+            )));
+        }
+        let channels = wav.channels.max(1) as usize;
+        Ok(wav
+            .samples
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect())
+    }
+}
+
+/// 读取语料目录（按名称排序；缺少或为空的 `.expected` 跳过）
+pub fn load_corpus(dir: &Path) -> VInputResult<Vec<EvalCase>> {
+    let read_text = |path: &Path| -> VInputResult<Option<String>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Some(text.trim().to_string()).filter(|t| !t.is_empty())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    };
+
+    let mut audio: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .collect();
+    audio.sort();
+
+    let mut cases = Vec::with_capacity(audio.len());
+    for path in audio {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let Some(expected) = read_text(&path.with_extension("expected"))? else {
+            tracing::warn!("跳过 {}：缺少参考文本 {}.expected", path.display(), name);
+            continue;
+        };
+        let spoken = read_text(&path.with_extension("spoken"))?;
+        cases.push(EvalCase {
+            name,
+            audio: path,
+            expected,
+            spoken,
+        });
+    }
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::WavWriter;
+
+    #[test]
+    fn test_load_corpus_pairs_files() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["002_日期", "001_天气", "003_无参考"] {
+            let mut writer = WavWriter::create(&dir.path().join(format!("{}.wav", name)), SAMPLE_RATE).unwrap();
+            writer.write_samples(&[0.0, 0.5]).unwrap();
+        }
+        std::fs::write(dir.path().join("001_天气.expected"), "今天天气很好。\n").unwrap();
+        std::fs::write(dir.path().join("002_日期.expected"), "今天是3月5日。").unwrap();
+        std::fs::write(dir.path().join("002_日期.spoken"), "今天是三月五日").unwrap();
+        std::fs::write(dir.path().join("003_无参考.expected"), "  \n").unwrap();
+
+        let cases = load_corpus(dir.path()).unwrap();
+        let names: Vec<&str> = cases.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["001_天气", "002_日期"]);
+        assert_eq!(cases[0].expected, "今天天气很好。");
+        assert_eq!(cases[0].spoken, None);
+        assert_eq!(cases[1].spoken.as_deref(), Some("今天是三月五日"));
+
+        let samples = cases[0].load_audio().unwrap();
+        assert_eq!(samples.len(), 2);
+    }
+
+    #[test]
+    fn test_load_audio_rejects_other_sample_rates() {
+        let dir = tempfile::tempdir().unwrap();
+        let audio = dir.path().join("a.wav");
+        WavWriter::create(&audio, 8000).unwrap();
+        let case = EvalCase {
+            name: "a".to_string(),
+            audio,
+            expected: "你好".to_string(),
+            spoken: None,
+        };
+        assert!(case.load_audio().is_err());
+    }
+}
@@ -0,0 +1,19 @@
+//! 识别准确率评测
+//!
+//! 用一组或多组配置（或模型）识别带参考文本的语料目录，分别统计：
+//! - 字错误率（CER）：只比对文字
+//! - 标点 F1：按文字对齐后比对标点位置与类别
+//! - ITN 完全匹配：口语形式参考文本经 ITN 后与书面形式是否一致（需要 `.spoken` 文件）
+//!
+//! 结果输出为 JSON / Markdown 报告，用于比较调整 `max_active_paths`、VAD 阈值等配置前后的效果。
+//! 命令行入口见 `examples/evaluate.rs`。
+
+pub mod corpus;
+pub mod report;
+pub mod runner;
+pub mod scoring;
+
+pub use corpus::{load_corpus, EvalCase};
+pub use report::{EvalReport, RunReport, RunSummary, UtteranceResult};
+pub use runner::evaluate;
+pub use scoring::{char_errors, exact_match, punctuation_counts, CharErrors, PunctuationCounts};
@@ -0,0 +1,263 @@
+//! 评测报告（JSON / Markdown）
+
+use super::scoring::{CharErrors, PunctuationCounts};
+use crate::error::{VInputError, VInputResult};
+use serde::Serialize;
+use std::fmt::Write as _;
+
+/// 一条语料的评测结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UtteranceResult {
+    pub name: String,
+    /// 书面形式参考文本
+    pub expected: String,
+    /// 识别结果（ITN 后，即上屏文本）
+    pub hypothesis: String,
+    /// 识别结果（ITN 前，含标点）
+    pub punctuated: String,
+    pub char_errors: CharErrors,
+    pub punctuation: PunctuationCounts,
+    /// 口语形式参考文本经 ITN 后是否与书面形式一致（无口语形式时为 None）
+    pub itn_exact_match: Option<bool>,
+    /// 音频时长（秒）
+    pub audio_secs: f64,
+    /// 处理耗时（秒）
+    pub processing_secs: f64,
+}
+
+/// 一组配置的汇总指标
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RunSummary {
+    pub utterances: usize,
+    /// 字错误率（所有语料的编辑距离之和 / 参考字数之和）
+    pub cer: Option<f64>,
+    pub char_errors: CharErrors,
+    pub punctuation_precision: Option<f64>,
+    pub punctuation_recall: Option<f64>,
+    pub punctuation_f1: Option<f64>,
+    pub punctuation: PunctuationCounts,
+    /// 有口语形式参考文本的语料数
+    pub itn_cases: usize,
+    /// ITN 完全匹配率
+    pub itn_exact_match: Option<f64>,
+    pub audio_secs: f64,
+    pub processing_secs: f64,
+    /// 实时率（处理耗时 / 音频时长）
+    pub real_time_factor: Option<f64>,
+}
+
+impl RunSummary {
+    fn new(utterances: &[UtteranceResult]) -> Self {
+        let mut summary = Self {
+            utterances: utterances.len(),
+            ..Self::default()
+        };
+        let mut itn_matched = 0;
+        for utterance in utterances {
+            summary.char_errors.add(utterance.char_errors);
+            summary.punctuation.add(utterance.punctuation);
+            if let Some(matched) = utterance.itn_exact_match {
+                summary.itn_cases += 1;
+                itn_matched += usize::from(matched);
+            }
+            summary.audio_secs += utterance.audio_secs;
+            summary.processing_secs += utterance.processing_secs;
+        }
+        summary.cer = summary.char_errors.rate();
+        summary.punctuation_precision = summary.punctuation.precision();
+        summary.punctuation_recall = summary.punctuation.recall();
+        summary.punctuation_f1 = summary.punctuation.f1();
+        summary.itn_exact_match =
+            (summary.itn_cases > 0).then(|| itn_matched as f64 / summary.itn_cases as f64);
+        summary.real_time_factor =
+            (summary.audio_secs > 0.0).then(|| summary.processing_secs / summary.audio_secs);
+        summary
+    }
+}
+
+/// 一组配置（或模型）的评测结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunReport {
+    /// 名称（配置文件名或模型目录名）
+    pub label: String,
+    /// 使用的模型目录
+    pub model_dir: String,
+    pub summary: RunSummary,
+    pub utterances: Vec<UtteranceResult>,
+}
+
+impl RunReport {
+    /// 汇总各条语料的结果
+    pub fn new(label: impl Into<String>, model_dir: impl Into<String>, utterances: Vec<UtteranceResult>) -> Self {
+        Self {
+            label: label.into(),
+            model_dir: model_dir.into(),
+            summary: RunSummary::new(&utterances),
+            utterances,
+        }
+    }
+}
+
+/// 评测报告：同一语料上的多组配置
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EvalReport {
+    /// 语料目录
+    pub corpus: String,
+    pub runs: Vec<RunReport>,
+}
+
+impl EvalReport {
+    /// JSON 格式
+    pub fn to_json(&self) -> VInputResult<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| VInputError::Generic(format!("序列化评测报告失败: {}", e)))
+    }
+
+    /// Markdown 格式：先并排比较汇总指标，再列出每组配置的逐条结果
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let utterances = self.runs.first().map_or(0, |run| run.utterances.len());
+        let _ = writeln!(md, "# V-Input 识别评测\n");
+        let _ = writeln!(md, "语料: `{}`（{} 条）\n", self.corpus, utterances);
+
+        let _ = write!(md, "| 指标 |");
+        for run in &self.runs {
+            let _ = write!(md, " {} |", escape(&run.label));
+        }
+        let _ = write!(md, "\n|---|");
+        for _ in &self.runs {
+            let _ = write!(md, "---|");
+        }
+        md.push('\n');
+
+        let rows: [(&str, SummaryCell); 6] = [
+            ("字错误率 (CER)", |s| percent(s.cer)),
+            ("标点 F1", |s| ratio(s.punctuation_f1)),
+            ("标点精确率", |s| ratio(s.punctuation_precision)),
+            ("标点召回率", |s| ratio(s.punctuation_recall)),
+            ("ITN 完全匹配", |s| match s.itn_exact_match {
+                Some(rate) => format!(
+                    "{} ({}/{})",
+                    percent(Some(rate)),
+                    (rate * s.itn_cases as f64).round() as usize,
+                    s.itn_cases
+                ),
+                None => "-".to_string(),
+            }),
+            ("实时率 (RTF)", |s| ratio(s.real_time_factor)),
+        ];
+        for (name, value) in rows {
+            let _ = write!(md, "| {} |", name);
+            for run in &self.runs {
+                let _ = write!(md, " {} |", value(&run.summary));
+            }
+            md.push('\n');
+        }
+
+        for run in &self.runs {
+            let _ = writeln!(md, "\n## {}\n", escape(&run.label));
+            let _ = writeln!(md, "模型: `{}`\n", run.model_dir);
+            let _ = writeln!(md, "| 语料 | CER | 标点 F1 | ITN | 识别结果 | 参考文本 |");
+            let _ = writeln!(md, "|---|---|---|---|---|---|");
+            for u in &run.utterances {
+                let itn = match u.itn_exact_match {
+                    Some(true) => "✅",
+                    Some(false) => "❌",
+                    None => "-",
+                };
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {} | {} | {} |",
+                    escape(&u.name),
+                    percent(u.char_errors.rate()),
+                    ratio(u.punctuation.f1()),
+                    itn,
+                    escape(&u.hypothesis),
+                    escape(&u.expected)
+                );
+            }
+        }
+        md
+    }
+}
+
+/// 汇总表中一个指标的格式化函数
+type SummaryCell = fn(&RunSummary) -> String;
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.2}%", v * 100.0))
+}
+
+fn ratio(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| format!("{:.3}", v))
+}
+
+/// 转义 Markdown 表格中的竖线与换行
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterance(name: &str, edits: usize, itn_exact_match: Option<bool>) -> UtteranceResult {
+        UtteranceResult {
+            name: name.to_string(),
+            expected: "今天天气很好。".to_string(),
+            hypothesis: "今天天器很好。".to_string(),
+            punctuated: "今天天器很好。".to_string(),
+            char_errors: CharErrors { edits, reference_chars: 6 },
+            punctuation: PunctuationCounts { true_positives: 1, false_positives: 1, false_negatives: 0 },
+            itn_exact_match,
+            audio_secs: 2.0,
+            processing_secs: 0.5,
+        }
+    }
+
+    fn report() -> EvalReport {
+        EvalReport {
+            corpus: "corpus".to_string(),
+            runs: vec![
+                RunReport::new(
+                    "beam4",
+                    "models/a",
+                    vec![utterance("001", 1, Some(true)), utterance("002", 2, Some(false))],
+                ),
+                RunReport::new("beam|8", "models/b", vec![utterance("001", 0, None), utterance("002", 0, None)]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_summary_aggregates_micro_averages() {
+        let summary = &report().runs[0].summary;
+        assert_eq!(summary.utterances, 2);
+        assert_eq!(summary.cer, Some(0.25));
+        assert_eq!(summary.punctuation_precision, Some(0.5));
+        assert_eq!(summary.punctuation_recall, Some(1.0));
+        assert_eq!(summary.itn_cases, 2);
+        assert_eq!(summary.itn_exact_match, Some(0.5));
+        assert_eq!(summary.real_time_factor, Some(0.25));
+
+        let empty = RunReport::new("empty", "", Vec::new());
+        assert_eq!(empty.summary.cer, None);
+        assert_eq!(empty.summary.real_time_factor, None);
+    }
+
+    #[test]
+    fn test_markdown_and_json() {
+        let report = report();
+        let md = report.to_markdown();
+        assert!(md.contains("语料: `corpus`（2 条）"));
+        assert!(md.contains("| 指标 | beam4 | beam\\|8 |"));
+        assert!(md.contains("| 字错误率 (CER) | 25.00% | 0.00% |"));
+        assert!(md.contains("| ITN 完全匹配 | 50.00% (1/2) | - |"));
+        assert!(md.contains("| 002 | 33.33% | 0.667 | ❌ | 今天天器很好。 | 今天天气很好。 |"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["runs"][1]["label"], "beam|8");
+        assert_eq!(json["runs"][0]["summary"]["cer"], 0.25);
+        assert!(json["runs"][1]["summary"]["itn_exact_match"].is_null());
+    }
+}
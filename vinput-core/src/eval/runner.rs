@@ -0,0 +1,79 @@
+//! 用一组配置识别整个语料
+
+use super::corpus::{EvalCase, SAMPLE_RATE};
+use super::report::{RunReport, UtteranceResult};
+use super::scoring::{char_errors, exact_match, punctuation_counts};
+use crate::error::VInputResult;
+use crate::itn::{ITNEngine, ITNMode};
+use crate::streaming::{PipelineState, StreamingConfig, StreamingPipeline};
+use std::time::Instant;
+use vinput_config::VInputConfig;
+
+/// 每帧样本数（与引擎音频线程一致）
+const FRAME_SIZE: usize = 512;
+/// 识别中每隔多少帧查询一次部分结果（与引擎的 Preedit 节流一致，标点引擎状态依赖查询时机）
+const PARTIAL_INTERVAL: usize = 5;
+
+/// 用 `config` 识别全部语料并评分
+///
+/// 与引擎音频线程的调用顺序一致：逐帧送入管道，识别中定期查询部分结果，
+/// 句子结束时取最终结果并做 ITN；音频结束后相当于手动停止，取剩余的最终结果。
+/// 一条语料中的多句结果直接拼接。
+pub fn evaluate(label: &str, config: &VInputConfig, cases: &[EvalCase]) -> VInputResult<RunReport> {
+    let mut pipeline = StreamingPipeline::new(StreamingConfig::from_config(config))?;
+    let itn = ITNEngine::new(ITNMode::Auto);
+
+    let mut utterances = Vec::with_capacity(cases.len());
+    for case in cases {
+        let samples = case.load_audio()?;
+        tracing::info!("评测 [{}] {}（{} 样本）", label, case.name, samples.len());
+
+        let start = Instant::now();
+        pipeline.reset()?;
+        let finals = transcribe(&mut pipeline, &samples)?;
+        let punctuated = finals.concat();
+        let hypothesis: String = finals.iter().map(|text| itn.process(text).text).collect();
+        let processing_secs = start.elapsed().as_secs_f64();
+
+        let itn_exact_match = case
+            .spoken
+            .as_ref()
+            .map(|spoken| exact_match(&case.expected, &itn.process(spoken).text));
+
+        utterances.push(UtteranceResult {
+            name: case.name.clone(),
+            expected: case.expected.clone(),
+            char_errors: char_errors(&case.expected, &hypothesis),
+            punctuation: punctuation_counts(&case.expected, &hypothesis),
+            itn_exact_match,
+            hypothesis,
+            punctuated,
+            audio_secs: samples.len() as f64 / SAMPLE_RATE as f64,
+            processing_secs,
+        });
+    }
+
+    Ok(RunReport::new(label, config.asr.model_dir.clone(), utterances))
+}
+
+/// 识别一段音频，返回各句带标点的最终结果（ITN 前）
+fn transcribe(pipeline: &mut StreamingPipeline, samples: &[f32]) -> VInputResult<Vec<String>> {
+    let mut finals = Vec::new();
+    let mut frame = vec![0.0f32; FRAME_SIZE];
+    for (index, chunk) in samples.chunks(FRAME_SIZE).enumerate() {
+        // 最后一块不足一帧时补零
+        frame[..chunk.len()].copy_from_slice(chunk);
+        frame[chunk.len()..].fill(0.0);
+
+        let result = pipeline.process(&frame)?;
+        if result.pipeline_state == PipelineState::Recognizing && (index + 1).is_multiple_of(PARTIAL_INTERVAL) {
+            pipeline.get_partial_result_with_punctuation();
+        }
+        if result.pipeline_state == PipelineState::Completed {
+            finals.push(pipeline.get_final_result_with_punctuation());
+        }
+    }
+    finals.push(pipeline.get_final_result_with_punctuation());
+    finals.retain(|text| !text.is_empty());
+    Ok(finals)
+}
@@ -0,0 +1,279 @@
+//! 文本评分：字错误率、标点 F1、ITN 完全匹配
+//!
+//! 三项指标互相独立：
+//! - 字错误率只比对文字（去掉标点与空白，英文不区分大小写）
+//! - 标点 F1 先按文字做编辑距离对齐，再比对对齐后同一位置的标点类别
+//! - ITN 完全匹配对口语形式参考文本做 ITN，与书面形式参考文本（去掉标点）比对
+
+use serde::{Deserialize, Serialize};
+
+/// 标点类别（全角 / 半角视为同一类）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PunctuationMark {
+    Comma,
+    Enumeration,
+    Period,
+    Question,
+    Exclamation,
+    Semicolon,
+    Colon,
+}
+
+impl PunctuationMark {
+    /// 字符对应的标点类别（非标点为 None）
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '，' | ',' => Some(Self::Comma),
+            '、' => Some(Self::Enumeration),
+            '。' | '.' => Some(Self::Period),
+            '？' | '?' => Some(Self::Question),
+            '！' | '!' => Some(Self::Exclamation),
+            '；' | ';' => Some(Self::Semicolon),
+            '：' | ':' => Some(Self::Colon),
+            _ => None,
+        }
+    }
+}
+
+/// 拆分后的文本：文字序列与标点（位置为其前面的文字数）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SplitText {
+    pub chars: Vec<char>,
+    pub marks: Vec<(usize, PunctuationMark)>,
+}
+
+/// 拆分文字与标点
+///
+/// 数字之间的 `.`（如 `3.5`）视为文字；引号、括号等其他符号与空白忽略
+pub fn split_text(text: &str) -> SplitText {
+    let mut split = SplitText::default();
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let between_digits = c == '.'
+            && i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+        if let Some(mark) = PunctuationMark::from_char(c).filter(|_| !between_digits) {
+            split.marks.push((split.chars.len(), mark));
+        } else if c.is_alphanumeric() || between_digits {
+            split.chars.push(c.to_ascii_lowercase());
+        }
+    }
+    split
+}
+
+/// 编辑距离对齐中的一步
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// 相同或替换（参考、识别各前进一字）
+    Pair,
+    /// 参考文本多出的字（删除错误）
+    Deletion,
+    /// 识别结果多出的字（插入错误）
+    Insertion,
+}
+
+/// 编辑距离与对齐路径
+fn align(reference: &[char], hypothesis: &[char]) -> (usize, Vec<Op>) {
+    let (n, m) = (reference.len(), hypothesis.len());
+    let mut dist = vec![vec![0usize; m + 1]; n + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = usize::from(reference[i - 1] != hypothesis[j - 1]);
+            dist[i][j] = (dist[i - 1][j - 1] + cost)
+                .min(dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1);
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        if i > 0 && j > 0 {
+            let cost = usize::from(reference[i - 1] != hypothesis[j - 1]);
+            if dist[i][j] == dist[i - 1][j - 1] + cost {
+                ops.push(Op::Pair);
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+        if i > 0 && dist[i][j] == dist[i - 1][j] + 1 {
+            ops.push(Op::Deletion);
+            i -= 1;
+        } else {
+            ops.push(Op::Insertion);
+            j -= 1;
+        }
+    }
+    ops.reverse();
+    (dist[n][m], ops)
+}
+
+/// 字错误统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharErrors {
+    /// 编辑距离（替换 + 删除 + 插入）
+    pub edits: usize,
+    /// 参考文本字数
+    pub reference_chars: usize,
+}
+
+impl CharErrors {
+    /// 字错误率（参考文本为空时为 None）
+    pub fn rate(&self) -> Option<f64> {
+        (self.reference_chars > 0).then(|| self.edits as f64 / self.reference_chars as f64)
+    }
+
+    /// 累加
+    pub fn add(&mut self, other: CharErrors) {
+        self.edits += other.edits;
+        self.reference_chars += other.reference_chars;
+    }
+}
+
+/// 字错误（忽略标点与空白）
+pub fn char_errors(reference: &str, hypothesis: &str) -> CharErrors {
+    let reference = split_text(reference).chars;
+    let hypothesis = split_text(hypothesis).chars;
+    CharErrors {
+        edits: align(&reference, &hypothesis).0,
+        reference_chars: reference.len(),
+    }
+}
+
+/// 标点匹配统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PunctuationCounts {
+    /// 位置与类别都正确
+    pub true_positives: usize,
+    /// 多出或类别错误
+    pub false_positives: usize,
+    /// 遗漏
+    pub false_negatives: usize,
+}
+
+impl PunctuationCounts {
+    pub fn precision(&self) -> Option<f64> {
+        let predicted = self.true_positives + self.false_positives;
+        (predicted > 0).then(|| self.true_positives as f64 / predicted as f64)
+    }
+
+    pub fn recall(&self) -> Option<f64> {
+        let actual = self.true_positives + self.false_negatives;
+        (actual > 0).then(|| self.true_positives as f64 / actual as f64)
+    }
+
+    /// F1（识别结果与参考文本都没有标点时为 None）
+    pub fn f1(&self) -> Option<f64> {
+        let total = 2 * self.true_positives + self.false_positives + self.false_negatives;
+        (total > 0).then(|| 2.0 * self.true_positives as f64 / total as f64)
+    }
+
+    /// 累加
+    pub fn add(&mut self, other: PunctuationCounts) {
+        self.true_positives += other.true_positives;
+        self.false_positives += other.false_positives;
+        self.false_negatives += other.false_negatives;
+    }
+}
+
+/// 标点匹配（按文字对齐后比对位置与类别）
+pub fn punctuation_counts(reference: &str, hypothesis: &str) -> PunctuationCounts {
+    let reference = split_text(reference);
+    let hypothesis = split_text(hypothesis);
+    let (_, ops) = align(&reference.chars, &hypothesis.chars);
+
+    // 识别结果中第 j 个字之后的位置 → 参考文本中的位置
+    let mut boundary = vec![0usize; hypothesis.chars.len() + 1];
+    let (mut i, mut j) = (0, 0);
+    for op in ops {
+        match op {
+            Op::Pair => {
+                i += 1;
+                j += 1;
+                boundary[j] = i;
+            }
+            Op::Deletion => i += 1,
+            Op::Insertion => {
+                j += 1;
+                boundary[j] = i;
+            }
+        }
+    }
+    // 句尾标点总是对应参考文本的句尾
+    if let Some(last) = boundary.last_mut() {
+        *last = reference.chars.len();
+    }
+
+    let mut unmatched = reference.marks.clone();
+    let mut counts = PunctuationCounts::default();
+    for &(position, mark) in &hypothesis.marks {
+        let target = (boundary[position], mark);
+        if let Some(index) = unmatched.iter().position(|&m| m == target) {
+            unmatched.swap_remove(index);
+            counts.true_positives += 1;
+        } else {
+            counts.false_positives += 1;
+        }
+    }
+    counts.false_negatives = unmatched.len();
+    counts
+}
+
+/// 去掉标点与空白后是否完全一致（ITN 完全匹配用）
+pub fn exact_match(reference: &str, hypothesis: &str) -> bool {
+    split_text(reference).chars == split_text(hypothesis).chars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_text_keeps_decimal_point() {
+        let split = split_text("价格是3.5元, 对吗？ OK");
+        assert_eq!(split.chars.iter().collect::<String>(), "价格是3.5元对吗ok");
+        assert_eq!(split.marks, vec![(7, PunctuationMark::Comma), (9, PunctuationMark::Question)]);
+    }
+
+    #[test]
+    fn test_char_errors() {
+        let errors = char_errors("今天天气很好。", "今天天器很好啊");
+        assert_eq!(errors, CharErrors { edits: 2, reference_chars: 6 });
+        assert!((errors.rate().unwrap() - 2.0 / 6.0).abs() < 1e-9);
+
+        assert_eq!(char_errors("，", "你好").rate(), None);
+        assert_eq!(char_errors("我要出门", "我要出门").edits, 0);
+    }
+
+    #[test]
+    fn test_punctuation_counts_follow_alignment() {
+        // 识别少了一个字，逗号位置仍按对齐后的位置比对
+        let counts = punctuation_counts("今天天气很好，我要出门逛街。", "今天气很好，我要出门逛街？");
+        assert_eq!(
+            counts,
+            PunctuationCounts { true_positives: 1, false_positives: 1, false_negatives: 1 }
+        );
+        assert_eq!(counts.f1(), Some(0.5));
+
+        let counts = punctuation_counts("不对,难道不是吗？", "不对，难道不是吗？");
+        assert_eq!(counts.true_positives, 2);
+        assert_eq!(counts.f1(), Some(1.0));
+
+        assert_eq!(punctuation_counts("你好", "你好").f1(), None);
+    }
+
+    #[test]
+    fn test_exact_match_ignores_punctuation() {
+        assert!(exact_match("今天是2003年3月5日,", "今天是2003年3月5日"));
+        assert!(!exact_match("3月8日", "三月八日"));
+    }
+}
@@ -4,54 +4,664 @@
 //! 完整集成: StreamingPipeline + ITN + Punctuation + Hotwords
 
 use super::safety::{check_null, check_null_mut, ffi_safe_call};
-use super::types::{VInputCommand, VInputCommandCallback, VInputEvent, VInputEventType, VInputFFIResult};
-use crate::audio::{AudioRingBuffer, AudioRingBufferConfig, PipeWireStream, PipeWireStreamConfig};
-use crate::config::VInputConfig;
-use crate::hotwords::HotwordsEngine;
+use super::types::{
+    VInputActivation, VInputCommand, VInputCommandCallback, VInputCommandPayload, VInputEvent,
+    VInputEventType, VInputFFIResult, VInputHandleCommandCallback, VInputMetrics,
+    VInputPreeditSegment, VInputShortcutAction,
+};
+use crate::asr::OnlineRecognizerConfig;
+use crate::audio::{
+    open_audio_source, AudioCaptureConfig, AudioQueueConfig, AudioRingBuffer, AudioRingBufferConfig, AudioRingConsumer,
+    AudioSource, AudioSourceConfig, DeviceEventListener, LevelMeter, PipeWireStreamConfig,
+};
+use crate::config::{ActivationConfig, ShortcutConfig, StopMode, VInputConfig};
+use crate::error::VInputResult;
+use crate::hotwords::{apply_hotwords_file, generated_hotwords_path, HotwordLearner, HotwordsConfig};
+use crate::kws::{KeywordSpotter, WakeWordConfig, WakeWordListener};
 use crate::itn::{ITNEngine, ITNMode};
-use crate::streaming::{StreamingConfig, StreamingPipeline};
+use crate::metrics::{MetricsExporter, PipelineMetrics, Stage};
+use crate::recorder::{SessionRecorder, SessionStore};
+use crate::recovery::{Degradation, RecoveryConfig, RecoverySupervisor, Supervised};
+use crate::streaming::preedit::segments_text;
+use crate::streaming::{
+    run_staged, HypothesisVariantsConfig, CancelConfig, Capture, Flow, PreeditSegment, StreamingCommitter, StreamingConfig,
+    StreamingPipeline, StreamingResult, VadFrontend,
+};
 use crate::undo::RecognitionHistory;
 use std::collections::VecDeque;
-use std::ffi::CString;
-use std::os::raw::c_char;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 
-/// 全局 V-Input Core 实例
-static VINPUT_CORE: Mutex<Option<VInputCoreState>> = Mutex::new(None);
+/// 默认引擎实例（供 `vinput_core_*` 兼容接口使用）
+static DEFAULT_ENGINE: Mutex<Option<VInputHandle>> = Mutex::new(None);
 
-/// 全局命令回调函数
-static COMMAND_CALLBACK: Mutex<Option<VInputCommandCallback>> = Mutex::new(None);
+/// 管道每帧样本数（512 samples = 32ms @ 16kHz）
+const FRAME_SIZE: usize = 512;
+
+/// 不透明的 V-Input 引擎句柄
+///
+/// 由 `vinput_engine_new` 创建、`vinput_engine_free` 释放；
+/// 每个句柄拥有独立的配置、管道、命令队列与回调，可在同一进程内并存
+pub struct VInputHandle {
+    state: Mutex<VInputCoreState>,
+}
+
+/// 命令回调目标
+#[derive(Clone, Copy)]
+enum CommandSink {
+    /// 兼容接口注册的回调（无 user_data）
+    Plain(VInputCommandCallback),
+    /// 句柄接口注册的回调
+    WithUserData(VInputHandleCommandCallback, *mut c_void),
+}
+
+// user_data 由调用方保证在回调线程（音频线程）中可用
+unsafe impl Send for CommandSink {}
+
+/// 引擎与音频线程共享的回调槽
+type SharedCommandSink = Arc<Mutex<Option<CommandSink>>>;
+
+/// 通过回调实时发送命令（未注册回调时丢弃），发送后释放命令资源
+fn emit_command(sink: &SharedCommandSink, mut cmd: VInputCommand) {
+    let target = *sink.lock().unwrap();
+    match target {
+        Some(CommandSink::Plain(callback)) => callback(&cmd as *const VInputCommand),
+        Some(CommandSink::WithUserData(callback, user_data)) => {
+            callback(&cmd as *const VInputCommand, user_data)
+        }
+        None => {}
+    }
+    vinput_command_free(&mut cmd as *mut VInputCommand);
+}
+
+/// 识别结果后处理（ITN、流式上屏、候选、历史记录），音频线程与停止录音共用
+#[derive(Clone)]
+struct ResultContext {
+    /// ITN 引擎
+    itn_engine: Arc<Mutex<ITNEngine>>,
+    /// ITN 是否启用（错误恢复降级时关闭）
+    itn_enabled: Arc<AtomicBool>,
+    /// 识别历史（用于撤销/重试）
+    recognition_history: Arc<Mutex<RecognitionHistory>>,
+    /// 长句流式上屏
+    streaming_committer: Arc<Mutex<StreamingCommitter>>,
+    /// 部分假设变体配置
+    variants_config: HypothesisVariantsConfig,
+    /// 语音取消口令
+    cancel_config: CancelConfig,
+    /// 性能指标（与管道共享）
+    metrics: PipelineMetrics,
+    /// 本次录音的会话录制（未启用会话录制时为 None）
+    recorder: Arc<Mutex<Option<SessionRecorder>>>,
+}
+
+impl ResultContext {
+    /// 写入会话录制（未在录制时不做任何事）
+    fn record(&self, f: impl FnOnce(&mut SessionRecorder)) {
+        if let Ok(mut slot) = self.recorder.lock() {
+            if let Some(recorder) = slot.as_mut() {
+                f(recorder);
+            }
+        }
+    }
+
+    /// 应用 ITN (文本规范化)
+    fn apply_itn(&self, text: &str) -> String {
+        if !self.itn_enabled.load(Ordering::Relaxed) {
+            return text.to_string();
+        }
+        if let Ok(itn) = self.itn_engine.lock() {
+            let itn_result = self.metrics.time(Stage::Itn, || itn.process(text));
+
+            if !itn_result.changes.is_empty() {
+                tracing::info!("✏️  ITN 完成: {} 处变更", itn_result.changes.len());
+                for change in &itn_result.changes {
+                    tracing::info!("    '{}' → '{}'", change.original_text, change.normalized_text);
+                }
+            }
+
+            itn_result.text
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// 关闭 ITN，此后直接上屏原始识别文本
+    fn disable_itn(&self) {
+        self.itn_enabled.store(false, Ordering::Relaxed);
+    }
+
+    /// 流式上屏：返回需要提前上屏的文本，以及去掉已上屏部分后的 Preedit 分段
+    fn commit_stable(&self, segments: Vec<PreeditSegment>) -> (Option<String>, Vec<PreeditSegment>) {
+        match self.streaming_committer.lock() {
+            Ok(mut committer) if committer.is_enabled() => {
+                let commit = committer.update(&segments, |text| self.apply_itn(text));
+                let pending = committer.pending_segments(&segments);
+                (commit, pending)
+            }
+            _ => (None, segments),
+        }
+    }
+
+    /// 整句结束：去掉已提前上屏的部分并应用 ITN，整句作为一条历史记录
+    ///
+    /// 返回仍需上屏的文本（可能为空）
+    fn finish_utterance(&self, raw_result_with_punct: &str) -> String {
+        let finish = match self.streaming_committer.lock() {
+            Ok(mut committer) => committer.finish(raw_result_with_punct),
+            Err(_) => crate::streaming::CommitFinish {
+                remainder: raw_result_with_punct.to_string(),
+                committed: String::new(),
+            },
+        };
+        if !finish.committed.is_empty() {
+            tracing::info!("📤 已流式上屏: [{}]，剩余: [{}]", finish.committed, finish.remainder);
+        }
+
+        let final_result = if finish.remainder.is_empty() {
+            String::new()
+        } else {
+            self.apply_itn(&finish.remainder)
+        };
+
+        // 记录到历史（提前上屏部分与剩余部分合并，撤销时整句一起撤销）
+        let utterance = format!("{}{}", finish.committed, final_result);
+        if !utterance.is_empty() {
+            if let Ok(mut history) = self.recognition_history.lock() {
+                history.push(utterance);
+                tracing::debug!("已记录到历史，当前历史数: {}", history.len());
+            }
+        }
+
+        final_result
+    }
+
+    /// 识别结果是否以取消口令结束
+    fn is_cancel_phrase(&self, raw_result_with_punct: &str) -> bool {
+        self.cancel_config.is_cancel(raw_result_with_punct)
+    }
+
+    /// 取消整句：不上屏、不记入历史
+    ///
+    /// 返回本句已流式上屏、需要由前端删除的文本（可能为空）
+    fn discard_utterance(&self) -> String {
+        match self.streaming_committer.lock() {
+            Ok(mut committer) => committer.finish("").committed,
+            Err(_) => String::new(),
+        }
+    }
+
+    /// 本句是否已有文本流式上屏（须在 `finish_utterance` 之前调用）
+    fn has_streamed(&self) -> bool {
+        self.streaming_committer
+            .lock()
+            .map(|committer| committer.has_committed())
+            .unwrap_or(false)
+    }
+
+    /// 上屏后的候选列表（候选 0 为已上屏文本）
+    fn candidates(&self, committed: &str, raw_result_with_punct: &str, hypotheses: &[String]) -> Vec<String> {
+        crate::streaming::hypothesis_variants::build_candidates(
+            &self.variants_config,
+            committed,
+            raw_result_with_punct,
+            hypotheses,
+            |text| self.apply_itn(text),
+        )
+    }
+
+    /// 用户选择了替代结果：更新历史，之后撤销时删除替换后的文本
+    ///
+    /// 返回被替换的原文本
+    fn select_candidate(&self, text: &str) -> Option<String> {
+        let mut history = self.recognition_history.lock().ok()?;
+        let previous = history.replace_last(text.to_string())?;
+        tracing::info!("🔀 候选替换: [{}] → [{}]", previous, text);
+        Some(previous)
+    }
+
+    /// 新会话开始时丢弃上一会话的流式上屏状态
+    fn reset_streaming_commit(&self) {
+        if let Ok(mut committer) = self.streaming_committer.lock() {
+            committer.reset();
+        }
+    }
+}
+
+/// 音频线程的自动停止条件（由 `[activation]` 的停止方式决定）
+#[derive(Debug, Clone, Copy)]
+struct StopPolicy {
+    /// 整句上屏后停止（AutoStop）
+    after_utterance: bool,
+    /// 静默超过该时长后停止（HandsFree）
+    idle_timeout_ms: Option<u64>,
+}
+
+impl From<&ActivationConfig> for StopPolicy {
+    fn from(activation: &ActivationConfig) -> Self {
+        Self {
+            after_utterance: activation.auto_stop(),
+            idle_timeout_ms: activation.idle_timeout_ms(),
+        }
+    }
+}
+
+impl StopPolicy {
+    /// 免提听写：静默 `idle_ms` 达到超时则通知前端停止录音，返回是否停止
+    fn stop_if_idle(&self, idle_ms: u64, command_sink: &SharedCommandSink) -> bool {
+        match self.idle_timeout_ms {
+            Some(timeout_ms) if idle_ms >= timeout_ms => {
+                tracing::info!("⏹️ 免提听写静默 {}ms，自动停止识别", timeout_ms);
+                emit_command(command_sink, VInputCommand::recording_stopped());
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 录音会话的音频输入源槽（音频线程打开 / 断流后重新打开，停止录音时取走）
+type SharedAudioSource = Arc<Mutex<Option<Box<dyn AudioSource>>>>;
+
+/// 一次录音会话：打开音频输入源并把音频送入管道，运行在音频线程
+///
+/// 由 `RecoverySupervisor` 监督：出错时按恢复策略重试、降级或重建管道
+struct AudioSession {
+    /// 流式识别管道（与引擎共享）
+    pipeline: Arc<Mutex<StreamingPipeline>>,
+    /// 音频输入源（与引擎共享，停止录音时由引擎取走并停止）
+    source: SharedAudioSource,
+    /// 当前输入源对应的 Ring Buffer 读端（输入源断流后为 None）
+    consumer: Option<AudioRingConsumer>,
+    /// 音频输入源配置
+    source_config: AudioSourceConfig,
+    /// PipeWire 参数
+    pipewire: PipeWireStreamConfig,
+    /// 活动设备变化监听
+    device_listener: DeviceEventListener,
+    /// 停止信号
+    stop_signal: Arc<Mutex<bool>>,
+    /// 命令回调
+    command_sink: SharedCommandSink,
+    /// 结果后处理
+    results: ResultContext,
+    /// 电平表与输入诊断
+    level_meter: LevelMeter,
+    /// 采集 → VAD → ASR 队列
+    queue_config: AudioQueueConfig,
+    /// 自动停止条件
+    stop_policy: StopPolicy,
+}
+
+impl AudioSession {
+    /// 打开音频输入源；已请求停止时不打开并返回 None
+    fn open_source(&mut self) -> VInputResult<Option<AudioRingConsumer>> {
+        // 持有输入源槽检查停止信号，避免与停止录音交错时打开的输入源无人回收
+        let mut slot = self.source.lock().unwrap();
+        if *self.stop_signal.lock().unwrap() {
+            return Ok(None);
+        }
+
+        // 创建音频环形缓冲区 (1 秒 @ 16kHz = 16000 samples)
+        let ring_buffer = AudioRingBuffer::new(AudioRingBufferConfig { capacity: 16000 });
+        let (producer, consumer) = ring_buffer.split();
+
+        let source = open_audio_source(
+            &self.source_config,
+            self.pipewire.clone(),
+            Arc::clone(&self.device_listener),
+            producer,
+        )?;
+        tracing::info!("音频输入源创建成功: {}", source.name());
+        *slot = Some(source);
+        Ok(Some(consumer))
+    }
+
+    /// 关闭音频输入源（下次运行时重新打开）
+    fn close_source(&mut self) {
+        if let Some(source) = self.source.lock().unwrap().take() {
+            source.stop();
+        }
+        self.consumer = None;
+    }
+
+    /// 输入源非正常结束（如 PipeWire 断流）时返回其错误
+    fn source_failure(&self) -> Option<crate::error::VInputError> {
+        self.source.lock().unwrap().as_ref().and_then(|source| source.failure())
+    }
+
+    /// 音频处理：采集、VAD 与 ASR 解码分别运行在音频线程、VAD 线程与 ASR 线程（见 `streaming::staged`），
+    /// 解码偶尔变慢时音频在有界队列中积压，不会让输入源的 Ring Buffer 溢出
+    ///
+    /// 收到停止信号时耗尽已采集的音频后正常返回；满足 `stop_policy` 的自动停止条件，
+    /// 或输入源正常结束（文件回放完毕、stdin EOF）时正常返回（发出 RecordingStopped）；
+    /// 管道处理出错或输入源断流时返回错误，交由监督器处理
+    fn process_audio(&mut self, consumer: &mut AudioRingConsumer) -> VInputResult<()> {
+        // 帧计数器，用于节流 Preedit 更新（降低 CPU 占用）
+        let mut frame_counter: u64 = 0;
+        // 已收到停止信号（之后只耗尽剩余音频，结果由停止录音统一处理）
+        let mut stopping = false;
+        let mut auto_stopped = false;
+
+        let command_sink = &self.command_sink;
+        let results = &self.results;
+        let stop_policy = self.stop_policy;
+        let stop_signal = &self.stop_signal;
+        let source = &self.source;
+        let level_meter = &mut self.level_meter;
+
+        // 采集：读取输入源的 Ring Buffer，收到停止信号后读完剩余音频即结束
+        // （避免末尾轻声音频因 PipeWire 停止时序问题被丢弃）
+        let capture = |buffer: &mut [f32]| {
+            let stop_requested = *stop_signal.lock().unwrap();
+            let samples_read = consumer.read(buffer);
+
+            // 下游跟不上采集时 Ring Buffer 写满，丢弃的样本计入指标
+            let overruns = consumer.overrun_count();
+            if overruns > 0 {
+                consumer.reset_overrun_count();
+                tracing::warn!("Ring buffer 溢出，丢弃 {} 个样本", overruns);
+                results.metrics.add_ring_overruns(overruns);
+            }
+
+            if samples_read > 0 {
+                return Ok(Capture::Samples(samples_read));
+            }
+            if stop_requested {
+                tracing::info!("收到停止信号，ring buffer 已耗尽");
+                return Ok(Capture::Finished);
+            }
+            // 缓冲区为空：检查输入源是否已断流或已正常结束
+            let (failure, ended) = match source.lock().unwrap().as_ref() {
+                Some(source) => (source.failure(), !source.is_running()),
+                None => (None, false),
+            };
+            if let Some(error) = failure {
+                return Err(error);
+            }
+            if !ended {
+                return Ok(Capture::Idle);
+            }
+            // 输入源结束前写入的音频可能在上次读取之后才到达
+            let samples_read = consumer.read(buffer);
+            if samples_read > 0 {
+                return Ok(Capture::Samples(samples_read));
+            }
+            // 文件回放完毕 / stdin EOF：结束会话，由前端停止录音取得最终结果
+            tracing::info!("⏹️ 音频输入源已结束，自动停止识别");
+            emit_command(command_sink, VInputCommand::recording_stopped());
+            Ok(Capture::Finished)
+        };
+
+        // VAD 线程：电平表与输入诊断（基于原始输入，不受语音增强影响）
+        let on_vad = |frame: &[f32], frontend: &VadFrontend| {
+            let level = level_meter.process(frame, frontend.energy_gate_threshold());
+            if let Some(reading) = level.reading {
+                emit_command(command_sink, VInputCommand::audio_level(&reading.to_payload()));
+            }
+            for warning in &level.warnings {
+                tracing::warn!("🎙️ 输入诊断: {}", warning.message());
+                emit_command(command_sink, VInputCommand::audio_warning(&warning.to_payload()));
+            }
+        };
+
+        // ASR 线程：识别结果处理
+        let on_asr = |pipe: &mut StreamingPipeline, frame: &[f32], result: StreamingResult| {
+            if !stopping && *stop_signal.lock().unwrap() {
+                tracing::info!("收到停止信号，耗尽队列中剩余音频后退出");
+                results.record(|r| r.record_stop());
+                stopping = true;
+            }
+            results.record(|r| r.record_frame(frame, &result));
+            if stopping {
+                return Ok(Flow::Continue);
+            }
+
+            frame_counter += 1;
+            if !result.partial_result.is_empty() {
+                tracing::debug!("识别中: {}", result.partial_result);
+            }
+
+            // 🎯 实时标点处理：在 Preedit 中显示带逗号的文本
+            // 节流：每 5 帧（~160ms）更新一次 Preedit，降低 CPU 占用
+            use crate::streaming::PipelineState;
+            if result.pipeline_state == PipelineState::Recognizing && frame_counter.is_multiple_of(5) {
+                // 获取带实时标点的分段文本（包含逗号，但不包含句尾标点）
+                let segments = pipe.get_partial_preedit_segments();
+                results.record(|r| r.record_partial(&segments_text(&segments)));
+
+                // 长句流式上屏：稳定且以标点结尾的前缀提前上屏，Preedit 只保留尾部
+                let (stable_commit, segments) = results.commit_stable(segments);
+                if let Some(text) = stable_commit {
+                    emit_command(command_sink, VInputCommand::commit_text(&text));
+                }
+
+                if !segments.is_empty() {
+                    tracing::debug!("📝 Preedit 显示（带逗号）: {:?}", segments);
+
+                    // 更新 Preedit 显示带标点的文本，并标注稳定 / 不稳定片段
+                    emit_command(command_sink, VInputCommand::update_preedit_segments(&segments));
+                } else {
+                    // 清除 Preedit（如果文本为空）
+                    emit_command(command_sink, VInputCommand::clear_preedit());
+                }
+            }
+
+            // 🎯 检测到句子结束（端点检测）
+            if result.pipeline_state == PipelineState::Completed {
+                tracing::info!("🔔 检测到句子结束，处理最终结果");
+
+                // 清除 Preedit
+                emit_command(command_sink, VInputCommand::clear_preedit());
+
+                // 获取带标点的最终结果
+                let raw_result_with_punct = pipe.get_final_result_with_punctuation();
+                let punctuation = pipe.take_punctuation_decision();
+                results.record(|r| r.record_final(&raw_result_with_punct, punctuation));
+
+                if results.is_cancel_phrase(&raw_result_with_punct) {
+                    // 语音取消：整句丢弃，已流式上屏的部分交由前端删除
+                    tracing::info!("🗑️ 检测到取消口令，丢弃: [{}]", raw_result_with_punct);
+                    results.metrics.utterance_discarded();
+                    pipe.take_hypothesis_variants();
+                    let streamed = results.discard_utterance();
+                    if !streamed.is_empty() {
+                        emit_command(command_sink, VInputCommand::undo_text(&streamed));
+                    }
+                } else if !raw_result_with_punct.is_empty() {
+                    tracing::info!("🎤 识别结果（含智能标点）: [{}]", raw_result_with_punct);
+
+                    // 应用 ITN（已流式上屏的部分不再重复上屏），并记录到历史
+                    let streamed = results.has_streamed();
+                    let final_result = results.finish_utterance(&raw_result_with_punct);
+
+                    tracing::info!("✅ 最终结果: [{}]", final_result);
+
+                    // 一次性上屏完整结果（包含标点）
+                    if !final_result.is_empty() {
+                        tracing::info!("📝 上屏完整结果: [{}]", final_result);
+
+                        // 上屏完整文本
+                        emit_command(command_sink, VInputCommand::commit_text(&final_result));
+                        results.metrics.committed();
+
+                        // 识别候选（已流式上屏的句子无法整句替换，不提供）
+                        let hypotheses = pipe.take_hypothesis_variants();
+                        if !streamed {
+                            let candidates = results.candidates(&final_result, &raw_result_with_punct, &hypotheses);
+                            if candidates.len() > 1 {
+                                emit_command(command_sink, VInputCommand::show_candidates(&final_result, &candidates));
+                            }
+                        }
+                    }
+
+                    tracing::info!("✨ 完整结果上屏完成");
+                } else {
+                    // 仍需结束本句的流式上屏状态（已上屏部分记入历史）
+                    results.finish_utterance(&raw_result_with_punct);
+                    results.metrics.utterance_discarded();
+                }
+                // get_final_result_with_punctuation() 内部已重置 pipeline，无需再次调用 reset()
+                if stop_policy.after_utterance {
+                    tracing::info!("⏹️ 整句已上屏，自动停止识别");
+                    emit_command(command_sink, VInputCommand::recording_stopped());
+                    auto_stopped = true;
+                    return Ok(Flow::Stop);
+                }
+                tracing::info!("🔄 Pipeline 已重置，准备接收下一句");
+            }
+
+            // 免提听写：长时间没有新语音则自动停止
+            if stop_policy.stop_if_idle(pipe.idle_duration_ms(), command_sink) {
+                auto_stopped = true;
+                return Ok(Flow::Stop);
+            }
+            Ok(Flow::Continue)
+        };
+
+        let result = run_staged(&self.queue_config, &self.pipeline, capture, on_vad, on_asr);
+        if result.is_ok() && !stopping && !auto_stopped {
+            // 收到停止信号时队列已空
+            self.results.record(|r| r.record_stop());
+        }
+        tracing::info!("音频处理结束");
+        result
+    }
+
+    /// 以当前配置重建管道（`adjust` 可修改配置），丢弃未完成的句子
+    fn rebuild_pipeline(&mut self, adjust: impl FnOnce(&mut StreamingConfig)) -> VInputResult<()> {
+        let mut pipe = self.pipeline.lock().unwrap();
+        let mut config = pipe.config().clone();
+        adjust(&mut config);
+        *pipe = StreamingPipeline::new(config)?;
+        pipe.set_metrics(self.results.metrics.clone());
+        drop(pipe);
+        self.results.record(|r| r.record_note("重建识别管道"));
+
+        emit_command(&self.command_sink, VInputCommand::clear_preedit());
+        self.results.reset_streaming_commit();
+        Ok(())
+    }
+}
+
+impl Supervised for AudioSession {
+    fn run(&mut self) -> VInputResult<()> {
+        let mut consumer = match self.consumer.take() {
+            Some(consumer) => consumer,
+            None => match self.open_source()? {
+                Some(consumer) => consumer,
+                None => return Ok(()),
+            },
+        };
+
+        let result = self.process_audio(&mut consumer);
+        match &result {
+            // 输入源断流：关闭，重试时重新打开
+            Err(_) if self.source_failure().is_some() => self.close_source(),
+            // 管道出错：保留输入源，重试时继续读取
+            Err(_) => self.consumer = Some(consumer),
+            Ok(()) => {}
+        }
+        result
+    }
+
+    fn degrade(&mut self, degradation: Degradation) -> VInputResult<()> {
+        match degradation {
+            Degradation::DisableItn => {
+                tracing::warn!("ITN 出错，关闭 ITN");
+                self.results.disable_itn();
+            }
+            Degradation::DisableHotwords => {
+                tracing::warn!("热词出错，去掉热词重建识别器");
+                self.rebuild_pipeline(|config| config.asr_config.hotwords_file = None)?;
+            }
+            Degradation::DisableEnhancement => {
+                tracing::warn!("音频处理跟不上，关闭语音增强");
+                self.pipeline.lock().unwrap().disable_enhancement();
+                self.results.record(|r| r.record_note("关闭语音增强"));
+            }
+            Degradation::DefaultDevice => {
+                tracing::warn!("首选音频设备不可用，改用默认音频源");
+                self.pipewire.preferred_devices.clear();
+                self.pipewire.target_node = None;
+                self.close_source();
+            }
+        }
+        Ok(())
+    }
+
+    fn restart(&mut self) -> VInputResult<()> {
+        self.rebuild_pipeline(|_| {})
+    }
+
+    fn is_stopping(&self) -> bool {
+        *self.stop_signal.lock().unwrap()
+    }
+}
 
 /// V-Input Core 完整状态
 struct VInputCoreState {
     /// 流式识别管道
     pipeline: Arc<Mutex<StreamingPipeline>>,
-    /// ITN 引擎（共享，供音频线程使用）
-    itn_engine: Arc<Mutex<ITNEngine>>,
-    /// 热词引擎（仅用于初始化日志）
-    #[allow(dead_code)]
-    hotwords_engine: Option<HotwordsEngine>,
+    /// 结果后处理（ITN、流式上屏、识别历史；共享，供音频线程使用）
+    results: ResultContext,
+    /// 热词配置（生成送入识别器的热词文件）
+    hotwords: HotwordsConfig,
+    /// `[asr] hotwords_file` 指定的热词文件（并入生成的热词文件）
+    user_hotwords_file: Option<PathBuf>,
+    /// 学到新热词后尚未重建识别器（录音中学到的词在下次开始录音时生效）
+    hotwords_stale: bool,
+    /// 热词自动学习（未启用时为 None）
+    hotword_learner: Option<HotwordLearner>,
     /// 命令队列（共享，供音频线程使用）
     command_queue: Arc<Mutex<VecDeque<VInputCommand>>>,
-    /// 识别历史（用于撤销/重试）
-    recognition_history: Arc<Mutex<RecognitionHistory>>,
     /// 录音状态
     is_recording: bool,
     /// 音频处理线程句柄
     audio_thread: Option<thread::JoinHandle<()>>,
     /// 停止信号
     stop_signal: Arc<Mutex<bool>>,
-    /// PipeWire 音频流
-    pipewire_stream: Option<PipeWireStream>,
+    /// 音频输入源（PipeWire / WAV 文件 / stdin / Unix socket；共享，断流后由音频线程重新打开）
+    audio_source: SharedAudioSource,
+    /// 音频捕获配置
+    audio_config: AudioCaptureConfig,
+    /// 命令回调（共享，供音频线程与设备监听使用）
+    command_sink: SharedCommandSink,
+    /// 前端快捷键配置
+    shortcuts: ShortcutConfig,
+    /// 录音触发方式
+    activation: ActivationConfig,
+    /// 唤醒词配置
+    wake_config: WakeWordConfig,
+    /// 关键词检测器（未启用唤醒词或模型加载失败时为 None）
+    wake_spotter: Option<Arc<KeywordSpotter>>,
+    /// 唤醒词监听线程（未录音且已注册回调时运行）
+    wake_listener: Option<WakeWordListener>,
+    /// 唤醒词监听使用的音频输入源
+    wake_source: Option<Box<dyn AudioSource>>,
+    /// 录音会话错误恢复配置
+    recovery: RecoveryConfig,
+    /// Prometheus 指标导出（未启用时为 None，仅持有以保持导出线程运行）
+    _metrics_exporter: Option<MetricsExporter>,
+    /// 会话包目录（未启用会话录制时为 None）
+    session_store: Option<SessionStore>,
 }
 
 impl VInputCoreState {
-    fn new() -> crate::error::VInputResult<Self> {
+    /// 创建引擎状态；`config_path` 为 None 时加载 ~/.config/vinput/config.toml
+    fn new(config_path: Option<&Path>) -> crate::error::VInputResult<Self> {
         tracing::info!("初始化 V-Input Core (完整版本)");
 
         // 加载配置
-        let config = match VInputConfig::load() {
+        let loaded = match config_path {
+            Some(path) => VInputConfig::load_from(path),
+            None => VInputConfig::load(),
+        };
+        let config = match loaded {
             Ok(cfg) => {
                 tracing::info!("✅ 成功加载配置文件");
                 cfg
@@ -68,342 +678,856 @@ impl VInputCoreState {
             config.punctuation.streaming_min_tokens
         );
 
-        let streaming_config = StreamingConfig {
-            vad_config: config.vad.clone(),
-            asr_config: config.asr.clone(),
-            punctuation_profile: config.punctuation.clone(),
-            endpoint_config: config.endpoint.clone(),
+        if config.activation.stop_mode == StopMode::HandsFree {
+            tracing::info!("🎧 免提听写：使用 hands_free_vad 配置");
+        }
+        // 配置的热词（含已启用分组）与学到的热词写入生成的热词文件，送入识别器
+        let hotword_learner = HotwordLearner::new(&config.hotwords.learning);
+        let user_hotwords_file = config.asr.hotwords_file.as_ref().map(PathBuf::from);
+        let mut streaming_config = StreamingConfig::from_config(&config);
+        apply_hotwords(
+            &mut streaming_config.asr_config,
+            user_hotwords_file.as_deref(),
+            &config.hotwords,
+            hotword_learner.as_ref(),
+        );
+
+        let mut pipeline = StreamingPipeline::new(streaming_config)?;
+        let metrics = PipelineMetrics::new();
+        pipeline.set_metrics(metrics.clone());
+        let metrics_exporter = if config.metrics.prometheus {
+            match config.metrics.socket_path() {
+                Some(path) => MetricsExporter::start(&path, metrics.clone())
+                    .map_err(|e| tracing::warn!("启动性能指标导出失败: {}", e))
+                    .ok(),
+                None => {
+                    tracing::warn!("无法获取运行时目录，未启动性能指标导出");
+                    None
+                }
+            }
+        } else {
+            None
         };
-        let pipeline = StreamingPipeline::new(streaming_config)?;
 
         // 创建后处理引擎
         let itn_engine = ITNEngine::new(ITNMode::Auto);
 
-        // 创建热词引擎（可选）
-        let hotwords_engine = if !config.hotwords.words.is_empty() {
-            let mut engine = HotwordsEngine::new();
-
-            // 添加所有配置的热词
-            for (word, weight) in &config.hotwords.words {
-                if let Err(e) = engine.add_hotword(word.clone(), *weight) {
-                    tracing::warn!("添加热词失败 '{}': {}", word, e);
+        // 加载唤醒词模型（可选，失败时仅禁用唤醒词）
+        let wake_spotter = if config.wake_word.enabled {
+            match KeywordSpotter::new(&config.wake_word) {
+                Ok(spotter) => {
+                    tracing::info!("唤醒词已启用，{} 个唤醒词", config.wake_word.keywords.len());
+                    Some(Arc::new(spotter))
+                }
+                Err(e) => {
+                    tracing::error!("加载唤醒词模型失败: {}，禁用唤醒词", e);
+                    None
                 }
             }
-
-            tracing::info!("热词引擎初始化成功，加载 {} 个热词", engine.count());
-            Some(engine)
         } else {
-            tracing::info!("未配置热词，跳过热词引擎初始化");
             None
         };
 
         Ok(Self {
             pipeline: Arc::new(Mutex::new(pipeline)),
-            itn_engine: Arc::new(Mutex::new(itn_engine)),
-            hotwords_engine,
+            results: ResultContext {
+                itn_engine: Arc::new(Mutex::new(itn_engine)),
+                itn_enabled: Arc::new(AtomicBool::new(true)),
+                recognition_history: Arc::new(Mutex::new(RecognitionHistory::new(50))),
+                streaming_committer: Arc::new(Mutex::new(StreamingCommitter::new(
+                    config.streaming_commit.clone(),
+                ))),
+                variants_config: config.hypothesis_variants.clone(),
+                cancel_config: config.cancel.clone(),
+                metrics,
+                recorder: Arc::new(Mutex::new(None)),
+            },
+            hotwords: config.hotwords.clone(),
+            user_hotwords_file,
+            hotwords_stale: false,
+            hotword_learner,
             command_queue: Arc::new(Mutex::new(VecDeque::new())),
-            recognition_history: Arc::new(Mutex::new(RecognitionHistory::new(50))),
             is_recording: false,
             audio_thread: None,
             stop_signal: Arc::new(Mutex::new(false)),
-            pipewire_stream: None,
+            audio_source: Arc::new(Mutex::new(None)),
+            audio_config: config.audio.clone(),
+            command_sink: Arc::new(Mutex::new(None)),
+            shortcuts: config.shortcuts.clone(),
+            activation: config.activation.clone(),
+            wake_config: config.wake_word.clone(),
+            wake_spotter,
+            wake_listener: None,
+            wake_source: None,
+            recovery: config.recovery.clone(),
+            _metrics_exporter: metrics_exporter,
+            session_store: SessionStore::from_config(&config),
         })
     }
 
+    /// PipeWire 参数（同时提供输出采样率与重采样设置）
+    fn pipewire_config(&self) -> PipeWireStreamConfig {
+        PipeWireStreamConfig::from_capture_config(&self.audio_config)
+    }
+
+    /// 学到新热词后重新生成热词文件并重建识别器
+    ///
+    /// 录音中不打断当前会话，推迟到下次开始录音时重建。
+    fn refresh_hotwords(&mut self) {
+        self.hotwords_stale = true;
+        if !self.is_recording {
+            self.reload_hotwords();
+        }
+    }
+
+    /// 以最新的热词文件重建识别管道
+    fn reload_hotwords(&mut self) {
+        self.hotwords_stale = false;
+        let mut pipe = self.pipeline.lock().unwrap();
+        let mut config = pipe.config().clone();
+        apply_hotwords(
+            &mut config.asr_config,
+            self.user_hotwords_file.as_deref(),
+            &self.hotwords,
+            self.hotword_learner.as_ref(),
+        );
+        match StreamingPipeline::new(config) {
+            Ok(rebuilt) => {
+                *pipe = rebuilt;
+                pipe.set_metrics(self.results.metrics.clone());
+            }
+            Err(e) => tracing::error!("以新热词重建识别器失败: {}，沿用原识别器", e),
+        }
+    }
+
+    /// 开始监听唤醒词（已启用、未录音且尚未监听时）
+    fn arm_wake_word(&mut self) {
+        let Some(spotter) = self.wake_spotter.clone() else {
+            return;
+        };
+        if self.is_recording || self.wake_listener.as_ref().is_some_and(|l| !l.is_finished()) {
+            return;
+        }
+        self.disarm_wake_word();
+
+        let ring_buffer = AudioRingBuffer::new(AudioRingBufferConfig { capacity: 16000 });
+        let (producer, consumer) = ring_buffer.split();
+
+        // 监听期间的设备切换不通知前端（录音开始时会重新通知）
+        let device_listener: DeviceEventListener = Arc::new(|_| {});
+        let source = match open_audio_source(&self.audio_config.source, self.pipewire_config(), device_listener, producer) {
+            Ok(source) => source,
+            Err(e) => {
+                tracing::error!("唤醒词监听无法打开音频输入源: {}", e);
+                return;
+            }
+        };
+
+        let command_sink = Arc::clone(&self.command_sink);
+        let on_detected = move |keyword: String| {
+            emit_command(&command_sink, VInputCommand::wake_word_detected(&keyword));
+        };
+        match WakeWordListener::spawn(spotter, consumer, self.wake_config.gate_noise_multiplier, on_detected) {
+            Ok(listener) => {
+                self.wake_listener = Some(listener);
+                self.wake_source = Some(source);
+            }
+            Err(e) => {
+                tracing::error!("启动唤醒词监听失败: {}", e);
+                source.stop();
+            }
+        }
+    }
+
+    /// 停止监听唤醒词（释放麦克风）
+    fn disarm_wake_word(&mut self) {
+        if let Some(source) = self.wake_source.take() {
+            source.stop();
+        }
+        if let Some(mut listener) = self.wake_listener.take() {
+            listener.stop();
+        }
+    }
+
     /// 启动录音
     fn start_recording(&mut self) {
         if self.is_recording {
-            tracing::warn!("已经在录音中");
-            return;
+            // 自动停止后前端尚未发送 StopRecording：先回收上一会话
+            if self.audio_thread.as_ref().is_some_and(|t| t.is_finished()) {
+                tracing::info!("回收已自动停止的录音会话");
+                self.stop_recording();
+            } else {
+                tracing::warn!("已经在录音中");
+                return;
+            }
         }
 
         tracing::info!("启动录音和识别");
+        if self.hotwords_stale {
+            self.reload_hotwords();
+        }
+        // 唤醒词监听与录音不能同时占用麦克风
+        self.disarm_wake_word();
         self.is_recording = true;
         *self.stop_signal.lock().unwrap() = false;
+        if let Some(store) = &self.session_store {
+            *self.results.recorder.lock().unwrap() = Some(store.start(16000, FRAME_SIZE));
+        }
 
-        // 创建音频环形缓冲区 (1 秒 @ 16kHz = 16000 samples)
-        let ring_buffer_config = AudioRingBufferConfig {
-            capacity: 16000,
+        // 活动设备变化（含首次选定、拔出回退、重新插入）通知前端
+        let device_sink = Arc::clone(&self.command_sink);
+        let device_listener: DeviceEventListener = Arc::new(move |event| {
+            emit_command(&device_sink, VInputCommand::audio_device_changed(&event.to_payload()));
+        });
+
+        // 音频输入源在音频线程中打开，断流时由监督器重新打开
+        let mut session = AudioSession {
+            pipeline: Arc::clone(&self.pipeline),
+            source: Arc::clone(&self.audio_source),
+            consumer: None,
+            source_config: self.audio_config.source.clone(),
+            pipewire: self.pipewire_config(),
+            device_listener,
+            stop_signal: Arc::clone(&self.stop_signal),
+            command_sink: Arc::clone(&self.command_sink),
+            results: self.results.clone(),
+            level_meter: LevelMeter::new(self.audio_config.level_meter.clone(), 16000),
+            queue_config: self.audio_config.queue.clone(),
+            stop_policy: StopPolicy::from(&self.activation),
         };
-        let ring_buffer = AudioRingBuffer::new(ring_buffer_config);
-        let (producer, consumer) = ring_buffer.split();
+        session.results.reset_streaming_commit();
+        let mut supervisor = RecoverySupervisor::new(self.recovery.clone());
+
+        self.audio_thread = Some(thread::spawn(move || {
+            tracing::info!("音频处理线程启动");
+            if let Err(report) = supervisor.supervise(&mut session) {
+                // 无法自动恢复：上报错误码，并让前端停止录音、释放麦克风
+                tracing::error!("❌ 录音会话无法恢复 [{}]: {}", report.code, report.message);
+                emit_command(&session.command_sink, VInputCommand::error(&report.to_payload()));
+                emit_command(&session.command_sink, VInputCommand::recording_stopped());
+            }
+            tracing::info!("音频处理线程退出");
+        }));
+    }
 
-        // 创建 PipeWire 音频流
-        let pw_config = PipeWireStreamConfig {
-            sample_rate: 16000,
-            channels: 1,
-            ..Default::default()
+    /// 停止录音并生成识别结果
+    fn stop_recording(&mut self) {
+        if !self.is_recording {
+            tracing::warn!("没有在录音");
+            return;
+        }
+
+        tracing::info!("🛑 手动停止录音");
+        self.halt_capture();
+        self.results.metrics.endpoint_reached();
+
+        // 获取识别结果（带智能标点）与替代假设
+        let (raw_result_with_punct, hypotheses) = if let Ok(mut pipe) = self.pipeline.lock() {
+            let result = pipe.get_final_result_with_punctuation();
+            let punctuation = pipe.take_punctuation_decision();
+            self.results.record(|r| r.record_final(&result, punctuation));
+            (result, pipe.take_hypothesis_variants())
+        } else {
+            (String::new(), Vec::new())
         };
+        self.save_session();
 
-        match PipeWireStream::new(pw_config, producer) {
-            Ok(stream) => {
-                tracing::info!("PipeWire 音频流创建成功");
-                self.pipewire_stream = Some(stream);
-                // 注意：不需要存储 ring_buffer，因为 split() 已经消费了它
+        if self.results.is_cancel_phrase(&raw_result_with_punct) {
+            tracing::info!("🗑️ 检测到取消口令，丢弃: [{}]", raw_result_with_punct);
+            self.results.metrics.utterance_discarded();
+            self.discard_streamed();
+            return;
+        }
 
-                // 启动音频处理线程
-                let pipeline = Arc::clone(&self.pipeline);
-                let stop_signal = Arc::clone(&self.stop_signal);
-                let command_queue = Arc::clone(&self.command_queue);
-                let itn_engine = Arc::clone(&self.itn_engine);
-                let recognition_history = Arc::clone(&self.recognition_history);
+        if raw_result_with_punct.is_empty() {
+            tracing::info!("识别结果为空，不生成命令");
+            self.results.metrics.utterance_discarded();
+            // 仍需结束本句的流式上屏状态（已上屏部分记入历史）
+            self.results.finish_utterance(&raw_result_with_punct);
+            return;
+        }
+
+        tracing::info!("🎤 识别结果（含智能标点）: [{}]", raw_result_with_punct);
 
-                self.audio_thread = Some(thread::spawn(move || {
-                    Self::audio_processing_loop(pipeline, consumer, stop_signal, command_queue, itn_engine, recognition_history);
-                }));
+        // 应用 ITN (文本规范化)，已流式上屏的部分不再重复上屏，并记录到历史
+        tracing::info!("📝 开始 ITN 处理...");
+        let streamed = self.results.has_streamed();
+        let final_result = self.results.finish_utterance(&raw_result_with_punct);
+
+        tracing::info!("✅ 最终结果: [{}]", final_result);
+
+        if final_result.is_empty() {
+            tracing::info!("全部文本已流式上屏，不生成命令");
+            self.results.metrics.utterance_discarded();
+            return;
+        }
+
+        // 识别候选（已流式上屏的句子无法整句替换，不提供）
+        let candidates = if streamed {
+            Vec::new()
+        } else {
+            self.results.candidates(&final_result, &raw_result_with_punct, &hypotheses)
+        };
+
+        // 生成命令序列：先上屏，再列出可替换的候选
+        if let Ok(mut queue) = self.command_queue.lock() {
+            queue.push_back(VInputCommand::commit_text(&final_result));
+            self.results.metrics.committed();
+            if candidates.len() > 1 {
+                queue.push_back(VInputCommand::show_candidates(&final_result, &candidates));
             }
-            Err(e) => {
-                tracing::error!("创建 PipeWire 流失败: {}, 停止录音", e);
-                self.is_recording = false;
+            tracing::info!("生成 {} 个命令", queue.len());
+        }
+    }
+
+    /// 取消录音：丢弃当前句子，不上屏、不记入历史
+    fn cancel_recording(&mut self) {
+        if !self.is_recording {
+            tracing::warn!("没有在录音");
+            return;
+        }
+
+        tracing::info!("🗑️ 取消录音");
+        self.halt_capture();
+
+        if let Ok(mut pipe) = self.pipeline.lock() {
+            pipe.discard_utterance();
+        }
+        self.results.record(|r| r.record_cancel());
+        self.save_session();
+        if let Ok(mut queue) = self.command_queue.lock() {
+            queue.push_back(VInputCommand::clear_preedit());
+        }
+        self.discard_streamed();
+    }
+
+    /// 停止采集：停止音频输入源并等待音频线程退出，随后恢复唤醒词监听
+    fn halt_capture(&mut self) {
+        self.is_recording = false;
+
+        // 停止音频输入源并发送停止信号（持有输入源槽，音频线程不会再重新打开）
+        {
+            let mut source_slot = self.audio_source.lock().unwrap();
+            if let Some(source) = source_slot.take() {
+                source.stop();
+                tracing::debug!("音频输入源已停止: {}", source.name());
             }
+            *self.stop_signal.lock().unwrap() = true;
         }
+
+        // 等待音频线程结束
+        if let Some(handle) = self.audio_thread.take() {
+            let _ = handle.join();
+        }
+
+        // 麦克风已释放，恢复唤醒词监听
+        self.arm_wake_word();
     }
 
-    /// 音频处理循环（从环形缓冲区读取并送入管道）
-    fn audio_processing_loop(
-        pipeline: Arc<Mutex<StreamingPipeline>>,
-        mut consumer: crate::audio::AudioRingConsumer,
-        stop_signal: Arc<Mutex<bool>>,
-        _command_queue: Arc<Mutex<VecDeque<VInputCommand>>>,
-        itn_engine: Arc<Mutex<ITNEngine>>,
-        recognition_history: Arc<Mutex<RecognitionHistory>>,
-    ) {
-        tracing::info!("音频处理线程启动");
+    /// 写出本次录音的会话包（未启用会话录制时不做任何事）
+    fn save_session(&self) {
+        let Some(store) = &self.session_store else {
+            return;
+        };
+        let Some(recorder) = self.results.recorder.lock().unwrap().take() else {
+            return;
+        };
+        match store.save(recorder) {
+            Ok(path) => tracing::info!("🎞️ 会话包已保存: {}", path.display()),
+            Err(e) => tracing::warn!("保存会话包失败: {}", e),
+        }
+    }
 
-        // 512 samples = 32ms @ 16kHz
-        const FRAME_SIZE: usize = 512;
-        let mut frame_buffer = vec![0.0f32; FRAME_SIZE];
+    /// 结束本句的流式上屏状态而不记入历史；已提前上屏的部分生成撤销命令交由前端删除
+    fn discard_streamed(&self) {
+        let streamed = self.results.discard_utterance();
+        if streamed.is_empty() {
+            return;
+        }
+        tracing::info!("删除已流式上屏的文本: [{}]", streamed);
+        if let Ok(mut queue) = self.command_queue.lock() {
+            queue.push_back(VInputCommand::undo_text(&streamed));
+        }
+    }
 
-        // 帧计数器，用于节流 Preedit 更新（降低 CPU 占用）
-        let mut frame_counter: u64 = 0;
+    /// 尝试接收命令
+    fn try_recv_command(&mut self) -> Option<VInputCommand> {
+        if let Ok(mut queue) = self.command_queue.lock() {
+            queue.pop_front()
+        } else {
+            None
+        }
+    }
 
-        loop {
-            // 检查停止信号
-            if *stop_signal.lock().unwrap() {
-                tracing::info!("收到停止信号，耗尽 ring buffer 剩余数据后退出");
-                // 耗尽 ring buffer 中已写入但未处理的剩余帧
-                // 避免末尾轻声音频因 PipeWire 停止时序问题被丢弃
-                loop {
-                    let remaining = consumer.read(&mut frame_buffer);
-                    if remaining < FRAME_SIZE {
-                        break;
+    /// 处理来自前端的事件
+    fn handle_event(&mut self, event: &VInputEvent) {
+        match event.event_type {
+            VInputEventType::StartRecording => {
+                tracing::info!("接收事件: StartRecording");
+                self.start_recording();
+            }
+            VInputEventType::StopRecording => {
+                tracing::info!("接收事件: StopRecording");
+                self.stop_recording();
+            }
+            VInputEventType::CancelRecording => {
+                tracing::info!("接收事件: CancelRecording");
+                self.cancel_recording();
+            }
+            VInputEventType::UndoRequest => {
+                tracing::info!("接收事件: UndoRequest");
+                if let Ok(mut history) = self.results.recognition_history.lock() {
+                    if let Some(undone_text) = history.undo() {
+                        tracing::info!("撤销文本: {}", undone_text);
+                        // 等待前端报告用户重新输入的文本（纠错学习）
+                        if let Some(learner) = &mut self.hotword_learner {
+                            learner.note_undo(&undone_text);
+                        }
+                        // 生成撤销命令
+                        if let Ok(mut queue) = self.command_queue.lock() {
+                            queue.push_back(VInputCommand::undo_text(&undone_text));
+                        }
+                    } else {
+                        tracing::warn!("没有可撤销的内容");
                     }
-                    if let Ok(mut pipe) = pipeline.lock() {
-                        let _ = pipe.process(&frame_buffer);
+                }
+            }
+            VInputEventType::RedoRequest => {
+                tracing::info!("接收事件: RedoRequest");
+                if let Ok(mut history) = self.results.recognition_history.lock() {
+                    if let Some(redone_text) = history.redo() {
+                        tracing::info!("重试文本: {}", redone_text);
+                        // 生成重试命令
+                        if let Ok(mut queue) = self.command_queue.lock() {
+                            queue.push_back(VInputCommand::redo_text(&redone_text));
+                        }
+                    } else {
+                        tracing::warn!("没有可重试的内容");
+                    }
+                }
+            }
+            VInputEventType::CandidateSelected => {
+                tracing::info!("接收事件: CandidateSelected");
+                let Some(text) = event_text(event) else { return };
+                if let Some(previous) = self.results.select_candidate(text) {
+                    let learned = self.hotword_learner.as_mut().and_then(|l| l.observe_replacement(&previous, text));
+                    if learned.is_some() {
+                        self.refresh_hotwords();
+                    }
+                }
+            }
+            VInputEventType::CorrectionText => {
+                tracing::info!("接收事件: CorrectionText");
+                let Some(text) = event_text(event) else { return };
+                let learned = self.hotword_learner.as_mut().and_then(|l| l.observe_retype(text));
+                if learned.is_some() {
+                    self.refresh_hotwords();
+                }
+            }
+            VInputEventType::AddHotword => {
+                tracing::info!("接收事件: AddHotword");
+                let Some(text) = event_text(event) else { return };
+                match &mut self.hotword_learner {
+                    Some(learner) => {
+                        if learner.add_explicit(text).is_some() {
+                            self.refresh_hotwords();
+                        }
                     }
+                    None => tracing::warn!("热词自动学习未启用，忽略: {}", text),
                 }
-                tracing::info!("ring buffer 耗尽，退出音频处理");
-                break;
             }
+            _ => {
+                tracing::debug!("接收事件: {:?} (暂不处理)", event.event_type);
+            }
+        }
+    }
+}
+
+/// 生成热词文件（配置的热词、`user_file` 中的词与学到的热词）并设为 `asr` 的热词文件
+fn apply_hotwords(
+    asr: &mut OnlineRecognizerConfig,
+    user_file: Option<&Path>,
+    hotwords: &HotwordsConfig,
+    learner: Option<&HotwordLearner>,
+) {
+    let Some(path) = generated_hotwords_path() else {
+        tracing::warn!("无法获取缓存目录，未生成热词文件");
+        return;
+    };
+    let learned = learner.map(HotwordLearner::active_hotwords).unwrap_or_default();
+    match apply_hotwords_file(asr, user_file, hotwords, &learned, &path) {
+        Ok(0) => tracing::info!("未配置热词"),
+        Ok(count) => tracing::info!("热词文件已生成: {}（{} 个热词）", path.display(), count),
+        Err(e) => tracing::warn!("生成热词文件失败: {}", e),
+    }
+}
+
+/// 事件携带的 UTF-8 文本（缺少或无效时记录警告并返回 None）
+fn event_text(event: &VInputEvent) -> Option<&str> {
+    if event.data.is_null() || event.data_len == 0 {
+        tracing::warn!("{:?} 缺少文本", event.event_type);
+        return None;
+    }
+    let data = unsafe { std::slice::from_raw_parts(event.data, event.data_len) };
+    match std::str::from_utf8(data) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            tracing::warn!("{:?} 文本不是有效 UTF-8: {}", event.event_type, e);
+            None
+        }
+    }
+}
+
+impl VInputHandle {
+    fn new(config_path: Option<&Path>) -> crate::error::VInputResult<Self> {
+        Ok(Self {
+            state: Mutex::new(VInputCoreState::new(config_path)?),
+        })
+    }
+
+    fn set_command_sink(&self, sink: Option<CommandSink>) {
+        let mut state = self.state.lock().unwrap();
+        let registered = sink.is_some();
+        *state.command_sink.lock().unwrap() = sink;
+
+        // 唤醒词需要通过回调通知前端：注册回调后才开始监听
+        if registered {
+            state.arm_wake_word();
+        } else {
+            state.disarm_wake_word();
+        }
+    }
 
-            // 从环形缓冲区读取音频
-            let samples_read = consumer.read(&mut frame_buffer);
+    fn send_event(&self, event: &VInputEvent) {
+        self.state.lock().unwrap().handle_event(event);
+    }
+
+    fn try_recv_command(&self) -> Option<VInputCommand> {
+        self.state.lock().unwrap().try_recv_command()
+    }
+
+    fn history(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        let history = state.results.recognition_history.lock().unwrap();
+        history.get_active_history()
+    }
+
+    fn activation(&self) -> VInputActivation {
+        VInputActivation::from(&self.state.lock().unwrap().activation)
+    }
 
-            if samples_read == 0 {
-                // 缓冲区为空，短暂休眠
-                thread::sleep(std::time::Duration::from_millis(10));
-                continue;
+    fn metrics(&self) -> PipelineMetrics {
+        self.state.lock().unwrap().results.metrics.clone()
+    }
+
+    fn shortcut(&self, action: VInputShortcutAction) -> String {
+        let state = self.state.lock().unwrap();
+        state.shortcuts.resolve(action.into()).to_string()
+    }
+}
+
+impl Drop for VInputHandle {
+    fn drop(&mut self) {
+        // 停止录音（如果正在录音）与唤醒词监听，等待线程退出后再释放资源
+        if let Ok(state) = self.state.get_mut() {
+            if state.is_recording {
+                state.stop_recording();
             }
+            state.disarm_wake_word();
+        }
+    }
+}
+
+/// 将句柄指针转换为引用
+///
+/// # Safety
+/// `handle` 必须为 null 或由 `vinput_engine_new` 返回且尚未释放
+unsafe fn handle_ref<'a>(handle: *const VInputHandle) -> Result<&'a VInputHandle, VInputFFIResult> {
+    check_null(handle, "handle")?;
+    Ok(&*handle)
+}
+
+/// 创建 V-Input 引擎实例
+///
+/// `config_path` 为 UTF-8 配置文件路径，传 null 使用 ~/.config/vinput/config.toml
+///
+/// # 返回值
+/// 成功返回句柄，失败返回 null
+/// 调用者需要使用 vinput_engine_free 释放
+#[no_mangle]
+pub extern "C" fn vinput_engine_new(config_path: *const c_char) -> *mut VInputHandle {
+    ffi_safe_call(|| {
+        crate::init_logging();
 
-            // 只处理完整的帧
-            if samples_read < FRAME_SIZE {
-                tracing::debug!("读取到不完整帧: {} samples", samples_read);
-                thread::sleep(std::time::Duration::from_millis(10));
-                continue;
+        let path = if config_path.is_null() {
+            None
+        } else {
+            let path = unsafe { CStr::from_ptr(config_path) }
+                .to_str()
+                .map_err(|_| VInputFFIResult::InvalidArgument)?;
+            Some(Path::new(path))
+        };
+        tracing::info!("V-Input Engine FFI: 创建实例 (config: {:?})", path);
+
+        match VInputHandle::new(path) {
+            Ok(handle) => Ok(Box::into_raw(Box::new(handle))),
+            Err(e) => {
+                tracing::error!("V-Input Engine 创建失败: {}", e);
+                Err(VInputFFIResult::InitFailed)
             }
+        }
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
 
-            // 送入管道处理
-            frame_counter += 1;
-            if let Ok(mut pipe) = pipeline.lock() {
-                match pipe.process(&frame_buffer) {
-                    Ok(result) => {
-                        if !result.partial_result.is_empty() {
-                            tracing::debug!("识别中: {}", result.partial_result);
-                        }
+/// 释放 V-Input 引擎实例（正在录音时先停止录音）
+#[no_mangle]
+pub extern "C" fn vinput_engine_free(handle: *mut VInputHandle) {
+    if handle.is_null() {
+        return;
+    }
 
-                        // 🎯 实时标点处理：在 Preedit 中显示带逗号的文本
-                        // 节流：每 5 帧（~160ms）更新一次 Preedit，降低 CPU 占用
-                        use crate::streaming::PipelineState;
-                        if result.pipeline_state == PipelineState::Recognizing && frame_counter % 5 == 0 {
-                            // 获取带实时标点的文本（包含逗号，但不包含句尾标点）
-                            let text_with_punctuation = pipe.get_partial_result_with_punctuation();
-
-                            if !text_with_punctuation.is_empty() {
-                                tracing::debug!("📝 Preedit 显示（带逗号）: [{}]", text_with_punctuation);
-
-                                // 更新 Preedit 显示带标点的文本
-                                if let Some(callback) = *COMMAND_CALLBACK.lock().unwrap() {
-                                    let cmd = VInputCommand::update_preedit(&text_with_punctuation);
-                                    callback(&cmd as *const VInputCommand);
-                                    vinput_command_free(&cmd as *const VInputCommand as *mut VInputCommand);
-                                }
-                            } else {
-                                // 清除 Preedit（如果文本为空）
-                                if let Some(callback) = *COMMAND_CALLBACK.lock().unwrap() {
-                                    let cmd = VInputCommand::clear_preedit();
-                                    callback(&cmd as *const VInputCommand);
-                                    vinput_command_free(&cmd as *const VInputCommand as *mut VInputCommand);
-                                }
-                            }
-                        }
+    let _ = ffi_safe_call(|| {
+        tracing::info!("V-Input Engine FFI: 释放实例");
+        drop(unsafe { Box::from_raw(handle) });
+        Ok(())
+    });
+}
+
+/// 注册实例的命令回调函数
+///
+/// `user_data` 在每次回调时原样传回；回调在音频线程中调用。
+/// 传入 null 回调可取消注册
+#[no_mangle]
+pub extern "C" fn vinput_engine_register_callback(
+    handle: *mut VInputHandle,
+    callback: Option<VInputHandleCommandCallback>,
+    user_data: *mut c_void,
+) -> VInputFFIResult {
+    match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        handle.set_command_sink(callback.map(|cb| CommandSink::WithUserData(cb, user_data)));
+        tracing::info!("✅ 实例命令回调注册成功");
+        Ok(VInputFFIResult::Success)
+    }) {
+        Ok(result) => result,
+        Err(e) => e,
+    }
+}
+
+/// 发送事件到 V-Input 引擎实例
+#[no_mangle]
+pub extern "C" fn vinput_engine_send_event(
+    handle: *mut VInputHandle,
+    event: *const VInputEvent,
+) -> VInputFFIResult {
+    match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        check_null(event, "event")?;
 
-                        // 🎯 检测到句子结束（端点检测）
-                        if result.pipeline_state == PipelineState::Completed {
-                            tracing::info!("🔔 检测到句子结束，处理最终结果");
+        handle.send_event(unsafe { &*event });
+        Ok(VInputFFIResult::Success)
+    }) {
+        Ok(result) => result,
+        Err(e) => e,
+    }
+}
 
-                            // 清除 Preedit
-                            if let Some(callback) = *COMMAND_CALLBACK.lock().unwrap() {
-                                let cmd = VInputCommand::clear_preedit();
-                                callback(&cmd as *const VInputCommand);
-                                vinput_command_free(&cmd as *const VInputCommand as *mut VInputCommand);
-                            }
+/// 尝试从 V-Input 引擎实例接收命令（非阻塞）
+#[no_mangle]
+pub extern "C" fn vinput_engine_try_recv_command(
+    handle: *mut VInputHandle,
+    command: *mut VInputCommand,
+) -> VInputFFIResult {
+    match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        check_null_mut(command, "command")?;
 
-                            // 获取带标点的最终结果
-                            let raw_result_with_punct = pipe.get_final_result_with_punctuation();
-
-                            if !raw_result_with_punct.is_empty() {
-                                tracing::info!("🎤 识别结果（含智能标点）: [{}]", raw_result_with_punct);
-
-                                // 应用 ITN
-                                let final_result = if let Ok(itn) = itn_engine.lock() {
-                                    let itn_result = itn.process(&raw_result_with_punct);
-
-                                    if !itn_result.changes.is_empty() {
-                                        tracing::info!("✏️  ITN 完成: {} 处变更", itn_result.changes.len());
-                                        for change in &itn_result.changes {
-                                            tracing::info!("    '{}' → '{}'", change.original_text, change.normalized_text);
-                                        }
-                                    }
-
-                                    itn_result.text
-                                } else {
-                                    raw_result_with_punct
-                                };
-
-                                tracing::info!("✅ 最终结果: [{}]", final_result);
-
-                                // 一次性上屏完整结果（包含标点）
-                                if !final_result.is_empty() {
-                                    tracing::info!("📝 上屏完整结果: [{}]", final_result);
-
-                                    // 上屏完整文本
-                                    if let Some(callback) = *COMMAND_CALLBACK.lock().unwrap() {
-                                        let cmd = VInputCommand::commit_text(&final_result);
-                                        callback(&cmd as *const VInputCommand);
-                                        vinput_command_free(&cmd as *const VInputCommand as *mut VInputCommand);
-                                    }
-                                }
-
-                                // 记录到历史
-                                if let Ok(mut history) = recognition_history.lock() {
-                                    history.push(final_result.clone());
-                                    tracing::debug!("已记录到历史，当前历史数: {}", history.len());
-                                }
-
-                                tracing::info!("✨ 完整结果上屏完成");
-                            }
-                            // get_final_result_with_punctuation() 内部已重置 pipeline，无需再次调用 reset()
-                            tracing::info!("🔄 Pipeline 已重置，准备接收下一句");
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("管道处理错误: {}", e);
-                        break;
-                    }
-                }
+        if let Some(cmd) = handle.try_recv_command() {
+            unsafe {
+                *command = cmd;
             }
+            tracing::debug!("返回命令: {:?}", unsafe { &*command }.command_type);
+            Ok(VInputFFIResult::Success)
+        } else {
+            Err(VInputFFIResult::NoData)
         }
-
-        tracing::info!("音频处理线程退出");
+    }) {
+        Ok(result) => result,
+        Err(e) => e,
     }
+}
 
-    /// 停止录音并生成识别结果
-    fn stop_recording(&mut self) {
-        if !self.is_recording {
-            tracing::warn!("没有在录音");
-            return;
-        }
-
-        tracing::info!("🛑 手动停止录音");
-        self.is_recording = false;
-
-        // 停止 PipeWire 流
-        if let Some(stream) = self.pipewire_stream.take() {
-            stream.stop();
-            tracing::debug!("PipeWire 流已停止");
-        }
+/// 字符串列表（FFI 兼容）
+#[repr(C)]
+pub struct VInputStringList {
+    /// 字符串数组指针（UTF-8）
+    pub items: *mut *mut c_char,
+    /// 字符串数量
+    pub count: usize,
+}
 
-        // 发送停止信号
-        *self.stop_signal.lock().unwrap() = true;
+impl VInputStringList {
+    /// 转换为 FFI 列表（含 NUL 的字符串截断到 NUL 之前）
+    fn into_raw(strings: &[String]) -> *mut Self {
+        let items: Box<[*mut c_char]> = strings
+            .iter()
+            .map(|s| {
+                let end = s.find('\0').unwrap_or(s.len());
+                CString::new(&s[..end]).unwrap_or_default().into_raw()
+            })
+            .collect();
+        let count = items.len();
+        Box::into_raw(Box::new(Self {
+            items: Box::into_raw(items) as *mut *mut c_char,
+            count,
+        }))
+    }
+}
 
-        // 等待音频线程结束
-        if let Some(handle) = self.audio_thread.take() {
-            let _ = handle.join();
-        }
+/// 获取实例的识别历史（未撤销的记录，按时间顺序；每条记录一个字符串，可含换行）
+///
+/// # 返回值
+/// 成功返回字符串列表指针（无历史时 count 为 0），失败返回 null
+/// 调用者需要使用 vinput_string_list_free 释放
+#[no_mangle]
+pub extern "C" fn vinput_engine_get_history(handle: *const VInputHandle) -> *mut VInputStringList {
+    ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        Ok(VInputStringList::into_raw(&handle.history()))
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
 
-        // 获取识别结果（带智能标点）
-        let raw_result_with_punct = if let Ok(mut pipe) = self.pipeline.lock() {
-            pipe.get_final_result_with_punctuation()
-        } else {
-            String::new()
-        };
+/// 释放字符串列表
+#[no_mangle]
+pub extern "C" fn vinput_string_list_free(list: *mut VInputStringList) {
+    if list.is_null() {
+        return;
+    }
 
-        if raw_result_with_punct.is_empty() {
-            tracing::info!("识别结果为空，不生成命令");
+    unsafe {
+        let list = Box::from_raw(list);
+        if list.items.is_null() {
             return;
         }
-
-        tracing::info!("🎤 识别结果（含智能标点）: [{}]", raw_result_with_punct);
-
-        // 应用 ITN (文本规范化)
-        tracing::info!("📝 开始 ITN 处理...");
-        let itn_result = if let Ok(itn) = self.itn_engine.lock() {
-            itn.process(&raw_result_with_punct)
-        } else {
-            crate::itn::ITNResult {
-                text: raw_result_with_punct.clone(),
-                changes: Vec::new(),
-            }
-        };
-        let final_result = itn_result.text;
-
-        if !itn_result.changes.is_empty() {
-            tracing::info!("✏️  ITN 完成: {} 处变更", itn_result.changes.len());
-            for change in &itn_result.changes {
-                tracing::info!("    '{}' → '{}'", change.original_text, change.normalized_text);
+        let items = Box::from_raw(std::ptr::slice_from_raw_parts_mut(list.items, list.count));
+        for item in items.iter() {
+            if !item.is_null() {
+                let _ = CString::from_raw(*item);
             }
-        } else {
-            tracing::info!("📋 ITN: 无需变更（输入已是规范格式）");
         }
+    }
+}
 
-        tracing::info!("✅ 最终结果: [{}]", final_result);
+/// 获取实例配置的前端快捷键（规范化的 `修饰键+键名` 写法，如 `Control+z`）
+///
+/// 配置值无效时返回默认快捷键
+///
+/// # 返回值
+/// 成功返回 UTF-8 字符串，失败返回 null
+/// 调用者需要使用 vinput_string_free 释放
+#[no_mangle]
+pub extern "C" fn vinput_engine_get_shortcut(
+    handle: *const VInputHandle,
+    action: VInputShortcutAction,
+) -> *mut c_char {
+    ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        let c_string = CString::new(handle.shortcut(action)).map_err(|_| VInputFFIResult::InternalError)?;
+        Ok(c_string.into_raw())
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
 
-        // 记录到历史
-        if let Ok(mut history) = self.recognition_history.lock() {
-            history.push(final_result.clone());
-            tracing::debug!("已记录到历史，当前历史数: {}", history.len());
+/// 获取实例的录音触发方式
+#[no_mangle]
+pub extern "C" fn vinput_engine_get_activation(
+    handle: *const VInputHandle,
+    activation: *mut VInputActivation,
+) -> VInputFFIResult {
+    match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        check_null_mut(activation, "activation")?;
+        unsafe {
+            *activation = handle.activation();
         }
+        Ok(VInputFFIResult::Success)
+    }) {
+        Ok(result) => result,
+        Err(e) => e,
+    }
+}
 
-        // 生成命令序列
-        if let Ok(mut queue) = self.command_queue.lock() {
-            queue.push_back(VInputCommand::show_candidate(&final_result));
-            queue.push_back(VInputCommand::commit_text(&final_result));
-            queue.push_back(VInputCommand::hide_candidate());
-            tracing::info!("生成 {} 个命令", queue.len());
+/// 获取实例的性能指标快照
+#[no_mangle]
+pub extern "C" fn vinput_engine_get_metrics(
+    handle: *const VInputHandle,
+    metrics: *mut VInputMetrics,
+) -> VInputFFIResult {
+    match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        check_null_mut(metrics, "metrics")?;
+        unsafe {
+            *metrics = VInputMetrics::from(&handle.metrics().snapshot());
         }
+        Ok(VInputFFIResult::Success)
+    }) {
+        Ok(result) => result,
+        Err(e) => e,
     }
+}
 
-    /// 尝试接收命令
-    fn try_recv_command(&mut self) -> Option<VInputCommand> {
-        if let Ok(mut queue) = self.command_queue.lock() {
-            queue.pop_front()
-        } else {
-            None
-        }
+/// 获取实例的性能指标（Prometheus 文本格式）
+///
+/// # 返回值
+/// 成功返回 UTF-8 字符串，失败返回 null
+/// 调用者需要使用 vinput_string_free 释放
+#[no_mangle]
+pub extern "C" fn vinput_engine_get_metrics_text(handle: *const VInputHandle) -> *mut c_char {
+    ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        let text = handle.metrics().snapshot().to_prometheus();
+        let c_string = CString::new(text).map_err(|_| VInputFFIResult::InternalError)?;
+        Ok(c_string.into_raw())
+    })
+    .unwrap_or(std::ptr::null_mut())
+}
+
+/// 清零实例的性能指标
+#[no_mangle]
+pub extern "C" fn vinput_engine_reset_metrics(handle: *const VInputHandle) -> VInputFFIResult {
+    match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        handle.metrics().reset();
+        Ok(VInputFFIResult::Success)
+    }) {
+        Ok(result) => result,
+        Err(e) => e,
+    }
+}
+
+/// 释放由 V-Input 返回的字符串
+#[no_mangle]
+pub extern "C" fn vinput_string_free(string: *mut c_char) {
+    if string.is_null() {
+        return;
+    }
+    let _ = unsafe { CString::from_raw(string) };
+}
+
+/// 在默认实例上执行操作（持锁期间实例不会被 shutdown 释放）
+fn with_default_engine(f: impl FnOnce(*mut VInputHandle) -> VInputFFIResult) -> VInputFFIResult {
+    match DEFAULT_ENGINE.lock() {
+        Ok(guard) => match guard.as_ref() {
+            Some(handle) => f(handle as *const VInputHandle as *mut VInputHandle),
+            None => VInputFFIResult::NotInitialized,
+        },
+        Err(_) => VInputFFIResult::InternalError,
     }
 }
 
-/// 初始化 V-Input Core
+/// 初始化 V-Input Core（默认实例）
 #[no_mangle]
 pub extern "C" fn vinput_core_init() -> VInputFFIResult {
     match ffi_safe_call(|| {
@@ -411,17 +1535,17 @@ pub extern "C" fn vinput_core_init() -> VInputFFIResult {
         crate::init_logging();
         tracing::info!("V-Input Core FFI: 初始化");
 
-        let mut core = VINPUT_CORE.lock().unwrap();
+        let mut core = DEFAULT_ENGINE.lock().unwrap();
 
         if core.is_some() {
             tracing::warn!("V-Input Core 已经初始化");
             return Ok(VInputFFIResult::Success);
         }
 
-        // 创建 Core 状态
-        match VInputCoreState::new() {
-            Ok(state) => {
-                *core = Some(state);
+        // 创建默认实例
+        match VInputHandle::new(None) {
+            Ok(handle) => {
+                *core = Some(handle);
                 tracing::info!("V-Input Core 初始化成功");
                 Ok(VInputFFIResult::Success)
             }
@@ -436,28 +1560,19 @@ pub extern "C" fn vinput_core_init() -> VInputFFIResult {
     }
 }
 
-/// 关闭 V-Input Core
+/// 关闭 V-Input Core（默认实例）
 #[no_mangle]
 pub extern "C" fn vinput_core_shutdown() -> VInputFFIResult {
     match ffi_safe_call(|| {
         tracing::info!("V-Input Core FFI: 关闭");
 
-        let mut core = VINPUT_CORE.lock().unwrap();
-
-        if core.is_none() {
+        // 先取出实例再释放，避免持锁等待音频线程退出
+        let handle = DEFAULT_ENGINE.lock().unwrap().take();
+        if handle.is_none() {
             tracing::warn!("V-Input Core 未初始化");
             return Ok(VInputFFIResult::Success);
         }
-
-        // 停止录音（如果正在录音）
-        if let Some(ref mut state) = *core {
-            if state.is_recording {
-                state.stop_recording();
-            }
-        }
-
-        // 清理资源
-        *core = None;
+        drop(handle);
 
         tracing::info!("V-Input Core 关闭成功");
         Ok(VInputFFIResult::Success)
@@ -467,115 +1582,67 @@ pub extern "C" fn vinput_core_shutdown() -> VInputFFIResult {
     }
 }
 
-/// 注册命令回调函数
+/// 注册命令回调函数（默认实例）
 ///
 /// C++ 插件在初始化时调用此函数，注册回调以接收实时命令
 /// 当 Rust Core 检测到句子结束时，会直接调用此回调
 #[no_mangle]
 pub extern "C" fn vinput_core_register_callback(callback: VInputCommandCallback) -> VInputFFIResult {
-    match ffi_safe_call(|| {
-        tracing::info!("V-Input Core FFI: 注册命令回调");
+    tracing::info!("V-Input Core FFI: 注册命令回调");
 
-        let mut callback_lock = COMMAND_CALLBACK.lock().unwrap();
-        *callback_lock = Some(callback);
+    with_default_engine(|handle| match ffi_safe_call(|| {
+        let handle = unsafe { handle_ref(handle) }?;
+        handle.set_command_sink(Some(CommandSink::Plain(callback)));
 
         tracing::info!("✅ 命令回调注册成功");
         Ok(VInputFFIResult::Success)
     }) {
         Ok(result) => result,
         Err(e) => e,
-    }
+    })
 }
 
-/// 发送事件到 V-Input Core
+/// 发送事件到 V-Input Core（默认实例）
 #[no_mangle]
 pub extern "C" fn vinput_core_send_event(event: *const VInputEvent) -> VInputFFIResult {
-    match ffi_safe_call(|| {
-        check_null(event, "event")?;
-
-        let event = unsafe { &*event };
-
-        let mut core_lock = VINPUT_CORE.lock().unwrap();
-        let core = core_lock
-            .as_mut()
-            .ok_or(VInputFFIResult::NotInitialized)?;
+    with_default_engine(|handle| vinput_engine_send_event(handle, event))
+}
 
-        match event.event_type {
-            VInputEventType::StartRecording => {
-                tracing::info!("接收事件: StartRecording");
-                core.start_recording();
-            }
-            VInputEventType::StopRecording => {
-                tracing::info!("接收事件: StopRecording");
-                core.stop_recording();
-            }
-            VInputEventType::UndoRequest => {
-                tracing::info!("接收事件: UndoRequest");
-                if let Ok(mut history) = core.recognition_history.lock() {
-                    if let Some(undone_text) = history.undo() {
-                        tracing::info!("撤销文本: {}", undone_text);
-                        // 生成撤销命令
-                        if let Ok(mut queue) = core.command_queue.lock() {
-                            queue.push_back(VInputCommand::undo_text(&undone_text));
-                        }
-                    } else {
-                        tracing::warn!("没有可撤销的内容");
-                    }
-                }
-            }
-            VInputEventType::RedoRequest => {
-                tracing::info!("接收事件: RedoRequest");
-                if let Ok(mut history) = core.recognition_history.lock() {
-                    if let Some(redone_text) = history.redo() {
-                        tracing::info!("重试文本: {}", redone_text);
-                        // 生成重试命令
-                        if let Ok(mut queue) = core.command_queue.lock() {
-                            queue.push_back(VInputCommand::redo_text(&redone_text));
-                        }
-                    } else {
-                        tracing::warn!("没有可重试的内容");
-                    }
-                }
-            }
-            _ => {
-                tracing::debug!("接收事件: {:?} (暂不处理)", event.event_type);
-            }
-        }
+/// 尝试接收命令（非阻塞，默认实例）
+#[no_mangle]
+pub extern "C" fn vinput_core_try_recv_command(command: *mut VInputCommand) -> VInputFFIResult {
+    with_default_engine(|handle| vinput_engine_try_recv_command(handle, command))
+}
 
-        Ok(VInputFFIResult::Success)
-    }) {
-        Ok(result) => result,
-        Err(e) => e,
+/// 获取配置的前端快捷键（默认实例，写法同 `vinput_engine_get_shortcut`）
+///
+/// # 返回值
+/// 成功返回 UTF-8 字符串，未初始化或失败返回 null
+/// 调用者需要使用 vinput_string_free 释放
+#[no_mangle]
+pub extern "C" fn vinput_core_get_shortcut(action: VInputShortcutAction) -> *mut c_char {
+    match DEFAULT_ENGINE.lock() {
+        Ok(guard) => match guard.as_ref() {
+            Some(handle) => vinput_engine_get_shortcut(handle, action),
+            None => std::ptr::null_mut(),
+        },
+        Err(_) => std::ptr::null_mut(),
     }
 }
 
-/// 尝试接收命令（非阻塞）
+/// 获取录音触发方式（默认实例）
 #[no_mangle]
-pub extern "C" fn vinput_core_try_recv_command(command: *mut VInputCommand) -> VInputFFIResult {
-    match ffi_safe_call(|| {
-        check_null_mut(command, "command")?;
-
-        let mut core_lock = VINPUT_CORE.lock().unwrap();
-        let core = core_lock
-            .as_mut()
-            .ok_or(VInputFFIResult::NotInitialized)?;
+pub extern "C" fn vinput_core_get_activation(activation: *mut VInputActivation) -> VInputFFIResult {
+    with_default_engine(|handle| vinput_engine_get_activation(handle, activation))
+}
 
-        if let Some(cmd) = core.try_recv_command() {
-            unsafe {
-                *command = cmd;
-            }
-            tracing::debug!("返回命令: {:?}", unsafe { &*command }.command_type);
-            Ok(VInputFFIResult::Success)
-        } else {
-            Err(VInputFFIResult::NoData)
-        }
-    }) {
-        Ok(result) => result,
-        Err(e) => e,
-    }
+/// 获取性能指标快照（默认实例）
+#[no_mangle]
+pub extern "C" fn vinput_core_get_metrics(metrics: *mut VInputMetrics) -> VInputFFIResult {
+    with_default_engine(|handle| vinput_engine_get_metrics(handle, metrics))
 }
 
-/// 释放命令资源
+/// 释放命令资源（含结构化载荷）
 #[no_mangle]
 pub extern "C" fn vinput_command_free(command: *mut VInputCommand) {
     if command.is_null() {
@@ -590,7 +1657,63 @@ pub extern "C" fn vinput_command_free(command: *mut VInputCommand) {
             cmd.text = std::ptr::null_mut();
             cmd.text_len = 0;
         }
+
+        if !cmd.payload.is_null() {
+            VInputCommandPayload::free_raw(cmd.payload);
+            cmd.payload = std::ptr::null_mut();
+        }
+    }
+}
+
+/// 获取命令的结构化载荷
+///
+/// # 返回值
+/// 无载荷或载荷版本不受支持时返回 null（前端应回退到 text）
+#[no_mangle]
+pub extern "C" fn vinput_command_payload(command: *const VInputCommand) -> *const VInputCommandPayload {
+    if command.is_null() {
+        return std::ptr::null();
+    }
+
+    let payload = unsafe { (*command).payload };
+    if payload.is_null() || unsafe { (*payload).version } != super::types::VINPUT_COMMAND_PAYLOAD_VERSION {
+        return std::ptr::null();
+    }
+    payload
+}
+
+/// 获取载荷中的第 index 个 Preedit 片段（越界返回 null）
+#[no_mangle]
+pub extern "C" fn vinput_payload_segment(
+    payload: *const VInputCommandPayload,
+    index: usize,
+) -> *const VInputPreeditSegment {
+    if payload.is_null() {
+        return std::ptr::null();
+    }
+
+    let payload = unsafe { &*payload };
+    if payload.segments.is_null() || index >= payload.segment_count {
+        return std::ptr::null();
+    }
+    unsafe { payload.segments.add(index) }
+}
+
+/// 获取载荷中的第 index 个候选文本（越界返回 null）
+#[no_mangle]
+pub extern "C" fn vinput_payload_candidate(
+    payload: *const VInputCommandPayload,
+    index: usize,
+) -> *const c_char {
+    if payload.is_null() {
+        return std::ptr::null();
+    }
+
+    let payload = unsafe { &*payload };
+    if payload.candidates.is_null() || index >= payload.candidate_count {
+        return std::ptr::null();
     }
+    unsafe { *payload.candidates.add(index) }
 }
 
 /// 获取版本字符串
@@ -707,3 +1830,162 @@ pub extern "C" fn vinput_audio_device_list_free(list: *mut VInputAudioDeviceList
         // list_box 自动释放
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ffi::types::VInputCommandType;
+
+    #[test]
+    fn test_engine_functions_reject_null_handle() {
+        let event = VInputEvent::new(VInputEventType::StartRecording);
+        let mut command = VInputCommand::new(VInputCommandType::CommitText);
+
+        assert_eq!(
+            vinput_engine_send_event(std::ptr::null_mut(), &event),
+            VInputFFIResult::NullPointer
+        );
+        assert_eq!(
+            vinput_engine_try_recv_command(std::ptr::null_mut(), &mut command),
+            VInputFFIResult::NullPointer
+        );
+        assert_eq!(
+            vinput_engine_register_callback(std::ptr::null_mut(), None, std::ptr::null_mut()),
+            VInputFFIResult::NullPointer
+        );
+        vinput_engine_free(std::ptr::null_mut());
+        assert!(vinput_engine_get_history(std::ptr::null()).is_null());
+        vinput_string_list_free(std::ptr::null_mut());
+        assert!(vinput_engine_get_shortcut(std::ptr::null(), VInputShortcutAction::Trigger).is_null());
+        let mut activation = VInputActivation::from(&ActivationConfig::default());
+        assert_eq!(
+            vinput_engine_get_activation(std::ptr::null(), &mut activation),
+            VInputFFIResult::NullPointer
+        );
+        let mut metrics = VInputMetrics::default();
+        assert_eq!(
+            vinput_engine_get_metrics(std::ptr::null(), &mut metrics),
+            VInputFFIResult::NullPointer
+        );
+        assert!(vinput_engine_get_metrics_text(std::ptr::null()).is_null());
+        assert_eq!(vinput_engine_reset_metrics(std::ptr::null()), VInputFFIResult::NullPointer);
+        vinput_string_free(std::ptr::null_mut());
+    }
+
+    #[test]
+    fn test_engine_new_rejects_invalid_utf8_path() {
+        let path = CString::new(vec![0xffu8, 0xfe]).unwrap();
+        assert!(vinput_engine_new(path.as_ptr()).is_null());
+    }
+
+    #[test]
+    fn test_string_list_keeps_multiline_entries() {
+        use std::ffi::CStr;
+
+        let history = vec!["第一行\n第二行".to_string(), String::new(), "末句".to_string()];
+        let list = VInputStringList::into_raw(&history);
+        let items = unsafe { std::slice::from_raw_parts((*list).items, (*list).count) };
+        let decoded: Vec<String> = items
+            .iter()
+            .map(|&item| unsafe { CStr::from_ptr(item) }.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(decoded, history);
+        vinput_string_list_free(list);
+
+        let empty = VInputStringList::into_raw(&[]);
+        assert_eq!(unsafe { (*empty).count }, 0);
+        vinput_string_list_free(empty);
+    }
+
+    #[test]
+    fn test_preedit_payload_accessors() {
+        use crate::streaming::{PreeditSegment, SegmentKind};
+        use crate::ffi::types::VInputSegmentAttribute;
+        use std::ffi::CStr;
+
+        let segments = vec![
+            PreeditSegment { text: "今天天气".to_string(), kind: SegmentKind::Stable },
+            PreeditSegment { text: "不错".to_string(), kind: SegmentKind::Unstable },
+        ];
+        let mut command = VInputCommand::update_preedit_segments(&segments);
+
+        let payload = vinput_command_payload(&command);
+        assert!(!payload.is_null());
+        assert_eq!(unsafe { (*payload).segment_count }, 2);
+        assert_eq!(unsafe { (*payload).cursor }, command.text_len);
+
+        let unstable = unsafe { &*vinput_payload_segment(payload, 1) };
+        assert_eq!(unstable.attribute, VInputSegmentAttribute::Unstable);
+        assert_eq!(unsafe { CStr::from_ptr(unstable.text) }.to_str().unwrap(), "不错");
+        assert!(vinput_payload_segment(payload, 2).is_null());
+        assert!(vinput_payload_candidate(payload, 0).is_null());
+
+        vinput_command_free(&mut command);
+        assert!(command.payload.is_null());
+        assert!(vinput_command_payload(&command).is_null());
+    }
+
+    #[test]
+    fn test_candidate_payload() {
+        use std::ffi::CStr;
+
+        let mut command = VInputCommand::show_candidate("你好");
+        let payload = vinput_command_payload(&command);
+        assert_eq!(unsafe { (*payload).candidate_count }, 1);
+        let candidate = vinput_payload_candidate(payload, 0);
+        assert_eq!(unsafe { CStr::from_ptr(candidate) }.to_str().unwrap(), "你好");
+        vinput_command_free(&mut command);
+    }
+
+    #[test]
+    fn test_show_candidates_payload() {
+        use std::ffi::CStr;
+
+        let candidates = vec!["我3点到。".to_string(), "我三点到。".to_string()];
+        let mut command = VInputCommand::show_candidates("我3点到。", &candidates);
+        assert_eq!(command.command_type, VInputCommandType::ShowCandidate);
+        let payload = vinput_command_payload(&command);
+        assert_eq!(unsafe { (*payload).candidate_count }, 2);
+        let second = vinput_payload_candidate(payload, 1);
+        assert_eq!(unsafe { CStr::from_ptr(second) }.to_str().unwrap(), "我三点到。");
+        assert!(vinput_payload_candidate(payload, 2).is_null());
+        vinput_command_free(&mut command);
+    }
+
+    #[test]
+    fn test_hands_free_idle_timeout_emits_recording_stopped() {
+        use std::sync::atomic::AtomicUsize;
+
+        static STOPPED: AtomicUsize = AtomicUsize::new(0);
+        extern "C" fn on_command(command: *const VInputCommand) {
+            if unsafe { (*command).command_type } == VInputCommandType::RecordingStopped {
+                STOPPED.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let sink: SharedCommandSink = Arc::new(Mutex::new(Some(CommandSink::Plain(on_command))));
+
+        let activation = ActivationConfig {
+            stop_mode: StopMode::HandsFree,
+            idle_timeout_ms: 320,
+            ..Default::default()
+        };
+        let policy = StopPolicy::from(&activation);
+        // 管道每个静默帧累计 32ms：第 10 帧达到超时并发出 RecordingStopped
+        let stopped_at = (1..=20u64).find(|frames| policy.stop_if_idle(frames * 32, &sink));
+        assert_eq!(stopped_at, Some(10));
+        assert_eq!(STOPPED.load(Ordering::SeqCst), 1);
+
+        // 按键说话不因静默停止
+        assert!(!StopPolicy::from(&ActivationConfig::default()).stop_if_idle(u64::MAX, &sink));
+        assert_eq!(STOPPED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_default_engine_not_initialized() {
+        let event = VInputEvent::new(VInputEventType::StopRecording);
+        assert_eq!(vinput_core_send_event(&event), VInputFFIResult::NotInitialized);
+        assert!(vinput_core_get_shortcut(VInputShortcutAction::Undo).is_null());
+        let mut metrics = VInputMetrics::default();
+        assert_eq!(vinput_core_get_metrics(&mut metrics), VInputFFIResult::NotInitialized);
+    }
+}
@@ -1,6 +1,12 @@
 //! FFI C-compatible 类型定义
 
-use std::os::raw::c_char;
+use crate::config::{ActivationConfig, ActivationMode, ShortcutAction, StopMode};
+use crate::metrics::{DurationStats, MetricsSnapshot, Stage};
+use crate::streaming::{PreeditSegment, SegmentKind};
+use std::os::raw::{c_char, c_void};
+
+/// 命令载荷版本（`VInputCommandPayload` 布局变化时递增）
+pub const VINPUT_COMMAND_PAYLOAD_VERSION: u32 = 1;
 
 /// FFI 结果码
 #[repr(C)]
@@ -42,6 +48,14 @@ pub enum VInputEventType {
     UndoRequest = 6,
     /// 重试请求
     RedoRequest = 7,
+    /// 用户从候选列表中选择了替代结果（data: 选中的 UTF-8 文本，已由前端替换上屏）
+    CandidateSelected = 8,
+    /// 取消录音：丢弃当前句子，不上屏、不记入历史
+    CancelRecording = 9,
+    /// 用户撤销后在原位置重新输入的文本（data: UTF-8 文本，用于学习热词）
+    CorrectionText = 10,
+    /// 用户把候选手动加为热词（data: UTF-8 文本）
+    AddHotword = 11,
 }
 
 /// V-Input 事件（从 Fcitx5 -> Rust Core）
@@ -61,11 +75,12 @@ pub struct VInputEvent {
 pub enum VInputCommandType {
     /// 提交文本
     CommitText = 1,
-    /// 显示候选
+    /// 显示候选（text: 刚上屏、待替换的文本；载荷 candidates: 候选列表，候选 0 即 text）
     ShowCandidate = 2,
     /// 隐藏候选
     HideCandidate = 3,
-    /// 错误消息
+    /// 错误消息（录音会话无法自动恢复时 text 为 `code\tmessage`，code 为错误码如 E2003，
+    /// 随后发出 RecordingStopped）
     Error = 4,
     /// 撤销文本
     UndoText = 5,
@@ -75,6 +90,201 @@ pub enum VInputCommandType {
     UpdatePreedit = 7,
     /// 清除 Preedit
     ClearPreedit = 8,
+    /// 活动音频设备变化（text: `name\tdescription\treason`，reason 为 initial / preferred / fallback）
+    AudioDeviceChanged = 9,
+    /// 麦克风电平（text: `rms_dbfs\tpeak_dbfs\tclipping`，约每 100ms 一次）
+    AudioLevel = 10,
+    /// 输入诊断警告（text: `kind\tmessage`，kind 为 clipping / no_signal / low_gain）
+    AudioWarning = 11,
+    /// 自动停止了识别（AutoStop 整句上屏后 / HandsFree 静默超时；前端应随后发送 StopRecording 释放麦克风）
+    RecordingStopped = 12,
+    /// 检测到唤醒词（text: 唤醒词文本；前端应随后发送 StartRecording 开始录音）
+    WakeWordDetected = 13,
+}
+
+/// 前端快捷键动作（对应 config.toml 的 `[shortcuts]` 段）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VInputShortcutAction {
+    /// 开始 / 停止录音
+    Trigger = 0,
+    /// 撤销上一次上屏
+    Undo = 1,
+    /// 重新上屏被撤销的文本
+    Redo = 2,
+}
+
+impl From<VInputShortcutAction> for ShortcutAction {
+    fn from(action: VInputShortcutAction) -> Self {
+        match action {
+            VInputShortcutAction::Trigger => Self::Trigger,
+            VInputShortcutAction::Undo => Self::Undo,
+            VInputShortcutAction::Redo => Self::Redo,
+        }
+    }
+}
+
+/// 触发键按法
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VInputActivationMode {
+    /// 按住触发键录音，松开停止
+    Hold = 0,
+    /// 按一下开始，再按一下停止
+    Toggle = 1,
+    /// 双击触发键开始，单击停止
+    DoubleTap = 2,
+}
+
+/// 停止方式
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VInputStopMode {
+    /// 整句上屏后自动停止（发出 RecordingStopped 命令）
+    AutoStop = 0,
+    /// 连续听写，直到再次按触发键
+    Continuous = 1,
+    /// 免提听写，静默超时后自动停止（发出 RecordingStopped 命令）
+    HandsFree = 2,
+}
+
+/// 录音触发方式（对应 config.toml 的 `[activation]` 段）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VInputActivation {
+    /// 触发键按法
+    pub mode: VInputActivationMode,
+    /// 停止方式
+    pub stop_mode: VInputStopMode,
+    /// 双击判定间隔（毫秒）
+    pub double_tap_interval_ms: u32,
+}
+
+impl From<&ActivationConfig> for VInputActivation {
+    fn from(config: &ActivationConfig) -> Self {
+        Self {
+            mode: match config.mode {
+                ActivationMode::Hold => VInputActivationMode::Hold,
+                ActivationMode::Toggle => VInputActivationMode::Toggle,
+                ActivationMode::DoubleTap => VInputActivationMode::DoubleTap,
+            },
+            stop_mode: match config.stop_mode {
+                StopMode::AutoStop => VInputStopMode::AutoStop,
+                StopMode::Continuous => VInputStopMode::Continuous,
+                StopMode::HandsFree => VInputStopMode::HandsFree,
+            },
+            double_tap_interval_ms: config.double_tap_interval_ms,
+        }
+    }
+}
+
+/// 管道性能指标快照（时长单位均为毫秒）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VInputMetrics {
+    /// 已处理的音频时长
+    pub audio_ms: f64,
+    /// 实时率（各阶段处理耗时合计 / 音频时长，< 1 表示快于实时）
+    pub real_time_factor: f64,
+    /// 语音增强平均耗时（每帧）
+    pub enhancement_mean_ms: f64,
+    /// VAD 平均耗时（每帧）
+    pub vad_mean_ms: f64,
+    /// ASR 送入音频与解码平均耗时（每次）
+    pub asr_decode_mean_ms: f64,
+    /// 标点平均耗时（每次部分 / 最终结果）
+    pub punctuation_mean_ms: f64,
+    /// ITN 平均耗时（每次）
+    pub itn_mean_ms: f64,
+    /// 首个部分结果延迟：最近一次
+    pub first_partial_last_ms: f64,
+    /// 首个部分结果延迟：平均
+    pub first_partial_mean_ms: f64,
+    /// 首个部分结果延迟：最大
+    pub first_partial_max_ms: f64,
+    /// 端点到上屏延迟：最近一次
+    pub endpoint_to_commit_last_ms: f64,
+    /// 端点到上屏延迟：平均
+    pub endpoint_to_commit_mean_ms: f64,
+    /// 端点到上屏延迟：最大
+    pub endpoint_to_commit_max_ms: f64,
+    /// 已上屏的句子数
+    pub utterances: u64,
+    /// Ring Buffer 溢出丢弃的样本数
+    pub ring_overruns: u64,
+}
+
+impl From<&MetricsSnapshot> for VInputMetrics {
+    fn from(snapshot: &MetricsSnapshot) -> Self {
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        let mean_ms = |stats: &DurationStats| ms(stats.mean());
+        Self {
+            audio_ms: ms(snapshot.audio),
+            real_time_factor: snapshot.real_time_factor(),
+            enhancement_mean_ms: mean_ms(snapshot.stage(Stage::Enhancement)),
+            vad_mean_ms: mean_ms(snapshot.stage(Stage::Vad)),
+            asr_decode_mean_ms: mean_ms(snapshot.stage(Stage::AsrDecode)),
+            punctuation_mean_ms: mean_ms(snapshot.stage(Stage::Punctuation)),
+            itn_mean_ms: mean_ms(snapshot.stage(Stage::Itn)),
+            first_partial_last_ms: ms(snapshot.first_partial.last),
+            first_partial_mean_ms: mean_ms(&snapshot.first_partial),
+            first_partial_max_ms: ms(snapshot.first_partial.max),
+            endpoint_to_commit_last_ms: ms(snapshot.endpoint_to_commit.last),
+            endpoint_to_commit_mean_ms: mean_ms(&snapshot.endpoint_to_commit),
+            endpoint_to_commit_max_ms: ms(snapshot.endpoint_to_commit.max),
+            utterances: snapshot.utterances,
+            ring_overruns: snapshot.ring_overruns,
+        }
+    }
+}
+
+/// Preedit 片段属性
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VInputSegmentAttribute {
+    /// 稳定文本
+    Stable = 0,
+    /// 不稳定文本（可能随后续解码改变）
+    Unstable = 1,
+    /// 低置信度文本（保留：流式 Paraformer 不提供逐 Token 置信度，当前不会产生）
+    LowConfidence = 2,
+}
+
+impl From<SegmentKind> for VInputSegmentAttribute {
+    fn from(kind: SegmentKind) -> Self {
+        match kind {
+            SegmentKind::Stable => Self::Stable,
+            SegmentKind::Unstable => Self::Unstable,
+        }
+    }
+}
+
+/// Preedit 片段
+#[repr(C)]
+pub struct VInputPreeditSegment {
+    /// 片段文本（UTF-8，以 null 结尾）
+    pub text: *mut c_char,
+    /// 文本长度（字节，不含 null）
+    pub text_len: usize,
+    /// 片段属性
+    pub attribute: VInputSegmentAttribute,
+}
+
+/// 结构化命令载荷（随 VInputCommand 一起由 vinput_command_free 释放）
+#[repr(C)]
+pub struct VInputCommandPayload {
+    /// 载荷版本（VINPUT_COMMAND_PAYLOAD_VERSION）
+    pub version: u32,
+    /// Preedit 片段数组（按顺序拼接即为命令 text）
+    pub segments: *mut VInputPreeditSegment,
+    /// 片段数量
+    pub segment_count: usize,
+    /// 光标位置（命令 text 中的字节偏移）
+    pub cursor: usize,
+    /// 候选文本数组（UTF-8，以 null 结尾）
+    pub candidates: *mut *mut c_char,
+    /// 候选数量
+    pub candidate_count: usize,
 }
 
 /// V-Input 命令（从 Rust Core -> Fcitx5）
@@ -86,6 +296,8 @@ pub struct VInputCommand {
     pub text: *mut c_char,
     /// 文本长度（不含 null）
     pub text_len: usize,
+    /// 结构化载荷（可为 null，旧前端可只读取 text）
+    pub payload: *mut VInputCommandPayload,
 }
 
 // VInputCommand 需要在 VecDeque 中存储，因此需要 Send
@@ -99,15 +311,12 @@ unsafe impl Sync for VInputCommand {}
 /// 参数: VInputCommand 指针（C++ 需要调用 vinput_command_free 释放）
 pub type VInputCommandCallback = extern "C" fn(*const VInputCommand);
 
-/// 不透明的 V-Input Core 句柄
-#[repr(C)]
-pub struct VInputHandle {
-    _private: [u8; 0],
-}
-
-// 实现 Send + Sync（实际实现在 Rust 侧）
-unsafe impl Send for VInputHandle {}
-unsafe impl Sync for VInputHandle {}
+/// 句柄回调函数类型（带 user_data）
+///
+/// 由 `vinput_engine_register_callback` 注册，`user_data` 原样传回，
+/// 便于同一进程内多个引擎实例把命令路由到各自的前端对象
+/// 参数: VInputCommand 指针（仅在回调期间有效）、注册时的 user_data
+pub type VInputHandleCommandCallback = extern "C" fn(*const VInputCommand, *mut c_void);
 
 impl VInputEvent {
     /// 创建新事件
@@ -129,6 +338,77 @@ impl VInputEvent {
     }
 }
 
+impl VInputCommandPayload {
+    /// 创建载荷并转移所有权给调用方（由 `free_raw` 释放）
+    pub fn into_raw(segments: &[PreeditSegment], cursor: usize, candidates: &[String]) -> *mut Self {
+        use std::ffi::CString;
+
+        let mut ffi_segments: Vec<VInputPreeditSegment> = segments
+            .iter()
+            .map(|segment| VInputPreeditSegment {
+                text: CString::new(segment.text.as_str()).unwrap_or_default().into_raw(),
+                text_len: segment.text.len(),
+                attribute: segment.kind.into(),
+            })
+            .collect();
+        let mut ffi_candidates: Vec<*mut c_char> = candidates
+            .iter()
+            .map(|candidate| CString::new(candidate.as_str()).unwrap_or_default().into_raw())
+            .collect();
+
+        // 长度与容量一致，便于释放时重建 Vec
+        ffi_segments.shrink_to_fit();
+        ffi_candidates.shrink_to_fit();
+
+        let payload = Box::new(Self {
+            version: VINPUT_COMMAND_PAYLOAD_VERSION,
+            segments: ffi_segments.as_mut_ptr(),
+            segment_count: ffi_segments.len(),
+            cursor,
+            candidates: ffi_candidates.as_mut_ptr(),
+            candidate_count: ffi_candidates.len(),
+        });
+
+        // 防止 Vec 被释放
+        std::mem::forget(ffi_segments);
+        std::mem::forget(ffi_candidates);
+
+        Box::into_raw(payload)
+    }
+
+    /// 释放 `into_raw` 创建的载荷
+    ///
+    /// # Safety
+    /// `payload` 必须为 null 或由 `into_raw` 返回且尚未释放
+    pub unsafe fn free_raw(payload: *mut Self) {
+        use std::ffi::CString;
+
+        if payload.is_null() {
+            return;
+        }
+
+        let payload = Box::from_raw(payload);
+
+        if !payload.segments.is_null() {
+            let segments = Vec::from_raw_parts(payload.segments, payload.segment_count, payload.segment_count);
+            for segment in segments {
+                if !segment.text.is_null() {
+                    let _ = CString::from_raw(segment.text);
+                }
+            }
+        }
+
+        if !payload.candidates.is_null() {
+            let candidates = Vec::from_raw_parts(payload.candidates, payload.candidate_count, payload.candidate_count);
+            for candidate in candidates {
+                if !candidate.is_null() {
+                    let _ = CString::from_raw(candidate);
+                }
+            }
+        }
+    }
+}
+
 impl VInputCommand {
     /// 创建空命令
     pub fn new(command_type: VInputCommandType) -> Self {
@@ -136,6 +416,7 @@ impl VInputCommand {
             command_type,
             text: std::ptr::null_mut(),
             text_len: 0,
+            payload: std::ptr::null_mut(),
         }
     }
 
@@ -147,10 +428,11 @@ impl VInputCommand {
             command_type: VInputCommandType::CommitText,
             text: c_text.into_raw(),
             text_len: text.len(),
+            payload: std::ptr::null_mut(),
         }
     }
 
-    /// 创建显示候选命令
+    /// 创建显示候选命令（载荷中的候选列表仅含该候选）
     pub fn show_candidate(candidate: &str) -> Self {
         use std::ffi::CString;
         let c_text = CString::new(candidate).unwrap();
@@ -158,6 +440,21 @@ impl VInputCommand {
             command_type: VInputCommandType::ShowCandidate,
             text: c_text.into_raw(),
             text_len: candidate.len(),
+            payload: VInputCommandPayload::into_raw(&[], 0, &[candidate.to_string()]),
+        }
+    }
+
+    /// 创建候选列表命令
+    ///
+    /// text 为刚上屏的文本（前端选择替代结果时据此删除），载荷携带全部候选
+    pub fn show_candidates(committed: &str, candidates: &[String]) -> Self {
+        use std::ffi::CString;
+        let c_text = CString::new(committed).unwrap();
+        Self {
+            command_type: VInputCommandType::ShowCandidate,
+            text: c_text.into_raw(),
+            text_len: committed.len(),
+            payload: VInputCommandPayload::into_raw(&[], 0, candidates),
         }
     }
 
@@ -167,6 +464,7 @@ impl VInputCommand {
             command_type: VInputCommandType::HideCandidate,
             text: std::ptr::null_mut(),
             text_len: 0,
+            payload: std::ptr::null_mut(),
         }
     }
 
@@ -178,6 +476,7 @@ impl VInputCommand {
             command_type: VInputCommandType::Error,
             text: c_text.into_raw(),
             text_len: message.len(),
+            payload: std::ptr::null_mut(),
         }
     }
 
@@ -189,6 +488,7 @@ impl VInputCommand {
             command_type: VInputCommandType::UndoText,
             text: c_text.into_raw(),
             text_len: text.len(),
+            payload: std::ptr::null_mut(),
         }
     }
 
@@ -200,6 +500,7 @@ impl VInputCommand {
             command_type: VInputCommandType::RedoText,
             text: c_text.into_raw(),
             text_len: text.len(),
+            payload: std::ptr::null_mut(),
         }
     }
 
@@ -211,6 +512,23 @@ impl VInputCommand {
             command_type: VInputCommandType::UpdatePreedit,
             text: c_text.into_raw(),
             text_len: text.len(),
+            payload: std::ptr::null_mut(),
+        }
+    }
+
+    /// 创建分段 Preedit 命令
+    ///
+    /// text 为所有片段拼接后的文本（兼容只读取 text 的前端），
+    /// 载荷携带片段属性，光标位于末尾
+    pub fn update_preedit_segments(segments: &[PreeditSegment]) -> Self {
+        use std::ffi::CString;
+        let text = crate::streaming::preedit::segments_text(segments);
+        let c_text = CString::new(text.as_str()).unwrap();
+        Self {
+            command_type: VInputCommandType::UpdatePreedit,
+            text: c_text.into_raw(),
+            text_len: text.len(),
+            payload: VInputCommandPayload::into_raw(segments, text.len(), &[]),
         }
     }
 
@@ -220,6 +538,65 @@ impl VInputCommand {
             command_type: VInputCommandType::ClearPreedit,
             text: std::ptr::null_mut(),
             text_len: 0,
+            payload: std::ptr::null_mut(),
+        }
+    }
+
+    /// 创建麦克风电平命令
+    pub fn audio_level(payload: &str) -> Self {
+        use std::ffi::CString;
+        let c_text = CString::new(payload).unwrap();
+        Self {
+            command_type: VInputCommandType::AudioLevel,
+            text: c_text.into_raw(),
+            text_len: payload.len(),
+            payload: std::ptr::null_mut(),
+        }
+    }
+
+    /// 创建输入诊断警告命令
+    pub fn audio_warning(payload: &str) -> Self {
+        use std::ffi::CString;
+        let c_text = CString::new(payload).unwrap();
+        Self {
+            command_type: VInputCommandType::AudioWarning,
+            text: c_text.into_raw(),
+            text_len: payload.len(),
+            payload: std::ptr::null_mut(),
+        }
+    }
+
+    /// 创建音频设备变化命令
+    pub fn audio_device_changed(payload: &str) -> Self {
+        use std::ffi::CString;
+        let c_text = CString::new(payload).unwrap();
+        Self {
+            command_type: VInputCommandType::AudioDeviceChanged,
+            text: c_text.into_raw(),
+            text_len: payload.len(),
+            payload: std::ptr::null_mut(),
+        }
+    }
+
+    /// 创建自动停止录音命令
+    pub fn recording_stopped() -> Self {
+        Self {
+            command_type: VInputCommandType::RecordingStopped,
+            text: std::ptr::null_mut(),
+            text_len: 0,
+            payload: std::ptr::null_mut(),
+        }
+    }
+
+    /// 创建唤醒词命令
+    pub fn wake_word_detected(keyword: &str) -> Self {
+        use std::ffi::CString;
+        let c_text = CString::new(keyword).unwrap();
+        Self {
+            command_type: VInputCommandType::WakeWordDetected,
+            text: c_text.into_raw(),
+            text_len: keyword.len(),
+            payload: std::ptr::null_mut(),
         }
     }
 }
@@ -3,9 +3,11 @@
 //! 管理热词列表，提供 sherpa-onnx 集成接口
 
 use crate::error::{VInputError, VInputResult};
-use crate::hotwords::parser::HotwordsParser;
+use crate::hotwords::parser::{HotwordEntry, HotwordsParser};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use vinput_config::asr::OnlineRecognizerConfig;
+use vinput_config::hotwords::HotwordsConfig;
 
 /// 热词引擎
 pub struct HotwordsEngine {
@@ -33,6 +35,27 @@ impl HotwordsEngine {
         }
     }
 
+    /// 由配置构建热词表
+    ///
+    /// 依次加入 `user_file`（`[asr] hotwords_file` 指定的热词文件）、未分组热词、
+    /// 已启用分组的热词，最后合并自动学习的热词。
+    pub fn from_config(config: &HotwordsConfig, user_file: Option<&Path>, learned: &[HotwordEntry]) -> Self {
+        let mut engine = Self::new();
+        engine.set_max_hotwords(config.max_words);
+        if let Some(path) = user_file {
+            if let Err(e) = engine.load_from_file(path) {
+                tracing::warn!("读取热词文件 {} 失败: {}", path.display(), e);
+            }
+        }
+        for (word, weight) in config.active_words() {
+            if let Err(e) = engine.add_hotword(word.clone(), weight) {
+                tracing::warn!("添加热词失败 '{}': {}", word, e);
+            }
+        }
+        engine.merge_learned(learned);
+        engine
+    }
+
     /// 从文件加载热词
     pub fn load_from_file(&mut self, path: &Path) -> VInputResult<()> {
         let hotwords = HotwordsParser::load_file(path)?;
@@ -74,6 +97,23 @@ impl HotwordsEngine {
         Ok(())
     }
 
+    /// 合并自动学习的热词（已配置的词保留原权重），返回新增的数量
+    pub fn merge_learned(&mut self, learned: &[HotwordEntry]) -> usize {
+        let mut added = 0;
+        for entry in learned {
+            if self.hotwords.contains_key(&entry.word) {
+                continue;
+            }
+            if self.hotwords.len() >= self.max_hotwords {
+                tracing::warn!("热词数已达上限 {}，忽略其余学到的热词", self.max_hotwords);
+                break;
+            }
+            self.hotwords.insert(entry.word.clone(), entry.weight);
+            added += 1;
+        }
+        added
+    }
+
     /// 移除单个热词
     pub fn remove_hotword(&mut self, word: &str) -> bool {
         self.hotwords.remove(word).is_some()
@@ -91,6 +131,17 @@ impl HotwordsEngine {
         self.hotwords.keys().map(|s| s.as_str()).collect::<Vec<_>>().join("\n")
     }
 
+    /// 写出 sherpa-onnx 热词文件（按词排序，内容稳定）
+    pub fn write_sherpa_file(&self, path: &Path) -> VInputResult<()> {
+        let mut words: Vec<&str> = self.hotwords.keys().map(|s| s.as_str()).collect();
+        words.sort_unstable();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, words.join("\n") + "\n")?;
+        Ok(())
+    }
+
     /// 获取热词列表
     pub fn get_hotwords(&self) -> &HashMap<String, f32> {
         &self.hotwords
@@ -123,9 +174,36 @@ impl Default for HotwordsEngine {
     }
 }
 
+/// 生成的热词文件路径（无法获取缓存目录时为 None）
+pub fn generated_hotwords_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("vinput").join("hotwords.txt"))
+}
+
+/// 把配置与学到的热词写入 `path`，并设为识别器的热词文件
+///
+/// `user_file` 为配置中原有的 `[asr] hotwords_file`，其中的词一并写入。
+/// 没有任何热词时清空 `asr.hotwords_file`。返回写入的热词数。
+pub fn apply_hotwords_file(
+    asr: &mut OnlineRecognizerConfig,
+    user_file: Option<&Path>,
+    config: &HotwordsConfig,
+    learned: &[HotwordEntry],
+    path: &Path,
+) -> VInputResult<usize> {
+    let engine = HotwordsEngine::from_config(config, user_file, learned);
+    if engine.count() == 0 {
+        asr.hotwords_file = None;
+        return Ok(0);
+    }
+    engine.write_sherpa_file(path)?;
+    asr.hotwords_file = Some(path.to_string_lossy().into_owned());
+    Ok(engine.count())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hotwords::HotwordGroup;
     use std::fs;
     use std::io::Write;
     use tempfile::NamedTempFile;
@@ -212,6 +290,74 @@ mod tests {
         assert!(engine.add_hotword("word3".to_string(), 2.5).is_err());
     }
 
+    #[test]
+    fn test_merge_learned_keeps_configured_weight() {
+        let mut engine = HotwordsEngine::new();
+        engine.add_hotword("张三".to_string(), 4.0).unwrap();
+        engine.set_max_hotwords(2);
+
+        let learned = vec![
+            HotwordEntry::new("张三".to_string(), 2.0),
+            HotwordEntry::new("李四".to_string(), 2.0),
+            HotwordEntry::new("王五".to_string(), 2.0),
+        ];
+        assert_eq!(engine.merge_learned(&learned), 1);
+        assert_eq!(engine.get_hotwords()["张三"], 4.0);
+        assert_eq!(engine.get_hotwords()["李四"], 2.0);
+    }
+
+    #[test]
+    fn test_apply_hotwords_file_includes_learned_words() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hotwords.txt");
+        let mut config = HotwordsConfig::default();
+        config.words.insert("深度学习".to_string(), 2.8);
+        let learned = vec![HotwordEntry::new("张三丰".to_string(), 2.0)];
+
+        let mut asr = OnlineRecognizerConfig::default();
+        assert_eq!(apply_hotwords_file(&mut asr, None, &config, &learned, &path).unwrap(), 2);
+
+        let file = asr.hotwords_file.expect("应设置热词文件");
+        let content = fs::read_to_string(&file).unwrap();
+        assert_eq!(content.lines().collect::<Vec<_>>(), ["张三丰", "深度学习"]);
+    }
+
+    #[test]
+    fn test_apply_hotwords_file_follows_group_toggle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hotwords.txt");
+        let mut group = HotwordGroup::new("医学");
+        group.words.insert("心肌梗死".to_string(), 3.0);
+        group.enabled = false;
+        let mut config = HotwordsConfig::default();
+        config.words.insert("深度学习".to_string(), 2.8);
+        config.groups.push(group);
+
+        let mut asr = OnlineRecognizerConfig::default();
+        apply_hotwords_file(&mut asr, None, &config, &[], &path).unwrap();
+        let content = fs::read_to_string(asr.hotwords_file.as_ref().unwrap()).unwrap();
+        assert!(!content.contains("心肌梗死"));
+
+        config.groups[0].enabled = true;
+        apply_hotwords_file(&mut asr, None, &config, &[], &path).unwrap();
+        let content = fs::read_to_string(asr.hotwords_file.as_ref().unwrap()).unwrap();
+        assert!(content.lines().any(|line| line == "心肌梗死"));
+        assert!(content.lines().any(|line| line == "深度学习"));
+    }
+
+    #[test]
+    fn test_apply_hotwords_file_without_words_clears_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut asr = OnlineRecognizerConfig {
+            hotwords_file: Some("/nonexistent/hotwords.txt".to_string()),
+            ..Default::default()
+        };
+        let config = HotwordsConfig::default();
+        let path = dir.path().join("hotwords.txt");
+        assert_eq!(apply_hotwords_file(&mut asr, None, &config, &[], &path).unwrap(), 0);
+        assert!(asr.hotwords_file.is_none());
+    }
+
     #[test]
     fn test_global_weight() {
         let mut engine = HotwordsEngine::new();
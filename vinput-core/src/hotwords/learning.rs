@@ -0,0 +1,448 @@
+//! 热词自动学习
+//!
+//! 用户纠正识别结果是很强的信号：撤销上屏后重新输入、从候选列表选择替代结果，
+//! 或在候选列表中手动「加为热词」。这里从纠正前后的文本中找出被改动的词，
+//! 记入单独的学习文件（默认 `~/.local/share/vinput/learned_hotwords.toml`）：
+//!
+//! ```toml
+//! rejected = ["不再学习的词"]
+//!
+//! [[word]]
+//! word = "深度操作系统"
+//! source = "correction"
+//! weight = 2.5
+//! hits = 2
+//! last_seen = 1760000000
+//! ```
+//!
+//! 权重按半衰期衰减，重复纠正时加强；衰减到热词最小权重以下的词自动删除。
+//! 引擎加载时合并生效的词（手动添加的词，以及纠正次数达到 `min_hits` 的词）。
+//! 每次记录都重新读取文件再写回，设置界面对文件的修改不会被覆盖。
+
+use crate::error::{VInputError, VInputResult};
+use crate::hotwords::parser::HotwordEntry;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use vinput_config::hotwords::HotwordLearningConfig;
+
+/// 热词最小权重（衰减到此以下的词删除）
+const MIN_WEIGHT: f32 = 1.0;
+/// 热词最大权重
+const MAX_WEIGHT: f32 = 5.0;
+/// 学到的词最多字符数（超过视为改写整句而不是纠正某个词）
+const MAX_WORD_CHARS: usize = 12;
+/// 中文词最少字符数（单字作为热词没有意义）
+const MIN_CJK_CHARS: usize = 2;
+
+/// 当前 Unix 时间（秒）
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 学习来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LearnSource {
+    /// 撤销后重新输入 / 从候选列表替换
+    Correction,
+    /// 手动「加为热词」
+    Explicit,
+}
+
+/// 学到的热词
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LearnedHotword {
+    pub word: String,
+    pub source: LearnSource,
+    /// `last_seen` 时的权重（未衰减）
+    pub weight: f32,
+    /// 被纠正 / 添加的次数
+    pub hits: u32,
+    /// 最近一次出现的 Unix 时间（秒）
+    pub last_seen: u64,
+}
+
+impl LearnedHotword {
+    /// 按半衰期衰减后的权重
+    pub fn effective_weight(&self, config: &HotwordLearningConfig, now: u64) -> f32 {
+        let age_days = now.saturating_sub(self.last_seen) as f32 / 86_400.0;
+        self.weight * 0.5f32.powf(age_days / config.half_life_days.max(f32::EPSILON))
+    }
+
+    /// 是否加载到热词引擎
+    pub fn is_active(&self, config: &HotwordLearningConfig) -> bool {
+        self.source == LearnSource::Explicit || self.hits >= config.min_hits
+    }
+}
+
+/// 学习文件内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LearnedHotwords {
+    /// 用户拒绝、不再学习的词
+    #[serde(default)]
+    pub rejected: Vec<String>,
+    #[serde(default, rename = "word")]
+    pub entries: Vec<LearnedHotword>,
+}
+
+impl LearnedHotwords {
+    /// 读取学习文件（不存在时为空）
+    pub fn load(path: &Path) -> VInputResult<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(VInputError::Hotword(format!(
+                    "Failed to read learned hotwords: {}",
+                    e
+                )))
+            }
+        };
+        toml::from_str(&content).map_err(|e| {
+            VInputError::Hotword(format!("Failed to parse learned hotwords: {}", e))
+        })
+    }
+
+    /// 写入学习文件（先写临时文件再改名）
+    pub fn save(&self, path: &Path) -> VInputResult<()> {
+        let content = toml::to_string(self).map_err(|e| {
+            VInputError::Hotword(format!("Failed to serialize learned hotwords: {}", e))
+        })?;
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let tmp = path.with_extension("toml.tmp");
+            std::fs::write(&tmp, content)?;
+            std::fs::rename(&tmp, path)
+        };
+        write().map_err(|e| VInputError::Hotword(format!("Failed to save learned hotwords: {}", e)))
+    }
+
+    pub fn get(&self, word: &str) -> Option<&LearnedHotword> {
+        self.entries.iter().find(|e| e.word == word)
+    }
+
+    /// 记录一次学习，返回更新后的条目（已拒绝或因超出上限被删除时返回 None）
+    ///
+    /// 已有的词在衰减后的权重上加强，手动添加会把来源升级为 `Explicit`。
+    pub fn record(
+        &mut self,
+        word: &str,
+        source: LearnSource,
+        config: &HotwordLearningConfig,
+        now: u64,
+    ) -> Option<&LearnedHotword> {
+        if self.rejected.iter().any(|w| w == word) {
+            return None;
+        }
+        let initial = match source {
+            LearnSource::Correction => config.correction_weight,
+            LearnSource::Explicit => config.explicit_weight,
+        };
+
+        match self.entries.iter_mut().find(|e| e.word == word) {
+            Some(entry) => {
+                let reinforced = entry.effective_weight(config, now) + config.reinforce_step;
+                entry.weight = reinforced.max(initial).clamp(MIN_WEIGHT, MAX_WEIGHT);
+                entry.hits += 1;
+                entry.last_seen = now;
+                if source == LearnSource::Explicit {
+                    entry.source = LearnSource::Explicit;
+                }
+            }
+            None => self.entries.push(LearnedHotword {
+                word: word.to_string(),
+                source,
+                weight: initial.clamp(MIN_WEIGHT, MAX_WEIGHT),
+                hits: 1,
+                last_seen: now,
+            }),
+        }
+        self.prune(config, now);
+        self.get(word)
+    }
+
+    /// 删除衰减到最小权重以下的词，超出上限时删除权重最低的词
+    pub fn prune(&mut self, config: &HotwordLearningConfig, now: u64) {
+        self.entries.retain(|e| e.effective_weight(config, now) >= MIN_WEIGHT);
+        if self.entries.len() > config.max_entries {
+            self.entries.sort_by(|a, b| {
+                b.effective_weight(config, now)
+                    .total_cmp(&a.effective_weight(config, now))
+            });
+            self.entries.truncate(config.max_entries);
+        }
+    }
+
+    /// 删除学到的词
+    pub fn remove(&mut self, word: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| e.word != word);
+        self.entries.len() != before
+    }
+
+    /// 拒绝学到的词：删除并不再学习
+    pub fn reject(&mut self, word: &str) {
+        self.remove(word);
+        if !self.rejected.iter().any(|w| w == word) {
+            self.rejected.push(word.to_string());
+        }
+    }
+
+    /// 加载到热词引擎的词（衰减后的权重）
+    pub fn active(&self, config: &HotwordLearningConfig, now: u64) -> Vec<HotwordEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.is_active(config))
+            .map(|e| (e, e.effective_weight(config, now)))
+            .filter(|(_, weight)| *weight >= MIN_WEIGHT)
+            .map(|(e, weight)| HotwordEntry::new(e.word.clone(), weight.min(MAX_WEIGHT)))
+            .collect()
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' | '\u{F900}'..='\u{FAFF}')
+}
+
+/// 词的组成字符（中文、字母、数字）
+fn is_word_char(c: char) -> bool {
+    is_cjk(c) || c.is_alphanumeric()
+}
+
+/// 从纠正前后的文本中找出被纠正的词
+///
+/// 去掉公共前后缀得到改动部分，英文扩展到整个单词，中文不足两字时
+/// 向两侧补齐到所在短语（标点、空白为界）；改动跨越多个短语、过长或
+/// 只是删除时返回 None。
+pub fn correction_word(original: &str, corrected: &str) -> Option<String> {
+    let original: Vec<char> = original.trim().chars().collect();
+    let corrected: Vec<char> = corrected.trim().chars().collect();
+    if corrected.is_empty() || original == corrected {
+        return None;
+    }
+
+    let prefix = original.iter().zip(&corrected).take_while(|(a, b)| a == b).count();
+    let max_suffix = original.len().min(corrected.len()) - prefix;
+    let suffix = original
+        .iter()
+        .rev()
+        .zip(corrected.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let (mut start, mut end) = (prefix, corrected.len() - suffix);
+    // 只删除了文字
+    if start >= end {
+        return None;
+    }
+
+    let ascii_word = |c: char| c.is_ascii_alphanumeric();
+    if corrected[start..end].iter().all(|&c| ascii_word(c)) {
+        // 英文扩展到整个单词
+        while start > 0 && ascii_word(corrected[start - 1]) {
+            start -= 1;
+        }
+        while end < corrected.len() && ascii_word(corrected[end]) {
+            end += 1;
+        }
+    } else {
+        // 中文向两侧补齐，不越过标点与空白
+        while end - start < MIN_CJK_CHARS {
+            if start > 0 && is_word_char(corrected[start - 1]) {
+                start -= 1;
+            } else if end < corrected.len() && is_word_char(corrected[end]) {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    let word: String = corrected[start..end].iter().collect();
+    let word = word.trim_matches(|c: char| !is_word_char(c));
+    let chars = word.chars().count();
+    let valid = !word.is_empty()
+        && chars <= MAX_WORD_CHARS
+        && word.chars().all(|c| is_word_char(c) || c == ' ')
+        && (chars >= MIN_CJK_CHARS || !word.chars().any(is_cjk));
+    valid.then(|| word.to_string())
+}
+
+/// 观察用户纠正并记录学到的词
+pub struct HotwordLearner {
+    config: HotwordLearningConfig,
+    path: PathBuf,
+    /// 最近一次撤销的文本及时间
+    pending_undo: Option<(String, Instant)>,
+}
+
+impl HotwordLearner {
+    /// 未启用自动学习或无法确定学习文件路径时返回 None
+    pub fn new(config: &HotwordLearningConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        Some(Self::with_path(config.clone(), config.file_path()?))
+    }
+
+    pub fn with_path(config: HotwordLearningConfig, path: PathBuf) -> Self {
+        Self {
+            config,
+            path,
+            pending_undo: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 读取学习文件中当前生效的词（读取失败时为空）
+    pub fn active_hotwords(&self) -> Vec<HotwordEntry> {
+        match LearnedHotwords::load(&self.path) {
+            Ok(learned) => learned.active(&self.config, unix_now()),
+            Err(e) => {
+                tracing::warn!("读取学习的热词失败: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// 用户撤销了上屏文本，等待随后重新输入的文本
+    pub fn note_undo(&mut self, text: &str) {
+        self.pending_undo = Some((text.to_string(), Instant::now()));
+    }
+
+    /// 用户在撤销的位置重新输入了文本
+    pub fn observe_retype(&mut self, typed: &str) -> Option<String> {
+        let (original, at) = self.pending_undo.take()?;
+        let window = Duration::from_secs(self.config.correction_window_secs);
+        if at.elapsed() > window {
+            tracing::debug!("重新输入超出纠错时间窗口，忽略");
+            return None;
+        }
+        self.observe_replacement(&original, typed)
+    }
+
+    /// 识别结果被替换为另一段文本（重新输入或选择候选）
+    pub fn observe_replacement(&mut self, original: &str, replacement: &str) -> Option<String> {
+        let word = correction_word(original, replacement)?;
+        self.record(&word, LearnSource::Correction)
+    }
+
+    /// 用户手动「加为热词」
+    pub fn add_explicit(&mut self, text: &str) -> Option<String> {
+        let word = text.trim_matches(|c: char| !is_word_char(c));
+        if word.is_empty() || word.chars().count() > MAX_WORD_CHARS {
+            tracing::warn!("无法加为热词（为空或过长）: {}", text);
+            return None;
+        }
+        self.record(word, LearnSource::Explicit)
+    }
+
+    fn record(&self, word: &str, source: LearnSource) -> Option<String> {
+        let result = LearnedHotwords::load(&self.path).and_then(|mut learned| {
+            let recorded = learned
+                .record(word, source, &self.config, unix_now())
+                .map(|entry| (entry.weight, entry.hits));
+            learned.save(&self.path)?;
+            Ok(recorded)
+        });
+        match result {
+            Ok(Some((weight, hits))) => {
+                tracing::info!("📚 学到热词: {} ({:?}, 权重 {:.1}, {} 次)", word, source, weight, hits);
+                Some(word.to_string())
+            }
+            Ok(None) => {
+                tracing::debug!("热词已被拒绝或超出学习上限: {}", word);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("记录学到的热词失败: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    #[test]
+    fn test_correction_word() {
+        assert_eq!(correction_word("我在用深度操做系统。", "我在用深度操作系统。"), Some("操作".to_string()));
+        assert_eq!(correction_word("请联系张山，谢谢", "请联系张三，谢谢"), Some("张三".to_string()));
+        assert_eq!(correction_word("I use rest daily", "I use Rust daily"), Some("Rust".to_string()));
+        assert_eq!(correction_word("今天天气很好", "今天很好"), None);
+        assert_eq!(correction_word("好的", "好的"), None);
+        assert_eq!(correction_word("一", "这是一段完全重新写过的很长的句子内容"), None);
+    }
+
+    #[test]
+    fn test_record_reinforces_and_decays() {
+        let config = HotwordLearningConfig::default();
+        let mut learned = LearnedHotwords::default();
+
+        let entry = learned.record("张三", LearnSource::Correction, &config, 0).unwrap();
+        assert_eq!((entry.weight, entry.hits), (2.0, 1));
+        assert!(learned.active(&config, 0).is_empty());
+
+        let entry = learned.record("张三", LearnSource::Correction, &config, 0).unwrap();
+        assert_eq!((entry.weight, entry.hits), (2.5, 2));
+        assert_eq!(learned.active(&config, 0)[0].weight, 2.5);
+
+        // 一个半衰期后减半
+        let later = 30 * DAY;
+        let weight = learned.get("张三").unwrap().effective_weight(&config, later);
+        assert!((weight - 1.25).abs() < 1e-4);
+
+        // 衰减到 1.0 以下后删除
+        learned.prune(&config, 60 * DAY);
+        assert!(learned.entries.is_empty());
+    }
+
+    #[test]
+    fn test_explicit_and_rejected() {
+        let config = HotwordLearningConfig::default();
+        let mut learned = LearnedHotwords::default();
+
+        learned.record("深度", LearnSource::Correction, &config, 0);
+        let entry = learned.record("深度", LearnSource::Explicit, &config, 0).unwrap();
+        assert_eq!(entry.source, LearnSource::Explicit);
+        assert_eq!(entry.weight, 3.0);
+        assert_eq!(learned.active(&config, 0).len(), 1);
+
+        learned.reject("深度");
+        assert!(learned.entries.is_empty());
+        assert!(learned.record("深度", LearnSource::Explicit, &config, 0).is_none());
+    }
+
+    #[test]
+    fn test_learner_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("learned_hotwords.toml");
+        let config = HotwordLearningConfig { min_hits: 1, ..Default::default() };
+        let mut learner = HotwordLearner::with_path(config, path.clone());
+
+        // 没有撤销时重新输入不算纠错
+        assert_eq!(learner.observe_retype("张三"), None);
+
+        learner.note_undo("请联系张山");
+        assert_eq!(learner.observe_retype("请联系张三"), Some("张三".to_string()));
+        assert_eq!(learner.add_explicit("「Kubernetes」"), Some("Kubernetes".to_string()));
+
+        let words: Vec<String> = learner.active_hotwords().into_iter().map(|e| e.word).collect();
+        assert_eq!(words, vec!["张三", "Kubernetes"]);
+        assert_eq!(LearnedHotwords::load(&path).unwrap().entries.len(), 2);
+    }
+}
@@ -5,6 +5,7 @@
 use std::collections::HashMap;
 use std::path::Path;
 use crate::error::{VInputError, VInputResult};
+use crate::hotwords::vocab::TokenVocabulary;
 
 /// 热词条目
 #[derive(Debug, Clone)]
@@ -25,6 +26,27 @@ impl HotwordEntry {
     pub fn validate_weight(weight: f32) -> bool {
         weight >= 1.0 && weight <= 5.0
     }
+
+    /// 检查条目，返回问题说明（为空表示没有问题）
+    ///
+    /// 提供模型词表时同时检查词表外的字符。
+    pub fn validate(&self, vocab: Option<&TokenVocabulary>) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.word.trim().is_empty() {
+            problems.push("热词不能为空".to_string());
+        }
+        if !Self::validate_weight(self.weight) {
+            problems.push(format!("权重 {} 超出范围 (1.0-5.0)", self.weight));
+        }
+        if let Some(vocab) = vocab {
+            let oov = vocab.oov_chars(&self.word);
+            if !oov.is_empty() {
+                let chars: String = oov.into_iter().collect();
+                problems.push(format!("模型词表中没有「{}」，无法识别出这个词", chars));
+            }
+        }
+        problems
+    }
 }
 
 /// 热词文件解析器
@@ -246,6 +268,19 @@ Transformer
         assert!(!HotwordEntry::validate_weight(5.1));
     }
 
+    #[test]
+    fn test_validate_entry() {
+        let vocab = TokenVocabulary::parse("<blank> 0\n深 1\n度 2\n");
+        assert!(HotwordEntry::new("深度".to_string(), 2.5).validate(Some(&vocab)).is_empty());
+
+        let problems = HotwordEntry::new("深度学习".to_string(), 6.0).validate(Some(&vocab));
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("权重"));
+        assert!(problems[1].contains("学习"));
+
+        assert!(HotwordEntry::new("深度学习".to_string(), 2.5).validate(None).is_empty());
+    }
+
     #[test]
     fn test_hotword_entry() {
         let entry = HotwordEntry::new("深度学习".to_string(), 2.8);
@@ -0,0 +1,497 @@
+//! 热词导入 / 导出
+//!
+//! 支持三种文件格式：
+//! - txt：每行 `词汇 [权重]`，`#` 开头为注释
+//! - csv：每行 `词汇,权重,分组`，权重与分组可省略，可带表头
+//! - toml：`[分组]` 表内 `词汇 = 权重`（与 `HotwordsParser::parse_toml` 相同），
+//!   `[default]` 与表外的词为未分组热词
+//!
+//! 导入分两步：先解析为 `ImportPreview`（无法导入的行记为 `ImportIssue`），
+//! 设置界面展示预览与冲突后再调用 `ImportPreview::apply` 写入配置。
+
+use crate::error::{VInputError, VInputResult};
+use crate::hotwords::parser::HotwordEntry;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use vinput_config::hotwords::{HotwordGroup, HotwordsConfig};
+
+/// 未指定权重时的默认权重
+pub const DEFAULT_WEIGHT: f32 = 2.5;
+/// TOML 中表示未分组热词的表名
+const DEFAULT_GROUP: &str = "default";
+
+/// 热词文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotwordFileFormat {
+    Txt,
+    Csv,
+    Toml,
+}
+
+impl HotwordFileFormat {
+    pub const ALL: [HotwordFileFormat; 3] = [Self::Txt, Self::Csv, Self::Toml];
+
+    /// 按扩展名判断格式（未知扩展名按 txt 处理）
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("csv") => Self::Csv,
+            Some("toml") => Self::Toml,
+            _ => Self::Txt,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Txt => "txt",
+            Self::Csv => "csv",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// 待导入的热词
+#[derive(Debug, Clone)]
+pub struct ImportedHotword {
+    pub entry: HotwordEntry,
+    /// 文件中指定的分组（None 时导入到用户选择的分组）
+    pub group: Option<String>,
+    /// 所在行号（从 1 开始）
+    pub line: usize,
+}
+
+/// 无法导入的行
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportIssue {
+    pub line: usize,
+    pub text: String,
+    pub reason: String,
+}
+
+/// 与已有热词的冲突
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotwordConflict {
+    pub word: String,
+    /// 已有热词所在分组（None 为未分组）
+    pub existing_group: Option<String>,
+    pub existing_weight: f32,
+    /// 导入到的分组
+    pub group: Option<String>,
+    pub weight: f32,
+}
+
+impl HotwordConflict {
+    /// 导入到同一分组（导入会改写权重）
+    pub fn same_group(&self) -> bool {
+        self.existing_group == self.group
+    }
+}
+
+/// 导入结果统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// 新增的热词
+    pub added: usize,
+    /// 改写权重的热词
+    pub updated: usize,
+    /// 已存在且未覆盖的热词
+    pub skipped: usize,
+}
+
+/// 导入预览
+#[derive(Debug, Clone)]
+pub struct ImportPreview {
+    pub format: HotwordFileFormat,
+    pub hotwords: Vec<ImportedHotword>,
+    pub issues: Vec<ImportIssue>,
+}
+
+impl ImportPreview {
+    /// 读取并解析热词文件（格式按扩展名判断）
+    pub fn load(path: &Path) -> VInputResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            VInputError::Hotword(format!("Failed to read hotwords file: {}", e))
+        })?;
+        Self::parse(&content, HotwordFileFormat::from_path(path))
+    }
+
+    /// 解析热词文件内容
+    ///
+    /// 单行错误（权重无效、超出范围、文件内重复）记入 `issues` 后继续，
+    /// 只有 TOML 语法错误会使整个文件失败。
+    pub fn parse(content: &str, format: HotwordFileFormat) -> VInputResult<Self> {
+        let mut preview = Self {
+            format,
+            hotwords: Vec::new(),
+            issues: Vec::new(),
+        };
+        match format {
+            HotwordFileFormat::Txt => preview.parse_txt(content),
+            HotwordFileFormat::Csv => preview.parse_csv(content),
+            HotwordFileFormat::Toml => preview.parse_toml(content)?,
+        }
+        Ok(preview)
+    }
+
+    fn parse_txt(&mut self, content: &str) {
+        for (i, raw) in content.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let word = parts.next().unwrap_or_default();
+            self.push(i + 1, raw, word, parts.next(), None);
+        }
+    }
+
+    fn parse_csv(&mut self, content: &str) {
+        for (i, raw) in content.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = split_csv_line(line);
+            let word = fields.first().map(|f| f.trim()).unwrap_or_default();
+            let weight = fields.get(1).map(|f| f.trim()).filter(|f| !f.is_empty());
+            let group = fields.get(2).map(|f| f.trim()).filter(|f| !f.is_empty());
+
+            // 表头
+            let is_header = self.hotwords.is_empty()
+                && self.issues.is_empty()
+                && matches!(word.to_lowercase().as_str(), "word" | "词汇" | "热词")
+                && weight.is_none_or(|w| w.parse::<f32>().is_err());
+            if is_header {
+                continue;
+            }
+            self.push(i + 1, raw, word, weight, group.map(str::to_string));
+        }
+    }
+
+    fn parse_toml(&mut self, content: &str) -> VInputResult<()> {
+        let table: toml::Table = content.parse().map_err(|e| {
+            VInputError::Hotword(format!("Failed to parse TOML: {}", e))
+        })?;
+
+        let mut line = 0;
+        for (key, value) in &table {
+            match value {
+                toml::Value::Table(words) => {
+                    let group = (key != DEFAULT_GROUP).then(|| key.clone());
+                    for (word, weight) in words {
+                        line += 1;
+                        self.push_toml(line, word, weight, group.clone());
+                    }
+                }
+                weight => {
+                    line += 1;
+                    self.push_toml(line, key, weight, None);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// TOML 没有行号信息，`line` 为条目序号
+    fn push_toml(&mut self, line: usize, word: &str, weight: &toml::Value, group: Option<String>) {
+        let text = format!("{} = {}", word, weight);
+        match weight.as_float().or_else(|| weight.as_integer().map(|w| w as f64)) {
+            Some(w) => self.push(line, &text, word, Some(&w.to_string()), group),
+            None => self.issue(line, &text, "权重必须是数字"),
+        }
+    }
+
+    fn push(&mut self, line: usize, text: &str, word: &str, weight: Option<&str>, group: Option<String>) {
+        if word.is_empty() {
+            self.issue(line, text, "缺少词汇");
+            return;
+        }
+        let weight = match weight.map(str::parse::<f32>) {
+            None => DEFAULT_WEIGHT,
+            Some(Ok(w)) => w,
+            Some(Err(_)) => {
+                self.issue(line, text, "权重不是数字");
+                return;
+            }
+        };
+        if !HotwordEntry::validate_weight(weight) {
+            self.issue(line, text, &format!("权重 {} 超出范围 (1.0-5.0)", weight));
+            return;
+        }
+        if let Some(first) = self.hotwords.iter().find(|h| h.entry.word == word && h.group == group) {
+            let reason = format!("与第 {} 行重复", first.line);
+            self.issue(line, text, &reason);
+            return;
+        }
+        self.hotwords.push(ImportedHotword {
+            entry: HotwordEntry::new(word.to_string(), weight),
+            group,
+            line,
+        });
+    }
+
+    fn issue(&mut self, line: usize, text: &str, reason: &str) {
+        self.issues.push(ImportIssue {
+            line,
+            text: text.trim().to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// 文件中出现的分组（按出现顺序，None 为未指定）
+    pub fn groups(&self) -> Vec<Option<&str>> {
+        let mut groups = Vec::new();
+        for hotword in &self.hotwords {
+            let group = hotword.group.as_deref();
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        groups
+    }
+
+    /// 热词导入到的分组：文件指定的分组优先，否则为 `target`
+    fn destination<'a>(hotword: &'a ImportedHotword, target: Option<&'a str>) -> Option<&'a str> {
+        hotword.group.as_deref().or(target)
+    }
+
+    /// 与已有热词的冲突：同一分组内权重不同，或词已在其他分组中
+    pub fn conflicts(&self, config: &HotwordsConfig, target: Option<&str>) -> Vec<HotwordConflict> {
+        let mut conflicts = Vec::new();
+        for hotword in &self.hotwords {
+            let group = Self::destination(hotword, target);
+            for (existing_group, existing_weight) in config.locate(&hotword.entry.word) {
+                if existing_group == group && existing_weight == hotword.entry.weight {
+                    continue;
+                }
+                conflicts.push(HotwordConflict {
+                    word: hotword.entry.word.clone(),
+                    existing_group: existing_group.map(str::to_string),
+                    existing_weight,
+                    group: group.map(str::to_string),
+                    weight: hotword.entry.weight,
+                });
+            }
+        }
+        conflicts
+    }
+
+    /// 写入配置，不存在的分组自动创建
+    ///
+    /// 目标分组中已有同名热词时，`overwrite` 为 true 则改写权重，否则保留原权重。
+    pub fn apply(&self, config: &mut HotwordsConfig, target: Option<&str>, overwrite: bool) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+        for hotword in &self.hotwords {
+            let group = Self::destination(hotword, target);
+            if let Some(name) = group {
+                if config.group(name).is_none() {
+                    config.groups.push(HotwordGroup::new(name));
+                }
+            }
+            let Some(words) = config.words_mut(group) else {
+                continue;
+            };
+            let entry = &hotword.entry;
+            match words.get_mut(&entry.word) {
+                None => {
+                    words.insert(entry.word.clone(), entry.weight);
+                    summary.added += 1;
+                }
+                Some(weight) if *weight == entry.weight => {}
+                Some(weight) if overwrite => {
+                    *weight = entry.weight;
+                    summary.updated += 1;
+                }
+                Some(_) => summary.skipped += 1,
+            }
+        }
+        summary
+    }
+}
+
+/// 按 CSV 规则拆分一行（支持双引号包裹与 `""` 转义）
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn sorted(words: &HashMap<String, f32>) -> Vec<(&String, &f32)> {
+    let mut words: Vec<_> = words.iter().collect();
+    words.sort_by(|a, b| a.0.cmp(b.0));
+    words
+}
+
+/// 导出全部热词（未分组热词在前，分组按配置顺序，组内按词排序）
+///
+/// txt 不含分组信息，分组名写为注释；停用分组也会导出。
+pub fn export(config: &HotwordsConfig, format: HotwordFileFormat) -> String {
+    let sections = std::iter::once((None, &config.words))
+        .chain(config.groups.iter().map(|g| (Some(g.name.as_str()), &g.words)));
+
+    let mut out = String::new();
+    match format {
+        HotwordFileFormat::Txt => {
+            out.push_str("# V-Input 热词导出  格式: 词 权重\n");
+            for (group, words) in sections {
+                if let Some(group) = group.filter(|_| !words.is_empty()) {
+                    let _ = writeln!(out, "\n# 分组：{}", group);
+                }
+                for (word, weight) in sorted(words) {
+                    let _ = writeln!(out, "{} {}", word, weight);
+                }
+            }
+        }
+        HotwordFileFormat::Csv => {
+            out.push_str("词汇,权重,分组\n");
+            for (group, words) in sections {
+                for (word, weight) in sorted(words) {
+                    let _ = writeln!(out, "{},{},{}", csv_field(word), weight, csv_field(group.unwrap_or_default()));
+                }
+            }
+        }
+        HotwordFileFormat::Toml => {
+            out.push_str("# V-Input 热词导出\n");
+            for (group, words) in sections {
+                if group.is_none() && words.is_empty() {
+                    continue;
+                }
+                let name = toml_key(group.unwrap_or(DEFAULT_GROUP));
+                let _ = writeln!(out, "\n[{}]", name);
+                for (word, weight) in sorted(words) {
+                    let _ = writeln!(out, "{} = {:?}", toml_key(word), weight);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// TOML 键一律加引号（热词多为中文）
+fn toml_key(key: &str) -> String {
+    toml::Value::String(key.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> HotwordsConfig {
+        let mut group = HotwordGroup::new("同事");
+        group.words.insert("张三".to_string(), 3.0);
+        let mut config = HotwordsConfig::default();
+        config.words.insert("深度学习".to_string(), 2.5);
+        config.groups.push(group);
+        config
+    }
+
+    #[test]
+    fn test_parse_txt_collects_issues() {
+        let preview = ImportPreview::parse(
+            "# 注释\n深度学习 2.8\n人工智能\n神经网络 abc\n机器学习 9\n深度学习 3.0\n",
+            HotwordFileFormat::Txt,
+        )
+        .unwrap();
+
+        assert_eq!(preview.hotwords.len(), 2);
+        assert_eq!(preview.hotwords[1].entry.weight, DEFAULT_WEIGHT);
+        let lines: Vec<usize> = preview.issues.iter().map(|i| i.line).collect();
+        assert_eq!(lines, vec![4, 5, 6]);
+        assert!(preview.issues[2].reason.contains("第 2 行"));
+    }
+
+    #[test]
+    fn test_parse_csv_with_header_and_quotes() {
+        let preview = ImportPreview::parse(
+            "词汇,权重,分组\n深度学习,2.8,\n\"Hello, World\",3,短语\n张三,,同事\n",
+            HotwordFileFormat::Csv,
+        )
+        .unwrap();
+
+        assert!(preview.issues.is_empty());
+        let words: Vec<&str> = preview.hotwords.iter().map(|h| h.entry.word.as_str()).collect();
+        assert_eq!(words, vec!["深度学习", "Hello, World", "张三"]);
+        assert_eq!(preview.groups(), vec![None, Some("短语"), Some("同事")]);
+        assert_eq!(preview.hotwords[2].entry.weight, DEFAULT_WEIGHT);
+    }
+
+    #[test]
+    fn test_conflicts_and_apply() {
+        let mut config = config();
+        let preview = ImportPreview::parse("深度学习 2.5\n张三 4.0\n李四 3.0\n", HotwordFileFormat::Txt).unwrap();
+
+        // 导入到「同事」：深度学习已在未分组中，张三权重不同
+        let conflicts = preview.conflicts(&config, Some("同事"));
+        assert_eq!(conflicts.len(), 2);
+        assert!(!conflicts[0].same_group());
+        assert!(conflicts[1].same_group());
+        assert_eq!(conflicts[1].existing_weight, 3.0);
+
+        let summary = preview.apply(&mut config, Some("同事"), false);
+        assert_eq!(summary, ImportSummary { added: 2, updated: 0, skipped: 1 });
+        assert_eq!(config.group("同事").unwrap().words["张三"], 3.0);
+
+        let summary = preview.apply(&mut config, Some("同事"), true);
+        assert_eq!(summary, ImportSummary { added: 0, updated: 1, skipped: 0 });
+        assert_eq!(config.group("同事").unwrap().words["张三"], 4.0);
+    }
+
+    #[test]
+    fn test_apply_creates_groups() {
+        let mut config = HotwordsConfig::default();
+        let preview = ImportPreview::parse("张三,3,同事\n深度学习,2.5\n", HotwordFileFormat::Csv).unwrap();
+        preview.apply(&mut config, None, false);
+
+        assert_eq!(config.words.len(), 1);
+        assert_eq!(config.group("同事").unwrap().words["张三"], 3.0);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let config = config();
+        for format in HotwordFileFormat::ALL {
+            let exported = export(&config, format);
+            let preview = ImportPreview::parse(&exported, format).unwrap();
+            assert!(preview.issues.is_empty(), "{:?}: {:?}", format, preview.issues);
+
+            let mut imported = HotwordsConfig::default();
+            preview.apply(&mut imported, None, false);
+            if format == HotwordFileFormat::Txt {
+                assert_eq!(imported.words.len(), 2);
+            } else {
+                assert_eq!(imported.words, config.words);
+                assert_eq!(imported.groups, config.groups);
+            }
+        }
+    }
+
+    #[test]
+    fn test_toml_export_is_parser_compatible() {
+        let exported = export(&config(), HotwordFileFormat::Toml);
+        let groups = crate::hotwords::HotwordsParser::parse_toml(&exported).unwrap();
+        assert_eq!(groups["default"]["深度学习"], 2.5);
+        assert_eq!(groups["同事"]["张三"], 3.0);
+    }
+}
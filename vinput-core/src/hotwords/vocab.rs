@@ -0,0 +1,86 @@
+//! 模型词表检查
+//!
+//! 热词中含有模型 `tokens.txt` 没有的字符时，识别器永远无法输出这个词，
+//! 热词加分也不会生效。这里按字符检查：中文按单字建模，英文 token 为小写子词
+//! （`and@@` 表示词内片段），英文字母按小写比较。
+
+use crate::error::{VInputError, VInputResult};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 模型词表中出现过的字符
+#[derive(Debug, Clone, Default)]
+pub struct TokenVocabulary {
+    chars: HashSet<char>,
+}
+
+impl TokenVocabulary {
+    /// 解析 sherpa-onnx 的 `tokens.txt`（每行 `token id`）
+    pub fn parse(content: &str) -> Self {
+        let mut chars = HashSet::new();
+        for line in content.lines() {
+            let Some(token) = line.split_whitespace().next() else {
+                continue;
+            };
+            // <blank> / <s> / <unk> 等特殊 token
+            if token.starts_with('<') && token.ends_with('>') {
+                continue;
+            }
+            let token = token.trim_end_matches("@@").trim_start_matches('▁');
+            chars.extend(token.chars().flat_map(char::to_lowercase));
+        }
+        Self { chars }
+    }
+
+    /// 读取模型目录下的 `tokens.txt`
+    pub fn load(model_dir: &Path) -> VInputResult<Self> {
+        let path = model_dir.join("tokens.txt");
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            VInputError::Hotword(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(Self::parse(&content))
+    }
+
+    /// 词表中的字符数
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// 词中不在词表内的字符（去重，按出现顺序；空白不计）
+    pub fn oov_chars(&self, word: &str) -> Vec<char> {
+        let mut oov = Vec::new();
+        for c in word.chars().filter(|c| !c.is_whitespace()) {
+            let known = c.to_lowercase().all(|l| self.chars.contains(&l));
+            if !known && !oov.contains(&c) {
+                oov.push(c);
+            }
+        }
+        oov
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKENS: &str = "<blank> 0\n<s> 1\n</s> 2\nand@@ 3\n深 4\n度 5\n学 6\n习 7\nrust 8\n";
+
+    #[test]
+    fn test_parse_skips_special_tokens() {
+        let vocab = TokenVocabulary::parse(TOKENS);
+        assert!(vocab.oov_chars("深度学习").is_empty());
+        assert_eq!(vocab.oov_chars("<s>"), vec!['<', '>']);
+    }
+
+    #[test]
+    fn test_oov_chars() {
+        let vocab = TokenVocabulary::parse(TOKENS);
+        assert!(vocab.oov_chars("Rust and").is_empty());
+        assert_eq!(vocab.oov_chars("深度学习框架框"), vec!['框', '架']);
+        assert_eq!(vocab.oov_chars("Rust2"), vec!['2']);
+    }
+}
@@ -0,0 +1,134 @@
+//! 唤醒词监听线程
+//!
+//! 未录音时从 Ring Buffer 读取音频，经能量门限过滤后送入关键词检测器；
+//! 检测到唤醒词后回调一次并退出（录音结束后由引擎重新启动监听）。
+//! 安静时不做任何 KWS 解码，空闲 CPU 占用仅为能量计算。
+
+use crate::audio::AudioRingConsumer;
+use crate::error::VInputResult;
+use crate::kws::spotter::KeywordSpotter;
+use crate::vad::{EnergyGate, EnergyGateConfig, VadConfig};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// 512 samples = 32ms @ 16kHz
+const FRAME_SIZE: usize = 512;
+/// 门限打开前保留的音频帧数（唤醒词开头常低于门限，10 帧 = 320ms）
+const PRE_ROLL_FRAMES: usize = 10;
+/// 能量回落后继续送入 KWS 的帧数（覆盖字间停顿，30 帧 = 960ms）
+const HANGOVER_FRAMES: u32 = 30;
+
+/// 唤醒词监听器（Drop 时停止线程）
+pub struct WakeWordListener {
+    stop_signal: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl WakeWordListener {
+    /// 启动监听线程，检测到唤醒词时以其显示文本调用 `on_detected`
+    pub fn spawn(
+        spotter: Arc<KeywordSpotter>,
+        consumer: AudioRingConsumer,
+        gate_noise_multiplier: f32,
+        on_detected: impl FnOnce(String) + Send + 'static,
+    ) -> VInputResult<Self> {
+        let stop_signal = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop_signal);
+
+        let gate_config = EnergyGateConfig {
+            noise_multiplier: gate_noise_multiplier,
+            ..VadConfig::hands_free_default().energy_gate
+        };
+
+        let thread = thread::Builder::new()
+            .name("vinput-kws".to_string())
+            .spawn(move || {
+                Self::listen_loop(&spotter, consumer, gate_config, &thread_stop, on_detected);
+            })?;
+
+        Ok(Self {
+            stop_signal,
+            thread: Some(thread),
+        })
+    }
+
+    /// 停止监听并等待线程退出
+    pub fn stop(&mut self) {
+        self.stop_signal.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+    }
+
+    /// 监听线程是否已结束（检测到唤醒词或出错）
+    pub fn is_finished(&self) -> bool {
+        self.thread.as_ref().is_none_or(|t| t.is_finished())
+    }
+
+    fn listen_loop(
+        spotter: &KeywordSpotter,
+        mut consumer: AudioRingConsumer,
+        gate_config: EnergyGateConfig,
+        stop_signal: &AtomicBool,
+        on_detected: impl FnOnce(String),
+    ) {
+        let mut stream = match spotter.create_stream() {
+            Ok(stream) => stream,
+            Err(e) => {
+                tracing::error!("创建 KWS 流失败: {}", e);
+                return;
+            }
+        };
+
+        tracing::info!("👂 唤醒词监听启动");
+        let mut gate = EnergyGate::new(gate_config);
+        let mut frame = vec![0.0f32; FRAME_SIZE];
+        let mut pre_roll: VecDeque<Vec<f32>> = VecDeque::with_capacity(PRE_ROLL_FRAMES);
+        let mut hangover: u32 = 0;
+
+        while !stop_signal.load(Ordering::Relaxed) {
+            // 只读取完整帧，避免丢弃不足一帧的样本
+            if consumer.available_samples() < FRAME_SIZE {
+                thread::sleep(Duration::from_millis(20));
+                continue;
+            }
+            consumer.read(&mut frame);
+
+            if gate.process(&frame) {
+                if hangover == 0 {
+                    // 门限刚打开：先补送之前的静音段，保留唤醒词开头
+                    for buffered in pre_roll.drain(..) {
+                        stream.accept_waveform(&buffered);
+                    }
+                }
+                hangover = HANGOVER_FRAMES;
+            } else if hangover == 0 {
+                if pre_roll.len() == PRE_ROLL_FRAMES {
+                    pre_roll.pop_front();
+                }
+                pre_roll.push_back(frame.clone());
+                continue;
+            } else {
+                hangover -= 1;
+            }
+
+            stream.accept_waveform(&frame);
+            if let Some(keyword) = stream.detect() {
+                tracing::info!("🔔 检测到唤醒词: {}", keyword);
+                on_detected(keyword);
+                break;
+            }
+        }
+
+        tracing::info!("唤醒词监听退出");
+    }
+}
+
+impl Drop for WakeWordListener {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
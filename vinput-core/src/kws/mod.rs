@@ -0,0 +1,10 @@
+//! KWS (Keyword Spotting) 模块
+//!
+//! 基于 sherpa-onnx 关键词检测器的唤醒词：未录音时低功耗监听，
+//! 检测到唤醒词后通知前端开始录音
+
+pub mod listener;
+pub mod spotter;
+
+pub use listener::WakeWordListener;
+pub use spotter::{KeywordSpotter, KeywordStream, WakeKeyword, WakeWordConfig};
@@ -0,0 +1,202 @@
+//! sherpa-onnx 关键词检测器安全封装
+
+use crate::asr::recognizer::{
+    SherpaOnnxCreateKeywordSpotter, SherpaOnnxCreateKeywordStream, SherpaOnnxDecodeKeywordStream,
+    SherpaOnnxDestroyKeywordResult, SherpaOnnxDestroyKeywordSpotter, SherpaOnnxDestroyOnlineStream,
+    SherpaOnnxFeatureConfig, SherpaOnnxGetKeywordResult, SherpaOnnxIsKeywordStreamReady,
+    SherpaOnnxKeywordSpotter, SherpaOnnxKeywordSpotterConfig, SherpaOnnxOnlineModelConfig,
+    SherpaOnnxOnlineStream, SherpaOnnxOnlineStreamAcceptWaveform,
+    SherpaOnnxOnlineTransducerModelConfig, SherpaOnnxResetKeywordStream,
+};
+use crate::error::{VInputError, VInputResult};
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use std::ptr;
+
+pub use vinput_config::kws::{WakeKeyword, WakeWordConfig};
+
+/// 关键词检测器（线程安全，模型只加载一次）
+pub struct KeywordSpotter {
+    inner: *const SherpaOnnxKeywordSpotter,
+}
+
+// sherpa-onnx 的 keyword spotter 与 recognizer 一样是线程安全的
+unsafe impl Send for KeywordSpotter {}
+unsafe impl Sync for KeywordSpotter {}
+
+impl KeywordSpotter {
+    /// 加载 KWS 模型
+    pub fn new(config: &WakeWordConfig) -> VInputResult<Self> {
+        let model_dir = Path::new(&config.model_dir);
+        let model_error = |reason: String| VInputError::ModelLoad {
+            path: config.model_dir.clone(),
+            reason,
+        };
+
+        let encoder_path = model_dir.join("encoder.int8.onnx");
+        let decoder_path = model_dir.join("decoder.int8.onnx");
+        let joiner_path = model_dir.join("joiner.int8.onnx");
+        let tokens_path = model_dir.join("tokens.txt");
+        let keywords_path = model_dir.join("keywords.txt");
+
+        for path in [&encoder_path, &decoder_path, &joiner_path, &tokens_path] {
+            if !path.exists() {
+                return Err(model_error(format!("KWS 模型文件不存在: {:?}", path)));
+            }
+        }
+
+        let keywords_buf = config.keywords_buffer();
+        if keywords_buf.is_empty() && !keywords_path.exists() {
+            return Err(model_error("未配置唤醒词且模型目录下没有 keywords.txt".to_string()));
+        }
+
+        let to_cstring = |path: &Path| {
+            CString::new(path.to_string_lossy().as_bytes())
+                .map_err(|e| model_error(format!("Invalid path encoding: {}", e)))
+        };
+        let encoder_cstr = to_cstring(&encoder_path)?;
+        let decoder_cstr = to_cstring(&decoder_path)?;
+        let joiner_cstr = to_cstring(&joiner_path)?;
+        let tokens_cstr = to_cstring(&tokens_path)?;
+        let keywords_file_cstr = to_cstring(&keywords_path)?;
+        let keywords_buf_cstr = CString::new(keywords_buf.as_str())
+            .map_err(|e| model_error(format!("Invalid keywords: {}", e)))?;
+        let provider_cstr = CString::new("cpu").unwrap();
+
+        let model_config = SherpaOnnxOnlineModelConfig {
+            transducer: SherpaOnnxOnlineTransducerModelConfig {
+                encoder: encoder_cstr.as_ptr(),
+                decoder: decoder_cstr.as_ptr(),
+                joiner: joiner_cstr.as_ptr(),
+            },
+            paraformer: unsafe { std::mem::zeroed() },
+            zipformer2_ctc: unsafe { std::mem::zeroed() },
+            tokens: tokens_cstr.as_ptr(),
+            num_threads: 1,
+            provider: provider_cstr.as_ptr(),
+            debug: 0,
+            model_type: ptr::null(),
+            modeling_unit: ptr::null(),
+            bpe_vocab: ptr::null(),
+            tokens_buf: ptr::null(),
+            tokens_buf_size: 0,
+            nemo_ctc: unsafe { std::mem::zeroed() },
+            t_one_ctc: unsafe { std::mem::zeroed() },
+        };
+
+        // 配置了唤醒词时使用内联缓冲区，否则使用模型自带的 keywords.txt
+        let (keywords_file, keywords_buf_ptr, keywords_buf_size) = if keywords_buf.is_empty() {
+            (keywords_file_cstr.as_ptr(), ptr::null(), 0)
+        } else {
+            (ptr::null(), keywords_buf_cstr.as_ptr(), keywords_buf.len() as i32)
+        };
+
+        let spotter_config = SherpaOnnxKeywordSpotterConfig {
+            feat_config: SherpaOnnxFeatureConfig {
+                sample_rate: 16000,
+                feature_dim: 80,
+            },
+            model_config,
+            max_active_paths: 4,
+            num_trailing_blanks: config.num_trailing_blanks,
+            keywords_score: config.keywords_score,
+            keywords_threshold: config.keywords_threshold,
+            keywords_file,
+            keywords_buf: keywords_buf_ptr,
+            keywords_buf_size,
+        };
+
+        tracing::info!("🔍 加载 KWS 模型: {:?}", model_dir);
+        let spotter = unsafe { SherpaOnnxCreateKeywordSpotter(&spotter_config) };
+        if spotter.is_null() {
+            return Err(model_error("Failed to create keyword spotter".to_string()));
+        }
+
+        Ok(Self { inner: spotter })
+    }
+
+    /// 创建新的检测流
+    pub fn create_stream(&self) -> VInputResult<KeywordStream<'_>> {
+        let stream = unsafe { SherpaOnnxCreateKeywordStream(self.inner) };
+        if stream.is_null() {
+            return Err(VInputError::AsrInference(
+                "Failed to create keyword stream".to_string(),
+            ));
+        }
+
+        Ok(KeywordStream {
+            inner: stream,
+            spotter: self,
+        })
+    }
+}
+
+impl Drop for KeywordSpotter {
+    fn drop(&mut self) {
+        if !self.inner.is_null() {
+            unsafe {
+                SherpaOnnxDestroyKeywordSpotter(self.inner);
+            }
+        }
+    }
+}
+
+/// 关键词检测流
+pub struct KeywordStream<'a> {
+    inner: *const SherpaOnnxOnlineStream,
+    spotter: &'a KeywordSpotter,
+}
+
+impl KeywordStream<'_> {
+    /// 输入音频数据（16kHz, 单声道, f32 格式）
+    pub fn accept_waveform(&mut self, samples: &[f32]) {
+        unsafe {
+            SherpaOnnxOnlineStreamAcceptWaveform(
+                self.inner,
+                16000,
+                samples.as_ptr(),
+                samples.len() as i32,
+            );
+        }
+    }
+
+    /// 解码已就绪的帧，检测到关键词时返回其显示文本（随后重置流）
+    pub fn detect(&mut self) -> Option<String> {
+        let spotter = self.spotter.inner;
+        while unsafe { SherpaOnnxIsKeywordStreamReady(spotter, self.inner) } != 0 {
+            unsafe { SherpaOnnxDecodeKeywordStream(spotter, self.inner) };
+
+            let result = unsafe { SherpaOnnxGetKeywordResult(spotter, self.inner) };
+            if result.is_null() {
+                continue;
+            }
+            let keyword = unsafe {
+                let keyword_ptr = (*result).keyword;
+                let keyword = if keyword_ptr.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr(keyword_ptr).to_string_lossy().into_owned()
+                };
+                SherpaOnnxDestroyKeywordResult(result);
+                keyword
+            };
+
+            if !keyword.is_empty() {
+                // 检测到关键词后必须重置，否则会重复触发
+                unsafe { SherpaOnnxResetKeywordStream(spotter, self.inner) };
+                return Some(keyword);
+            }
+        }
+        None
+    }
+}
+
+impl Drop for KeywordStream<'_> {
+    fn drop(&mut self) {
+        if !self.inner.is_null() {
+            unsafe {
+                SherpaOnnxDestroyOnlineStream(self.inner);
+            }
+        }
+    }
+}
@@ -9,13 +9,19 @@ pub mod ffi;
 pub mod audio;
 pub mod vad;
 pub mod asr;
+pub mod kws;
 pub mod streaming;
 pub mod state_machine;
 pub mod endpointing;
+pub mod eval;
 pub mod itn;
 pub mod punctuation;
 pub mod hotwords;
+pub mod metrics;
+pub mod models;
+pub mod recorder;
 pub mod undo;
+pub mod recovery;
 pub mod config;
 pub mod error;
 
@@ -0,0 +1,169 @@
+//! Prometheus 文本格式导出（本地 Unix socket）
+//!
+//! 每个连接返回一次当前快照后关闭。客户端先发送 HTTP 请求（如
+//! `curl --unix-socket <path> http://localhost/metrics`）时按 HTTP 响应返回，
+//! 否则（如 `socat - UNIX-CONNECT:<path>`）直接返回文本。
+
+use super::PipelineMetrics;
+use crate::error::{VInputError, VInputResult};
+use std::io::{Read, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// 等待客户端请求的时长（超时后按纯文本返回）
+const REQUEST_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 指标导出线程（drop 时停止并删除 socket 文件）
+pub struct MetricsExporter {
+    path: PathBuf,
+    quit: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl MetricsExporter {
+    /// 监听 `path` 并在后台线程中响应连接
+    pub fn start(path: &Path, metrics: PipelineMetrics) -> VInputResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // 清理上次遗留的 socket 文件；路径上是其他文件时报错，不删除
+        if let Ok(metadata) = std::fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(VInputError::Generic(format!(
+                    "{} 已存在且不是 socket 文件",
+                    path.display()
+                )));
+            }
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)
+            .map_err(|e| VInputError::Generic(format!("绑定 {} 失败: {}", path.display(), e)))?;
+        listener.set_nonblocking(true)?;
+        tracing::info!("📈 性能指标导出: {}", path.display());
+
+        let quit = Arc::new(AtomicBool::new(false));
+        let thread_quit = Arc::clone(&quit);
+        let thread = thread::Builder::new()
+            .name("vinput-metrics".to_string())
+            .spawn(move || {
+                while !thread_quit.load(Ordering::Acquire) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            if let Err(e) = respond(stream, &metrics) {
+                                tracing::debug!("指标连接写入失败: {}", e);
+                            }
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                            thread::sleep(Duration::from_millis(100));
+                        }
+                        Err(e) => {
+                            tracing::warn!("指标 socket 出错，停止导出: {}", e);
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            quit,
+            thread: Some(thread),
+        })
+    }
+
+    /// socket 路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.quit.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// 写出当前快照：收到 HTTP 请求时附带响应头
+fn respond(mut stream: UnixStream, metrics: &PipelineMetrics) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+
+    let mut request = [0u8; 512];
+    let is_http = match stream.read(&mut request) {
+        Ok(n) => request[..n].starts_with(b"GET "),
+        Err(e)
+            if e.kind() == std::io::ErrorKind::WouldBlock
+                || e.kind() == std::io::ErrorKind::TimedOut =>
+        {
+            false
+        }
+        Err(e) => return Err(e),
+    };
+
+    let body = metrics.snapshot().to_prometheus();
+    if is_http {
+        write!(
+            stream,
+            "HTTP/1.0 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )?;
+    }
+    stream.write_all(body.as_bytes())?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::Stage;
+
+    fn fetch(path: &Path, request: &[u8]) -> String {
+        let mut stream = UnixStream::connect(path).unwrap();
+        stream.write_all(request).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_exporter_serves_plain_and_http() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run/metrics.sock");
+        let metrics = PipelineMetrics::new();
+        metrics.record_stage(Stage::Vad, Duration::from_millis(2));
+
+        let exporter = MetricsExporter::start(&path, metrics.clone()).unwrap();
+        assert!(path.exists());
+
+        let plain = fetch(&path, b"");
+        assert!(plain.starts_with("# HELP vinput_stage_seconds_total"));
+        assert!(plain.contains("vinput_stage_calls_total{stage=\"vad\"} 1\n"));
+
+        metrics.add_ring_overruns(3);
+        let http = fetch(&path, b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        assert!(http.starts_with("HTTP/1.0 200 OK\r\n"));
+        assert!(http.contains("vinput_ring_overrun_samples_total 3\n"));
+
+        drop(exporter);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_exporter_keeps_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.sock");
+        std::fs::write(&path, b"keep me").unwrap();
+
+        assert!(MetricsExporter::start(&path, PipelineMetrics::new()).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"keep me");
+    }
+}
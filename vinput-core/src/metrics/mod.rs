@@ -0,0 +1,355 @@
+//! 管道性能指标
+//!
+//! 统计各阶段处理耗时（语音增强、VAD、ASR 解码、标点、ITN）、实时率 (RTF)、
+//! 首个部分结果延迟、端点到上屏延迟与 Ring Buffer 溢出。
+//!
+//! `PipelineMetrics` 是可克隆的共享句柄：管道、结果后处理与音频线程各持一份，
+//! FFI 与 Prometheus 导出只读取快照，不需要获取管道锁。
+
+pub mod exporter;
+
+pub use exporter::MetricsExporter;
+pub use vinput_config::metrics::MetricsConfig;
+
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 管道处理阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// 语音增强（降噪）
+    Enhancement,
+    /// VAD
+    Vad,
+    /// ASR 送入音频与解码
+    AsrDecode,
+    /// 标点（部分结果与最终结果）
+    Punctuation,
+    /// 文本规范化
+    Itn,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Enhancement,
+        Stage::Vad,
+        Stage::AsrDecode,
+        Stage::Punctuation,
+        Stage::Itn,
+    ];
+
+    /// 指标标签名
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Enhancement => "enhancement",
+            Stage::Vad => "vad",
+            Stage::AsrDecode => "asr_decode",
+            Stage::Punctuation => "punctuation",
+            Stage::Itn => "itn",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// 耗时统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DurationStats {
+    /// 次数
+    pub count: u64,
+    /// 合计
+    pub total: Duration,
+    /// 最大值
+    pub max: Duration,
+    /// 最近一次
+    pub last: Duration,
+}
+
+impl DurationStats {
+    pub fn record(&mut self, duration: Duration) {
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+        self.last = duration;
+    }
+
+    /// 平均值（无记录时为 0）
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        self.total.div_f64(self.count as f64)
+    }
+}
+
+/// 指标快照
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MetricsSnapshot {
+    /// 各阶段耗时（按 `Stage::ALL` 顺序）
+    pub stages: [DurationStats; 5],
+    /// 已处理的音频时长
+    pub audio: Duration,
+    /// 语音开始（创建 ASR 流）到第一个非空部分结果
+    pub first_partial: DurationStats,
+    /// 句子结束（端点或停止录音）到最终结果上屏
+    pub endpoint_to_commit: DurationStats,
+    /// 已上屏的句子数
+    pub utterances: u64,
+    /// Ring Buffer 溢出丢弃的样本数
+    pub ring_overruns: u64,
+}
+
+impl MetricsSnapshot {
+    pub fn stage(&self, stage: Stage) -> &DurationStats {
+        &self.stages[stage.index()]
+    }
+
+    /// 各阶段处理耗时合计
+    pub fn processing(&self) -> Duration {
+        self.stages.iter().map(|s| s.total).sum()
+    }
+
+    /// 实时率：处理耗时 / 音频时长（< 1 表示快于实时；未处理音频时为 0）
+    pub fn real_time_factor(&self) -> f64 {
+        if self.audio.is_zero() {
+            return 0.0;
+        }
+        self.processing().as_secs_f64() / self.audio.as_secs_f64()
+    }
+
+    /// Prometheus 文本格式（exposition format 0.0.4）
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP vinput_stage_seconds_total Processing time spent in each pipeline stage.\n");
+        out.push_str("# TYPE vinput_stage_seconds_total counter\n");
+        for stage in Stage::ALL {
+            let _ = writeln!(out, "vinput_stage_seconds_total{{stage=\"{}\"}} {}",
+                stage.name(), self.stage(stage).total.as_secs_f64());
+        }
+        out.push_str("# HELP vinput_stage_calls_total Number of times each pipeline stage ran.\n");
+        out.push_str("# TYPE vinput_stage_calls_total counter\n");
+        for stage in Stage::ALL {
+            let _ = writeln!(out, "vinput_stage_calls_total{{stage=\"{}\"}} {}",
+                stage.name(), self.stage(stage).count);
+        }
+        out.push_str("# HELP vinput_stage_max_seconds Longest single run of each pipeline stage.\n");
+        out.push_str("# TYPE vinput_stage_max_seconds gauge\n");
+        for stage in Stage::ALL {
+            let _ = writeln!(out, "vinput_stage_max_seconds{{stage=\"{}\"}} {}",
+                stage.name(), self.stage(stage).max.as_secs_f64());
+        }
+
+        write_counter(&mut out, "vinput_audio_seconds_total", "Audio processed by the pipeline.",
+            self.audio.as_secs_f64());
+        out.push_str("# HELP vinput_real_time_factor Processing time divided by audio time.\n");
+        out.push_str("# TYPE vinput_real_time_factor gauge\n");
+        let _ = writeln!(out, "vinput_real_time_factor {}", self.real_time_factor());
+
+        write_summary(&mut out, "vinput_first_partial_latency_seconds",
+            "Time from speech start to the first partial result.", &self.first_partial);
+        write_summary(&mut out, "vinput_endpoint_to_commit_latency_seconds",
+            "Time from end of utterance to final text commit.", &self.endpoint_to_commit);
+
+        write_counter(&mut out, "vinput_utterances_total", "Utterances committed.",
+            self.utterances as f64);
+        write_counter(&mut out, "vinput_ring_overrun_samples_total",
+            "Audio samples dropped because the ring buffer was full.", self.ring_overruns as f64);
+        out
+    }
+}
+
+fn write_counter(out: &mut String, name: &str, help: &str, value: f64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn write_summary(out: &mut String, name: &str, help: &str, stats: &DurationStats) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} summary", name);
+    let _ = writeln!(out, "{}_sum {}", name, stats.total.as_secs_f64());
+    let _ = writeln!(out, "{}_count {}", name, stats.count);
+    let _ = writeln!(out, "# TYPE {}_max gauge", name);
+    let _ = writeln!(out, "{}_max {}", name, stats.max.as_secs_f64());
+}
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    snapshot: MetricsSnapshot,
+    /// 当前句子的语音开始时间（尚未出现部分结果）
+    speech_started: Option<Instant>,
+    /// 当前句子的结束时间（尚未上屏）
+    endpoint_reached: Option<Instant>,
+}
+
+/// 管道指标（共享句柄，克隆后指向同一份统计）
+#[derive(Debug, Clone, Default)]
+pub struct PipelineMetrics {
+    state: Arc<Mutex<MetricsState>>,
+}
+
+impl PipelineMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut MetricsState) -> T) -> Option<T> {
+        self.state.lock().ok().map(|mut state| f(&mut state))
+    }
+
+    /// 当前统计快照
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.with_state(|state| state.snapshot.clone()).unwrap_or_default()
+    }
+
+    /// 清零
+    pub fn reset(&self) {
+        self.with_state(|state| *state = MetricsState::default());
+    }
+
+    /// 执行 `f` 并记入指定阶段的耗时
+    pub fn time<T>(&self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record_stage(stage, start.elapsed());
+        result
+    }
+
+    pub fn record_stage(&self, stage: Stage, duration: Duration) {
+        self.with_state(|state| state.snapshot.stages[stage.index()].record(duration));
+    }
+
+    /// 记录送入管道的音频时长
+    pub fn record_audio(&self, duration: Duration) {
+        self.with_state(|state| state.snapshot.audio += duration);
+    }
+
+    /// 语音开始（创建 ASR 流）：开始计算首个部分结果延迟
+    pub fn speech_started(&self) {
+        self.with_state(|state| {
+            state.speech_started = Some(Instant::now());
+            // 上一句未上屏（被丢弃或取消）时不计入端点到上屏延迟
+            state.endpoint_reached = None;
+        });
+    }
+
+    /// 出现非空部分结果（每句只记录第一个）
+    pub fn partial_result(&self) {
+        self.with_state(|state| {
+            if let Some(start) = state.speech_started.take() {
+                state.snapshot.first_partial.record(start.elapsed());
+            }
+        });
+    }
+
+    /// 句子结束（端点检测或停止录音）：开始计算端点到上屏延迟
+    pub fn endpoint_reached(&self) {
+        self.with_state(|state| {
+            state.speech_started = None;
+            state.endpoint_reached.get_or_insert_with(Instant::now);
+        });
+    }
+
+    /// 最终结果已上屏
+    pub fn committed(&self) {
+        self.with_state(|state| {
+            if let Some(end) = state.endpoint_reached.take() {
+                state.snapshot.endpoint_to_commit.record(end.elapsed());
+            }
+            state.snapshot.utterances += 1;
+        });
+    }
+
+    /// 句子被丢弃（过短、取消、结果为空）：不计入端点到上屏延迟
+    pub fn utterance_discarded(&self) {
+        self.with_state(|state| {
+            state.speech_started = None;
+            state.endpoint_reached = None;
+        });
+    }
+
+    /// 累加 Ring Buffer 溢出丢弃的样本数
+    pub fn add_ring_overruns(&self, samples: u64) {
+        self.with_state(|state| state.snapshot.ring_overruns += samples);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duration_stats() {
+        let mut stats = DurationStats::default();
+        assert_eq!(stats.mean(), Duration::ZERO);
+        stats.record(Duration::from_millis(10));
+        stats.record(Duration::from_millis(30));
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.mean(), Duration::from_millis(20));
+        assert_eq!(stats.max, Duration::from_millis(30));
+        assert_eq!(stats.last, Duration::from_millis(30));
+    }
+
+    #[test]
+    fn test_real_time_factor() {
+        let metrics = PipelineMetrics::new();
+        assert_eq!(metrics.snapshot().real_time_factor(), 0.0);
+
+        metrics.record_audio(Duration::from_secs(2));
+        metrics.record_stage(Stage::Vad, Duration::from_millis(100));
+        metrics.record_stage(Stage::AsrDecode, Duration::from_millis(300));
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.processing(), Duration::from_millis(400));
+        assert!((snapshot.real_time_factor() - 0.2).abs() < 1e-9);
+        assert_eq!(snapshot.stage(Stage::AsrDecode).count, 1);
+    }
+
+    #[test]
+    fn test_latency_tracking() {
+        let metrics = PipelineMetrics::new();
+        let shared = metrics.clone();
+
+        // 第一个部分结果只记一次
+        metrics.speech_started();
+        shared.partial_result();
+        shared.partial_result();
+        // 端点到上屏
+        metrics.endpoint_reached();
+        shared.committed();
+        // 被丢弃的句子不计入延迟
+        metrics.endpoint_reached();
+        metrics.utterance_discarded();
+        metrics.committed();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.first_partial.count, 1);
+        assert_eq!(snapshot.endpoint_to_commit.count, 1);
+        assert_eq!(snapshot.utterances, 2);
+
+        metrics.reset();
+        assert_eq!(shared.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn test_prometheus_text() {
+        let metrics = PipelineMetrics::new();
+        metrics.record_stage(Stage::Itn, Duration::from_millis(5));
+        metrics.add_ring_overruns(512);
+        let text = metrics.snapshot().to_prometheus();
+
+        assert!(text.contains("vinput_stage_seconds_total{stage=\"itn\"} 0.005\n"));
+        assert!(text.contains("vinput_stage_calls_total{stage=\"vad\"} 0\n"));
+        assert!(text.contains("# TYPE vinput_first_partial_latency_seconds summary\n"));
+        assert!(text.contains("vinput_ring_overrun_samples_total 512\n"));
+        // 每个样本行都有对应的 TYPE 声明
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            let family = name.trim_end_matches("_sum").trim_end_matches("_count");
+            assert!(text.contains(&format!("# TYPE {} ", family)), "缺少 TYPE: {}", name);
+        }
+    }
+}
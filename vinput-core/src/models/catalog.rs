@@ -0,0 +1,188 @@
+//! 模型目录
+//!
+//! 目录为 TOML 文件，每个 `[[model]]` 描述一个可下载的模型压缩包。
+//! 内置目录随程序编译（`models/catalog.toml`），也可从文件加载自定义目录。
+
+use super::ModelError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// 内置模型目录
+const BUILTIN_CATALOG: &str = include_str!("../../../models/catalog.toml");
+
+/// 模型类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModelType {
+    /// 流式 Paraformer（`OnlineRecognizer` 加载）
+    StreamingParaformer,
+}
+
+impl ModelType {
+    /// 安装后模型目录中必须存在的文件
+    pub fn required_files(self) -> &'static [&'static str] {
+        match self {
+            ModelType::StreamingParaformer => &["encoder.int8.onnx", "decoder.int8.onnx", "tokens.txt"],
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModelType::StreamingParaformer => "流式 Paraformer",
+        }
+    }
+}
+
+/// 目录中的一个模型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelEntry {
+    /// 安装目录名
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    pub model_type: ModelType,
+    /// 语言代码（如 `zh-en`）
+    pub language: String,
+    /// tar.bz2 压缩包地址
+    pub url: String,
+    /// 压缩包 SHA-256（小写十六进制），为空时拒绝安装
+    #[serde(default)]
+    pub sha256: String,
+    /// 压缩包大小（字节），0 表示未知
+    #[serde(default)]
+    pub size_bytes: u64,
+}
+
+impl ModelEntry {
+    /// 压缩包文件名（URL 最后一段）
+    pub fn archive_name(&self) -> &str {
+        self.url.rsplit('/').next().unwrap_or(&self.url)
+    }
+
+    /// 语言显示名称
+    pub fn language_label(&self) -> String {
+        self.language
+            .split('-')
+            .map(|code| match code {
+                "zh" => "中文",
+                "en" => "英文",
+                "yue" => "粤语",
+                other => other,
+            })
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.id.is_empty() || self.id.starts_with('.') || self.id.contains(['/', '\\']) || self.id.contains(char::is_whitespace) {
+            return Err(format!("模型 id '{}' 不能为空、以 . 开头或包含路径分隔符与空白", self.id));
+        }
+        if self.id == super::store::ACTIVE_LINK {
+            return Err(format!("模型 id 不能为保留名称 '{}'", self.id));
+        }
+        if !self.url.starts_with("https://") && !self.url.starts_with("http://") {
+            return Err(format!("模型 {} 的 url 应为 http(s) 地址", self.id));
+        }
+        if !self.url.ends_with(".tar.bz2") {
+            return Err(format!("模型 {} 的 url 应指向 .tar.bz2 压缩包", self.id));
+        }
+        let sha256_ok = self.sha256.is_empty()
+            || (self.sha256.len() == 64 && self.sha256.chars().all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c)));
+        if !sha256_ok {
+            return Err(format!("模型 {} 的 sha256 应为 64 位小写十六进制", self.id));
+        }
+        Ok(())
+    }
+}
+
+/// 模型目录
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    #[serde(rename = "model", default)]
+    pub models: Vec<ModelEntry>,
+}
+
+impl ModelCatalog {
+    /// 内置目录
+    pub fn builtin() -> Result<Self, ModelError> {
+        Self::parse(BUILTIN_CATALOG)
+    }
+
+    /// 从文件加载目录
+    pub fn load(path: &Path) -> Result<Self, ModelError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse(&content)
+    }
+
+    /// 解析并校验目录
+    pub fn parse(content: &str) -> Result<Self, ModelError> {
+        let catalog: Self = toml::from_str(content).map_err(|e| ModelError::CatalogParse(e.to_string()))?;
+
+        let mut ids = HashSet::new();
+        for entry in &catalog.models {
+            entry.validate().map_err(ModelError::CatalogParse)?;
+            if !ids.insert(entry.id.as_str()) {
+                return Err(ModelError::CatalogParse(format!("模型 id 重复: {}", entry.id)));
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// 按 id 查找模型
+    pub fn get(&self, id: &str) -> Result<&ModelEntry, ModelError> {
+        self.models
+            .iter()
+            .find(|m| m.id == id)
+            .ok_or_else(|| ModelError::UnknownModel(id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_catalog_parses() {
+        let catalog = ModelCatalog::builtin().unwrap();
+        assert!(!catalog.models.is_empty());
+        for entry in &catalog.models {
+            assert!(entry.archive_name().starts_with(&entry.id), "{}", entry.id);
+        }
+        let entry = catalog.get("sherpa-onnx-streaming-paraformer-bilingual-zh-en").unwrap();
+        assert_eq!(entry.model_type, ModelType::StreamingParaformer);
+        assert_eq!(entry.language_label(), "中文+英文");
+    }
+
+    // 内置目录的每个条目都应可安装（运行 ./update-model-catalog.sh 填写）
+    #[test]
+    #[ignore = "内置目录尚未填写 sha256 与 size_bytes，联网运行 ./update-model-catalog.sh 后去掉"]
+    fn test_builtin_catalog_has_checksums() {
+        let catalog = ModelCatalog::builtin().unwrap();
+        for entry in &catalog.models {
+            assert_eq!(entry.sha256.len(), 64, "{} 缺少 sha256", entry.id);
+            assert!(entry.sha256.chars().all(|c| c.is_ascii_hexdigit()), "{}", entry.id);
+            assert!(entry.size_bytes > 0, "{} 缺少 size_bytes", entry.id);
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_entries() {
+        let entry = |id: &str, sha256: &str| {
+            format!(
+                "[[model]]\nid = \"{}\"\nname = \"m\"\nmodel_type = \"streaming-paraformer\"\nlanguage = \"zh\"\nurl = \"https://example.com/m.tar.bz2\"\nsha256 = \"{}\"\n",
+                id, sha256
+            )
+        };
+
+        assert!(ModelCatalog::parse(&entry("m", "")).is_ok());
+        assert!(ModelCatalog::parse(&entry("../m", "")).is_err());
+        assert!(ModelCatalog::parse(&entry("active", "")).is_err());
+        assert!(ModelCatalog::parse(&entry("m", "ABC")).is_err());
+        assert!(ModelCatalog::parse(&format!("{}{}", entry("m", ""), entry("m", ""))).is_err());
+        assert!(matches!(
+            ModelCatalog::parse(&entry("m", "")).unwrap().get("x"),
+            Err(ModelError::UnknownModel(_))
+        ));
+    }
+}
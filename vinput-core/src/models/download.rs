@@ -0,0 +1,247 @@
+//! 断点续传下载
+//!
+//! 下载内容先写入 `<dest>.part`，完成后改名为 `dest`。`.part` 已存在时
+//! 以 `Range: bytes=N-` 续传；服务器不支持 Range（返回 200）时从头下载。
+
+use super::ModelError;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 连接超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// 读取超时（两次收到数据的最长间隔）
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// 读取缓冲区大小
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 下载进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DownloadProgress {
+    /// 已下载字节数（含续传前已有的部分）
+    pub downloaded: u64,
+    /// 总字节数（服务器未提供时为 None）
+    pub total: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// 完成比例（0.0 ~ 1.0）
+    pub fn fraction(&self) -> Option<f32> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.downloaded as f64 / total as f64).min(1.0) as f32)
+    }
+}
+
+/// HTTP 下载器
+pub struct Downloader {
+    agent: ureq::Agent,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Downloader {
+    pub fn new() -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(CONNECT_TIMEOUT)
+            .timeout_read(READ_TIMEOUT)
+            .build();
+        Self { agent }
+    }
+
+    /// `dest` 对应的临时文件
+    pub fn part_path(dest: &Path) -> PathBuf {
+        let mut name = dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".part");
+        dest.with_file_name(name)
+    }
+
+    /// 下载到 `dest`（已存在则直接返回）
+    ///
+    /// `on_progress` 每读取一块数据调用一次，返回 `ControlFlow::Break` 时取消下载，
+    /// 已下载部分保留在 `.part` 文件中供下次续传。
+    pub fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        on_progress: &mut dyn FnMut(DownloadProgress) -> ControlFlow<()>,
+    ) -> Result<(), ModelError> {
+        if dest.exists() {
+            return Ok(());
+        }
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let part = Self::part_path(dest);
+        let offset = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.agent.get(url);
+        if offset > 0 {
+            tracing::info!("续传 {}，已下载 {} 字节", url, offset);
+            request = request.set("Range", &format!("bytes={}-", offset));
+        }
+
+        let response = match request.call() {
+            Ok(response) => response,
+            // .part 已是完整文件（上次在改名前中断）
+            Err(ureq::Error::Status(416, _)) if offset > 0 => {
+                fs::rename(&part, dest)?;
+                return Ok(());
+            }
+            Err(e) => {
+                return Err(ModelError::Download {
+                    url: url.to_string(),
+                    reason: e.to_string(),
+                })
+            }
+        };
+
+        let content_length = response
+            .header("Content-Length")
+            .and_then(|v| v.trim().parse::<u64>().ok());
+        let resumed = offset > 0 && response.status() == 206;
+        let (mut downloaded, total) = if resumed {
+            (offset, content_length.map(|len| len + offset))
+        } else {
+            if offset > 0 {
+                tracing::info!("服务器不支持续传，重新下载 {}", url);
+            }
+            (0, content_length)
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(&part)?;
+
+        let mut reader = response.into_reader();
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        loop {
+            let n = reader.read(&mut buf).map_err(|e| ModelError::Download {
+                url: url.to_string(),
+                reason: e.to_string(),
+            })?;
+            if n == 0 {
+                break;
+            }
+            file.write_all(&buf[..n])?;
+            downloaded += n as u64;
+
+            if on_progress(DownloadProgress { downloaded, total }).is_break() {
+                file.flush()?;
+                return Err(ModelError::Cancelled);
+            }
+        }
+        file.sync_all()?;
+
+        if let Some(total) = total {
+            if downloaded != total {
+                return Err(ModelError::Download {
+                    url: url.to_string(),
+                    reason: format!("连接提前关闭：已下载 {} / {} 字节", downloaded, total),
+                });
+            }
+        }
+
+        fs::rename(&part, dest)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::serve;
+    use super::*;
+
+    fn body() -> Vec<u8> {
+        (0..200_000u32).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[test]
+    fn test_download_full() {
+        let server = serve("model.tar.bz2", body(), true);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.tar.bz2");
+
+        let mut calls = 0;
+        Downloader::new()
+            .download(&server.url, &dest, &mut |_| {
+                calls += 1;
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), body());
+        assert!(!Downloader::part_path(&dest).exists());
+        assert!(calls > 0);
+        assert_eq!(*server.ranges.lock().unwrap(), vec![None]);
+    }
+
+    #[test]
+    fn test_resume_from_part_file() {
+        let server = serve("model.tar.bz2", body(), true);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.tar.bz2");
+        fs::write(Downloader::part_path(&dest), &body()[..1000]).unwrap();
+
+        let mut first = None;
+        Downloader::new()
+            .download(&server.url, &dest, &mut |p| {
+                first.get_or_insert(p);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), body());
+        assert_eq!(*server.ranges.lock().unwrap(), vec![Some("bytes=1000-".to_string())]);
+        let first = first.unwrap();
+        assert!(first.downloaded > 1000);
+        assert_eq!(first.total, Some(body().len() as u64));
+    }
+
+    #[test]
+    fn test_restart_when_range_ignored() {
+        let server = serve("model.tar.bz2", body(), false);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.tar.bz2");
+        fs::write(Downloader::part_path(&dest), b"stale partial data").unwrap();
+
+        Downloader::new()
+            .download(&server.url, &dest, &mut |_| ControlFlow::Continue(()))
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), body());
+    }
+
+    #[test]
+    fn test_cancel_keeps_part_file() {
+        let server = serve("model.tar.bz2", body(), true);
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("model.tar.bz2");
+
+        let err = Downloader::new()
+            .download(&server.url, &dest, &mut |_| ControlFlow::Break(()))
+            .unwrap_err();
+        assert!(matches!(err, ModelError::Cancelled));
+        assert!(!dest.exists());
+        assert!(fs::metadata(Downloader::part_path(&dest)).unwrap().len() > 0);
+    }
+
+    #[test]
+    fn test_http_error_reported() {
+        let server = serve("model.tar.bz2", body(), true);
+        let dir = tempfile::tempdir().unwrap();
+        let url = server.url.replace("model.tar.bz2", "missing.tar.bz2");
+
+        let err = Downloader::new()
+            .download(&url, &dir.path().join("missing.tar.bz2"), &mut |_| ControlFlow::Continue(()))
+            .unwrap_err();
+        assert!(matches!(err, ModelError::Download { .. }));
+    }
+}
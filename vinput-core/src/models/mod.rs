@@ -0,0 +1,312 @@
+//! 模型管理模块
+//!
+//! 按模型目录（`models/catalog.toml`）下载、校验并安装识别模型：
+//! - catalog：模型目录（下载地址、SHA-256、大小、模型类型、语言）
+//! - download：HTTP 下载，`.part` 文件断点续传
+//! - store：SHA-256 校验、解压 tar.bz2 到 `~/.local/share/vinput/models/<id>`，
+//!   通过 `active` 符号链接原子切换当前模型
+//!
+//! `asr.model_dir` 指向 `ModelStore::active_dir()` 时，切换模型后重新加载识别器即可生效。
+
+pub mod catalog;
+pub mod download;
+pub mod store;
+
+pub use catalog::{ModelCatalog, ModelEntry, ModelType};
+pub use download::{DownloadProgress, Downloader};
+pub use store::ModelStore;
+
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// 模型管理错误
+#[derive(Error, Debug)]
+pub enum ModelError {
+    #[error("Model catalog parse error: {0}")]
+    CatalogParse(String),
+
+    #[error("Unknown model: {0}")]
+    UnknownModel(String),
+
+    #[error("Model not installed: {0}")]
+    NotInstalled(String),
+
+    #[error("Model is active and cannot be removed: {0}")]
+    ModelActive(String),
+
+    #[error("Catalog entry has no SHA-256 checksum: {0}")]
+    MissingChecksum(String),
+
+    #[error("Download failed: {url} - {reason}")]
+    Download { url: String, reason: String },
+
+    #[error("Download cancelled")]
+    Cancelled,
+
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("Invalid model archive: {0}")]
+    Archive(String),
+
+    #[error("Cannot determine user data directory")]
+    NoDataDir,
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// 模型管理器：目录 + 本地存储 + 下载器
+pub struct ModelManager {
+    catalog: ModelCatalog,
+    store: ModelStore,
+    downloader: Downloader,
+}
+
+impl ModelManager {
+    pub fn new(catalog: ModelCatalog, store: ModelStore) -> Self {
+        Self {
+            catalog,
+            store,
+            downloader: Downloader::new(),
+        }
+    }
+
+    /// 内置目录 + 默认存储目录
+    pub fn open_default() -> Result<Self, ModelError> {
+        Ok(Self::new(ModelCatalog::builtin()?, ModelStore::open_default()?))
+    }
+
+    pub fn catalog(&self) -> &ModelCatalog {
+        &self.catalog
+    }
+
+    pub fn store(&self) -> &ModelStore {
+        &self.store
+    }
+
+    /// 下载、校验并安装模型，返回安装目录
+    ///
+    /// 下载中断后再次调用会从 `.part` 文件续传；校验失败时删除已下载的文件，
+    /// 下次重新下载。`on_progress` 返回 `ControlFlow::Break` 时取消下载。
+    pub fn install(
+        &self,
+        id: &str,
+        on_progress: &mut dyn FnMut(DownloadProgress) -> ControlFlow<()>,
+    ) -> Result<PathBuf, ModelError> {
+        let entry = self.catalog.get(id)?;
+        // 下载前检查，避免下载几百 MB 后才发现无法校验
+        if entry.sha256.is_empty() {
+            return Err(ModelError::MissingChecksum(entry.id.clone()));
+        }
+
+        let archive = self.store.download_path(entry);
+        self.downloader.download(&entry.url, &archive, on_progress)?;
+
+        match self.store.install_archive(entry, &archive) {
+            Ok(dir) => {
+                let _ = std::fs::remove_file(&archive);
+                tracing::info!("模型已安装: {} -> {:?}", entry.id, dir);
+                Ok(dir)
+            }
+            Err(e @ ModelError::ChecksumMismatch { .. }) => {
+                let _ = std::fs::remove_file(&archive);
+                Err(e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// 切换当前模型，返回 `active` 目录
+    pub fn activate(&self, id: &str) -> Result<PathBuf, ModelError> {
+        let entry = self.catalog.get(id)?;
+        self.store.activate(&entry.id)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    //! 测试用本地 HTTP 服务与压缩包夹具
+
+    use bzip2::write::BzEncoder;
+    use bzip2::Compression;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    /// 只服务一个文件的 HTTP/1.1 服务，记录收到的 Range 头
+    pub struct TestServer {
+        pub url: String,
+        pub ranges: Arc<Mutex<Vec<Option<String>>>>,
+    }
+
+    /// 启动服务，`honor_range` 为 false 时忽略 Range 请求头返回完整内容
+    pub fn serve(path: &str, body: Vec<u8>, honor_range: bool) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/{}", listener.local_addr().unwrap(), path);
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let recorded = ranges.clone();
+        let expected_path = format!("/{}", path);
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut range = None;
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("range") {
+                            range = Some(value.trim().to_string());
+                        }
+                    }
+                }
+                recorded.lock().unwrap().push(range.clone());
+
+                if request_line.split_whitespace().nth(1) != Some(expected_path.as_str()) {
+                    let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                    continue;
+                }
+
+                let start = range
+                    .filter(|_| honor_range)
+                    .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse::<usize>().ok());
+                let response = match start {
+                    Some(start) => {
+                        let part = &body[start.min(body.len())..];
+                        let mut head = format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                            part.len(),
+                            start,
+                            body.len().saturating_sub(1),
+                            body.len()
+                        )
+                        .into_bytes();
+                        head.extend_from_slice(part);
+                        head
+                    }
+                    None => {
+                        let mut head = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        head.extend_from_slice(&body);
+                        head
+                    }
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        TestServer { url, ranges }
+    }
+
+    /// 构造 tar.bz2 压缩包，文件位于 `top_dir/` 下
+    pub fn fixture_archive(top_dir: &str, files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(BzEncoder::new(Vec::new(), Compression::fast()));
+        for (name, data) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, format!("{}/{}", top_dir, name), *data)
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// Paraformer 模型夹具（识别器需要的三个文件）
+    pub fn paraformer_archive(top_dir: &str, marker: &[u8]) -> Vec<u8> {
+        fixture_archive(
+            top_dir,
+            &[
+                ("encoder.int8.onnx", marker),
+                ("decoder.int8.onnx", b"decoder"),
+                ("tokens.txt", b"<blk> 0\n"),
+            ],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{paraformer_archive, serve};
+    use super::*;
+    use std::fs;
+
+    fn manager(root: &std::path::Path, url: &str, sha256: &str) -> ModelManager {
+        let catalog = ModelCatalog::parse(&format!(
+            r#"
+[[model]]
+id = "paraformer-test"
+name = "测试模型"
+model_type = "streaming-paraformer"
+language = "zh-en"
+url = "{}"
+sha256 = "{}"
+size_bytes = 0
+"#,
+            url, sha256
+        ))
+        .unwrap();
+        ModelManager::new(catalog, ModelStore::new(root))
+    }
+
+    #[test]
+    fn test_install_and_activate_from_local_server() {
+        let archive = paraformer_archive("paraformer-test", b"encoder");
+        let server = serve("paraformer-test.tar.bz2", archive.clone(), true);
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(dir.path(), &server.url, &store::sha256_hex(&archive));
+
+        let mut last = None;
+        let installed = manager
+            .install("paraformer-test", &mut |p| {
+                last = Some(p);
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(fs::read(installed.join("encoder.int8.onnx")).unwrap(), b"encoder");
+        assert_eq!(last.unwrap().downloaded, archive.len() as u64);
+        // 安装成功后删除下载的压缩包
+        assert!(!manager.store().download_path(manager.catalog().get("paraformer-test").unwrap()).exists());
+
+        let active = manager.activate("paraformer-test").unwrap();
+        assert_eq!(fs::read(active.join("tokens.txt")).unwrap(), b"<blk> 0\n");
+        assert_eq!(manager.store().active().as_deref(), Some("paraformer-test"));
+    }
+
+    #[test]
+    fn test_checksum_mismatch_discards_download() {
+        let archive = paraformer_archive("paraformer-test", b"encoder");
+        let server = serve("paraformer-test.tar.bz2", archive, true);
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(dir.path(), &server.url, &"0".repeat(64));
+
+        let err = manager.install("paraformer-test", &mut |_| ControlFlow::Continue(())).unwrap_err();
+        assert!(matches!(err, ModelError::ChecksumMismatch { .. }));
+        assert!(!manager.store().model_dir("paraformer-test").exists());
+        assert!(!manager.store().download_path(manager.catalog().get("paraformer-test").unwrap()).exists());
+    }
+
+    #[test]
+    fn test_missing_checksum_refused_before_download() {
+        let server = serve("paraformer-test.tar.bz2", Vec::new(), true);
+        let dir = tempfile::tempdir().unwrap();
+        let manager = manager(dir.path(), &server.url, "");
+
+        let err = manager.install("paraformer-test", &mut |_| ControlFlow::Continue(())).unwrap_err();
+        assert!(matches!(err, ModelError::MissingChecksum(_)));
+        assert!(server.ranges.lock().unwrap().is_empty());
+    }
+}
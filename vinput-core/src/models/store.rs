@@ -0,0 +1,328 @@
+//! 本地模型存储
+//!
+//! 目录结构：
+//! ```text
+//! ~/.local/share/vinput/models/
+//! ├── active -> sherpa-onnx-streaming-paraformer-bilingual-zh-en   当前模型（符号链接）
+//! ├── sherpa-onnx-streaming-paraformer-bilingual-zh-en/            已安装模型
+//! └── .downloads/                                                  下载中的压缩包
+//! ```
+//!
+//! 解压先写入 `.staging-<id>`，校验文件齐全后整体改名为 `<id>`；切换模型时
+//! 先创建临时符号链接再 rename 覆盖 `active`，读取方不会看到半成品或断开的链接。
+
+use super::catalog::ModelEntry;
+use super::ModelError;
+use bzip2::read::BzDecoder;
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// 当前模型符号链接名
+pub const ACTIVE_LINK: &str = "active";
+/// 下载目录名
+const DOWNLOAD_DIR: &str = ".downloads";
+
+/// 字节串的 SHA-256（小写十六进制）
+pub fn sha256_hex(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+/// 文件的 SHA-256（小写十六进制）
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 本地模型存储
+pub struct ModelStore {
+    root: PathBuf,
+}
+
+impl ModelStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// 默认存储目录：`~/.local/share/vinput/models`
+    pub fn default_root() -> Result<PathBuf, ModelError> {
+        dirs::data_dir()
+            .map(|dir| dir.join("vinput").join("models"))
+            .ok_or(ModelError::NoDataDir)
+    }
+
+    pub fn open_default() -> Result<Self, ModelError> {
+        Ok(Self::new(Self::default_root()?))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 模型安装目录
+    pub fn model_dir(&self, id: &str) -> PathBuf {
+        self.root.join(id)
+    }
+
+    /// 当前模型目录（`asr.model_dir` 指向此路径）
+    pub fn active_dir(&self) -> PathBuf {
+        self.root.join(ACTIVE_LINK)
+    }
+
+    /// 压缩包下载位置
+    pub fn download_path(&self, entry: &ModelEntry) -> PathBuf {
+        self.root.join(DOWNLOAD_DIR).join(entry.archive_name())
+    }
+
+    /// 模型是否已安装（所需文件齐全）
+    pub fn is_installed(&self, entry: &ModelEntry) -> bool {
+        let dir = self.model_dir(&entry.id);
+        entry.model_type.required_files().iter().all(|f| dir.join(f).is_file())
+    }
+
+    /// 已安装模型占用的字节数
+    pub fn installed_size(&self, id: &str) -> u64 {
+        fn dir_size(path: &Path) -> u64 {
+            fs::read_dir(path)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|e| match e.file_type() {
+                    Ok(t) if t.is_dir() => dir_size(&e.path()),
+                    _ => e.metadata().map(|m| m.len()).unwrap_or(0),
+                })
+                .sum()
+        }
+        dir_size(&self.model_dir(id))
+    }
+
+    /// 当前模型 id
+    pub fn active(&self) -> Option<String> {
+        let target = fs::read_link(self.active_dir()).ok()?;
+        target.file_name()?.to_str().map(str::to_string)
+    }
+
+    /// 校验压缩包并安装，返回安装目录
+    ///
+    /// 同名模型已安装时整体替换。
+    pub fn install_archive(&self, entry: &ModelEntry, archive: &Path) -> Result<PathBuf, ModelError> {
+        if entry.sha256.is_empty() {
+            return Err(ModelError::MissingChecksum(entry.id.clone()));
+        }
+        let actual = sha256_file(archive)?;
+        if actual != entry.sha256 {
+            return Err(ModelError::ChecksumMismatch {
+                expected: entry.sha256.clone(),
+                actual,
+            });
+        }
+
+        fs::create_dir_all(&self.root)?;
+        let staging = self.root.join(format!(".staging-{}", entry.id));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+
+        let result = self.unpack_and_move(entry, archive, &staging);
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+
+    fn unpack_and_move(&self, entry: &ModelEntry, archive: &Path, staging: &Path) -> Result<PathBuf, ModelError> {
+        unpack_tar_bz2(archive, staging)?;
+
+        // sherpa-onnx 压缩包内通常有一层与包同名的目录
+        let source = single_subdir(staging)?.unwrap_or_else(|| staging.to_path_buf());
+        let missing: Vec<_> = entry
+            .model_type
+            .required_files()
+            .iter()
+            .filter(|f| !source.join(f).is_file())
+            .copied()
+            .collect();
+        if !missing.is_empty() {
+            return Err(ModelError::Archive(format!("缺少模型文件: {}", missing.join(", "))));
+        }
+
+        let target = self.model_dir(&entry.id);
+        if target.exists() {
+            let old = self.root.join(format!(".old-{}", entry.id));
+            if old.exists() {
+                fs::remove_dir_all(&old)?;
+            }
+            fs::rename(&target, &old)?;
+            fs::rename(&source, &target)?;
+            fs::remove_dir_all(&old)?;
+        } else {
+            fs::rename(&source, &target)?;
+        }
+        Ok(target)
+    }
+
+    /// 原子切换当前模型，返回 `active` 目录
+    pub fn activate(&self, id: &str) -> Result<PathBuf, ModelError> {
+        if !self.model_dir(id).is_dir() {
+            return Err(ModelError::NotInstalled(id.to_string()));
+        }
+
+        let tmp = self.root.join(format!(".{}.tmp", ACTIVE_LINK));
+        if fs::symlink_metadata(&tmp).is_ok() {
+            fs::remove_file(&tmp)?;
+        }
+        // 相对链接：存储目录整体移动后仍然有效
+        std::os::unix::fs::symlink(id, &tmp)?;
+        fs::rename(&tmp, self.active_dir())?;
+
+        tracing::info!("当前模型已切换为 {}", id);
+        Ok(self.active_dir())
+    }
+
+    /// 删除已安装模型（当前模型不可删除）
+    pub fn remove(&self, id: &str) -> Result<(), ModelError> {
+        if self.active().as_deref() == Some(id) {
+            return Err(ModelError::ModelActive(id.to_string()));
+        }
+        let dir = self.model_dir(id);
+        if !dir.is_dir() {
+            return Err(ModelError::NotInstalled(id.to_string()));
+        }
+        fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
+
+/// 解压 tar.bz2，拒绝解压到目标目录之外的条目
+fn unpack_tar_bz2(archive: &Path, dest: &Path) -> Result<(), ModelError> {
+    let mut tar = tar::Archive::new(BzDecoder::new(File::open(archive)?));
+    let entries = tar.entries().map_err(|e| ModelError::Archive(e.to_string()))?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| ModelError::Archive(e.to_string()))?;
+        let path = entry.path().map_err(|e| ModelError::Archive(e.to_string()))?.into_owned();
+        let inside = entry
+            .unpack_in(dest)
+            .map_err(|e| ModelError::Archive(format!("{}: {}", path.display(), e)))?;
+        if !inside {
+            return Err(ModelError::Archive(format!("条目路径越界: {}", path.display())));
+        }
+    }
+    Ok(())
+}
+
+/// 目录下只有一个子目录（没有其他文件）时返回该子目录
+fn single_subdir(dir: &Path) -> io::Result<Option<PathBuf>> {
+    let entries: Vec<_> = fs::read_dir(dir)?.collect::<Result<_, _>>()?;
+    match entries.as_slice() {
+        [only] if only.file_type()?.is_dir() => Ok(Some(only.path())),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::catalog::ModelType;
+    use super::super::test_support::{fixture_archive, paraformer_archive};
+    use super::*;
+
+    fn entry(id: &str, archive: &[u8]) -> ModelEntry {
+        ModelEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            model_type: ModelType::StreamingParaformer,
+            language: "zh".to_string(),
+            url: format!("https://example.com/{}.tar.bz2", id),
+            sha256: sha256_hex(archive),
+            size_bytes: archive.len() as u64,
+        }
+    }
+
+    fn write_archive(dir: &Path, data: &[u8]) -> PathBuf {
+        let path = dir.join("archive.tar.bz2");
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_install_strips_top_level_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ModelStore::new(dir.path().join("models"));
+        let data = paraformer_archive("model-a", b"v1");
+        let entry = entry("model-a", &data);
+
+        let installed = store.install_archive(&entry, &write_archive(dir.path(), &data)).unwrap();
+        assert_eq!(installed, store.model_dir("model-a"));
+        assert_eq!(fs::read(installed.join("encoder.int8.onnx")).unwrap(), b"v1");
+        assert!(store.is_installed(&entry));
+        assert!(!store.root().join(".staging-model-a").exists());
+    }
+
+    #[test]
+    fn test_reinstall_replaces_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ModelStore::new(dir.path().join("models"));
+
+        let v1 = paraformer_archive("model-a", b"v1");
+        store.install_archive(&entry("model-a", &v1), &write_archive(dir.path(), &v1)).unwrap();
+        let v2 = paraformer_archive("model-a", b"v2");
+        store.install_archive(&entry("model-a", &v2), &write_archive(dir.path(), &v2)).unwrap();
+
+        assert_eq!(fs::read(store.model_dir("model-a").join("encoder.int8.onnx")).unwrap(), b"v2");
+        assert!(!store.root().join(".old-model-a").exists());
+    }
+
+    #[test]
+    fn test_incomplete_archive_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ModelStore::new(dir.path().join("models"));
+        let data = fixture_archive("model-a", &[("tokens.txt", b"<blk> 0\n")]);
+        let entry = entry("model-a", &data);
+
+        let err = store.install_archive(&entry, &write_archive(dir.path(), &data)).unwrap_err();
+        assert!(matches!(err, ModelError::Archive(ref msg) if msg.contains("encoder.int8.onnx")));
+        assert!(!store.model_dir("model-a").exists());
+    }
+
+    #[test]
+    fn test_activate_switches_link() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ModelStore::new(dir.path().join("models"));
+        for (id, marker) in [("model-a", b"a"), ("model-b", b"b")] {
+            let data = paraformer_archive(id, marker);
+            store.install_archive(&entry(id, &data), &write_archive(dir.path(), &data)).unwrap();
+        }
+
+        assert_eq!(store.active(), None);
+        store.activate("model-a").unwrap();
+        assert_eq!(fs::read(store.active_dir().join("encoder.int8.onnx")).unwrap(), b"a");
+        store.activate("model-b").unwrap();
+        assert_eq!(store.active().as_deref(), Some("model-b"));
+        assert_eq!(fs::read(store.active_dir().join("encoder.int8.onnx")).unwrap(), b"b");
+
+        assert!(matches!(store.activate("model-c"), Err(ModelError::NotInstalled(_))));
+        assert!(matches!(store.remove("model-b"), Err(ModelError::ModelActive(_))));
+        store.remove("model-a").unwrap();
+        assert!(!store.model_dir("model-a").exists());
+    }
+}
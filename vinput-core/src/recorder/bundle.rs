@@ -0,0 +1,277 @@
+//! 会话包格式
+//!
+//! 会话包是一个 tar 文件：
+//!
+//! ```text
+//! manifest.toml   格式版本、录制时间、采样率、帧长、帧数、是否截断
+//! config.toml     录音时的完整配置
+//! audio.wav       送入管道的音频（32-bit float 单声道，与现场逐样本一致）
+//! events.toml     按帧号记录的事件（[[event]]，kind 区分类型）
+//! ```
+//!
+//! 帧号从 1 开始，表示该事件发生在第几帧送入管道之后。
+
+use crate::audio::{decode_wav, encode_wav_f32};
+use crate::error::{VInputError, VInputResult};
+use crate::streaming::PunctuationDecision;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use vinput_config::VInputConfig;
+
+/// 会话包格式版本
+pub const BUNDLE_VERSION: u32 = 1;
+/// 会话包扩展名
+pub const BUNDLE_EXTENSION: &str = "vinput-session";
+
+const MANIFEST_FILE: &str = "manifest.toml";
+const CONFIG_FILE: &str = "config.toml";
+const AUDIO_FILE: &str = "audio.wav";
+const EVENTS_FILE: &str = "events.toml";
+
+/// 会话包描述
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionManifest {
+    /// 会话包格式版本
+    pub version: u32,
+    /// 录制时的 V-Input 版本
+    pub vinput_version: String,
+    /// 开始录制的 Unix 时间（秒）
+    pub started_at: u64,
+    /// 采样率 (Hz)
+    pub sample_rate: u32,
+    /// 每帧样本数
+    pub frame_size: usize,
+    /// 录制的帧数
+    pub frames: u64,
+    /// 是否因超过最长录制时长而截断（截断后的音频与事件未录制）
+    pub truncated: bool,
+}
+
+/// 会话事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionEvent {
+    /// VAD 状态变化
+    Vad { frame: u64, state: String, speech_prob: f32 },
+    /// VAD 停顿达到逗号阈值
+    Comma { frame: u64 },
+    /// 结束或丢弃句子
+    Endpoint { frame: u64, trigger: String },
+    /// 查询 Preedit 部分结果（含实时逗号）
+    Partial { frame: u64, text: String },
+    /// 最终结果（含标点，ITN 之前）
+    Final {
+        frame: u64,
+        raw: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        punctuation: Option<PunctuationDecision>,
+    },
+    /// 收到停止信号：此后的帧只送入管道，最终结果在全部帧之后生成
+    Stop { frame: u64 },
+    /// 取消录音：丢弃未完成的句子
+    Cancel { frame: u64 },
+    /// 回放无法复现的现场操作（如重建管道、关闭语音增强）
+    Note { frame: u64, message: String },
+}
+
+impl SessionEvent {
+    /// 事件发生的帧号
+    pub fn frame(&self) -> u64 {
+        match self {
+            Self::Vad { frame, .. }
+            | Self::Comma { frame }
+            | Self::Endpoint { frame, .. }
+            | Self::Partial { frame, .. }
+            | Self::Final { frame, .. }
+            | Self::Stop { frame }
+            | Self::Cancel { frame }
+            | Self::Note { frame, .. } => *frame,
+        }
+    }
+}
+
+/// events.toml 的内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EventLog {
+    #[serde(default)]
+    event: Vec<SessionEvent>,
+}
+
+/// 一次录音会话的完整记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionBundle {
+    pub manifest: SessionManifest,
+    /// 配置快照（TOML 文本）
+    pub config: String,
+    /// 送入管道的音频
+    pub samples: Vec<f32>,
+    /// 按发生顺序排列的事件
+    pub events: Vec<SessionEvent>,
+}
+
+impl SessionBundle {
+    /// 解析配置快照（按当前版本迁移）
+    pub fn config(&self) -> VInputResult<VInputConfig> {
+        VInputConfig::parse(&self.config)
+            .map(|(config, _)| config)
+            .map_err(|e| VInputError::Generic(format!("会话包配置无效: {}", e)))
+    }
+
+    /// 按帧长切分的音频（与现场送入管道的帧一致）
+    pub fn frames(&self) -> impl Iterator<Item = &[f32]> {
+        self.samples.chunks(self.manifest.frame_size.max(1))
+    }
+
+    /// 写入会话包（先写临时文件再改名）
+    pub fn write_to(&self, path: &Path) -> VInputResult<()> {
+        let manifest = toml::to_string(&self.manifest)
+            .map_err(|e| VInputError::Generic(format!("序列化会话描述失败: {}", e)))?;
+        let events = toml::to_string(&EventLog { event: self.events.clone() })
+            .map_err(|e| VInputError::Generic(format!("序列化会话事件失败: {}", e)))?;
+        let audio = encode_wav_f32(&self.samples, self.manifest.sample_rate);
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("part");
+        let mut builder = tar::Builder::new(std::fs::File::create(&tmp)?);
+        for (name, data) in [
+            (MANIFEST_FILE, manifest.as_bytes()),
+            (CONFIG_FILE, self.config.as_bytes()),
+            (AUDIO_FILE, audio.as_slice()),
+            (EVENTS_FILE, events.as_bytes()),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(self.manifest.started_at);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data)?;
+        }
+        builder.into_inner()?.sync_all()?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// 读取会话包
+    pub fn read_from(path: &Path) -> VInputResult<Self> {
+        let invalid = |reason: String| VInputError::Generic(format!("{}: {}", path.display(), reason));
+
+        let mut manifest = None;
+        let mut config = None;
+        let mut audio = None;
+        let mut events = None;
+        let mut archive = tar::Archive::new(std::fs::File::open(path)?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let name = entry.path()?.to_string_lossy().into_owned();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            let text = || String::from_utf8(data.clone()).map_err(|_| invalid(format!("{} 不是 UTF-8 文本", name)));
+            match name.as_str() {
+                MANIFEST_FILE => manifest = Some(text()?),
+                CONFIG_FILE => config = Some(text()?),
+                EVENTS_FILE => events = Some(text()?),
+                AUDIO_FILE => audio = Some(data),
+                _ => {}
+            }
+        }
+
+        let missing = |name: &str| invalid(format!("缺少 {}", name));
+        let manifest: SessionManifest = toml::from_str(&manifest.ok_or_else(|| missing(MANIFEST_FILE))?)
+            .map_err(|e| invalid(format!("{} 无效: {}", MANIFEST_FILE, e)))?;
+        if manifest.version > BUNDLE_VERSION {
+            return Err(invalid(format!(
+                "会话包版本 {} 高于当前支持的版本 {}",
+                manifest.version, BUNDLE_VERSION
+            )));
+        }
+        let events: EventLog = toml::from_str(&events.ok_or_else(|| missing(EVENTS_FILE))?)
+            .map_err(|e| invalid(format!("{} 无效: {}", EVENTS_FILE, e)))?;
+        let wav = decode_wav(&audio.ok_or_else(|| missing(AUDIO_FILE))?)?;
+        if wav.channels != 1 || wav.sample_rate != manifest.sample_rate {
+            return Err(invalid(format!(
+                "音频格式与描述不符（{} 声道，{} Hz）",
+                wav.channels, wav.sample_rate
+            )));
+        }
+
+        Ok(Self {
+            manifest,
+            config: config.ok_or_else(|| missing(CONFIG_FILE))?,
+            samples: wav.samples,
+            events: events.event,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> SessionBundle {
+        SessionBundle {
+            manifest: SessionManifest {
+                version: BUNDLE_VERSION,
+                vinput_version: "0.1.0".to_string(),
+                started_at: 1_760_000_000,
+                sample_rate: 16000,
+                frame_size: 4,
+                frames: 2,
+                truncated: false,
+            },
+            config: VInputConfig::default().to_toml_string().unwrap(),
+            samples: vec![0.0, 0.25, -0.5, 0.125, 0.3, -0.3, 0.0, 1.0],
+            events: vec![
+                SessionEvent::Vad { frame: 1, state: "Speech".to_string(), speech_prob: 0.875 },
+                SessionEvent::Partial { frame: 2, text: "今天天气".to_string() },
+                SessionEvent::Final {
+                    frame: 2,
+                    raw: "今天，天气很好。".to_string(),
+                    punctuation: Some(PunctuationDecision {
+                        plain_text: "今天天气很好".to_string(),
+                        commas: vec![2],
+                        ending: "。".to_string(),
+                    }),
+                },
+                SessionEvent::Final { frame: 2, raw: "好".to_string(), punctuation: None },
+                SessionEvent::Stop { frame: 2 },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions").join("a.vinput-session");
+        let original = bundle();
+        original.write_to(&path).unwrap();
+        assert!(!path.with_extension("part").exists());
+
+        let loaded = SessionBundle::read_from(&path).unwrap();
+        assert_eq!(loaded, original);
+        assert_eq!(loaded.frames().count(), 2);
+        assert_eq!(loaded.config().unwrap().recorder, VInputConfig::default().recorder);
+        assert_eq!(loaded.events[2].frame(), 2);
+    }
+
+    #[test]
+    fn test_read_rejects_newer_version_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("b.vinput-session");
+        let mut newer = bundle();
+        newer.manifest.version = BUNDLE_VERSION + 1;
+        newer.write_to(&path).unwrap();
+        assert!(SessionBundle::read_from(&path).is_err());
+
+        let mut builder = tar::Builder::new(std::fs::File::create(&path).unwrap());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        header.set_cksum();
+        builder.append_data(&mut header, MANIFEST_FILE, std::io::empty()).unwrap();
+        builder.finish().unwrap();
+        drop(builder);
+        assert!(SessionBundle::read_from(&path).is_err());
+    }
+}
@@ -0,0 +1,17 @@
+//! 会话录制模块（问题复现）
+//!
+//! 开启 `[recorder]` 后，每次录音送入管道的音频、VAD 判决、部分 / 最终结果、
+//! 标点决策与配置快照写入一个会话包（默认 `~/.local/share/vinput/sessions`）：
+//! - bundle：会话包格式（tar：manifest / config / audio.wav / events）
+//! - session：录音时逐帧记录，结束后写出并清理旧会话包
+//! - replay：用会话包重建管道回放，与现场记录逐条比对
+//!
+//! 用户可把会话包附在问题报告中，用 `examples/replay_session.rs` 复现。
+
+pub mod bundle;
+pub mod replay;
+pub mod session;
+
+pub use bundle::{SessionBundle, SessionEvent, SessionManifest, BUNDLE_EXTENSION};
+pub use replay::{compare, replay, Difference, ReplayReport};
+pub use session::{SessionRecorder, SessionStore};
@@ -0,0 +1,273 @@
+//! 会话回放与比对
+//!
+//! 用会话包中的配置（可替换模型目录等本机路径）重建 `StreamingPipeline`，逐帧送入录制的音频：
+//! - 在现场查询过部分结果的帧上同样查询（标点引擎的状态依赖查询时机）
+//! - 管道判定句子结束时取最终结果（收到停止信号之后的帧只送入管道，与现场一致）
+//! - 全部帧送完后按现场结束方式取最终结果或丢弃
+//!
+//! 回放产生的事件与录制的事件按类型逐条比对（VAD 只比对帧号与状态）。
+//! ITN 与长句流式上屏在管道之外，不参与比对。
+
+use super::bundle::{SessionBundle, SessionEvent};
+use super::session::SessionRecorder;
+use crate::error::VInputResult;
+use crate::streaming::{PipelineState, StreamingConfig, StreamingPipeline};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use vinput_config::VInputConfig;
+
+/// 一处差异
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// 事件类型（vad / comma / endpoint / partial / final）
+    pub kind: &'static str,
+    /// 该类型事件的序号（从 0 开始）
+    pub index: usize,
+    /// 现场记录（缺失时为 None）
+    pub recorded: Option<String>,
+    /// 回放结果（缺失时为 None）
+    pub replayed: Option<String>,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |event: &Option<String>| event.clone().unwrap_or_else(|| "（无）".to_string());
+        write!(
+            f,
+            "{}[{}]\n  现场: {}\n  回放: {}",
+            self.kind,
+            self.index,
+            show(&self.recorded),
+            show(&self.replayed)
+        )
+    }
+}
+
+/// 回放报告
+#[derive(Debug, Clone)]
+pub struct ReplayReport {
+    /// 回放的帧数
+    pub frames: u64,
+    /// 回放产生的事件
+    pub events: Vec<SessionEvent>,
+    /// 与现场记录的差异
+    pub differences: Vec<Difference>,
+    /// 回放无法复现的现场操作（存在时差异可能由此导致）
+    pub notes: Vec<String>,
+}
+
+impl ReplayReport {
+    /// 回放结果与现场一致
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// 回放得到的最终结果
+    pub fn finals(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                SessionEvent::Final { raw, .. } => Some(raw.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "回放 {} 帧，最终结果 {} 句", self.frames, self.finals().len())?;
+        for (i, text) in self.finals().iter().enumerate() {
+            writeln!(f, "  [{}] {}", i, text)?;
+        }
+        for note in &self.notes {
+            writeln!(f, "⚠️ {}", note)?;
+        }
+        if self.is_identical() {
+            writeln!(f, "✅ 与现场记录一致")
+        } else {
+            writeln!(f, "❌ {} 处差异:", self.differences.len())?;
+            for difference in &self.differences {
+                writeln!(f, "{}", difference)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 回放会话包并与现场记录比对
+///
+/// `config` 通常为 `bundle.config()`，可在回放前修改（如本机的模型目录）
+pub fn replay(bundle: &SessionBundle, config: &VInputConfig) -> VInputResult<ReplayReport> {
+    let mut pipeline = StreamingPipeline::new(StreamingConfig::from_config(config))?;
+    let mut recorder = SessionRecorder::new(
+        bundle.config.clone(),
+        bundle.manifest.sample_rate,
+        bundle.manifest.frame_size,
+        usize::MAX,
+    );
+
+    let partial_frames: BTreeSet<u64> = bundle
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::Partial { frame, .. } => Some(*frame),
+            _ => None,
+        })
+        .collect();
+    let stop_frame = bundle.events.iter().find_map(|event| match event {
+        SessionEvent::Stop { frame } => Some(*frame),
+        _ => None,
+    });
+    let cancelled = bundle.events.iter().any(|event| matches!(event, SessionEvent::Cancel { .. }));
+
+    for samples in bundle.frames() {
+        let result = pipeline.process(samples)?;
+        recorder.record_frame(samples, &result);
+        let frame = recorder.frames();
+
+        // 收到停止信号后现场只耗尽剩余音频，不处理结果
+        if stop_frame.is_some_and(|stop| frame > stop) {
+            continue;
+        }
+        if partial_frames.contains(&frame) {
+            recorder.record_partial(&pipeline.get_partial_result_with_punctuation());
+        }
+        if result.pipeline_state == PipelineState::Completed {
+            let raw = pipeline.get_final_result_with_punctuation();
+            recorder.record_final(&raw, pipeline.take_punctuation_decision());
+        }
+    }
+
+    if cancelled {
+        pipeline.discard_utterance();
+    } else if !bundle.manifest.truncated {
+        // 手动停止或自动停止后的停止录音：取剩余的最终结果
+        let raw = pipeline.get_final_result_with_punctuation();
+        recorder.record_final(&raw, pipeline.take_punctuation_decision());
+    }
+
+    let replayed = recorder.finish();
+    let mut notes: Vec<String> = bundle
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            SessionEvent::Note { frame, message } => Some(format!("第 {} 帧: {}", frame, message)),
+            _ => None,
+        })
+        .collect();
+    if bundle.manifest.truncated {
+        notes.push("会话包已截断，截断之后的音频与事件未录制".to_string());
+    }
+
+    Ok(ReplayReport {
+        frames: replayed.manifest.frames,
+        differences: compare(&bundle.events, &replayed.events),
+        events: replayed.events,
+        notes,
+    })
+}
+
+/// 按类型逐条比对两组事件
+pub fn compare(recorded: &[SessionEvent], replayed: &[SessionEvent]) -> Vec<Difference> {
+    let recorded = group_by_kind(recorded);
+    let replayed = group_by_kind(replayed);
+    let kinds: BTreeSet<&'static str> = recorded.keys().chain(replayed.keys()).copied().collect();
+
+    let mut differences = Vec::new();
+    for kind in kinds {
+        let empty = Vec::new();
+        let left = recorded.get(kind).unwrap_or(&empty);
+        let right = replayed.get(kind).unwrap_or(&empty);
+        for index in 0..left.len().max(right.len()) {
+            let (a, b) = (left.get(index), right.get(index));
+            if a != b {
+                differences.push(Difference {
+                    kind,
+                    index,
+                    recorded: a.cloned(),
+                    replayed: b.cloned(),
+                });
+            }
+        }
+    }
+    differences
+}
+
+/// 参与比对的事件按类型分组，每个事件描述为一行文本
+fn group_by_kind(events: &[SessionEvent]) -> BTreeMap<&'static str, Vec<String>> {
+    let mut groups: BTreeMap<&'static str, Vec<String>> = BTreeMap::new();
+    for event in events {
+        let (kind, description) = match event {
+            SessionEvent::Vad { frame, state, .. } => ("vad", format!("#{} {}", frame, state)),
+            SessionEvent::Comma { frame } => ("comma", format!("#{}", frame)),
+            SessionEvent::Endpoint { frame, trigger } => ("endpoint", format!("#{} {}", frame, trigger)),
+            SessionEvent::Partial { frame, text } => ("partial", format!("#{} '{}'", frame, text)),
+            SessionEvent::Final { frame, raw, punctuation } => {
+                let decision = punctuation
+                    .as_ref()
+                    .map(|p| format!(" 逗号位置={:?} 句尾='{}'", p.commas, p.ending))
+                    .unwrap_or_default();
+                ("final", format!("#{} '{}'{}", frame, raw, decision))
+            }
+            SessionEvent::Stop { .. } | SessionEvent::Cancel { .. } | SessionEvent::Note { .. } => continue,
+        };
+        groups.entry(kind).or_default().push(description);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::PunctuationDecision;
+
+    fn vad(frame: u64, state: &str, speech_prob: f32) -> SessionEvent {
+        SessionEvent::Vad { frame, state: state.to_string(), speech_prob }
+    }
+
+    fn final_result(frame: u64, raw: &str, commas: Vec<usize>) -> SessionEvent {
+        SessionEvent::Final {
+            frame,
+            raw: raw.to_string(),
+            punctuation: Some(PunctuationDecision {
+                plain_text: raw.replace(['，', '。'], ""),
+                commas,
+                ending: "。".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_compare_identical_ignores_speech_prob_and_control_events() {
+        let recorded = vec![
+            vad(1, "Speech", 0.9),
+            SessionEvent::Stop { frame: 3 },
+            final_result(5, "今天，天气很好。", vec![2]),
+        ];
+        let replayed = vec![vad(1, "Speech", 0.7), final_result(5, "今天，天气很好。", vec![2])];
+        assert!(compare(&recorded, &replayed).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_changed_and_missing_events() {
+        let recorded = vec![
+            vad(1, "Speech", 0.9),
+            SessionEvent::Endpoint { frame: 20, trigger: "VadSilence".to_string() },
+            final_result(20, "今天，天气很好。", vec![2]),
+            final_result(40, "出去走走。", vec![]),
+        ];
+        let replayed = vec![
+            vad(1, "Speech", 0.9),
+            SessionEvent::Endpoint { frame: 22, trigger: "VadSilence".to_string() },
+            final_result(22, "今天天气很好。", vec![]),
+        ];
+
+        let differences = compare(&recorded, &replayed);
+        let kinds: Vec<(&str, usize)> = differences.iter().map(|d| (d.kind, d.index)).collect();
+        assert_eq!(kinds, vec![("endpoint", 0), ("final", 0), ("final", 1)]);
+        assert_eq!(differences[1].recorded.as_deref(), Some("#20 '今天，天气很好。' 逗号位置=[2] 句尾='。'"));
+        assert!(differences[2].replayed.is_none());
+        assert!(differences[2].to_string().contains("回放: （无）"));
+    }
+}
@@ -0,0 +1,318 @@
+//! 录音会话录制与会话包目录
+
+use super::bundle::{SessionBundle, SessionEvent, SessionManifest, BUNDLE_EXTENSION, BUNDLE_VERSION};
+use crate::error::VInputResult;
+use crate::streaming::{PunctuationDecision, StreamingResult};
+use crate::vad::VadState;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use vinput_config::VInputConfig;
+
+/// 当前 Unix 时间（秒）
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 一次录音的录制器：随音频线程逐帧记录，结束后由 `SessionStore::save` 写出
+pub struct SessionRecorder {
+    manifest: SessionManifest,
+    config: String,
+    samples: Vec<f32>,
+    /// 最多录制的样本数
+    max_samples: usize,
+    events: Vec<SessionEvent>,
+    /// 上一帧的 VAD 状态（只记录状态变化）
+    last_vad: Option<VadState>,
+}
+
+impl SessionRecorder {
+    /// 开始录制；`config` 为配置快照（TOML 文本），`max_samples` 为最多录制的样本数
+    pub fn new(config: String, sample_rate: u32, frame_size: usize, max_samples: usize) -> Self {
+        Self {
+            manifest: SessionManifest {
+                version: BUNDLE_VERSION,
+                vinput_version: env!("CARGO_PKG_VERSION").to_string(),
+                started_at: unix_now(),
+                sample_rate,
+                frame_size,
+                frames: 0,
+                truncated: false,
+            },
+            config,
+            samples: Vec::new(),
+            max_samples,
+            events: Vec::new(),
+            last_vad: None,
+        }
+    }
+
+    /// 已录制的帧数
+    pub fn frames(&self) -> u64 {
+        self.manifest.frames
+    }
+
+    /// 是否已达到最长录制时长
+    pub fn is_truncated(&self) -> bool {
+        self.manifest.truncated
+    }
+
+    /// 记录送入管道的一帧及其处理结果
+    pub fn record_frame(&mut self, samples: &[f32], result: &StreamingResult) {
+        if self.manifest.truncated {
+            return;
+        }
+        if self.samples.len() + samples.len() > self.max_samples {
+            tracing::warn!("会话录制达到最长时长，之后的音频与事件不再录制");
+            self.manifest.truncated = true;
+            return;
+        }
+
+        self.samples.extend_from_slice(samples);
+        self.manifest.frames += 1;
+        let frame = self.manifest.frames;
+
+        if self.last_vad != Some(result.vad_state) {
+            self.last_vad = Some(result.vad_state);
+            self.events.push(SessionEvent::Vad {
+                frame,
+                state: format!("{:?}", result.vad_state),
+                speech_prob: result.speech_prob,
+            });
+        }
+        if result.should_add_comma {
+            self.events.push(SessionEvent::Comma { frame });
+        }
+        if let Some(trigger) = result.endpoint {
+            self.events.push(SessionEvent::Endpoint { frame, trigger: format!("{:?}", trigger) });
+        }
+    }
+
+    /// 记录一次 Preedit 部分结果查询（文本为空也记录，回放时在同一帧查询）
+    pub fn record_partial(&mut self, text: &str) {
+        let frame = self.manifest.frames;
+        self.push(SessionEvent::Partial { frame, text: text.to_string() });
+    }
+
+    /// 记录最终结果（空结果不记录）
+    pub fn record_final(&mut self, raw: &str, punctuation: Option<PunctuationDecision>) {
+        if raw.is_empty() {
+            return;
+        }
+        let frame = self.manifest.frames;
+        self.push(SessionEvent::Final { frame, raw: raw.to_string(), punctuation });
+    }
+
+    /// 记录停止信号
+    pub fn record_stop(&mut self) {
+        let frame = self.manifest.frames;
+        self.push(SessionEvent::Stop { frame });
+    }
+
+    /// 记录取消录音
+    pub fn record_cancel(&mut self) {
+        let frame = self.manifest.frames;
+        self.push(SessionEvent::Cancel { frame });
+    }
+
+    /// 记录回放无法复现的现场操作
+    pub fn record_note(&mut self, message: impl Into<String>) {
+        let frame = self.manifest.frames;
+        self.push(SessionEvent::Note { frame, message: message.into() });
+    }
+
+    /// 截断后不再记录事件（对应的音频已不在会话包中）
+    fn push(&mut self, event: SessionEvent) {
+        if !self.manifest.truncated {
+            self.events.push(event);
+        }
+    }
+
+    /// 结束录制
+    pub fn finish(self) -> SessionBundle {
+        SessionBundle {
+            manifest: self.manifest,
+            config: self.config,
+            samples: self.samples,
+            events: self.events,
+        }
+    }
+}
+
+/// 会话包目录：按配置开始录制、写出会话包并清理旧会话包
+#[derive(Debug, Clone)]
+pub struct SessionStore {
+    dir: PathBuf,
+    max_bundles: usize,
+    max_duration_secs: u64,
+    /// 配置快照（TOML 文本）
+    config: String,
+}
+
+impl SessionStore {
+    /// 按 `[recorder]` 配置创建（未启用或无法获取数据目录时为 None）
+    pub fn from_config(config: &VInputConfig) -> Option<Self> {
+        if !config.recorder.enabled {
+            return None;
+        }
+        let Some(dir) = config.recorder.dir_path() else {
+            tracing::warn!("无法获取数据目录，未启用会话录制");
+            return None;
+        };
+        let snapshot = match config.to_toml_string() {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                tracing::warn!("配置快照生成失败: {}，未启用会话录制", e);
+                return None;
+            }
+        };
+        tracing::info!("🎞️ 会话录制已启用: {}", dir.display());
+        Some(Self {
+            dir,
+            max_bundles: config.recorder.max_bundles,
+            max_duration_secs: config.recorder.max_duration_secs,
+            config: snapshot,
+        })
+    }
+
+    /// 会话包目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 开始录制一次录音
+    pub fn start(&self, sample_rate: u32, frame_size: usize) -> SessionRecorder {
+        let max_samples = (self.max_duration_secs * sample_rate as u64) as usize;
+        SessionRecorder::new(self.config.clone(), sample_rate, frame_size, max_samples)
+    }
+
+    /// 写出会话包并删除超出 `max_bundles` 的最旧会话包，返回会话包路径
+    pub fn save(&self, recorder: SessionRecorder) -> VInputResult<PathBuf> {
+        let bundle = recorder.finish();
+        let stem = format!("session-{}", bundle.manifest.started_at);
+        let mut path = self.dir.join(format!("{}.{}", stem, BUNDLE_EXTENSION));
+        let mut n = 1;
+        while path.exists() {
+            path = self.dir.join(format!("{}-{}.{}", stem, n, BUNDLE_EXTENSION));
+            n += 1;
+        }
+        bundle.write_to(&path)?;
+
+        let bundles = self.list();
+        for old in bundles.iter().take(bundles.len().saturating_sub(self.max_bundles)) {
+            if let Err(e) = std::fs::remove_file(old) {
+                tracing::warn!("删除旧会话包失败 {}: {}", old.display(), e);
+            }
+        }
+        Ok(path)
+    }
+
+    /// 已保存的会话包（从旧到新）
+    pub fn list(&self) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut bundles: Vec<(SystemTime, (u64, u32), PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == BUNDLE_EXTENSION))
+            .map(|path| {
+                let modified = path
+                    .metadata()
+                    .and_then(|m| m.modified())
+                    .unwrap_or(UNIX_EPOCH);
+                (modified, bundle_order(&path), path)
+            })
+            .collect();
+        bundles.sort();
+        bundles.into_iter().map(|(_, _, path)| path).collect()
+    }
+}
+
+/// 由文件名 `session-<开始时间>[-<序号>]` 得到的先后顺序（修改时间相同时使用）
+fn bundle_order(path: &Path) -> (u64, u32) {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let mut parts = stem.trim_start_matches("session-").splitn(2, '-');
+    let started_at = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    let n = parts.next().and_then(|s| s.parse().ok()).unwrap_or(0);
+    (started_at, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::streaming::{EndpointTrigger, PipelineState};
+
+    fn result(vad_state: VadState, endpoint: Option<EndpointTrigger>) -> StreamingResult {
+        StreamingResult {
+            partial_result: String::new(),
+            stable_text: String::new(),
+            unstable_text: String::new(),
+            should_add_comma: false,
+            endpoint,
+            is_final: false,
+            vad_state,
+            pipeline_state: PipelineState::Recognizing,
+            speech_prob: 0.5,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_recorder_logs_state_changes_and_truncates() {
+        let mut recorder = SessionRecorder::new(String::new(), 16000, 2, 6);
+        recorder.record_frame(&[0.1, 0.2], &result(VadState::Speech, None));
+        recorder.record_frame(&[0.1, 0.2], &result(VadState::Speech, None));
+        recorder.record_partial("你好");
+        recorder.record_frame(&[0.0, 0.0], &result(VadState::Silence, Some(EndpointTrigger::VadSilence)));
+        recorder.record_final("你好。", None);
+        recorder.record_final("", None);
+        assert!(!recorder.is_truncated());
+
+        recorder.record_frame(&[0.0, 0.0], &result(VadState::Silence, None));
+        assert!(recorder.is_truncated());
+        recorder.record_stop();
+
+        let bundle = recorder.finish();
+        assert_eq!(bundle.manifest.frames, 3);
+        assert!(bundle.manifest.truncated);
+        assert_eq!(bundle.samples.len(), 6);
+        assert_eq!(
+            bundle.events,
+            vec![
+                SessionEvent::Vad { frame: 1, state: "Speech".to_string(), speech_prob: 0.5 },
+                SessionEvent::Partial { frame: 2, text: "你好".to_string() },
+                SessionEvent::Vad { frame: 3, state: "Silence".to_string(), speech_prob: 0.5 },
+                SessionEvent::Endpoint { frame: 3, trigger: "VadSilence".to_string() },
+                SessionEvent::Final { frame: 3, raw: "你好。".to_string(), punctuation: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_store_saves_and_prunes_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = VInputConfig::default();
+        assert!(SessionStore::from_config(&config).is_none());
+
+        config.recorder.enabled = true;
+        config.recorder.dir = dir.path().to_string_lossy().into_owned();
+        config.recorder.max_bundles = 2;
+        let store = SessionStore::from_config(&config).unwrap();
+
+        let saved: Vec<PathBuf> = (0..3)
+            .map(|_| store.save(store.start(16000, 512)).unwrap())
+            .collect();
+        assert_ne!(saved[0], saved[1]);
+
+        let remaining = store.list();
+        assert_eq!(remaining.len(), 2);
+        assert!(!saved[0].exists());
+        assert!(saved[2].exists());
+
+        let bundle = SessionBundle::read_from(&saved[2]).unwrap();
+        assert!(bundle.config().unwrap().recorder.enabled);
+    }
+}
@@ -0,0 +1,396 @@
+//! 错误恢复监督
+//!
+//! 按 `VInputError::recovery_strategy()` 处理录音会话中的错误：
+//! - Retry：指数退避后重新运行（PipeWire 断流、ASR / VAD 推理失败等瞬时错误）
+//! - Degrade：关闭出错的可选功能（ITN、热词、语音增强、首选设备）后继续
+//! - UserAction：停止会话，把错误码与提示上报前端
+//! - Restart：重建识别管道后继续
+//!
+//! 重试 / 重启次数用尽或同一降级再次出错时，升级为上报前端。
+
+use crate::error::{RecoveryStrategy, VInputError, VInputResult};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub use vinput_config::recovery::RecoveryConfig;
+
+/// 连续运行超过该时长视为已恢复，重新累计重试次数
+const STABLE_RUN: Duration = Duration::from_secs(10);
+/// 退避等待期间检查停止请求的间隔
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// 降级措施
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Degradation {
+    /// 关闭 ITN，直接上屏原始识别文本
+    DisableItn,
+    /// 去掉热词重建识别器
+    DisableHotwords,
+    /// 关闭语音增强（降低音频线程负载）
+    DisableEnhancement,
+    /// 忽略首选设备，使用默认音频源
+    DefaultDevice,
+}
+
+impl Degradation {
+    /// Degrade 类错误对应的降级措施
+    pub fn for_error(error: &VInputError) -> Option<Self> {
+        match error {
+            VInputError::ItnConversion(_) => Some(Self::DisableItn),
+            VInputError::Hotword(_) => Some(Self::DisableHotwords),
+            VInputError::RingBufferOverrun { .. } => Some(Self::DisableEnhancement),
+            VInputError::AudioDeviceNotFound(_) => Some(Self::DefaultDevice),
+            _ => None,
+        }
+    }
+}
+
+/// 上报前端的错误（`to_payload()` 作为 Error 命令的 text）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    /// 错误码（如 `E2003`）
+    pub code: &'static str,
+    /// 用户可读的提示
+    pub message: String,
+}
+
+impl ErrorReport {
+    pub fn from_error(error: &VInputError) -> Self {
+        Self {
+            code: error.error_code(),
+            message: error.user_message(),
+        }
+    }
+
+    /// 命令文本：`code\tmessage`
+    pub fn to_payload(&self) -> String {
+        format!("{}\t{}", self.code, self.message)
+    }
+}
+
+/// 监督器对一次错误的处理决定
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecoveryAction {
+    /// 等待 `delay` 后重新运行（`attempt` 从 1 开始）
+    Retry { attempt: u32, delay: Duration },
+    /// 执行降级措施后重新运行
+    Degrade(Degradation),
+    /// 重建管道后重新运行
+    Restart,
+    /// 无法自动恢复，停止并上报前端
+    Report(ErrorReport),
+}
+
+/// 受监督的任务（录音会话）
+pub trait Supervised {
+    /// 运行直到正常结束（Ok）或出错
+    fn run(&mut self) -> VInputResult<()>;
+
+    /// 执行降级措施
+    fn degrade(&mut self, degradation: Degradation) -> VInputResult<()>;
+
+    /// 重建管道
+    fn restart(&mut self) -> VInputResult<()>;
+
+    /// 是否已请求停止（退避等待期间轮询）
+    fn is_stopping(&self) -> bool;
+}
+
+/// 错误恢复监督器（每个录音会话一个）
+#[derive(Debug)]
+pub struct RecoverySupervisor {
+    config: RecoveryConfig,
+    /// 连续重试次数
+    retries: u32,
+    /// 已重建管道次数
+    restarts: u32,
+    /// 已执行的降级措施
+    degraded: Vec<Degradation>,
+}
+
+impl RecoverySupervisor {
+    pub fn new(config: RecoveryConfig) -> Self {
+        Self {
+            config,
+            retries: 0,
+            restarts: 0,
+            degraded: Vec::new(),
+        }
+    }
+
+    /// 根据错误的恢复策略决定处理方式
+    pub fn on_error(&mut self, error: &VInputError) -> RecoveryAction {
+        error.log();
+
+        match error.recovery_strategy() {
+            RecoveryStrategy::Retry => {
+                if self.retries >= self.config.max_retries {
+                    tracing::error!("重试 {} 次仍失败，放弃恢复", self.retries);
+                    return RecoveryAction::Report(ErrorReport::from_error(error));
+                }
+                self.retries += 1;
+                RecoveryAction::Retry {
+                    attempt: self.retries,
+                    delay: self.backoff(self.retries),
+                }
+            }
+            RecoveryStrategy::Degrade => match Degradation::for_error(error) {
+                Some(degradation) if !self.degraded.contains(&degradation) => {
+                    self.degraded.push(degradation);
+                    RecoveryAction::Degrade(degradation)
+                }
+                // 已降级仍出错，或没有可用的降级措施
+                _ => RecoveryAction::Report(ErrorReport::from_error(error)),
+            },
+            RecoveryStrategy::UserAction => RecoveryAction::Report(ErrorReport::from_error(error)),
+            RecoveryStrategy::Restart => {
+                if self.restarts >= self.config.max_restarts {
+                    tracing::error!("重建管道 {} 次仍失败，放弃恢复", self.restarts);
+                    return RecoveryAction::Report(ErrorReport::from_error(error));
+                }
+                self.restarts += 1;
+                // 新管道重新累计重试次数
+                self.retries = 0;
+                RecoveryAction::Restart
+            }
+        }
+    }
+
+    /// 任务已稳定运行，重新累计重试次数
+    pub fn on_recovered(&mut self) {
+        self.retries = 0;
+    }
+
+    /// 是否已执行过该降级措施
+    pub fn is_degraded(&self, degradation: Degradation) -> bool {
+        self.degraded.contains(&degradation)
+    }
+
+    /// 第 `attempt` 次重试前的等待时长
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        let delay_ms = self
+            .config
+            .initial_backoff_ms
+            .saturating_mul(factor)
+            .min(self.config.max_backoff_ms);
+        Duration::from_millis(delay_ms)
+    }
+
+    /// 运行任务并按恢复策略处理错误，直到正常结束或需要上报前端
+    ///
+    /// 等待重试期间收到停止请求时视为正常结束
+    pub fn supervise(&mut self, task: &mut impl Supervised) -> Result<(), ErrorReport> {
+        let mut outcome = self.run_task(task);
+        loop {
+            let error = match outcome {
+                Ok(()) => return Ok(()),
+                Err(_) if task.is_stopping() => return Ok(()),
+                Err(error) => error,
+            };
+
+            outcome = match self.on_error(&error) {
+                RecoveryAction::Retry { attempt, delay } => {
+                    tracing::warn!("🔁 {:?} 后第 {} 次重试", delay, attempt);
+                    if !wait_unless_stopping(task, delay) {
+                        return Ok(());
+                    }
+                    self.run_task(task)
+                }
+                RecoveryAction::Degrade(degradation) => {
+                    tracing::warn!("⬇️ 降级: {:?}", degradation);
+                    task.degrade(degradation).and_then(|()| self.run_task(task))
+                }
+                RecoveryAction::Restart => {
+                    tracing::warn!("🔄 重建管道（第 {} 次）", self.restarts);
+                    task.restart().and_then(|()| self.run_task(task))
+                }
+                RecoveryAction::Report(report) => return Err(report),
+            };
+        }
+    }
+
+    /// 运行一次任务；出错前已稳定运行足够久则重新累计重试次数
+    fn run_task(&mut self, task: &mut impl Supervised) -> VInputResult<()> {
+        let started = Instant::now();
+        let result = task.run();
+        if result.is_err() && started.elapsed() >= STABLE_RUN {
+            self.on_recovered();
+        }
+        result
+    }
+}
+
+/// 等待 `delay`，期间收到停止请求则提前返回 false
+fn wait_unless_stopping(task: &impl Supervised, delay: Duration) -> bool {
+    let deadline = Instant::now() + delay;
+    loop {
+        if task.is_stopping() {
+            return false;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return true;
+        }
+        thread::sleep(STOP_POLL_INTERVAL.min(deadline - now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// 按脚本依次返回结果的故障注入任务
+    #[derive(Default)]
+    struct FaultyTask {
+        runs: VecDeque<VInputResult<()>>,
+        restart_failures: VecDeque<VInputError>,
+        run_count: usize,
+        degraded: Vec<Degradation>,
+        restart_count: usize,
+        stopping: bool,
+    }
+
+    impl FaultyTask {
+        fn new(runs: Vec<VInputResult<()>>) -> Self {
+            Self {
+                runs: runs.into(),
+                ..Self::default()
+            }
+        }
+    }
+
+    impl Supervised for FaultyTask {
+        fn run(&mut self) -> VInputResult<()> {
+            self.run_count += 1;
+            self.runs.pop_front().unwrap_or(Ok(()))
+        }
+
+        fn degrade(&mut self, degradation: Degradation) -> VInputResult<()> {
+            self.degraded.push(degradation);
+            Ok(())
+        }
+
+        fn restart(&mut self) -> VInputResult<()> {
+            self.restart_count += 1;
+            self.restart_failures.pop_front().map_or(Ok(()), Err)
+        }
+
+        fn is_stopping(&self) -> bool {
+            self.stopping
+        }
+    }
+
+    fn supervisor() -> RecoverySupervisor {
+        RecoverySupervisor::new(RecoveryConfig {
+            initial_backoff_ms: 0,
+            ..RecoveryConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_retry_transient_errors() {
+        let mut task = FaultyTask::new(vec![
+            Err(VInputError::PipeWire("pw-record 进程意外结束".to_string())),
+            Err(VInputError::AsrInference("decode failed".to_string())),
+            Ok(()),
+        ]);
+        assert_eq!(supervisor().supervise(&mut task), Ok(()));
+        assert_eq!(task.run_count, 3);
+        assert_eq!(task.restart_count, 0);
+
+        // 重试次数用尽后上报
+        let mut task = FaultyTask::new(
+            (0..10)
+                .map(|_| Err(VInputError::AsrInference("decode failed".to_string())))
+                .collect(),
+        );
+        let report = supervisor().supervise(&mut task).unwrap_err();
+        assert_eq!(report.code, "E2002");
+        assert_eq!(task.run_count, 4);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_limit() {
+        let mut supervisor = RecoverySupervisor::new(RecoveryConfig {
+            max_retries: 5,
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 3000,
+            ..RecoveryConfig::default()
+        });
+        let error = VInputError::PipeWire("broken pipe".to_string());
+        let delays: Vec<u64> = (0..5)
+            .map(|_| match supervisor.on_error(&error) {
+                RecoveryAction::Retry { delay, .. } => delay.as_millis() as u64,
+                other => panic!("unexpected action: {:?}", other),
+            })
+            .collect();
+        assert_eq!(delays, vec![1000, 2000, 3000, 3000, 3000]);
+
+        supervisor.on_recovered();
+        assert!(matches!(
+            supervisor.on_error(&error),
+            RecoveryAction::Retry { attempt: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_degrade_once_then_report() {
+        let mut task = FaultyTask::new(vec![
+            Err(VInputError::ItnConversion("bad number".to_string())),
+            Err(VInputError::Hotword("bad hotword".to_string())),
+            Ok(()),
+        ]);
+        let mut supervisor = supervisor();
+        assert_eq!(supervisor.supervise(&mut task), Ok(()));
+        assert_eq!(task.degraded, vec![Degradation::DisableItn, Degradation::DisableHotwords]);
+        assert!(supervisor.is_degraded(Degradation::DisableItn));
+
+        // 降级后仍出同样的错误
+        let mut task = FaultyTask::new(vec![
+            Err(VInputError::AudioDeviceNotFound("usb-mic".to_string())),
+            Err(VInputError::AudioDeviceNotFound("usb-mic".to_string())),
+        ]);
+        let report = supervisor.supervise(&mut task).unwrap_err();
+        assert_eq!(report.code, "E1002");
+        assert_eq!(task.degraded, vec![Degradation::DefaultDevice]);
+    }
+
+    #[test]
+    fn test_user_action_reported_immediately() {
+        let mut task = FaultyTask::new(vec![Err(VInputError::RecognizerNotReady)]);
+        let report = supervisor().supervise(&mut task).unwrap_err();
+        assert_eq!(task.run_count, 1);
+        assert_eq!(report.code, "E2003");
+        assert!(report.to_payload().starts_with("E2003\t"));
+    }
+
+    #[test]
+    fn test_restart_until_limit() {
+        let null = || VInputError::NullPointer {
+            param: "asr_stream".to_string(),
+        };
+
+        let mut task = FaultyTask::new(vec![Err(null()), Ok(())]);
+        assert_eq!(supervisor().supervise(&mut task), Ok(()));
+        assert_eq!(task.restart_count, 1);
+        assert_eq!(task.run_count, 2);
+
+        // 重建本身失败也计入次数
+        let mut task = FaultyTask::new(vec![Err(null())]);
+        task.restart_failures = vec![null(), null()].into();
+        let report = supervisor().supervise(&mut task).unwrap_err();
+        assert_eq!(report.code, "E7001");
+        assert_eq!(task.restart_count, 2);
+        assert_eq!(task.run_count, 1);
+    }
+
+    #[test]
+    fn test_stop_request_ends_supervision() {
+        let mut task = FaultyTask::new(vec![Err(VInputError::AsrInference("decode failed".to_string()))]);
+        task.stopping = true;
+        assert_eq!(supervisor().supervise(&mut task), Ok(()));
+        assert_eq!(task.run_count, 1);
+    }
+}
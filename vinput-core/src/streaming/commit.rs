@@ -0,0 +1,293 @@
+//! 长句流式上屏
+//!
+//! 长时间连续说话时，端点检测触发前所有文本都停留在 Preedit。
+//! 本模块在 Preedit 中找到已稳定若干次更新、且以标点边界结尾的前缀，
+//! 将其提前上屏，Preedit 中只保留尾部。
+//!
+//! - 只在稳定片段内、标点边界处切分，避免数字被拆开后 ITN 只转换一半
+//! - 整句结束时返回整句已上屏文本，供撤销历史作为一条记录
+
+use crate::itn::Tokenizer;
+use crate::streaming::preedit::{PreeditSegment, SegmentKind};
+
+pub use vinput_config::streaming::StreamingCommitConfig;
+
+/// 整句结束时的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitFinish {
+    /// 最终结果中尚未上屏的部分（未经 ITN）
+    pub remainder: String,
+    /// 本句已提前上屏的文本（ITN 后）
+    pub committed: String,
+}
+
+/// 流式上屏器
+pub struct StreamingCommitter {
+    config: StreamingCommitConfig,
+    /// 当前候选前缀（未上屏部分中，到最后一个标点边界为止）
+    candidate: String,
+    /// 候选前缀连续保持不变的更新次数
+    stable_count: u32,
+    /// 已上屏的非标点字符数（用于在后续结果中跳过已上屏部分）
+    committed_chars: usize,
+    /// 已上屏文本（ITN 后）
+    committed_output: String,
+}
+
+impl StreamingCommitter {
+    /// 创建流式上屏器
+    pub fn new(config: StreamingCommitConfig) -> Self {
+        Self {
+            config,
+            candidate: String::new(),
+            stable_count: 0,
+            committed_chars: 0,
+            committed_output: String::new(),
+        }
+    }
+
+    /// 是否启用
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// 本句是否已有文本提前上屏
+    pub fn has_committed(&self) -> bool {
+        self.committed_chars > 0
+    }
+
+    /// 根据最新 Preedit 分段判断是否提前上屏
+    ///
+    /// `itn` 用于规范化待上屏文本；返回需要通过 CommitText 上屏的文本
+    pub fn update(
+        &mut self,
+        segments: &[PreeditSegment],
+        itn: impl FnOnce(&str) -> String,
+    ) -> Option<String> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let pending = self.pending_segments(segments);
+        let candidate = Self::find_candidate(&pending);
+
+        if candidate.is_empty() || candidate != self.candidate {
+            self.candidate = candidate;
+            self.stable_count = u32::from(!self.candidate.is_empty());
+            return None;
+        }
+
+        self.stable_count += 1;
+        let chars = plain_char_count(&self.candidate);
+        if self.stable_count < self.config.stable_updates || chars < self.config.min_commit_chars {
+            return None;
+        }
+
+        let raw = std::mem::take(&mut self.candidate);
+        self.stable_count = 0;
+        self.committed_chars += chars;
+
+        let output = itn(&raw);
+        tracing::info!("📤 流式上屏: [{}] → [{}]", raw, output);
+        self.committed_output.push_str(&output);
+        Some(output)
+    }
+
+    /// 去掉已上屏前缀后的 Preedit 分段
+    pub fn pending_segments(&self, segments: &[PreeditSegment]) -> Vec<PreeditSegment> {
+        if self.committed_chars == 0 {
+            return segments.to_vec();
+        }
+
+        let mut to_skip = self.committed_chars;
+        let mut skipping_punct = true;
+        let mut result: Vec<PreeditSegment> = Vec::new();
+
+        for segment in segments {
+            let mut text = String::new();
+            for ch in segment.text.chars() {
+                if to_skip > 0 {
+                    if !Tokenizer::is_punctuation(ch) {
+                        to_skip -= 1;
+                    }
+                    continue;
+                }
+                // 跳过紧随已上屏前缀的标点
+                if skipping_punct && Tokenizer::is_punctuation(ch) {
+                    continue;
+                }
+                skipping_punct = false;
+                text.push(ch);
+            }
+            if !text.is_empty() {
+                result.push(PreeditSegment { text, kind: segment.kind });
+            }
+        }
+
+        result
+    }
+
+    /// 整句结束：从最终结果中去掉已上屏部分，并重置状态
+    pub fn finish(&mut self, final_text: &str) -> CommitFinish {
+        let remainder = if self.committed_chars == 0 {
+            final_text.to_string()
+        } else {
+            let segment = PreeditSegment {
+                text: final_text.to_string(),
+                kind: SegmentKind::Stable,
+            };
+            self.pending_segments(std::slice::from_ref(&segment))
+                .into_iter()
+                .map(|s| s.text)
+                .collect()
+        };
+
+        let committed = std::mem::take(&mut self.committed_output);
+        self.reset();
+
+        CommitFinish { remainder, committed }
+    }
+
+    /// 重置状态（新会话开始）
+    pub fn reset(&mut self) {
+        self.candidate.clear();
+        self.stable_count = 0;
+        self.committed_chars = 0;
+        self.committed_output.clear();
+    }
+
+    /// 在未上屏分段中寻找候选前缀：稳定区内最后一个标点边界（含标点）之前的文本
+    fn find_candidate(pending: &[PreeditSegment]) -> String {
+        let mut prefix = String::new();
+        let mut confirmed_len = 0;
+        // 紧跟在数字后的边界需等后续文本确认不是同一个数字（如 "一千，二百"）
+        let mut tentative_len: Option<usize> = None;
+        let mut prev_char: Option<char> = None;
+
+        'outer: for segment in pending {
+            if segment.kind == SegmentKind::Unstable {
+                break;
+            }
+            for ch in segment.text.chars() {
+                if Tokenizer::is_punctuation(ch) {
+                    if prev_char.is_some_and(is_chinese_number_char) {
+                        tentative_len = Some(prefix.len() + ch.len_utf8());
+                    } else if tentative_len.is_none() {
+                        confirmed_len = prefix.len() + ch.len_utf8();
+                    }
+                } else if let Some(len) = tentative_len.take() {
+                    if is_chinese_number_char(ch) {
+                        break 'outer;
+                    }
+                    confirmed_len = len;
+                }
+                prefix.push(ch);
+                prev_char = Some(ch);
+            }
+        }
+
+        prefix.truncate(confirmed_len);
+        prefix
+    }
+}
+
+/// 非标点字符数
+fn plain_char_count(text: &str) -> usize {
+    text.chars().filter(|c| !Tokenizer::is_punctuation(*c)).count()
+}
+
+/// 是否为中文数字字符（与 StreamingPipeline 的 ITN 判定一致）
+fn is_chinese_number_char(c: char) -> bool {
+    matches!(c,
+        '零' | '一' | '二' | '三' | '四' | '五' | '六' | '七' | '八' | '九' |
+        '十' | '百' | '千' | '万' | '亿' | '点'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> StreamingCommitConfig {
+        StreamingCommitConfig {
+            enabled: true,
+            stable_updates: 3,
+            min_commit_chars: 2,
+        }
+    }
+
+    fn segments(stable: &str, unstable: &str) -> Vec<PreeditSegment> {
+        vec![
+            PreeditSegment { text: stable.to_string(), kind: SegmentKind::Stable },
+            PreeditSegment { text: unstable.to_string(), kind: SegmentKind::Unstable },
+        ]
+    }
+
+    #[test]
+    fn test_commits_stable_prefix_after_n_updates() {
+        let mut committer = StreamingCommitter::new(config());
+        let preedit = segments("今天天气不错，我们", "出去");
+
+        assert_eq!(committer.update(&preedit, str::to_string), None);
+        assert_eq!(committer.update(&preedit, str::to_string), None);
+        assert_eq!(
+            committer.update(&preedit, str::to_string),
+            Some("今天天气不错，".to_string())
+        );
+
+        // 已上屏部分从 Preedit 中去掉
+        let pending = committer.pending_segments(&segments("今天天气不错，我们出去", "玩吧"));
+        assert_eq!(pending[0].text, "我们出去");
+        assert_eq!(pending[1].text, "玩吧");
+    }
+
+    #[test]
+    fn test_changing_prefix_resets_stability() {
+        let mut committer = StreamingCommitter::new(config());
+        committer.update(&segments("今天天气，", "不错"), str::to_string);
+        committer.update(&segments("今天天气，", "不错"), str::to_string);
+        // 解码修正了前缀
+        assert_eq!(committer.update(&segments("今天的天气，", "不错"), str::to_string), None);
+        assert_eq!(committer.update(&segments("今天的天气，", "不错"), str::to_string), None);
+        assert!(committer.update(&segments("今天的天气，", "不错"), str::to_string).is_some());
+    }
+
+    #[test]
+    fn test_no_commit_without_boundary_or_when_disabled() {
+        let mut committer = StreamingCommitter::new(config());
+        for _ in 0..5 {
+            assert_eq!(committer.update(&segments("今天天气不错", "我们"), str::to_string), None);
+        }
+
+        let mut disabled = StreamingCommitter::new(StreamingCommitConfig::default());
+        for _ in 0..10 {
+            assert_eq!(disabled.update(&segments("今天天气，不错", "我们"), str::to_string), None);
+        }
+    }
+
+    #[test]
+    fn test_number_across_boundary_not_split() {
+        let mut committer = StreamingCommitter::new(config());
+        for _ in 0..5 {
+            assert_eq!(committer.update(&segments("一千，二百", "块钱"), str::to_string), None);
+        }
+    }
+
+    #[test]
+    fn test_itn_applied_and_finish_returns_remainder() {
+        let mut committer = StreamingCommitter::new(config());
+        let preedit = segments("我买了三个苹果，然后", "回家");
+        let itn = |s: &str| s.replace("三个", "3个");
+
+        committer.update(&preedit, itn);
+        committer.update(&preedit, itn);
+        assert_eq!(committer.update(&preedit, itn), Some("我买了3个苹果，".to_string()));
+        assert!(committer.has_committed());
+
+        // 最终结果的标点可能与 Preedit 不同，按非标点字数跳过
+        let finish = committer.finish("我买了三个苹果，然后回家了。");
+        assert_eq!(finish.remainder, "然后回家了。");
+        assert_eq!(finish.committed, "我买了3个苹果，");
+        assert!(!committer.has_committed());
+    }
+}
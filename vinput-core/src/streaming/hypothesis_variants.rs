@@ -0,0 +1,212 @@
+//! 部分假设变体
+//!
+//! sherpa-onnx 在线识别器的 C API 只返回最优路径（modified_beam_search 的其余
+//! 路径不对外暴露），拿不到解码器的 N-best。这里在流式解码过程中记录被后续
+//! 解码推翻的部分假设，整句结束时取与最终结果等长、逐字对齐的假设作为变体，
+//! 再加上原始（未 ITN）文本等，组成上屏后的候选列表。
+//!
+//! - 候选 0 始终是已上屏的文本
+//! - 没有替代假设或原文变体时不提供候选（仅去掉句尾标点不足以打开候选框）
+//! - 替代假设沿用最终结果的标点位置，并同样经过 ITN
+//! - 已流式上屏的句子不提供候选（前端无法整句替换）
+
+use crate::itn::Tokenizer;
+use std::collections::VecDeque;
+
+pub use vinput_config::streaming::HypothesisVariantsConfig;
+
+/// 部分假设记录器
+///
+/// 记录解码过程中出现过的不同部分结果（纯文本，不含标点）
+#[derive(Debug, Clone)]
+pub struct HypothesisTracker {
+    recent: VecDeque<String>,
+    capacity: usize,
+}
+
+impl HypothesisTracker {
+    /// 创建记录器
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            recent: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// 记录一次部分结果（与上一次相同时忽略）
+    pub fn observe(&mut self, partial: &str) {
+        if self.capacity == 0 || partial.is_empty() {
+            return;
+        }
+        if self.recent.back().is_some_and(|last| last == partial) {
+            return;
+        }
+        if self.recent.len() == self.capacity {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(partial.to_string());
+    }
+
+    /// 与最终结果等长但内容不同的假设（最近的在前，已去重）
+    ///
+    /// 等长保证可以逐字对齐，沿用最终结果的标点
+    pub fn variants_for(&self, final_plain: &str) -> Vec<String> {
+        let target_len = final_plain.chars().count();
+        let mut variants: Vec<String> = Vec::new();
+        for hypothesis in self.recent.iter().rev() {
+            if hypothesis == final_plain || hypothesis.chars().count() != target_len {
+                continue;
+            }
+            if !variants.contains(hypothesis) {
+                variants.push(hypothesis.clone());
+            }
+        }
+        variants
+    }
+
+    /// 清空记录（新句子开始）
+    pub fn reset(&mut self) {
+        self.recent.clear();
+    }
+}
+
+/// 将最终结果的标点移植到逐字对齐的替代假设上
+///
+/// 替代假设的非标点字数须与 `punctuated` 一致，否则返回 None
+pub fn transplant_punctuation(punctuated: &str, plain: &str) -> Option<String> {
+    let mut plain_chars = plain.chars().filter(|c| !Tokenizer::is_punctuation(*c));
+    let mut result = String::with_capacity(punctuated.len());
+    for ch in punctuated.chars() {
+        if Tokenizer::is_punctuation(ch) {
+            result.push(ch);
+        } else {
+            result.push(plain_chars.next()?);
+        }
+    }
+    if plain_chars.next().is_some() {
+        return None;
+    }
+    Some(result)
+}
+
+/// 组装候选列表
+///
+/// - `committed`: 已上屏文本（候选 0）
+/// - `raw_with_punct`: 未经 ITN 的最终结果（含标点）
+/// - `hypotheses`: 逐字对齐的替代假设（纯文本）
+/// - `itn`: 文本规范化
+///
+/// 返回去重后的候选；只有一个候选时前端无需显示。
+/// 去掉句尾标点的变体只在已有其他候选时附加。
+pub fn build_candidates(
+    config: &HypothesisVariantsConfig,
+    committed: &str,
+    raw_with_punct: &str,
+    hypotheses: &[String],
+    itn: impl Fn(&str) -> String,
+) -> Vec<String> {
+    let mut candidates = vec![committed.to_string()];
+    if !config.enabled || committed.is_empty() {
+        return candidates;
+    }
+
+    // 替代假设（ITN 后）
+    for hypothesis in hypotheses {
+        if let Some(text) = transplant_punctuation(raw_with_punct, hypothesis) {
+            push_unique(&mut candidates, itn(&text));
+        }
+    }
+
+    // 未经 ITN 的原始文本（如 "三点" 被误转为 "3 点" 时可还原）
+    push_unique(&mut candidates, raw_with_punct.to_string());
+
+    if candidates.len() == 1 {
+        return candidates;
+    }
+
+    // 去掉句尾标点（插入到句子中间时常用）
+    push_unique(&mut candidates, committed.trim_end_matches(['。', '？', '！', '.', '?', '!']).to_string());
+
+    candidates.truncate(config.max_candidates.max(1));
+    candidates
+}
+
+/// 追加非空且不重复的候选
+fn push_unique(candidates: &mut Vec<String>, candidate: String) {
+    if !candidate.is_empty() && !candidates.contains(&candidate) {
+        candidates.push(candidate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_keeps_distinct_recent_hypotheses() {
+        let mut tracker = HypothesisTracker::new(3);
+        for partial in ["今天", "今天天", "今天天汽", "今天天汽", "今天天气", "今天填气"] {
+            tracker.observe(partial);
+        }
+        // 容量 3：最早的 "今天"、"今天天" 被挤出
+        assert_eq!(tracker.variants_for("今天天气"), vec!["今天填气", "今天天汽"]);
+
+        tracker.reset();
+        assert!(tracker.variants_for("今天天气").is_empty());
+    }
+
+    #[test]
+    fn test_transplant_punctuation() {
+        assert_eq!(
+            transplant_punctuation("你好，世界。", "你号世界"),
+            Some("你号，世界。".to_string())
+        );
+        assert_eq!(transplant_punctuation("你好，世界。", "你好世"), None);
+        assert_eq!(transplant_punctuation("你好。", "你好世界"), None);
+    }
+
+    #[test]
+    fn test_build_candidates_dedup_and_order() {
+        let config = HypothesisVariantsConfig { enabled: true, ..Default::default() };
+        let itn = |s: &str| s.replace("三点", "3点");
+        let candidates = build_candidates(
+            &config,
+            "我3点到。",
+            "我三点到。",
+            &["我三点道".to_string(), "我三点到".to_string()],
+            itn,
+        );
+        assert_eq!(candidates, vec!["我3点到。", "我3点道。", "我三点到。", "我3点到"]);
+    }
+
+    #[test]
+    fn test_build_candidates_disabled_or_limited() {
+        let disabled = HypothesisVariantsConfig::default();
+        assert!(!disabled.enabled);
+        assert_eq!(
+            build_candidates(&disabled, "好的。", "好的。", &["号的".to_string()], str::to_string),
+            vec!["好的。"]
+        );
+
+        let limited = HypothesisVariantsConfig { enabled: true, max_candidates: 2, ..Default::default() };
+        let candidates = build_candidates(
+            &limited,
+            "好的。",
+            "好的。",
+            &["号的".to_string(), "好得".to_string()],
+            str::to_string,
+        );
+        assert_eq!(candidates, vec!["好的。", "号的。"]);
+    }
+
+    #[test]
+    fn test_build_candidates_skips_punctuation_only_variant() {
+        let config = HypothesisVariantsConfig { enabled: true, ..Default::default() };
+        // 只有去掉句尾标点的变体时不打开候选框
+        assert_eq!(build_candidates(&config, "好的。", "好的。", &[], str::to_string), vec!["好的。"]);
+        assert_eq!(
+            build_candidates(&config, "3点。", "三点。", &[], |s: &str| s.replace("三点", "3点")),
+            vec!["3点。", "三点。", "3点"]
+        );
+    }
+}
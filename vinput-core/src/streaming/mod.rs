@@ -2,6 +2,18 @@
 //!
 //! 流式语音识别管道，集成 VAD 和 ASR
 
+pub mod commit;
+pub mod hypothesis_variants;
 pub mod pipeline;
+pub mod preedit;
+pub mod staged;
 
-pub use pipeline::{StreamingPipeline, StreamingConfig, StreamingResult, PipelineState};
+pub use pipeline::{
+    AnalyzedFrame, EndpointTrigger, FrontendControl, PunctuationDecision, StreamingPipeline, StreamingConfig,
+    StreamingResult, PipelineState, VadFrontend,
+};
+pub use staged::{run_staged, Capture, Flow};
+pub use hypothesis_variants::{HypothesisVariantsConfig, HypothesisTracker};
+pub use vinput_config::cancel::{self, CancelConfig};
+pub use commit::{CommitFinish, StreamingCommitConfig, StreamingCommitter};
+pub use preedit::{PreeditSegment, SegmentKind};
@@ -3,11 +3,18 @@
 //! 将 VAD 检测结果与 ASR 识别器连接，实现端到端的流式语音识别
 
 use crate::asr::{OnlineRecognizer, OnlineRecognizerConfig, OnlineStream};
+use crate::audio::{EnhancementConfig, SpeechEnhancer};
+use crate::config::{StopMode, VInputConfig};
 use crate::endpointing::{EndpointDetector, EndpointDetectorConfig, EndpointResult};
-use crate::error::VInputResult;
+use crate::error::{VInputError, VInputResult};
+use crate::metrics::{PipelineMetrics, Stage};
 use crate::punctuation::{PunctuationEngine, StyleProfile};
-use crate::vad::{VadConfig, VadManager, VadState};
-use std::time::Instant;
+use crate::streaming::hypothesis_variants::{HypothesisVariantsConfig, HypothesisTracker};
+use crate::streaming::preedit::{build_segments, segments_text, PreeditSegment};
+use crate::vad::{VadConfig, VadManager, VadResult, VadState};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// 流式管道配置
 #[derive(Debug, Clone)]
@@ -20,6 +27,10 @@ pub struct StreamingConfig {
     pub punctuation_profile: StyleProfile,
     /// 端点检测配置
     pub endpoint_config: EndpointDetectorConfig,
+    /// 语音增强配置（VAD 与 ASR 之前）
+    pub enhancement_config: EnhancementConfig,
+    /// 识别候选配置
+    pub variants_config: HypothesisVariantsConfig,
 }
 
 impl Default for StreamingConfig {
@@ -29,10 +40,48 @@ impl Default for StreamingConfig {
             asr_config: OnlineRecognizerConfig::default(),
             punctuation_profile: StyleProfile::default(),
             endpoint_config: EndpointDetectorConfig::default(),
+            enhancement_config: EnhancementConfig::default(),
+            variants_config: HypothesisVariantsConfig::default(),
         }
     }
 }
 
+impl StreamingConfig {
+    /// 从完整配置构建（免提听写使用 `hands_free_vad`）
+    pub fn from_config(config: &VInputConfig) -> Self {
+        // 免提听写麦克风常开，换用抗背景人声的 VAD 配置
+        let vad_config = if config.activation.stop_mode == StopMode::HandsFree {
+            config.hands_free_vad.clone()
+        } else {
+            config.vad.clone()
+        };
+
+        Self {
+            vad_config,
+            asr_config: config.asr.clone(),
+            punctuation_profile: config.punctuation.clone(),
+            endpoint_config: config.endpoint.clone(),
+            enhancement_config: config.enhancement.clone(),
+            variants_config: config.hypothesis_variants.clone(),
+        }
+    }
+}
+
+/// 结束（或丢弃）当前句子的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointTrigger {
+    /// VAD 尾部静音达到断句延迟
+    VadSilence,
+    /// ASR 端点（缓冲期结束后提交）
+    AsrEndpoint,
+    /// 语音过长，强制分段
+    ForcedSegmentation,
+    /// 强制超时
+    Timeout,
+    /// 语音过短，作为噪声丢弃
+    TooShort,
+}
+
 /// 管道状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PipelineState {
@@ -53,8 +102,10 @@ pub struct StreamingResult {
     pub stable_text: String,
     /// 不稳定的文本（保留在 Preedit）
     pub unstable_text: String,
-    /// 是否应该添加逗号（检测到停顿）
+    /// 本帧 VAD 停顿达到逗号阈值（最终结果将在此处插入逗号）
     pub should_add_comma: bool,
+    /// 本帧结束或丢弃句子的原因（None 表示句子继续）
+    pub endpoint: Option<EndpointTrigger>,
     /// 是否为最终结果
     pub is_final: bool,
     /// VAD 状态
@@ -67,10 +118,139 @@ pub struct StreamingResult {
     pub duration_ms: u64,
 }
 
+/// 最终结果的标点决策（会话录制与回放比对用）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PunctuationDecision {
+    /// 插入标点前的纯文本
+    pub plain_text: String,
+    /// 实际插入逗号的位置（纯文本字符下标）
+    pub commas: Vec<usize>,
+    /// 句尾标点
+    pub ending: String,
+}
+
+/// 一帧经语音增强与 VAD 处理后的结果（VAD 阶段 → ASR 阶段）
+#[derive(Debug, Clone)]
+pub struct AnalyzedFrame {
+    /// 增强后的音频（未启用语音增强时为原始音频）
+    pub samples: Vec<f32>,
+    /// VAD 结果
+    pub vad: VadResult,
+    /// 分析时 VAD 前端的重置代数（与管道不一致时属于已结束的句子）
+    pub epoch: u64,
+}
+
+/// 管道前端：语音增强 + VAD
+///
+/// 通常由管道持有（`StreamingPipeline::process`）；分阶段运行时由 `detach_frontend`
+/// 取出，在独立的 VAD 线程中运行，管道的重置等请求经 `FrontendControl` 转交
+pub struct VadFrontend {
+    /// 语音增强器（未启用时为 None）
+    enhancer: Option<SpeechEnhancer>,
+    vad_manager: VadManager,
+    /// 重置代数（每次管道重置加一）
+    epoch: u64,
+    metrics: PipelineMetrics,
+}
+
+impl VadFrontend {
+    fn new(config: &StreamingConfig, metrics: PipelineMetrics) -> VInputResult<Self> {
+        let enhancer = if config.enhancement_config.enabled {
+            Some(SpeechEnhancer::new(config.enhancement_config.clone())?)
+        } else {
+            None
+        };
+        Ok(Self {
+            enhancer,
+            vad_manager: VadManager::new(config.vad_config.clone())?,
+            epoch: 0,
+            metrics,
+        })
+    }
+
+    /// 语音增强 + VAD
+    pub fn analyze(&mut self, samples: &[f32]) -> VInputResult<AnalyzedFrame> {
+        // 语音增强（降噪后的音频同时送入 VAD 与 ASR）
+        let samples = match &mut self.enhancer {
+            Some(enhancer) => self.metrics.time(Stage::Enhancement, || enhancer.process(samples)),
+            None => samples.to_vec(),
+        };
+        let vad = self.metrics.time(Stage::Vad, || self.vad_manager.process(&samples))?;
+
+        Ok(AnalyzedFrame {
+            samples,
+            vad,
+            epoch: self.epoch,
+        })
+    }
+
+    /// 按顺序执行管道转交的请求（VAD 线程每帧分析前调用）
+    pub fn apply(&mut self, control: &FrontendControl) {
+        let requests = std::mem::take(&mut *control.requests.lock().unwrap());
+        for request in requests {
+            match request {
+                FrontendRequest::Reset(epoch) => self.reset_to(epoch),
+                FrontendRequest::ForceState(state) => self.vad_manager.force_state(state),
+                FrontendRequest::DisableEnhancement => self.enhancer = None,
+            }
+        }
+    }
+
+    /// 获取 Energy Gate 当前通过阈值（用于输入增益诊断）
+    pub fn energy_gate_threshold(&self) -> Option<f32> {
+        self.vad_manager.energy_gate_threshold()
+    }
+
+    /// 当前重置代数
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// 重置语音增强器（保留噪声估计）与 VAD，进入新的代数
+    fn reset_to(&mut self, epoch: u64) {
+        if let Some(enhancer) = &mut self.enhancer {
+            enhancer.reset();
+        }
+        self.vad_manager.reset();
+        self.epoch = epoch;
+    }
+}
+
+/// 管道转交给 VAD 前端的请求
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrontendRequest {
+    Reset(u64),
+    ForceState(VadState),
+    DisableEnhancement,
+}
+
+/// 分离出去的 VAD 前端的控制通道（管道 → VAD 线程，按请求顺序执行）
+#[derive(Debug, Clone, Default)]
+pub struct FrontendControl {
+    requests: Arc<Mutex<Vec<FrontendRequest>>>,
+}
+
+impl FrontendControl {
+    fn push(&self, request: FrontendRequest) {
+        self.requests.lock().unwrap().push(request);
+    }
+}
+
+/// VAD 前端的位置
+enum Frontend {
+    /// 与管道在同一线程
+    Attached(Box<VadFrontend>),
+    /// 在独立的 VAD 线程（分阶段运行时）
+    Detached(FrontendControl),
+}
+
 /// VAD-ASR 流式识别管道
 pub struct StreamingPipeline {
     config: StreamingConfig,
-    vad_manager: VadManager,
+    /// 语音增强 + VAD
+    frontend: Frontend,
+    /// 当前句子的 VAD 前端代数（早于此代数分析的帧丢弃）
+    frontend_epoch: u64,
     asr_recognizer: OnlineRecognizer,
     asr_stream: Option<OnlineStream<'static>>,
     punctuation_engine: PunctuationEngine,
@@ -88,6 +268,8 @@ pub struct StreamingPipeline {
     total_frames: u64,
     /// 送入 ASR 的音频帧数
     asr_frames: u64,
+    /// 连续处于 Idle 状态的帧数（免提听写静默超时用，与墙上时钟无关）
+    idle_frames: u64,
 
     // ── VAD 停顿检测（帧计数法，与墙上时钟无关，测试/生产均适用）──────────────
     /// VAD 检测到的停顿逗号插入位置（部分结果字符数，在停顿达到阈值时快照）
@@ -105,6 +287,16 @@ pub struct StreamingPipeline {
     vad_comma_recorded_for_pause: bool,
     /// 上一帧的 ASR 部分结果字符数（停顿发生时用于定位逗号位置）
     last_partial_char_count: usize,
+
+    /// 解码过程中出现过的部分假设（用于生成替代候选）
+    hypotheses: HypothesisTracker,
+    /// 上一句最终结果的替代假设（由 `take_hypothesis_variants` 取走）
+    final_variants: Vec<String>,
+    /// 上一句最终结果的标点决策（由 `take_punctuation_decision` 取走）
+    final_punctuation: Option<PunctuationDecision>,
+
+    /// 性能指标（可与引擎共享，见 `set_metrics`）
+    metrics: PipelineMetrics,
 }
 
 impl StreamingPipeline {
@@ -119,14 +311,17 @@ impl StreamingPipeline {
             config.endpoint_config.min_speech_duration_ms
         );
 
-        let vad_manager = VadManager::new(config.vad_config.clone())?;
+        let metrics = PipelineMetrics::new();
+        let frontend = VadFrontend::new(&config, metrics.clone())?;
         let asr_recognizer = OnlineRecognizer::new(&config.asr_config)?;
         let punctuation_engine = PunctuationEngine::new(config.punctuation_profile.clone());
         let endpoint_detector = EndpointDetector::new(config.endpoint_config.clone());
+        let hypotheses = HypothesisTracker::new(config.variants_config.hypothesis_history);
 
         Ok(Self {
             config,
-            vad_manager,
+            frontend: Frontend::Attached(Box::new(frontend)),
+            frontend_epoch: 0,
             asr_recognizer,
             punctuation_engine,
             endpoint_detector,
@@ -136,12 +331,17 @@ impl StreamingPipeline {
             asr_endpoint_grace_remaining: 0,
             total_frames: 0,
             asr_frames: 0,
+            idle_frames: 0,
             vad_pause_char_positions: Vec::new(),
             vad_prev_is_speech: false,
             vad_last_speech_asr_frame: 0,
             vad_silence_frame_count: 0,
             vad_comma_recorded_for_pause: false,
             last_partial_char_count: 0,
+            hypotheses,
+            final_variants: Vec::new(),
+            final_punctuation: None,
+            metrics,
         })
     }
 
@@ -154,10 +354,35 @@ impl StreamingPipeline {
     /// # 返回
     /// - `StreamingResult`: 流式识别结果
     pub fn process(&mut self, samples: &[f32]) -> VInputResult<StreamingResult> {
-        self.total_frames += 1;
+        // 0-1. 语音增强 + VAD
+        let frame = match &mut self.frontend {
+            Frontend::Attached(frontend) => frontend.analyze(samples)?,
+            Frontend::Detached(_) => {
+                return Err(VInputError::Generic(
+                    "VAD 前端运行在独立线程，应改用 process_frame".to_string(),
+                ));
+            }
+        };
+        self.process_analyzed(frame)
+    }
 
-        // 1. VAD 处理
-        let vad_result = self.vad_manager.process(samples)?;
+    /// 处理 VAD 线程送来的帧（分阶段运行时在 ASR 线程调用）
+    ///
+    /// 管道已重置、而 VAD 线程尚未执行重置时分析的帧不属于当前句子，不处理并返回 None
+    /// （调用方应在 VAD 线程重置后重新分析这些帧）
+    pub fn process_frame(&mut self, frame: AnalyzedFrame) -> VInputResult<Option<StreamingResult>> {
+        if frame.epoch != self.frontend_epoch {
+            return Ok(None);
+        }
+        self.process_analyzed(frame).map(Some)
+    }
+
+    fn process_analyzed(&mut self, frame: AnalyzedFrame) -> VInputResult<StreamingResult> {
+        self.total_frames += 1;
+        let sample_rate = self.config.vad_config.silero.sample_rate as f64;
+        let samples = frame.samples.as_slice();
+        self.metrics.record_audio(Duration::from_secs_f64(samples.len() as f64 / sample_rate));
+        let vad_result = frame.vad;
         let now = Instant::now();
 
         // 1.5 将音频送入端点检测器（用于能量分析）
@@ -170,16 +395,20 @@ impl StreamingPipeline {
         let endpoint_result = self.endpoint_detector.process_vad(is_speech);
 
         // 3. 根据端点检测结果处理状态
+        let mut endpoint = None;
+        let mut should_add_comma = false;
         match endpoint_result {
             EndpointResult::TooShort => {
                 // 语音过短，忽略并重置
                 tracing::info!("Pipeline: 语音过短，忽略");
+                endpoint = Some(EndpointTrigger::TooShort);
                 self.reset()?;
                 self.pipeline_state = PipelineState::Idle;
             }
             EndpointResult::ForcedSegmentation => {
                 // 语音过长，强制分段
                 tracing::info!("Pipeline: 语音过长，强制分段");
+                endpoint = Some(EndpointTrigger::ForcedSegmentation);
                 if let Some(stream) = &mut self.asr_stream {
                     stream.input_finished();
                 }
@@ -188,6 +417,7 @@ impl StreamingPipeline {
             EndpointResult::Timeout => {
                 // 强制超时
                 tracing::warn!("Pipeline: 强制超时");
+                endpoint = Some(EndpointTrigger::Timeout);
                 if let Some(stream) = &mut self.asr_stream {
                     stream.input_finished();
                 }
@@ -196,6 +426,7 @@ impl StreamingPipeline {
             EndpointResult::Detected => {
                 // 检测到端点
                 tracing::info!("Pipeline: VAD 端点检测完成");
+                endpoint = Some(EndpointTrigger::VadSilence);
                 if let Some(stream) = &mut self.asr_stream {
                     stream.input_finished();
                 }
@@ -215,10 +446,9 @@ impl StreamingPipeline {
                         // 注入 Pre-roll 音频（如果有）
                         if let Some(pre_roll_audio) = &vad_result.pre_roll_audio {
                             if !pre_roll_audio.is_empty() {
-                                stream.accept_waveform(
-                                    pre_roll_audio,
-                                    self.config.vad_config.silero.sample_rate as i32,
-                                );
+                                self.metrics.time(Stage::AsrDecode, || {
+                                    stream.accept_waveform(pre_roll_audio, sample_rate as i32)
+                                });
                                 // 按实际帧数计数（512 samples/帧），而非固定 +1
                                 // 这样 asr_frames * 32ms 与 token 的 start_time_ms 保持对齐
                                 self.asr_frames += (pre_roll_audio.len() as u64 + 511) / 512;
@@ -237,6 +467,7 @@ impl StreamingPipeline {
 
                         self.pipeline_state = PipelineState::Recognizing;
                         self.speech_start_time = Some(now);
+                        self.metrics.speech_started();
                     }
 
                     // 识别中，继续送入音频
@@ -323,6 +554,7 @@ impl StreamingPipeline {
                                 );
                                 self.vad_pause_char_positions.push(char_pos);
                                 self.vad_comma_recorded_for_pause = true;
+                                should_add_comma = true;
                             } else if char_pos > *self.vad_pause_char_positions.last().unwrap() {
                                 // ASR 在停顿期间解码了更多字符：更新位置（更精确的词边界）
                                 tracing::info!(
@@ -342,7 +574,7 @@ impl StreamingPipeline {
         if self.pipeline_state == PipelineState::Recognizing {
             if let Some(stream) = &mut self.asr_stream {
                 if stream.is_ready(&self.asr_recognizer) {
-                    stream.decode(&self.asr_recognizer);
+                    self.metrics.time(Stage::AsrDecode, || stream.decode(&self.asr_recognizer));
                 }
 
                 if self.asr_endpoint_grace_remaining > 0 {
@@ -358,6 +590,7 @@ impl StreamingPipeline {
                         // 缓冲期结束：刷新并提交
                         stream.input_finished();
                         self.pipeline_state = PipelineState::Completed;
+                        endpoint = Some(EndpointTrigger::AsrEndpoint);
                         tracing::info!("Pipeline: ASR 端点缓冲期结束，准备上屏");
                     }
                 } else {
@@ -385,6 +618,13 @@ impl StreamingPipeline {
 
         let is_final = self.pipeline_state == PipelineState::Completed;
 
+        // 静默计时：句子完成并重置后重新从 Idle 开始计数
+        if self.pipeline_state == PipelineState::Idle {
+            self.idle_frames += 1;
+        } else {
+            self.idle_frames = 0;
+        }
+
         let duration_ms = self.speech_start_time
             .map(|start| now.duration_since(start).as_millis() as u64)
             .unwrap_or(0);
@@ -402,16 +642,23 @@ impl StreamingPipeline {
                 );
             }
             self.last_partial_char_count = new_count;
+            self.hypotheses.observe(&partial_result);
+            self.metrics.partial_result();
         }
 
-        // 8. 检测是否应该添加逗号（停顿检测）
-        let should_add_comma = false; // TODO: 实现停顿检测逻辑
+        // 句子结束：开始计算端点到上屏延迟（过短的句子直接丢弃）
+        match endpoint {
+            Some(EndpointTrigger::TooShort) => self.metrics.utterance_discarded(),
+            Some(_) => self.metrics.endpoint_reached(),
+            None => {}
+        }
 
         Ok(StreamingResult {
             partial_result,
             stable_text,
             unstable_text,
             should_add_comma,
+            endpoint,
             is_final,
             vad_state: vad_result.state,
             pipeline_state: self.pipeline_state,
@@ -423,10 +670,8 @@ impl StreamingPipeline {
     /// 将音频数据送入 ASR（内部方法，避免借用冲突）
     fn feed_audio_to_asr_internal(&mut self, samples: &[f32]) -> VInputResult<()> {
         if let Some(stream) = &mut self.asr_stream {
-            stream.accept_waveform(
-                samples,
-                self.config.vad_config.silero.sample_rate as i32,
-            );
+            let sample_rate = self.config.vad_config.silero.sample_rate as i32;
+            self.metrics.time(Stage::AsrDecode, || stream.accept_waveform(samples, sample_rate));
             self.asr_frames += 1;
 
             // 每 50 帧（约 1.6 秒）打印一次日志
@@ -447,8 +692,12 @@ impl StreamingPipeline {
             stream.reset(&self.asr_recognizer);
         }
 
-        // 重置 VAD
-        self.vad_manager.reset();
+        // 重置语音增强器（保留噪声估计）与 VAD；VAD 前端分离时转交 VAD 线程
+        self.frontend_epoch += 1;
+        match &mut self.frontend {
+            Frontend::Attached(frontend) => frontend.reset_to(self.frontend_epoch),
+            Frontend::Detached(control) => control.push(FrontendRequest::Reset(self.frontend_epoch)),
+        }
 
         // 重置标点引擎
         self.punctuation_engine.reset_sentence();
@@ -463,6 +712,7 @@ impl StreamingPipeline {
         // asr_frames 必须归零：ASR token 时间戳从每条新流的 0ms 开始，
         // 若不归零则 VAD 停顿时刻与 token 时间戳对不齐
         self.asr_frames = 0;
+        self.idle_frames = 0;
 
         // 重置 VAD 停顿检测状态
         self.vad_pause_char_positions.clear();
@@ -471,6 +721,7 @@ impl StreamingPipeline {
         self.vad_silence_frame_count = 0;
         self.vad_comma_recorded_for_pause = false;
         self.last_partial_char_count = 0;
+        self.hypotheses.reset();
 
         Ok(())
     }
@@ -481,7 +732,10 @@ impl StreamingPipeline {
     /// （Silero v6.2 需要约 20 帧 / 640ms 才能输出高置信度语音概率）。
     /// 若不立即启动，句子开头的音频会在 Silero 预热期间被丢弃。
     pub fn force_vad_state(&mut self, state: VadState) {
-        self.vad_manager.force_state(state);
+        match &mut self.frontend {
+            Frontend::Attached(frontend) => frontend.vad_manager.force_state(state),
+            Frontend::Detached(control) => control.push(FrontendRequest::ForceState(state)),
+        }
 
         // PushToTalk: 强制进入语音状态时，立即启动 ASR 流
         if matches!(state, VadState::Speech) && self.pipeline_state == PipelineState::Idle {
@@ -492,6 +746,7 @@ impl StreamingPipeline {
                     self.asr_stream = Some(stream_static);
                     self.pipeline_state = PipelineState::Recognizing;
                     self.speech_start_time = Some(Instant::now());
+                    self.metrics.speech_started();
                     tracing::info!("PushToTalk: 立即启动 ASR 流（跳过 Silero ~20 帧预热延迟）");
                 }
                 Err(e) => {
@@ -501,14 +756,88 @@ impl StreamingPipeline {
         }
     }
 
+    /// 管道配置（重建管道时使用）
+    pub fn config(&self) -> &StreamingConfig {
+        &self.config
+    }
+
+    /// 性能指标
+    pub fn metrics(&self) -> &PipelineMetrics {
+        &self.metrics
+    }
+
+    /// 改用共享的指标句柄（引擎重建管道后保留累计指标）
+    pub fn set_metrics(&mut self, metrics: PipelineMetrics) {
+        if let Frontend::Attached(frontend) = &mut self.frontend {
+            frontend.metrics = metrics.clone();
+        }
+        self.metrics = metrics;
+    }
+
+    /// 关闭语音增强（错误恢复降级：降低音频线程负载）
+    pub fn disable_enhancement(&mut self) {
+        match &mut self.frontend {
+            Frontend::Attached(frontend) => frontend.enhancer = None,
+            Frontend::Detached(control) => control.push(FrontendRequest::DisableEnhancement),
+        }
+        self.config.enhancement_config.enabled = false;
+    }
+
+    /// 取出 VAD 前端，交给独立的 VAD 线程运行（已取出时返回 None）
+    ///
+    /// 取出期间只能用 `process_frame` 处理帧；VAD 线程每帧分析前应调用
+    /// `VadFrontend::apply` 执行管道转交的请求，结束后用 `attach_frontend` 放回
+    pub fn detach_frontend(&mut self) -> Option<(VadFrontend, FrontendControl)> {
+        let control = FrontendControl::default();
+        match std::mem::replace(&mut self.frontend, Frontend::Detached(control.clone())) {
+            Frontend::Attached(mut frontend) => {
+                frontend.metrics = self.metrics.clone();
+                Some((*frontend, control))
+            }
+            detached => {
+                self.frontend = detached;
+                None
+            }
+        }
+    }
+
+    /// 放回 VAD 前端，并执行 VAD 线程退出后才转交的请求
+    pub fn attach_frontend(&mut self, mut frontend: VadFrontend) {
+        if let Frontend::Detached(control) = &self.frontend {
+            frontend.apply(control);
+        }
+        self.frontend = Frontend::Attached(Box::new(frontend));
+    }
+
+    /// 当前句子的 VAD 前端代数（每次重置加一）
+    pub fn frontend_epoch(&self) -> u64 {
+        self.frontend_epoch
+    }
+
     /// 获取当前管道状态
     pub fn pipeline_state(&self) -> PipelineState {
         self.pipeline_state
     }
 
-    /// 获取 VAD 状态
-    pub fn vad_state(&self) -> VadState {
-        self.vad_manager.state()
+    /// 自上次语音结束以来的静默时长（ms，按音频帧计，1 帧 = 32ms）
+    pub fn idle_duration_ms(&self) -> u64 {
+        self.idle_frames * 32
+    }
+
+    /// 获取 VAD 状态（VAD 前端已取出时为 None）
+    pub fn vad_state(&self) -> Option<VadState> {
+        match &self.frontend {
+            Frontend::Attached(frontend) => Some(frontend.vad_manager.state()),
+            Frontend::Detached(_) => None,
+        }
+    }
+
+    /// 获取 Energy Gate 当前通过阈值（用于输入增益诊断；VAD 前端已取出时为 None）
+    pub fn energy_gate_threshold(&self) -> Option<f32> {
+        match &self.frontend {
+            Frontend::Attached(frontend) => frontend.energy_gate_threshold(),
+            Frontend::Detached(_) => None,
+        }
     }
 
     /// 获取统计信息（用于调试）
@@ -566,16 +895,31 @@ impl StreamingPipeline {
     /// 用于在识别过程中显示带标点的 Preedit
     /// 不会重置管道状态，不会添加句尾标点
     pub fn get_partial_result_with_punctuation(&mut self) -> String {
+        segments_text(&self.get_partial_preedit_segments())
+    }
+
+    /// 获取实时识别结果的 Preedit 分段（带实时标点处理）
+    ///
+    /// 与 `get_partial_result_with_punctuation` 文本一致，额外标注
+    /// 稳定 / 不稳定片段
+    pub fn get_partial_preedit_segments(&mut self) -> Vec<PreeditSegment> {
+        let start = Instant::now();
+        let segments = self.build_partial_segments();
+        self.metrics.record_stage(Stage::Punctuation, start.elapsed());
+        segments
+    }
+
+    fn build_partial_segments(&mut self) -> Vec<PreeditSegment> {
         if let Some(stream) = &self.asr_stream {
             // 获取详细结果（包含 Token 和时间戳）
             let detailed_result = stream.get_detailed_result(&self.asr_recognizer);
 
             if detailed_result.is_empty() {
-                return String::new();
+                return Vec::new();
             }
 
             // 处理每个 Token，添加逗号（但不添加句尾标点）
-            let mut text_with_commas = String::new();
+            let mut processed_tokens = Vec::with_capacity(detailed_result.tokens.len());
 
             for token in &detailed_result.tokens {
                 // 转换为 TokenInfo
@@ -583,13 +927,15 @@ impl StreamingPipeline {
 
                 // 处理 Token（可能在前面添加逗号）
                 if let Some(processed_token) = self.punctuation_engine.process_token(token_info) {
-                    text_with_commas.push_str(&processed_token);
+                    processed_tokens.push(processed_token);
                 }
             }
 
-            text_with_commas
+            // 含中文数字时整段等待 ITN，与 split_stable_unstable 一致
+            let all_unstable = Self::contains_chinese_number(&detailed_result.text);
+            build_segments(&processed_tokens, all_unstable)
         } else {
-            String::new()
+            Vec::new()
         }
     }
 
@@ -597,6 +943,9 @@ impl StreamingPipeline {
     ///
     /// 调用此方法后会自动重置管道状态
     pub fn get_final_result_with_punctuation(&mut self) -> String {
+        self.final_variants.clear();
+        self.final_punctuation = None;
+
         // 通知解码器输入已结束，触发最终 beam search 完成
         // 对于轻声末字：ASR 缓冲区里有这些帧，但未经 input_finished() 就无法提交
         if let Some(stream) = &mut self.asr_stream {
@@ -607,11 +956,12 @@ impl StreamingPipeline {
         // 最终一次解码，处理 input_finished() 后的剩余帧
         if let Some(stream) = &mut self.asr_stream {
             if stream.is_ready(&self.asr_recognizer) {
-                stream.decode(&self.asr_recognizer);
+                self.metrics.time(Stage::AsrDecode, || stream.decode(&self.asr_recognizer));
                 tracing::info!("🔚 最终解码完成");
             }
         }
 
+        let punctuation_start = Instant::now();
         let result = if let Some(stream) = &self.asr_stream {
             // 获取详细结果（包含 Token 和时间戳）
             let detailed_result = stream.get_detailed_result(&self.asr_recognizer);
@@ -672,6 +1022,12 @@ impl StreamingPipeline {
                 }
                 logic_comma_positions.extend(vad_comma_positions);
 
+                // 记录与最终结果逐字对齐的替代假设（reset 前取出）
+                self.final_variants = self.hypotheses.variants_for(&plain_text);
+                if !self.final_variants.is_empty() {
+                    tracing::info!("  🔀 替代假设: {:?}", self.final_variants);
+                }
+
                 tracing::info!("📝 纯文本: '{}', 逗号位置(逻辑词+VAD停顿): {:?}",
                     plain_text, logic_comma_positions);
 
@@ -684,6 +1040,7 @@ impl StreamingPipeline {
                 const MIN_CHARS_BETWEEN_COMMAS: usize = 3;
                 let mut final_text = String::with_capacity(plain_text.len() + comma_positions.len() * 3);
                 let mut last_comma_at: Option<usize> = None;
+                let mut inserted_commas = Vec::new();
 
                 for (i, ch) in plain_text.char_indices().map(|(_, c)| c).enumerate() {
                     // 检查此位置是否应插入逗号
@@ -696,6 +1053,7 @@ impl StreamingPipeline {
                             tracing::info!("  ✅ 在第 {} 个字符前插入逗号", i);
                             final_text.push('，');
                             last_comma_at = Some(i);
+                            inserted_commas.push(i);
                         }
                     }
                     final_text.push(ch);
@@ -732,6 +1090,11 @@ impl StreamingPipeline {
 
                 tracing::info!("  句尾标点: '{}'（基于文本: '{}'）", ending, final_text);
                 final_text.push_str(&ending);
+                self.final_punctuation = Some(PunctuationDecision {
+                    plain_text,
+                    commas: inserted_commas,
+                    ending,
+                });
 
                 tracing::info!("✅ 标点处理完成: '{}'", final_text);
                 final_text
@@ -740,6 +1103,7 @@ impl StreamingPipeline {
             tracing::warn!("⚠️  ASR 流为空");
             String::new()
         };
+        self.metrics.record_stage(Stage::Punctuation, punctuation_start.elapsed());
 
         // 重置管道以准备下一次识别
         let _ = self.reset();
@@ -747,6 +1111,29 @@ impl StreamingPipeline {
         result
     }
 
+    /// 丢弃当前句子（取消）：销毁进行中的 ASR 流，不产生最终结果
+    pub fn discard_utterance(&mut self) {
+        tracing::info!("🗑️ 丢弃当前句子");
+        self.final_variants.clear();
+        self.final_punctuation = None;
+        self.metrics.utterance_discarded();
+        let _ = self.reset();
+    }
+
+    /// 取走上一句最终结果的替代假设（纯文本，与最终结果逐字对齐）
+    ///
+    /// 在 `get_final_result_with_punctuation` 之后调用
+    pub fn take_hypothesis_variants(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.final_variants)
+    }
+
+    /// 取走上一句最终结果的标点决策（结果为空时为 None）
+    ///
+    /// 在 `get_final_result_with_punctuation` 之后调用
+    pub fn take_punctuation_decision(&mut self) -> Option<PunctuationDecision> {
+        self.final_punctuation.take()
+    }
+
     /// 获取最终识别结果（不带标点，原始文本）
     ///
     /// 调用此方法后会自动重置管道状态
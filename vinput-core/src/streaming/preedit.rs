@@ -0,0 +1,96 @@
+//! Preedit 分段
+//!
+//! 将实时识别的 Token 划分为稳定 / 不稳定片段，
+//! 供前端以不同样式（如下划线）渲染 Preedit。
+//!
+//! 识别器不提供逐 Token 置信度（Paraformer 结果只有文本与 Token），不区分低置信度片段；
+//! FFI 的 `VInputSegmentAttribute::LowConfidence` 为此保留，日后支持时无需提升载荷版本。
+
+/// 片段属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// 稳定文本（后续解码不太会改变）
+    Stable,
+    /// 不稳定文本（末尾若干字，或等待 ITN 的数字）
+    Unstable,
+}
+
+/// Preedit 片段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreeditSegment {
+    /// 片段文本
+    pub text: String,
+    /// 片段属性
+    pub kind: SegmentKind,
+}
+
+/// 末尾保留为不稳定的字符数（与 `split_stable_unstable` 一致）
+pub const KEEP_LAST_CHARS: usize = 2;
+
+/// 由已加标点的 Token 文本序列构建 Preedit 片段
+///
+/// - 末尾 [`KEEP_LAST_CHARS`] 个字符为不稳定
+/// - `all_unstable` 为 true 时（如含中文数字、等待 ITN）全部为不稳定
+///
+/// 相邻同属性片段会被合并
+pub fn build_segments(tokens: &[String], all_unstable: bool) -> Vec<PreeditSegment> {
+    let total_chars: usize = tokens.iter().map(|t| t.chars().count()).sum();
+    let stable_chars = if all_unstable {
+        0
+    } else {
+        total_chars.saturating_sub(KEEP_LAST_CHARS)
+    };
+
+    let mut segments: Vec<PreeditSegment> = Vec::new();
+    let mut pos = 0;
+    for text in tokens {
+        for ch in text.chars() {
+            let kind = if pos < stable_chars { SegmentKind::Stable } else { SegmentKind::Unstable };
+            match segments.last_mut() {
+                Some(last) if last.kind == kind => last.text.push(ch),
+                _ => segments.push(PreeditSegment { text: ch.to_string(), kind }),
+            }
+            pos += 1;
+        }
+    }
+
+    segments
+}
+
+/// 拼接所有片段的文本
+pub fn segments_text(segments: &[PreeditSegment]) -> String {
+    segments.iter().map(|s| s.text.as_str()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(items: &[&str]) -> Vec<String> {
+        items.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_tail_is_unstable() {
+        let segments = build_segments(&tokens(&["今天", "天气", "不错"]), false);
+        assert_eq!(
+            segments,
+            vec![
+                PreeditSegment { text: "今天天气".into(), kind: SegmentKind::Stable },
+                PreeditSegment { text: "不错".into(), kind: SegmentKind::Unstable },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_unstable_and_short_text() {
+        let segments = build_segments(&tokens(&["三百", "块"]), true);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].kind, SegmentKind::Unstable);
+
+        let segments = build_segments(&tokens(&["好"]), false);
+        assert_eq!(segments[0].kind, SegmentKind::Unstable);
+
+        assert!(build_segments(&[], false).is_empty());
+    }
+}
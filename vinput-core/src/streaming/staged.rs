@@ -0,0 +1,569 @@
+//! 分阶段运行：采集 → VAD → ASR 解码
+//!
+//! 三个阶段分别运行在调用线程、VAD 线程与 ASR 线程，经 `AudioQueueManager` 的两级有界队列衔接：
+//! 队列使用量达到背压阈值时上游阶段暂停读取，解码偶尔变慢只会让队列积压而不丢音频，
+//! 采集阶段也读不动时才由输入源自己的 Ring Buffer 溢出。
+//!
+//! VAD 线程可以领先 ASR 线程若干帧。管道重置（句子结束等）后，VAD 线程按重置前的状态
+//! 分析过的帧退回重新分析，因此识别结果与逐帧调用 `StreamingPipeline::process` 一致。
+
+use crate::audio::{AudioQueueConfig, AudioQueueManager};
+use crate::error::{VInputError, VInputResult};
+use crate::streaming::pipeline::{AnalyzedFrame, FrontendControl, StreamingPipeline, StreamingResult, VadFrontend};
+use crate::vad::VadResult;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+/// 每帧样本数（与引擎音频线程一致）
+const FRAME_SIZE: usize = 512;
+/// 等待队列变化的最长时间（同时是采集阶段无数据时的轮询间隔）
+const WAIT: Duration = Duration::from_millis(10);
+
+/// 采集阶段一次读取的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capture {
+    /// 读到的样本数（写在缓冲区开头）
+    Samples(usize),
+    /// 暂无数据
+    Idle,
+    /// 输入结束（已读完停止前的全部音频）
+    Finished,
+}
+
+/// ASR 阶段处理完一帧后的去向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    /// 自动停止：丢弃队列中剩余的音频
+    Stop,
+}
+
+/// 分阶段运行识别管道，直到输入结束、`on_asr` 返回 `Flow::Stop` 或任一阶段出错
+///
+/// - `capture`：在调用线程读取输入（最多读满缓冲区，缓冲区长度即下游队列的余量）
+/// - `on_vad`：VAD 线程中每帧分析前调用，参数为原始音频（重新分析的帧不再调用）
+/// - `on_asr`：ASR 线程中每帧处理后调用，参数为原始音频与识别结果，调用时持有管道锁
+///
+/// 运行期间 VAD 前端从管道中取出，返回前放回
+pub fn run_staged<C, V, A>(
+    config: &AudioQueueConfig,
+    pipeline: &Mutex<StreamingPipeline>,
+    capture: C,
+    on_vad: V,
+    on_asr: A,
+) -> VInputResult<()>
+where
+    C: FnMut(&mut [f32]) -> VInputResult<Capture>,
+    V: FnMut(&[f32], &VadFrontend) + Send,
+    A: FnMut(&mut StreamingPipeline, &[f32], StreamingResult) -> VInputResult<Flow> + Send,
+{
+    let (mut frontend, control) = pipeline
+        .lock()
+        .unwrap()
+        .detach_frontend()
+        .ok_or_else(|| VInputError::Generic("VAD 前端已在其他线程运行".to_string()))?;
+    let queues = StageQueues::new(config.clone());
+
+    let result = thread::scope(|scope| {
+        let vad = thread::Builder::new()
+            .name("vinput-vad".to_string())
+            .spawn_scoped(scope, || vad_stage(&queues, &mut frontend, &control, on_vad));
+        let asr = thread::Builder::new()
+            .name("vinput-asr".to_string())
+            .spawn_scoped(scope, || asr_stage(&queues, pipeline, on_asr));
+        let (vad, asr) = match (vad, asr) {
+            (Ok(vad), Ok(asr)) => (vad, asr),
+            (vad, asr) => {
+                queues.abort();
+                drop((vad, asr));
+                return Err(VInputError::Generic("创建 VAD / ASR 线程失败".to_string()));
+            }
+        };
+
+        let captured = capture_stage(&queues, config.capture_to_vad_capacity, capture);
+        let vad = vad.join().unwrap_or_else(|_| Err(VInputError::Generic("VAD 线程崩溃".to_string())));
+        let asr = asr.join().unwrap_or_else(|_| Err(VInputError::Generic("ASR 线程崩溃".to_string())));
+        captured.and(vad).and(asr)
+    });
+
+    pipeline.lock().unwrap_or_else(PoisonError::into_inner).attach_frontend(frontend);
+    result
+}
+
+/// 采集阶段：读取输入写入 Capture → VAD 队列
+fn capture_stage(
+    queues: &StageQueues,
+    capacity: usize,
+    mut capture: impl FnMut(&mut [f32]) -> VInputResult<Capture>,
+) -> VInputResult<()> {
+    let mut buffer = vec![0.0f32; capacity];
+    loop {
+        let Some(room) = queues.until(QueueState::capture_room) else {
+            return Ok(());
+        };
+        match capture(&mut buffer[..room]) {
+            Ok(Capture::Samples(count)) => {
+                let written = queues.update(|state| state.queues.write_from_capture(&buffer[..count]));
+                if let Err(e) = written {
+                    queues.abort();
+                    return Err(e);
+                }
+            }
+            Ok(Capture::Idle) => queues.pause(),
+            Ok(Capture::Finished) => {
+                queues.update(|state| state.capture_finished = true);
+                return Ok(());
+            }
+            Err(e) => {
+                queues.abort();
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// VAD 阶段：语音增强 + VAD，结果写入 VAD → ASR 队列
+fn vad_stage(
+    queues: &StageQueues,
+    frontend: &mut VadFrontend,
+    control: &FrontendControl,
+    mut on_vad: impl FnMut(&[f32], &VadFrontend),
+) -> VInputResult<()> {
+    let _guard = AbortOnPanic(queues);
+    loop {
+        // 先执行管道转交的请求：重置后队列中按旧状态分析的帧退回重新分析
+        let next = queues.until(|state| {
+            frontend.apply(control);
+            state.next_for_vad(frontend.epoch())
+        });
+        let PendingFrame { seq, raw, fresh } = match next {
+            Some(Ok(pending)) => pending,
+            Some(Err(e)) => {
+                queues.abort();
+                return Err(e);
+            }
+            None => return Ok(()),
+        };
+
+        if fresh {
+            on_vad(&raw, frontend);
+        }
+        let pushed = frontend
+            .analyze(&raw)
+            .and_then(|frame| queues.update(|state| state.push_analyzed(seq, raw, frame)));
+        if let Err(e) = pushed {
+            queues.abort();
+            return Err(e);
+        }
+    }
+}
+
+/// ASR 阶段：解码与结果处理
+fn asr_stage(
+    queues: &StageQueues,
+    pipeline: &Mutex<StreamingPipeline>,
+    mut on_asr: impl FnMut(&mut StreamingPipeline, &[f32], StreamingResult) -> VInputResult<Flow>,
+) -> VInputResult<()> {
+    let _guard = AbortOnPanic(queues);
+    let mut epoch = pipeline.lock().unwrap().frontend_epoch();
+    while let Some((seq, raw, frame)) = queues.until(|state| state.next_for_asr(epoch)) {
+        let mut pipe = pipeline.lock().unwrap();
+        if frame.epoch != pipe.frontend_epoch() {
+            // 管道在本阶段之外被重置（取帧之后），退回重新分析
+            epoch = pipe.frontend_epoch();
+            queues.update(|state| state.reanalyze.insert(seq, raw));
+            continue;
+        }
+
+        let flow = pipe
+            .process_frame(frame)
+            .and_then(|result| match result {
+                Some(result) => on_asr(&mut pipe, &raw, result),
+                None => Ok(Flow::Continue),
+            });
+        epoch = pipe.frontend_epoch();
+        match flow {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::Stop) => {
+                queues.abort();
+                return Ok(());
+            }
+            Err(e) => {
+                queues.abort();
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 线程崩溃时通知其他阶段退出
+struct AbortOnPanic<'a>(&'a StageQueues);
+
+impl Drop for AbortOnPanic<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.abort();
+        }
+    }
+}
+
+/// 各阶段共享的队列
+struct StageQueues {
+    state: Mutex<QueueState>,
+    changed: Condvar,
+}
+
+impl StageQueues {
+    fn new(config: AudioQueueConfig) -> Self {
+        Self {
+            state: Mutex::new(QueueState::new(config)),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// 修改队列状态并唤醒等待的阶段
+    fn update<R>(&self, f: impl FnOnce(&mut QueueState) -> R) -> R {
+        let result = f(&mut self.lock());
+        self.changed.notify_all();
+        result
+    }
+
+    /// 等待 `step` 就绪（返回 `Some`）；已中止时返回 None
+    fn until<T>(&self, mut step: impl FnMut(&mut QueueState) -> Step<T>) -> Option<T> {
+        let mut state = self.lock();
+        loop {
+            if state.aborted {
+                return None;
+            }
+            match step(&mut state) {
+                Step::Ready(value) => {
+                    drop(state);
+                    self.changed.notify_all();
+                    return Some(value);
+                }
+                Step::Finished => {
+                    drop(state);
+                    self.changed.notify_all();
+                    return None;
+                }
+                Step::Wait => {
+                    state = self
+                        .changed
+                        .wait_timeout(state, WAIT)
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .0;
+                }
+            }
+        }
+    }
+
+    /// 等待一会儿（队列变化或中止时提前返回）
+    fn pause(&self) {
+        let state = self.lock();
+        if !state.aborted {
+            let _ = self.changed.wait_timeout(state, WAIT);
+        }
+    }
+
+    /// 中止运行：各阶段尽快退出，丢弃队列中的音频
+    fn abort(&self) {
+        self.update(|state| state.aborted = true);
+    }
+}
+
+/// 某个阶段的下一步
+#[derive(Debug, PartialEq)]
+enum Step<T> {
+    Ready(T),
+    Wait,
+    /// 该阶段没有更多输入
+    Finished,
+}
+
+/// VAD → ASR 队列中一帧的附加信息（音频本身在队列中）
+struct FrameInfo {
+    seq: u64,
+    /// 原始音频（会话录制与重新分析用）
+    raw: Vec<f32>,
+    /// 队列中的样本数（增强后的音频）
+    len: usize,
+    vad: VadResult,
+    epoch: u64,
+}
+
+/// 等待 VAD 分析的帧
+#[derive(Debug, PartialEq)]
+struct PendingFrame {
+    /// 帧序号（按采集顺序）
+    seq: u64,
+    raw: Vec<f32>,
+    /// 是否首次分析（重新分析的帧为 false）
+    fresh: bool,
+}
+
+struct QueueState {
+    queues: AudioQueueManager,
+    /// VAD → ASR 队列中各帧的信息（与队列中的音频顺序一致）
+    analyzed: VecDeque<FrameInfo>,
+    /// 需要按新状态重新分析的帧（按序号），VAD 阶段优先处理
+    reanalyze: BTreeMap<u64, Vec<f32>>,
+    /// 下一帧的序号
+    next_seq: u64,
+    capture_finished: bool,
+    vad_finished: bool,
+    aborted: bool,
+    /// 是否正处于背压（只用于日志）
+    throttled: bool,
+}
+
+impl QueueState {
+    fn new(config: AudioQueueConfig) -> Self {
+        Self {
+            queues: AudioQueueManager::new(config),
+            analyzed: VecDeque::new(),
+            reanalyze: BTreeMap::new(),
+            next_seq: 0,
+            capture_finished: false,
+            vad_finished: false,
+            aborted: false,
+            throttled: false,
+        }
+    }
+
+    /// Capture → VAD 队列的余量
+    fn capture_room(&mut self) -> Step<usize> {
+        match self.queues.capture_to_vad_headroom() {
+            0 => {
+                self.set_throttled(true, "VAD 处理跟不上采集");
+                Step::Wait
+            }
+            room => Step::Ready(room),
+        }
+    }
+
+    /// VAD 阶段的下一帧（`epoch` 为执行完管道请求后的前端代数）
+    ///
+    /// 队列在背压阈值以下容纳不了一帧时返回错误（否则会永远等待）
+    fn next_for_vad(&mut self, epoch: u64) -> Step<VInputResult<PendingFrame>> {
+        let (capture_limit, asr_limit) = self.queues.backpressure_limits();
+        if capture_limit.min(asr_limit) < FRAME_SIZE {
+            return Step::Ready(Err(VInputError::Generic(format!(
+                "音频队列在背压阈值以下容纳不了一帧（{} 个采样点）：Capture → VAD {}，VAD → ASR {}",
+                FRAME_SIZE, capture_limit, asr_limit
+            ))));
+        }
+
+        // 队列中代数不同的帧是重置前分析的，退回重新分析
+        while self.analyzed.front().is_some_and(|info| info.epoch != epoch) {
+            let (info, _) = self.pop_analyzed();
+            self.reanalyze.insert(info.seq, info.raw);
+        }
+
+        if self.queues.vad_to_asr_headroom() < FRAME_SIZE {
+            self.set_throttled(true, "ASR 解码跟不上");
+            return Step::Wait;
+        }
+        if let Some((seq, raw)) = self.reanalyze.pop_first() {
+            return Step::Ready(Ok(PendingFrame { seq, raw, fresh: false }));
+        }
+        if self.queues.capture_to_vad_available() >= FRAME_SIZE {
+            self.set_throttled(false, "");
+            let mut raw = vec![0.0f32; FRAME_SIZE];
+            self.queues.read_for_vad(&mut raw);
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            return Step::Ready(Ok(PendingFrame { seq, raw, fresh: true }));
+        }
+        // 输入结束后等 ASR 取完队列：其间退回的帧仍需重新分析（不足一帧的尾部丢弃）
+        if self.capture_finished && self.analyzed.is_empty() {
+            self.vad_finished = true;
+            return Step::Finished;
+        }
+        Step::Wait
+    }
+
+    fn push_analyzed(&mut self, seq: u64, raw: Vec<f32>, frame: AnalyzedFrame) -> VInputResult<()> {
+        self.queues.write_from_vad(&frame.samples)?;
+        self.analyzed.push_back(FrameInfo {
+            seq,
+            raw,
+            len: frame.samples.len(),
+            vad: frame.vad,
+            epoch: frame.epoch,
+        });
+        Ok(())
+    }
+
+    /// ASR 阶段的下一帧（`epoch` 为管道当前的前端代数）
+    fn next_for_asr(&mut self, epoch: u64) -> Step<(u64, Vec<f32>, AnalyzedFrame)> {
+        while !self.analyzed.is_empty() {
+            let (info, samples) = self.pop_analyzed();
+            if info.epoch != epoch {
+                // 管道已重置而 VAD 线程尚未执行重置
+                self.reanalyze.insert(info.seq, info.raw);
+                continue;
+            }
+            let frame = AnalyzedFrame {
+                samples,
+                vad: info.vad,
+                epoch: info.epoch,
+            };
+            return Step::Ready((info.seq, info.raw, frame));
+        }
+        if self.vad_finished {
+            Step::Finished
+        } else {
+            Step::Wait
+        }
+    }
+
+    fn pop_analyzed(&mut self) -> (FrameInfo, Vec<f32>) {
+        let info = self.analyzed.pop_front().expect("analyzed 非空");
+        let samples = self.queues.read_for_asr(info.len);
+        (info, samples)
+    }
+
+    fn set_throttled(&mut self, throttled: bool, reason: &str) {
+        if throttled != self.throttled {
+            self.throttled = throttled;
+            if throttled {
+                tracing::debug!("分阶段运行: {}，暂停读取上游队列", reason);
+            } else {
+                tracing::debug!("分阶段运行: 背压解除");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vad::VadState;
+
+    fn state_with_frames(frames: usize) -> QueueState {
+        let mut state = QueueState::new(AudioQueueConfig::default());
+        for i in 0..frames {
+            state.queues.write_from_capture(&[i as f32; FRAME_SIZE]).unwrap();
+        }
+        state
+    }
+
+    /// 模拟 VAD 分析：取下一帧并按 `epoch` 写入队列，返回帧序号
+    fn analyze(state: &mut QueueState, epoch: u64) -> Option<(u64, bool)> {
+        match state.next_for_vad(epoch) {
+            Step::Ready(Ok(PendingFrame { seq, raw, fresh })) => {
+                let frame = AnalyzedFrame {
+                    samples: raw.clone(),
+                    vad: VadResult {
+                        state: VadState::Silence,
+                        state_changed: false,
+                        speech_prob: 0.0,
+                        pre_roll_audio: None,
+                    },
+                    epoch,
+                };
+                state.push_analyzed(seq, raw, frame).unwrap();
+                Some((seq, fresh))
+            }
+            _ => None,
+        }
+    }
+
+    fn take(state: &mut QueueState, epoch: u64) -> Option<u64> {
+        match state.next_for_asr(epoch) {
+            Step::Ready((seq, raw, frame)) => {
+                assert_eq!(raw, frame.samples);
+                assert_eq!(raw[0], seq as f32);
+                Some(seq)
+            }
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_frames_analyzed_before_reset_are_reanalyzed_in_order() {
+        let mut state = state_with_frames(5);
+        for seq in 0..4 {
+            assert_eq!(analyze(&mut state, 0), Some((seq, true)));
+        }
+
+        // ASR 处理第 0 帧时管道重置：VAD 线程执行重置前，ASR 取到的旧帧全部退回
+        assert_eq!(take(&mut state, 0), Some(0));
+        assert_eq!(take(&mut state, 1), None);
+        assert_eq!(state.reanalyze.keys().copied().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        // VAD 线程执行重置后按顺序重新分析，再继续读取新帧
+        assert_eq!(analyze(&mut state, 1), Some((1, false)));
+        assert_eq!(analyze(&mut state, 1), Some((2, false)));
+        assert_eq!(take(&mut state, 1), Some(1));
+        assert_eq!(analyze(&mut state, 1), Some((3, false)));
+        assert_eq!(analyze(&mut state, 1), Some((4, true)));
+        assert_eq!((take(&mut state, 1), take(&mut state, 1), take(&mut state, 1)), (Some(2), Some(3), Some(4)));
+    }
+
+    #[test]
+    fn test_vad_retracts_stale_frames_after_reset() {
+        let mut state = state_with_frames(3);
+        for _ in 0..3 {
+            analyze(&mut state, 0);
+        }
+        // VAD 线程先执行了重置：队列中的旧帧退回，ASR 不会取到
+        assert_eq!(analyze(&mut state, 1), Some((0, false)));
+        assert_eq!(state.reanalyze.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(take(&mut state, 1), Some(0));
+    }
+
+    #[test]
+    fn test_finish_waits_for_asr_to_drain() {
+        let mut state = state_with_frames(1);
+        state.queues.write_from_capture(&[0.0; 100]).unwrap();
+        state.capture_finished = true;
+
+        assert_eq!(analyze(&mut state, 0), Some((0, true)));
+        // 队列未取完前 VAD 不结束（取走的帧可能因重置退回）
+        assert!(matches!(state.next_for_vad(0), Step::Wait));
+        assert!(matches!(state.next_for_asr(0), Step::Ready(_)));
+        assert!(matches!(state.next_for_asr(0), Step::Wait));
+        // 不足一帧的尾部丢弃
+        assert!(matches!(state.next_for_vad(0), Step::Finished));
+        assert!(matches!(state.next_for_asr(0), Step::Finished));
+    }
+
+    #[test]
+    fn test_vad_holds_off_when_asr_queue_is_full() {
+        let config = AudioQueueConfig {
+            capture_to_vad_capacity: 16000,
+            vad_to_asr_capacity: FRAME_SIZE * 4,
+            backpressure_threshold: 50,
+        };
+        let mut state = QueueState::new(config);
+        for i in 0..4 {
+            state.queues.write_from_capture(&[i as f32; FRAME_SIZE]).unwrap();
+        }
+        assert!(analyze(&mut state, 0).is_some());
+        assert!(analyze(&mut state, 0).is_some());
+        assert!(matches!(state.next_for_vad(0), Step::Wait));
+        assert_eq!(state.queues.capture_to_vad_available(), FRAME_SIZE * 2);
+
+        assert_eq!(take(&mut state, 0), Some(0));
+        assert_eq!(analyze(&mut state, 0), Some((2, true)));
+    }
+
+    #[test]
+    fn test_vad_fails_when_threshold_leaves_no_frame() {
+        // 默认容量、阈值 1%：阈值以下只有 160 / 320 个采样点
+        let config = AudioQueueConfig {
+            backpressure_threshold: 1,
+            ..AudioQueueConfig::default()
+        };
+        let mut state = QueueState::new(config);
+        assert!(matches!(state.next_for_vad(0), Step::Ready(Err(_))));
+    }
+}
@@ -79,6 +79,14 @@ impl RecognitionHistory {
         None
     }
 
+    /// 替换最近一条未撤销的记录（用户从候选列表中选择了替代结果）
+    ///
+    /// 返回被替换的原文本，如果没有可替换的记录则返回 None
+    pub fn replace_last(&mut self, text: String) -> Option<String> {
+        let entry = self.history.iter_mut().rev().find(|e| !e.undone)?;
+        Some(std::mem::replace(&mut entry.text, text))
+    }
+
     /// 获取当前有效的识别历史（未撤销的）
     pub fn get_active_history(&self) -> Vec<String> {
         self.history
@@ -182,6 +190,20 @@ mod tests {
         assert_eq!(active, vec!["第二句话", "第三句话", "第四句话"]);
     }
 
+    #[test]
+    fn test_replace_last() {
+        let mut history = RecognitionHistory::new(10);
+        assert_eq!(history.replace_last("无".to_string()), None);
+
+        history.push("第一句话".to_string());
+        history.push("第二句话".to_string());
+        history.undo();
+
+        // 跳过已撤销的记录
+        assert_eq!(history.replace_last("第一局话".to_string()), Some("第一句话".to_string()));
+        assert_eq!(history.undo(), Some("第一局话".to_string()));
+    }
+
     #[test]
     fn test_clear() {
         let mut history = RecognitionHistory::new(10);
@@ -89,6 +89,13 @@ impl EnergyGate {
     pub fn noise_baseline(&self) -> f32 {
         self.noise_baseline
     }
+
+    /// 当前通过阈值（RMS 需超过该值才送入 VAD；禁用时为 None）
+    pub fn threshold(&self) -> Option<f32> {
+        self.config
+            .enabled
+            .then(|| self.noise_baseline * self.config.noise_multiplier)
+    }
 }
 
 #[cfg(test)]
@@ -234,6 +234,11 @@ impl VadManager {
     pub fn noise_baseline(&self) -> f32 {
         self.energy_gate.noise_baseline()
     }
+
+    /// 获取 Energy Gate 当前通过阈值（禁用时为 None）
+    pub fn energy_gate_threshold(&self) -> Option<f32> {
+        self.energy_gate.threshold()
+    }
 }
 
 // 无 ONNX Runtime 的简化实现（用于编译测试）
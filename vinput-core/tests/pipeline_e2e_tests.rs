@@ -12,10 +12,12 @@
 use std::path::{Path, PathBuf};
 use vinput_core::{
     asr::OnlineRecognizerConfig,
+    audio::EnhancementConfig,
+    config::{ActivationConfig, StopMode},
     endpointing::EndpointDetectorConfig,
     itn::{ITNEngine, ITNMode},
     punctuation::StyleProfile,
-    streaming::{PipelineState, StreamingConfig, StreamingPipeline},
+    streaming::{HypothesisVariantsConfig, PipelineState, StreamingConfig, StreamingPipeline},
     vad::{VadConfig, VadState},
 };
 
@@ -114,6 +116,8 @@ fn create_pipeline() -> StreamingPipeline {
         asr_config,
         punctuation_profile,
         endpoint_config,
+        enhancement_config: EnhancementConfig::default(),
+        variants_config: HypothesisVariantsConfig::default(),
     };
 
     StreamingPipeline::new(config).expect("创建 StreamingPipeline 失败")
@@ -225,6 +229,39 @@ fn collect_test_cases() -> Vec<(PathBuf, String)> {
 // 测试入口
 // ─────────────────────────────────────────────────────────────────────────────
 
+/// 免提听写：持续静默时 idle_duration_ms 逐帧累计到超时，开始说话后清零
+#[test]
+fn test_idle_duration_reaches_hands_free_timeout() {
+    init_log();
+
+    if !models_available() {
+        eprintln!("⏭  跳过免提静默测试：模型不在 {}", MODELS_DIR);
+        return;
+    }
+
+    let activation = ActivationConfig {
+        stop_mode: StopMode::HandsFree,
+        idle_timeout_ms: 320,
+        ..Default::default()
+    };
+    let timeout_ms = activation.idle_timeout_ms().unwrap();
+
+    let mut pipeline = create_pipeline();
+    let silence = [0.0f32; 512];
+    let mut frames = 0;
+    while pipeline.idle_duration_ms() < timeout_ms {
+        let result = pipeline.process(&silence).expect("处理音频帧失败");
+        assert_eq!(result.pipeline_state, PipelineState::Idle);
+        frames += 1;
+        assert!(frames <= 100, "静默计时没有增长");
+    }
+    assert_eq!(frames, 10);
+
+    pipeline.force_vad_state(VadState::Speech);
+    pipeline.process(&silence).expect("处理音频帧失败");
+    assert_eq!(pipeline.idle_duration_ms(), 0);
+}
+
 /// 主 E2E 测试：遍历所有测试对
 #[test]
 fn test_all_recordings() {
@@ -0,0 +1,198 @@
+//! 基于 vinput-core 句柄接口的引擎实现
+//!
+//! 与 fcitx5 插件使用同一套 C ABI（`vinput_engine_*`）：实时命令经回调转为
+//! [`EngineEvent`]，停止录音时排队的最终结果经 `vinput_engine_try_recv_command` 取出。
+
+use crate::engine::{EngineError, EngineEvent, VoiceEngine};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_void;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use vinput_core::ffi::{
+    vinput_command_free, vinput_engine_free, vinput_engine_get_history, vinput_engine_new,
+    vinput_engine_register_callback, vinput_engine_send_event, vinput_engine_try_recv_command,
+    vinput_string_list_free, VInputCommand, VInputCommandType, VInputEvent, VInputEventType,
+    VInputFFIResult, VInputHandle,
+};
+
+/// vinput-core 引擎
+pub struct CoreEngine {
+    handle: *mut VInputHandle,
+    /// 配置文件路径（None 使用 ~/.config/vinput/config.toml）
+    config_path: Option<CString>,
+    /// 事件通道（装箱以保证地址稳定，作为回调的 user_data）
+    events: Box<Sender<EngineEvent>>,
+    recording: bool,
+}
+
+// 句柄内部自带锁，可在服务线程间转移
+unsafe impl Send for CoreEngine {}
+
+impl CoreEngine {
+    /// 创建引擎并注册命令回调
+    pub fn new(config_path: Option<&Path>, events: Sender<EngineEvent>) -> Result<Self, EngineError> {
+        let config_path = config_path
+            .map(|path| {
+                CString::new(path.to_string_lossy().into_owned())
+                    .map_err(|_| EngineError::Failed(format!("配置路径无效: {:?}", path)))
+            })
+            .transpose()?;
+
+        let mut engine = Self {
+            handle: std::ptr::null_mut(),
+            config_path,
+            events: Box::new(events),
+            recording: false,
+        };
+        engine.open()?;
+        Ok(engine)
+    }
+
+    /// 创建引擎句柄并注册回调
+    fn open(&mut self) -> Result<(), EngineError> {
+        let path = self
+            .config_path
+            .as_ref()
+            .map_or(std::ptr::null(), |path| path.as_ptr());
+        let handle = vinput_engine_new(path);
+        if handle.is_null() {
+            return Err(EngineError::Failed("创建 V-Input 引擎失败".to_string()));
+        }
+
+        let user_data = &*self.events as *const Sender<EngineEvent> as *mut c_void;
+        let result = vinput_engine_register_callback(handle, Some(on_command), user_data);
+        if result != VInputFFIResult::Success {
+            vinput_engine_free(handle);
+            return Err(ffi_error("注册命令回调", result));
+        }
+
+        self.handle = handle;
+        Ok(())
+    }
+
+    /// 发送事件
+    fn send(&mut self, event_type: VInputEventType) -> Result<(), EngineError> {
+        let event = VInputEvent::new(event_type);
+        match vinput_engine_send_event(self.handle, &event) {
+            VInputFFIResult::Success => Ok(()),
+            result => Err(ffi_error("发送事件", result)),
+        }
+    }
+
+    /// 取出排队的命令并转发
+    fn drain_commands(&mut self) {
+        loop {
+            let mut command = VInputCommand::new(VInputCommandType::ClearPreedit);
+            if vinput_engine_try_recv_command(self.handle, &mut command) != VInputFFIResult::Success {
+                break;
+            }
+            if let Some(event) = command_event(&command) {
+                let _ = self.events.send(event);
+            }
+            vinput_command_free(&mut command);
+        }
+    }
+}
+
+impl VoiceEngine for CoreEngine {
+    fn start(&mut self) -> Result<(), EngineError> {
+        if self.recording {
+            return Err(EngineError::Busy);
+        }
+        self.send(VInputEventType::StartRecording)?;
+        self.recording = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), EngineError> {
+        if !self.recording {
+            return Err(EngineError::NotRecording);
+        }
+        self.send(VInputEventType::StopRecording)?;
+        self.recording = false;
+        self.drain_commands();
+        Ok(())
+    }
+
+    fn cancel(&mut self) -> Result<(), EngineError> {
+        if !self.recording {
+            return Err(EngineError::NotRecording);
+        }
+        // 引擎丢弃当前句子，只排队清除 Preedit 的命令
+        self.send(VInputEventType::CancelRecording)?;
+        self.recording = false;
+        self.drain_commands();
+        Ok(())
+    }
+
+    fn reload_config(&mut self) -> Result<(), EngineError> {
+        if self.recording {
+            return Err(EngineError::Busy);
+        }
+        tracing::info!("重新加载配置");
+        vinput_engine_free(std::mem::replace(&mut self.handle, std::ptr::null_mut()));
+        self.open()
+    }
+
+    fn history(&self) -> Vec<String> {
+        let list = vinput_engine_get_history(self.handle);
+        if list.is_null() {
+            return Vec::new();
+        }
+        let items = unsafe { std::slice::from_raw_parts((*list).items, (*list).count) };
+        let history = items
+            .iter()
+            .map(|&item| unsafe { CStr::from_ptr(item) }.to_string_lossy().into_owned())
+            .collect();
+        vinput_string_list_free(list);
+        history
+    }
+
+    fn is_recording(&self) -> bool {
+        self.recording
+    }
+}
+
+impl Drop for CoreEngine {
+    fn drop(&mut self) {
+        vinput_engine_free(self.handle);
+    }
+}
+
+/// 命令回调（音频线程中调用）
+extern "C" fn on_command(command: *const VInputCommand, user_data: *mut c_void) {
+    if command.is_null() || user_data.is_null() {
+        return;
+    }
+    let events = unsafe { &*(user_data as *const Sender<EngineEvent>) };
+    if let Some(event) = command_event(unsafe { &*command }) {
+        let _ = events.send(event);
+    }
+}
+
+/// 将引擎命令转换为服务事件
+fn command_event(command: &VInputCommand) -> Option<EngineEvent> {
+    let text = if command.text.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(command.text) }.to_string_lossy().into_owned()
+    };
+
+    match command.command_type {
+        VInputCommandType::CommitText => Some(EngineEvent::Final(text)),
+        VInputCommandType::UpdatePreedit => Some(EngineEvent::Partial(text)),
+        VInputCommandType::ClearPreedit => Some(EngineEvent::Partial(String::new())),
+        // 载荷格式: code\tmessage（Error，也可能仅 message）/ kind\tmessage（AudioWarning）
+        VInputCommandType::Error | VInputCommandType::AudioWarning => {
+            let message = text.split_once('\t').map_or(text.as_str(), |(_, message)| message);
+            Some(EngineEvent::Error(message.to_string()))
+        }
+        VInputCommandType::RecordingStopped => Some(EngineEvent::RecordingStopped),
+        VInputCommandType::WakeWordDetected => Some(EngineEvent::WakeWordDetected(text)),
+        _ => None,
+    }
+}
+
+fn ffi_error(action: &str, result: VInputFFIResult) -> EngineError {
+    EngineError::Failed(format!("{}失败: {:?}", action, result))
+}
@@ -0,0 +1,56 @@
+//! 语音引擎抽象
+//!
+//! D-Bus 服务只依赖 [`VoiceEngine`]：生产环境由 `CoreEngine`（vinput-core 句柄接口）实现，
+//! 测试中可替换为不依赖模型与音频设备的假引擎。
+
+/// 引擎事件（由服务转发为 D-Bus 信号）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineEvent {
+    /// 实时识别结果（Preedit 全文，空字符串表示清除）
+    Partial(String),
+    /// 上屏文本（整句结束或长句流式上屏）
+    Final(String),
+    /// 错误或输入诊断消息
+    Error(String),
+    /// 引擎自动停止录音（静音超时、免提空闲超时、会话无法恢复），服务随后调用 [`VoiceEngine::stop`]
+    RecordingStopped,
+    /// 检测到唤醒词，服务随后调用 [`VoiceEngine::start`]
+    WakeWordDetected(String),
+}
+
+/// 引擎操作错误
+#[derive(Debug, thiserror::Error)]
+pub enum EngineError {
+    /// 正在录音，无法执行该操作
+    #[error("正在录音")]
+    Busy,
+    /// 没有在录音
+    #[error("没有在录音")]
+    NotRecording,
+    /// 引擎调用失败
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// 可由 D-Bus 服务驱动的语音引擎
+///
+/// 识别结果通过创建引擎时传入的事件通道异步发出
+pub trait VoiceEngine: Send + 'static {
+    /// 开始录音
+    fn start(&mut self) -> Result<(), EngineError>;
+
+    /// 停止录音，最终结果以 [`EngineEvent::Final`] 发出
+    fn stop(&mut self) -> Result<(), EngineError>;
+
+    /// 取消录音，丢弃本次识别结果
+    fn cancel(&mut self) -> Result<(), EngineError>;
+
+    /// 重新加载配置文件（录音中不可重载）
+    fn reload_config(&mut self) -> Result<(), EngineError>;
+
+    /// 识别历史（未撤销的记录，按时间顺序）
+    fn history(&self) -> Vec<String>;
+
+    /// 是否正在录音
+    fn is_recording(&self) -> bool;
+}
@@ -0,0 +1,7 @@
+//! V-Input 守护进程
+//!
+//! 在会话 D-Bus 上导出语音输入引擎，供 fcitx5 以外的客户端使用
+
+pub mod core_engine;
+pub mod engine;
+pub mod service;
@@ -0,0 +1,98 @@
+//! V-Input 守护进程
+//!
+//! 持有 vinput-core 引擎，在会话 D-Bus 上导出 `org.vinput.VInput1`，
+//! 供脚本、GNOME 扩展等非 fcitx5 客户端使用语音输入。
+//!
+//! 用法: vinput-daemon [--config <config.toml>] [--address <D-Bus 地址>]
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::mpsc;
+use vinput_daemon::core_engine::CoreEngine;
+use vinput_daemon::service;
+
+/// 命令行参数
+struct Args {
+    /// 配置文件路径（默认 ~/.config/vinput/config.toml）
+    config: Option<PathBuf>,
+    /// D-Bus 地址（默认连接会话总线，测试时可指定私有总线）
+    address: Option<String>,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Args {
+            config: None,
+            address: None,
+        };
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--config" => {
+                    args.config = Some(iter.next().ok_or("--config 需要参数")?.into());
+                }
+                "--address" => {
+                    args.address = Some(iter.next().ok_or("--address 需要参数")?);
+                }
+                "-h" | "--help" => {
+                    return Err("用法: vinput-daemon [--config <config.toml>] [--address <D-Bus 地址>]".to_string());
+                }
+                other => return Err(format!("未知参数: {}", other)),
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_env("VINPUT_LOG")
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (events_tx, events_rx) = mpsc::channel();
+    let engine = match CoreEngine::new(args.config.as_deref(), events_tx) {
+        Ok(engine) => engine,
+        Err(e) => {
+            tracing::error!("❌ 引擎初始化失败: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let connection = match &args.address {
+        Some(address) => zbus::blocking::connection::Builder::address(address.as_str())
+            .and_then(|builder| builder.build()),
+        None => zbus::blocking::Connection::session(),
+    };
+    let connection = match connection {
+        Ok(connection) => connection,
+        Err(e) => {
+            tracing::error!("❌ 连接 D-Bus 失败: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let forwarder = match service::serve(&connection, Box::new(engine), events_rx) {
+        Ok(forwarder) => forwarder,
+        Err(e) => {
+            tracing::error!("❌ 导出 D-Bus 服务失败: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    tracing::info!("✅ V-Input 守护进程已启动: {} {}", service::BUS_NAME, service::OBJECT_PATH);
+
+    // 方法调用由 zbus 内部线程处理；引擎常驻，转发线程不会退出
+    let _ = forwarder.join();
+    ExitCode::SUCCESS
+}
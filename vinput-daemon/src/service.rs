@@ -0,0 +1,175 @@
+//! 会话 D-Bus 服务
+//!
+//! 在 `org.vinput.VInput1` 上导出引擎控制接口：
+//!
+//! - 方法：`Start` / `Stop` / `Cancel` / `ReloadConfig` / `GetHistory`
+//! - 属性：`Recording`
+//! - 信号：`PartialResult(s)` / `FinalResult(s)` / `Error(s)`
+//!
+//! 引擎自动停止录音或检测到唤醒词时，服务与 fcitx5 前端一样补发 Stop / Start，
+//! 并通过 `PropertiesChanged` 通知 `Recording` 的变化。
+
+use crate::engine::{EngineError, EngineEvent, VoiceEngine};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use zbus::fdo;
+use zbus::object_server::SignalContext;
+
+/// 服务的会话总线名称
+pub const BUS_NAME: &str = "org.vinput.VInput1";
+/// 引擎对象路径
+pub const OBJECT_PATH: &str = "/org/vinput/VInput1";
+
+/// D-Bus 接口实现
+pub struct VInputService {
+    engine: Arc<Mutex<Box<dyn VoiceEngine>>>,
+}
+
+impl VInputService {
+    /// 包装引擎
+    pub fn new(engine: Box<dyn VoiceEngine>) -> Self {
+        Self {
+            engine: Arc::new(Mutex::new(engine)),
+        }
+    }
+
+    fn with_engine<T>(
+        &self,
+        f: impl FnOnce(&mut dyn VoiceEngine) -> Result<T, EngineError>,
+    ) -> fdo::Result<T> {
+        let mut engine = self
+            .engine
+            .lock()
+            .map_err(|_| fdo::Error::Failed("引擎状态锁已损坏".to_string()))?;
+        f(engine.as_mut()).map_err(|e| fdo::Error::Failed(e.to_string()))
+    }
+
+    /// 处理改变录音状态的引擎事件，返回录音状态是否可能变化
+    fn apply_engine_event(&self, event: &EngineEvent) -> fdo::Result<bool> {
+        match event {
+            // 已被 Stop / Cancel 结束时忽略
+            EngineEvent::RecordingStopped => self.with_engine(|engine| match engine.stop() {
+                Err(EngineError::NotRecording) => Ok(false),
+                result => result.map(|()| true),
+            }),
+            EngineEvent::WakeWordDetected(keyword) => {
+                tracing::info!("检测到唤醒词: {}", keyword);
+                self.with_engine(|engine| match engine.start() {
+                    Err(EngineError::Busy) => Ok(false),
+                    result => result.map(|()| true),
+                })
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[zbus::interface(name = "org.vinput.VInput1")]
+impl VInputService {
+    /// 开始录音
+    async fn start(&self, #[zbus(signal_context)] ctxt: SignalContext<'_>) -> fdo::Result<()> {
+        self.with_engine(|engine| engine.start())?;
+        self.recording_changed(&ctxt).await?;
+        Ok(())
+    }
+
+    /// 停止录音并上屏最终结果（通过 FinalResult 信号）
+    async fn stop(&self, #[zbus(signal_context)] ctxt: SignalContext<'_>) -> fdo::Result<()> {
+        self.with_engine(|engine| engine.stop())?;
+        self.recording_changed(&ctxt).await?;
+        Ok(())
+    }
+
+    /// 取消录音，不产生最终结果
+    async fn cancel(&self, #[zbus(signal_context)] ctxt: SignalContext<'_>) -> fdo::Result<()> {
+        self.with_engine(|engine| engine.cancel())?;
+        self.recording_changed(&ctxt).await?;
+        Ok(())
+    }
+
+    /// 重新加载 ~/.config/vinput/config.toml
+    fn reload_config(&self) -> fdo::Result<()> {
+        self.with_engine(|engine| engine.reload_config())
+    }
+
+    /// 识别历史（未撤销的记录，按时间顺序）
+    fn get_history(&self) -> fdo::Result<Vec<String>> {
+        self.with_engine(|engine| Ok(engine.history()))
+    }
+
+    /// 是否正在录音
+    #[zbus(property)]
+    fn recording(&self) -> bool {
+        self.engine
+            .lock()
+            .map(|engine| engine.is_recording())
+            .unwrap_or(false)
+    }
+
+    /// 实时识别结果（Preedit 全文，空字符串表示清除）
+    #[zbus(signal)]
+    pub async fn partial_result(ctxt: &SignalContext<'_>, text: &str) -> zbus::Result<()>;
+
+    /// 上屏文本
+    #[zbus(signal)]
+    pub async fn final_result(ctxt: &SignalContext<'_>, text: &str) -> zbus::Result<()>;
+
+    /// 错误或输入诊断消息
+    #[zbus(signal)]
+    pub async fn error(ctxt: &SignalContext<'_>, message: &str) -> zbus::Result<()>;
+}
+
+/// 启动信号转发线程：把引擎事件转为 D-Bus 信号，事件通道关闭后退出
+pub fn spawn_signal_forwarder(
+    connection: zbus::blocking::Connection,
+    events: Receiver<EngineEvent>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let iface = match connection
+            .object_server()
+            .interface::<_, VInputService>(OBJECT_PATH)
+        {
+            Ok(iface) => iface,
+            Err(e) => {
+                tracing::error!("获取服务接口失败: {}", e);
+                return;
+            }
+        };
+        let ctxt = iface.signal_context();
+
+        for event in events {
+            let result = match &event {
+                EngineEvent::Partial(text) => zbus::block_on(VInputService::partial_result(ctxt, text)),
+                EngineEvent::Final(text) => zbus::block_on(VInputService::final_result(ctxt, text)),
+                EngineEvent::Error(message) => zbus::block_on(VInputService::error(ctxt, message)),
+                EngineEvent::RecordingStopped | EngineEvent::WakeWordDetected(_) => {
+                    let service = iface.get();
+                    match service.apply_engine_event(&event) {
+                        Ok(true) => zbus::block_on(service.recording_changed(ctxt)),
+                        Ok(false) => Ok(()),
+                        Err(e) => Err(e.into()),
+                    }
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!("发送信号失败 ({:?}): {}", event, e);
+            }
+        }
+
+        tracing::info!("引擎事件通道已关闭，信号转发线程退出");
+    })
+}
+
+/// 在给定连接上导出服务并申请总线名称，返回信号转发线程
+pub fn serve(
+    connection: &zbus::blocking::Connection,
+    engine: Box<dyn VoiceEngine>,
+    events: Receiver<EngineEvent>,
+) -> zbus::Result<JoinHandle<()>> {
+    connection
+        .object_server()
+        .at(OBJECT_PATH, VInputService::new(engine))?;
+    connection.request_name(BUS_NAME)?;
+    Ok(spawn_signal_forwarder(connection.clone(), events))
+}
@@ -0,0 +1,224 @@
+//! D-Bus 服务集成测试
+//!
+//! 在私有 dbus-daemon 上导出服务（使用假引擎，不需要模型与音频设备），
+//! 通过客户端连接调用方法并接收信号。未安装 dbus-daemon 时跳过。
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Sender};
+use vinput_daemon::engine::{EngineError, EngineEvent, VoiceEngine};
+use vinput_daemon::service::{self, BUS_NAME, OBJECT_PATH};
+use zbus::blocking::{Connection, Proxy};
+use zbus::proxy::CacheProperties;
+
+/// 私有会话总线（测试结束时终止）
+struct PrivateBus {
+    child: Child,
+    address: String,
+}
+
+impl PrivateBus {
+    fn start() -> Option<Self> {
+        let mut child = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+
+        let mut address = String::new();
+        let stdout = child.stdout.take()?;
+        BufReader::new(stdout).read_line(&mut address).ok()?;
+        let address = address.trim().to_string();
+        if address.is_empty() {
+            let _ = child.kill();
+            return None;
+        }
+        Some(Self { child, address })
+    }
+
+    fn connect(&self) -> Connection {
+        zbus::blocking::connection::Builder::address(self.address.as_str())
+            .and_then(|builder| builder.build())
+            .expect("连接私有总线失败")
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 假引擎：开始录音时给出部分结果，停止时给出最终结果
+struct FakeEngine {
+    events: Sender<EngineEvent>,
+    recording: bool,
+    history: Vec<String>,
+}
+
+impl VoiceEngine for FakeEngine {
+    fn start(&mut self) -> Result<(), EngineError> {
+        if self.recording {
+            return Err(EngineError::Busy);
+        }
+        self.recording = true;
+        let _ = self.events.send(EngineEvent::Partial("今天天气".to_string()));
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), EngineError> {
+        if !self.recording {
+            return Err(EngineError::NotRecording);
+        }
+        self.recording = false;
+        self.history.push("今天天气不错。".to_string());
+        let _ = self.events.send(EngineEvent::Final("今天天气不错。".to_string()));
+        Ok(())
+    }
+
+    fn cancel(&mut self) -> Result<(), EngineError> {
+        if !self.recording {
+            return Err(EngineError::NotRecording);
+        }
+        self.recording = false;
+        let _ = self.events.send(EngineEvent::Partial(String::new()));
+        Ok(())
+    }
+
+    fn reload_config(&mut self) -> Result<(), EngineError> {
+        if self.recording {
+            return Err(EngineError::Busy);
+        }
+        Ok(())
+    }
+
+    fn history(&self) -> Vec<String> {
+        self.history.clone()
+    }
+
+    fn is_recording(&self) -> bool {
+        self.recording
+    }
+}
+
+fn client_proxy(connection: &Connection) -> Proxy<'static> {
+    zbus::blocking::proxy::Builder::new(connection)
+        .destination(BUS_NAME)
+        .and_then(|b| b.path(OBJECT_PATH))
+        .and_then(|b| b.interface(BUS_NAME))
+        .map(|b| b.cache_properties(CacheProperties::No))
+        .and_then(|b| b.build())
+        .expect("创建客户端代理失败")
+}
+
+#[test]
+fn test_dbus_start_stop_cancel_and_history() {
+    let Some(bus) = PrivateBus::start() else {
+        eprintln!("未找到 dbus-daemon，跳过");
+        return;
+    };
+
+    let (events_tx, events_rx) = mpsc::channel();
+    let engine = FakeEngine {
+        events: events_tx,
+        recording: false,
+        history: Vec::new(),
+    };
+    let server = bus.connect();
+    let _forwarder = service::serve(&server, Box::new(engine), events_rx).expect("导出服务失败");
+
+    let client = bus.connect();
+    let proxy = client_proxy(&client);
+    let mut partials = proxy.receive_signal("PartialResult").unwrap();
+    let mut finals = proxy.receive_signal("FinalResult").unwrap();
+
+    // Start：进入录音状态并收到部分结果
+    proxy.call_method("Start", &()).unwrap();
+    assert!(proxy.get_property::<bool>("Recording").unwrap());
+    let partial = partials.next().unwrap();
+    assert_eq!(partial.body().deserialize::<String>().unwrap(), "今天天气");
+
+    // 录音中不可重载配置，重复 Start 报错
+    assert!(proxy.call_method("ReloadConfig", &()).is_err());
+    assert!(proxy.call_method("Start", &()).is_err());
+
+    // Stop：收到最终结果并记入历史
+    proxy.call_method("Stop", &()).unwrap();
+    assert!(!proxy.get_property::<bool>("Recording").unwrap());
+    let final_result = finals.next().unwrap();
+    assert_eq!(final_result.body().deserialize::<String>().unwrap(), "今天天气不错。");
+
+    let history: Vec<String> = proxy.call("GetHistory", &()).unwrap();
+    assert_eq!(history, vec!["今天天气不错。"]);
+
+    // Cancel：清除部分结果，不产生新的历史
+    proxy.call_method("Start", &()).unwrap();
+    partials.next().unwrap();
+    proxy.call_method("Cancel", &()).unwrap();
+    let cleared = partials.next().unwrap();
+    assert_eq!(cleared.body().deserialize::<String>().unwrap(), "");
+    let history: Vec<String> = proxy.call("GetHistory", &()).unwrap();
+    assert_eq!(history.len(), 1);
+
+    proxy.call_method("ReloadConfig", &()).unwrap();
+}
+
+#[test]
+fn test_dbus_auto_stop_and_wake_word_update_recording() {
+    let Some(bus) = PrivateBus::start() else {
+        eprintln!("未找到 dbus-daemon，跳过");
+        return;
+    };
+
+    let (events_tx, events_rx) = mpsc::channel();
+    let engine = FakeEngine {
+        events: events_tx.clone(),
+        recording: false,
+        history: Vec::new(),
+    };
+    let server = bus.connect();
+    let _forwarder = service::serve(&server, Box::new(engine), events_rx).expect("导出服务失败");
+
+    let client = bus.connect();
+    let proxy = client_proxy(&client);
+    let properties = zbus::blocking::fdo::PropertiesProxy::builder(&client)
+        .destination(BUS_NAME)
+        .and_then(|b| b.path(OBJECT_PATH))
+        .and_then(|b| b.build())
+        .expect("创建属性代理失败");
+    let mut changes = properties.receive_properties_changed().unwrap();
+    let mut finals = proxy.receive_signal("FinalResult").unwrap();
+    let mut next_recording = || -> bool {
+        let change = changes.next().unwrap();
+        let args = change.args().unwrap();
+        args.changed_properties()["Recording"].downcast_ref::<bool>().unwrap()
+    };
+
+    proxy.call_method("Start", &()).unwrap();
+    assert!(next_recording());
+
+    // 引擎自动停止（如免提空闲超时）：服务补发 Stop，取出最终结果并通知 Recording 变化
+    events_tx.send(EngineEvent::RecordingStopped).unwrap();
+    assert!(!next_recording());
+    assert!(!proxy.get_property::<bool>("Recording").unwrap());
+    let final_result = finals.next().unwrap();
+    assert_eq!(final_result.body().deserialize::<String>().unwrap(), "今天天气不错。");
+
+    // 唤醒词：服务开始录音
+    events_tx.send(EngineEvent::WakeWordDetected("小V小V".to_string())).unwrap();
+    assert!(next_recording());
+    assert!(proxy.get_property::<bool>("Recording").unwrap());
+
+    // 已经停止后再收到自动停止时忽略（事件按顺序转发，以部分结果为界）
+    proxy.call_method("Stop", &()).unwrap();
+    assert!(!next_recording());
+    let mut partials = proxy.receive_signal("PartialResult").unwrap();
+    events_tx.send(EngineEvent::RecordingStopped).unwrap();
+    events_tx.send(EngineEvent::Partial("标记".to_string())).unwrap();
+    let marker = partials.next().unwrap();
+    assert_eq!(marker.body().deserialize::<String>().unwrap(), "标记");
+    proxy.call_method("Start", &()).unwrap();
+    assert!(next_recording());
+}
@@ -1,39 +1,68 @@
 //! 基本设置面板
 
 use eframe::egui;
-use crate::config::VInputConfig;
+use crate::config::{ActivationConfig, ActivationMode, ShortcutConfig, StopMode, VInputConfig};
+use crate::mic_meter::MicMeter;
+use crate::shortcuts::{self, ShortcutTarget};
+use vinput_core::audio::{enumerate_audio_devices, LevelMeterConfig};
 
 pub struct BasicSettingsPanel {
     itn_mode: String,
     audio_device: String,
     audio_devices: Vec<(String, String)>,
     language: String,
-    /// 当前已保存的热键字符串
-    hotkey: String,
-    /// 是否处于捕获模式
-    capturing: bool,
+    /// 快捷键（触发 / 撤销 / 重试）
+    shortcuts: ShortcutConfig,
+    /// 录音触发方式
+    activation: ActivationConfig,
+    /// 正在捕获的快捷键（None 表示未处于捕获模式）
+    capturing: Option<ShortcutTarget>,
     /// 已按下但尚未确认的独立修饰键（等待释放来确认是单独按键而非组合键前缀）
     pending_modifier: Option<String>,
     /// 上一帧的修饰键状态（用于检测修饰键按下/释放）
     prev_modifiers: egui::Modifiers,
+    /// 麦克风电平测试（开启时持有后台采集）
+    mic_meter: Option<MicMeter>,
+    /// 电平测试启动失败的提示
+    mic_meter_error: Option<String>,
+    /// 电平表诊断阈值
+    level_meter: LevelMeterConfig,
 }
 
 impl BasicSettingsPanel {
     pub fn new(config: &VInputConfig) -> Self {
-        Self {
+        let mut panel = Self {
             itn_mode: "Auto".to_string(),
-            audio_device: "default".to_string(),
+            audio_device: config.audio.preferred_devices.first()
+                .cloned()
+                .unwrap_or_else(|| "default".to_string()),
             audio_devices: vec![("default".to_string(), "默认设备".to_string())],
             language: "zh-CN".to_string(),
-            hotkey: config.basic.hotkey.clone(),
-            capturing: false,
+            shortcuts: config.shortcuts.clone(),
+            activation: config.activation.clone(),
+            capturing: None,
             pending_modifier: None,
             prev_modifiers: egui::Modifiers::NONE,
-        }
+            mic_meter: None,
+            mic_meter_error: None,
+            level_meter: config.audio.level_meter.clone(),
+        };
+        panel.refresh_audio_devices();
+        panel
     }
 
     pub fn apply_to_config(&self, config: &mut VInputConfig) {
-        config.basic.hotkey = self.hotkey.clone();
+        config.shortcuts = self.shortcuts.clone();
+        config.activation = self.activation.clone();
+
+        // 选中的设备放在首选列表最前，其余首选设备保留为后备
+        let preferred = &mut config.audio.preferred_devices;
+        if self.audio_device == "default" {
+            preferred.clear();
+        } else {
+            preferred.retain(|d| d != &self.audio_device);
+            preferred.insert(0, self.audio_device.clone());
+        }
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
@@ -84,16 +113,18 @@ impl BasicSettingsPanel {
             // 音频输入设备
             ui.label(egui::RichText::new("音频输入设备").size(13.0).strong());
             ui.add_space(6.0);
+            let mut device_changed = false;
             ui.group(|ui| {
                 ui.horizontal(|ui| {
                     egui::ComboBox::from_id_salt("audio_device")
                         .width(280.0)
-                        .selected_text(egui::RichText::new(&self.audio_device).size(13.0))
+                        .selected_text(egui::RichText::new(self.audio_device_label()).size(13.0))
                         .show_ui(ui, |ui| {
                             for (id, desc) in &self.audio_devices {
                                 if ui.selectable_value(&mut self.audio_device, id.clone(),
                                     egui::RichText::new(desc).size(13.0)).clicked() {
                                     modified = true;
+                                    device_changed = true;
                                 }
                             }
                         });
@@ -102,26 +133,113 @@ impl BasicSettingsPanel {
                         self.refresh_audio_devices();
                     }
                 });
+                ui.add_space(4.0);
+                ui.label(egui::RichText::new("首选设备拔出时自动回退到默认设备，重新插入后自动切回").size(11.0)
+                    .color(egui::Color32::from_rgb(140, 140, 140)));
+                ui.add_space(8.0);
+                self.ui_mic_meter(ui, device_changed);
             });
 
             ui.add_space(12.0);
 
-            // 全局热键
-            ui.label(egui::RichText::new("全局热键").size(13.0).strong());
+            // 录音触发方式
+            ui.label(egui::RichText::new("录音触发方式").size(13.0).strong());
             ui.add_space(6.0);
+            modified |= self.ui_activation(ui);
 
-            if self.capturing {
-                modified |= self.ui_capture_mode(ui);
+            ui.add_space(12.0);
+
+            // 快捷键
+            ui.label(egui::RichText::new("快捷键").size(13.0).strong());
+            ui.add_space(6.0);
+
+            if let Some(target) = self.capturing {
+                modified |= self.ui_capture_mode(ui, target);
             } else {
-                self.ui_display_mode(ui, &mut modified);
+                self.ui_display_mode(ui);
             }
+            self.ui_conflicts(ui);
         });
 
         modified
     }
 
+    /// 触发方式：按法与停止方式，返回是否产生修改
+    fn ui_activation(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut modified = false;
+        ui.group(|ui| {
+            let prev_mode = self.activation.mode;
+            ui.radio_value(&mut self.activation.mode, ActivationMode::Toggle,
+                egui::RichText::new("单击切换  —  按一下开始录音，再按一下停止").size(13.0));
+            ui.add_space(2.0);
+            ui.radio_value(&mut self.activation.mode, ActivationMode::Hold,
+                egui::RichText::new("按住说话  —  按住触发键录音，松开即停止").size(13.0));
+            ui.add_space(2.0);
+            ui.radio_value(&mut self.activation.mode, ActivationMode::DoubleTap,
+                egui::RichText::new("双击开始  —  快速按两下开始录音，单击停止（避免误触）").size(13.0));
+            if self.activation.mode != prev_mode { modified = true; }
+
+            if self.activation.mode == ActivationMode::DoubleTap {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("双击间隔").size(13.0));
+                    if ui.add(egui::Slider::new(&mut self.activation.double_tap_interval_ms, 200..=800)
+                        .suffix(" ms")).changed() {
+                        modified = true;
+                    }
+                });
+            }
+
+            ui.add_space(6.0);
+            ui.separator();
+            ui.add_space(4.0);
+
+            let prev_stop = self.activation.stop_mode;
+            ui.radio_value(&mut self.activation.stop_mode, StopMode::Continuous,
+                egui::RichText::new("连续听写  —  说完一句自动上屏并继续录音，直到再次按键").size(13.0));
+            ui.add_space(2.0);
+            ui.radio_value(&mut self.activation.stop_mode, StopMode::AutoStop,
+                egui::RichText::new("自动停止  —  说完一句上屏后自动停止录音").size(13.0));
+            ui.add_space(2.0);
+            ui.radio_value(&mut self.activation.stop_mode, StopMode::HandsFree,
+                egui::RichText::new("免提听写  —  逐句上屏，过滤背景人声，长时间无语音后自动停止").size(13.0));
+            if self.activation.stop_mode != prev_stop { modified = true; }
+
+            if self.activation.stop_mode == StopMode::HandsFree {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("静默超时").size(13.0));
+                    let mut secs = self.activation.idle_timeout_ms / 1000;
+                    if ui.add(egui::Slider::new(&mut secs, 0..=300)
+                        .suffix(" 秒")).changed() {
+                        self.activation.idle_timeout_ms = secs * 1000;
+                        modified = true;
+                    }
+                    if secs == 0 {
+                        ui.label(egui::RichText::new("（不自动停止）").size(12.0)
+                            .color(egui::Color32::GRAY));
+                    }
+                });
+            }
+        });
+        modified
+    }
+
+    /// 快捷键冲突提示
+    fn ui_conflicts(&self, ui: &mut egui::Ui) {
+        let warnings = shortcuts::conflicts(&self.shortcuts, self.activation.mode);
+        if warnings.is_empty() {
+            return;
+        }
+        ui.add_space(6.0);
+        for warning in warnings {
+            ui.label(egui::RichText::new(format!("⚠ {}", warning)).size(12.0)
+                .color(egui::Color32::from_rgb(220, 150, 60)));
+        }
+    }
+
     /// 捕获模式 UI —— 内联显示，返回是否产生修改
-    fn ui_capture_mode(&mut self, ui: &mut egui::Ui) -> bool {
+    fn ui_capture_mode(&mut self, ui: &mut egui::Ui, target: ShortcutTarget) -> bool {
         let mut modified = false;
 
         // ① 先读取本帧所有按键事件（必须在渲染 widget 之前，避免事件被消费）
@@ -157,13 +275,13 @@ impl BasicSettingsPanel {
         match key_result {
             Some(KeyResult::Cancel) => {
                 // Esc 取消，清空 pending
-                self.capturing = false;
+                self.capturing = None;
                 self.pending_modifier = None;
             }
             Some(KeyResult::Captured(hotkey)) => {
                 // 普通按键（可能带修饰键）直接确认，忽略 pending
-                self.hotkey = hotkey;
-                self.capturing = false;
+                *target.get_mut(&mut self.shortcuts) = hotkey;
+                self.capturing = None;
                 self.pending_modifier = None;
                 modified = true;
             }
@@ -176,8 +294,8 @@ impl BasicSettingsPanel {
                         (self.prev_modifiers.alt   && !current_mods.alt)   ||
                         (self.prev_modifiers.shift && !current_mods.shift);
                     if released {
-                        self.hotkey = pending;
-                        self.capturing = false;
+                        *target.get_mut(&mut self.shortcuts) = pending;
+                        self.capturing = None;
                         modified = true;
                     } else {
                         // 仍在按住，继续等待
@@ -185,13 +303,13 @@ impl BasicSettingsPanel {
                     }
                 } else {
                     // 检测修饰键是否刚被按下（由 false → true）
-                    // 注意：此处无法区分左/右 Ctrl，统一记为 RCtrl（与默认热键一致）
+                    // 注意：此处无法区分左/右修饰键，统一记为右侧按键（与默认触发键 Control_R 一致）
                     if !self.prev_modifiers.ctrl && current_mods.ctrl {
-                        self.pending_modifier = Some("RCtrl".to_string());
+                        self.pending_modifier = Some("Control_R".to_string());
                     } else if !self.prev_modifiers.alt && current_mods.alt {
-                        self.pending_modifier = Some("Alt".to_string());
+                        self.pending_modifier = Some("Alt_R".to_string());
                     } else if !self.prev_modifiers.shift && current_mods.shift {
-                        self.pending_modifier = Some("Shift".to_string());
+                        self.pending_modifier = Some("Shift_R".to_string());
                     }
                 }
             }
@@ -202,9 +320,9 @@ impl BasicSettingsPanel {
 
         // ③ 渲染捕获提示框
         let hint = if let Some(ref pending) = self.pending_modifier {
-            format!("已检测到：{}  —  松开按键确认，或继续按其他键组合", hotkey_display(pending))
+            format!("已检测到：{}  —  松开按键确认，或继续按其他键组合", shortcuts::display_name(pending))
         } else {
-            "请按下目标热键（单键或组合键均可）".to_string()
+            format!("请按下新的{}快捷键（单键或组合键均可）", target.label())
         };
 
         let frame_color = if self.pending_modifier.is_some() {
@@ -245,62 +363,145 @@ impl BasicSettingsPanel {
     }
 
     /// 正常展示模式 UI
-    fn ui_display_mode(&mut self, ui: &mut egui::Ui, modified: &mut bool) {
+    fn ui_display_mode(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
-            ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("当前热键：").size(13.0));
-                egui::Frame::new()
-                    .fill(egui::Color32::from_rgb(40, 44, 52))
-                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(80, 90, 110)))
-                    .corner_radius(4.0)
-                    .inner_margin(egui::Margin::symmetric(8, 3))
-                    .show(ui, |ui| {
-                        ui.label(egui::RichText::new(hotkey_display(&self.hotkey))
-                            .size(13.0).strong()
-                            .color(egui::Color32::from_rgb(80, 160, 240)));
-                    });
-                ui.add_space(12.0);
-                if ui.add_sized([60.0, 26.0],
-                    egui::Button::new(egui::RichText::new("修改").size(13.0))).clicked() {
-                    self.capturing = true;
-                    self.pending_modifier = None;
-                    self.prev_modifiers = egui::Modifiers::NONE;
-                    *modified = false; // 进入捕获模式本身不算修改
-                }
-            });
-            ui.add_space(4.0);
-            ui.label(egui::RichText::new("注意：Wayland 下全局热键支持有限").size(11.0)
+            for target in ShortcutTarget::ALL {
+                ui.horizontal(|ui| {
+                    ui.add_sized([56.0, 22.0], egui::Label::new(
+                        egui::RichText::new(format!("{}：", target.label())).size(13.0)));
+                    egui::Frame::new()
+                        .fill(egui::Color32::from_rgb(40, 44, 52))
+                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(80, 90, 110)))
+                        .corner_radius(4.0)
+                        .inner_margin(egui::Margin::symmetric(8, 3))
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new(shortcuts::display_name(target.get(&self.shortcuts)))
+                                .size(13.0).strong()
+                                .color(egui::Color32::from_rgb(80, 160, 240)));
+                        });
+                    ui.add_space(12.0);
+                    // 进入捕获模式本身不算修改
+                    if ui.add_sized([60.0, 26.0],
+                        egui::Button::new(egui::RichText::new("修改").size(13.0))).clicked() {
+                        self.capturing = Some(target);
+                        self.pending_modifier = None;
+                        self.prev_modifiers = egui::Modifiers::NONE;
+                    }
+                });
+                ui.add_space(2.0);
+            }
+            ui.add_space(2.0);
+            ui.label(egui::RichText::new("快捷键由 Fcitx5 / IBus 输入法前端处理，修改后需重启输入法生效").size(11.0)
                 .color(egui::Color32::from_rgb(160, 130, 60)));
         });
-        let _ = modified; // suppress unused warning
     }
 
-    fn refresh_audio_devices(&mut self) {
-        self.audio_devices = vec![
-            ("default".to_string(), "默认设备".to_string()),
-            ("alsa_input.pci-0000_00_1f.3.analog-stereo".to_string(), "内置音频 模拟立体声".to_string()),
-        ];
+    /// 麦克风电平测试：实时电平条 + 削波 / 无信号 / 增益过低提示
+    fn ui_mic_meter(&mut self, ui: &mut egui::Ui, device_changed: bool) {
+        // 切换设备时重新打开采集
+        if device_changed && self.mic_meter.is_some() {
+            self.mic_meter = None;
+            self.start_mic_meter();
+        }
+
+        ui.horizontal(|ui| {
+            let label = if self.mic_meter.is_some() { "停止测试" } else { "测试麦克风" };
+            // 已在测试时点击即停止（take 释放后台采集）
+            if ui.button(egui::RichText::new(label).size(13.0)).clicked()
+                && self.mic_meter.take().is_none()
+            {
+                self.start_mic_meter();
+            }
+
+            let Some(meter) = &self.mic_meter else {
+                if let Some(err) = &self.mic_meter_error {
+                    ui.label(egui::RichText::new(err).size(11.0)
+                        .color(egui::Color32::from_rgb(200, 80, 80)));
+                }
+                return;
+            };
+
+            let level = meter.level();
+            // -60 dBFS ~ 0 dBFS 映射到电平条
+            let fraction = ((level.rms_dbfs + 60.0) / 60.0).clamp(0.0, 1.0);
+            let color = if level.clipping {
+                egui::Color32::from_rgb(220, 70, 60)
+            } else if level.rms_dbfs > -12.0 {
+                egui::Color32::from_rgb(220, 180, 60)
+            } else {
+                egui::Color32::from_rgb(60, 180, 100)
+            };
+            ui.add(egui::ProgressBar::new(fraction)
+                .desired_width(200.0)
+                .fill(color)
+                .text(egui::RichText::new(format!("{:.0} dBFS（峰值 {:.0}）", level.rms_dbfs, level.peak_dbfs)).size(11.0)));
+
+            if level.clipping {
+                ui.label(egui::RichText::new("输入削波，请降低增益").size(11.0)
+                    .color(egui::Color32::from_rgb(220, 70, 60)));
+            } else if level.no_signal {
+                ui.label(egui::RichText::new("没有检测到信号，请检查麦克风是否静音").size(11.0)
+                    .color(egui::Color32::from_rgb(160, 130, 60)));
+            } else if level.low_gain {
+                ui.label(egui::RichText::new("说话音量过低，请提高输入增益").size(11.0)
+                    .color(egui::Color32::from_rgb(160, 130, 60)));
+            }
+
+            // 持续重绘以刷新电平
+            ui.ctx().request_repaint_after(std::time::Duration::from_millis(50));
+        });
+    }
+
+    fn start_mic_meter(&mut self) {
+        match MicMeter::start(&self.audio_device, &self.level_meter) {
+            Ok(meter) => {
+                self.mic_meter = Some(meter);
+                self.mic_meter_error = None;
+            }
+            Err(e) => {
+                tracing::warn!("启动麦克风电平测试失败: {}", e);
+                self.mic_meter_error = Some(format!("无法打开音频设备（需要 parec）: {}", e));
+            }
+        }
+    }
+
+    fn audio_device_label(&self) -> String {
+        self.audio_devices
+            .iter()
+            .find(|(id, _)| id == &self.audio_device)
+            .map(|(_, desc)| desc.clone())
+            .unwrap_or_else(|| self.audio_device.clone())
     }
-}
 
-/// 将存储的热键字符串转为友好显示名
-fn hotkey_display(hotkey: &str) -> &str {
-    match hotkey {
-        "RCtrl"  => "右 Ctrl",
-        "LCtrl"  => "左 Ctrl",
-        "Ctrl"   => "Ctrl",
-        "Alt"    => "Alt",
-        "Shift"  => "Shift",
-        other    => other,
+    fn refresh_audio_devices(&mut self) {
+        self.audio_devices = vec![("default".to_string(), "默认设备".to_string())];
+
+        // 使用 vinput-core 的设备枚举（已过滤输出设备的 monitor）
+        match enumerate_audio_devices() {
+            Ok(devices) => {
+                for device in devices {
+                    let label = format!("{}（{}）", device.description, device.name);
+                    self.audio_devices.push((device.name, label));
+                }
+            }
+            Err(e) => tracing::warn!("枚举音频设备失败: {}", e),
+        }
+
+        // 当前首选设备未接入时仍保留在列表中，避免选择被意外清除
+        if self.audio_device != "default"
+            && !self.audio_devices.iter().any(|(id, _)| id == &self.audio_device)
+        {
+            self.audio_devices.push((self.audio_device.clone(), format!("{}（未连接）", self.audio_device)));
+        }
     }
 }
 
 /// 将修饰键格式化为前缀字符串（带 + 号后缀）
 fn format_modifiers(modifiers: &egui::Modifiers) -> String {
     let mut parts: Vec<&str> = Vec::new();
-    if modifiers.ctrl    { parts.push("Ctrl"); }
-    if modifiers.alt     { parts.push("Alt"); }
+    if modifiers.ctrl    { parts.push("Control"); }
     if modifiers.shift   { parts.push("Shift"); }
+    if modifiers.alt     { parts.push("Alt"); }
     if modifiers.mac_cmd || (modifiers.command && !modifiers.ctrl) {
         parts.push("Super");
     }
@@ -311,18 +512,18 @@ fn format_modifiers(modifiers: &egui::Modifiers) -> String {
     }
 }
 
-/// 将 egui Key 转换为可读名称；返回 "?" 表示忽略该键
+/// 将 egui Key 转换为 X11 keysym 名称；返回 "?" 表示忽略该键
 fn egui_key_name(key: &egui::Key) -> &'static str {
     match key {
-        egui::Key::A => "A", egui::Key::B => "B", egui::Key::C => "C",
-        egui::Key::D => "D", egui::Key::E => "E", egui::Key::F => "F",
-        egui::Key::G => "G", egui::Key::H => "H", egui::Key::I => "I",
-        egui::Key::J => "J", egui::Key::K => "K", egui::Key::L => "L",
-        egui::Key::M => "M", egui::Key::N => "N", egui::Key::O => "O",
-        egui::Key::P => "P", egui::Key::Q => "Q", egui::Key::R => "R",
-        egui::Key::S => "S", egui::Key::T => "T", egui::Key::U => "U",
-        egui::Key::V => "V", egui::Key::W => "W", egui::Key::X => "X",
-        egui::Key::Y => "Y", egui::Key::Z => "Z",
+        egui::Key::A => "a", egui::Key::B => "b", egui::Key::C => "c",
+        egui::Key::D => "d", egui::Key::E => "e", egui::Key::F => "f",
+        egui::Key::G => "g", egui::Key::H => "h", egui::Key::I => "i",
+        egui::Key::J => "j", egui::Key::K => "k", egui::Key::L => "l",
+        egui::Key::M => "m", egui::Key::N => "n", egui::Key::O => "o",
+        egui::Key::P => "p", egui::Key::Q => "q", egui::Key::R => "r",
+        egui::Key::S => "s", egui::Key::T => "t", egui::Key::U => "u",
+        egui::Key::V => "v", egui::Key::W => "w", egui::Key::X => "x",
+        egui::Key::Y => "y", egui::Key::Z => "z",
         egui::Key::Num0 => "0", egui::Key::Num1 => "1", egui::Key::Num2 => "2",
         egui::Key::Num3 => "3", egui::Key::Num4 => "4", egui::Key::Num5 => "5",
         egui::Key::Num6 => "6", egui::Key::Num7 => "7", egui::Key::Num8 => "8",
@@ -334,7 +535,7 @@ fn egui_key_name(key: &egui::Key) -> &'static str {
         egui::Key::F13 => "F13", egui::Key::F14 => "F14", egui::Key::F15 => "F15",
         egui::Key::F16 => "F16", egui::Key::F17 => "F17", egui::Key::F18 => "F18",
         egui::Key::F19 => "F19", egui::Key::F20 => "F20",
-        egui::Key::Space      => "Space",
+        egui::Key::Space      => "space",
         egui::Key::Enter      => "Return",
         egui::Key::Tab        => "Tab",
         egui::Key::Backspace  => "BackSpace",
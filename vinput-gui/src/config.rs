@@ -1,275 +1,135 @@
 //! V-Input 配置管理
+//!
+//! 配置结构、版本迁移与校验来自 vinput-config（与 vinput-core 共用），
+//! 这里只负责首次启动时创建配置文件，以及把迁移后的旧版配置写回
 
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 
-/// V-Input 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VInputConfig {
-    /// 基本配置
-    #[serde(default)]
-    pub basic: BasicConfig,
-    /// 热词配置
-    pub hotwords: HotwordsConfig,
-    /// 标点配置
-    pub punctuation: PunctuationConfig,
-    /// VAD 配置
-    pub vad: VadConfig,
-    /// ASR 配置
-    pub asr: AsrConfig,
-    /// 端点检测配置
-    #[serde(default)]
-    pub endpoint: EndpointConfig,
-}
-
-/// 基本配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BasicConfig {
-    /// 触发热键（如 "Ctrl+Alt+V"）
-    pub hotkey: String,
-}
-
-impl Default for BasicConfig {
-    fn default() -> Self {
-        Self {
-            hotkey: "RCtrl".to_string(),
-        }
-    }
-}
-
-/// 热词配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct HotwordsConfig {
-    /// 热词列表 (词汇 → 权重)
-    pub words: HashMap<String, f32>,
-    /// 全局权重
-    pub global_weight: f32,
-    /// 最大热词数
-    pub max_words: usize,
-}
-
-/// 标点配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PunctuationConfig {
-    /// 风格名称
-    pub style: String,
-    /// 停顿检测阈值
-    pub pause_ratio: f32,
-    /// 最小 token 数
-    pub min_tokens: usize,
-    /// 允许感叹号
-    pub allow_exclamation: bool,
-    /// 问号严格模式
-    pub question_strict: bool,
-}
+pub use vinput_config::{
+    ActivationConfig, ActivationMode, ConfigError, KeyCombo, ShortcutConfig, StopMode,
+    StyleProfile, VInputConfig,
+};
 
-/// VAD 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VadConfig {
-    /// 启动阈值
-    pub start_threshold: f32,
-    /// 结束阈值
-    pub end_threshold: f32,
-    /// 最小语音时长 (ms)
-    pub min_speech_duration: u64,
-    /// 最小静音时长 (ms)
-    pub min_silence_duration: u64,
-}
+/// 系统安装的示例配置
+const EXAMPLE_CONFIG_PATH: &str = "/usr/share/droplet-voice-input/config.toml.example";
 
-/// ASR 配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AsrConfig {
-    /// 模型目录
-    pub model_dir: String,
-    /// 采样率
-    pub sample_rate: i32,
-    /// 热词文件路径
-    pub hotwords_file: Option<String>,
-    /// 热词分数
-    pub hotwords_score: f32,
+/// 加载配置（文件不存在时从系统示例文件创建）
+pub fn load() -> Result<VInputConfig, ConfigError> {
+    load_from(&VInputConfig::config_path()?, Path::new(EXAMPLE_CONFIG_PATH))
 }
 
-/// 端点检测配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EndpointConfig {
-    /// 最小语音长度（毫秒）
-    pub min_speech_duration_ms: u64,
-    /// 最大语音长度（毫秒）
-    pub max_speech_duration_ms: u64,
-    /// 语音结束后的静音等待时间（毫秒）
-    pub trailing_silence_ms: u64,
-    /// 强制超时（毫秒）
-    pub force_timeout_ms: u64,
-    /// 是否启用 VAD 辅助端点检测
-    pub vad_assisted: bool,
-    /// VAD 检测到静音后的确认帧数
-    pub vad_silence_confirm_frames: usize,
-}
-
-const RECOMMENDED_TRAILING_SILENCE_MS: u64 = 1000;
-const RECOMMENDED_VAD_SILENCE_CONFIRM_FRAMES: usize = 8;
-const RECOMMENDED_MIN_SILENCE_DURATION_MS: u64 = 700;
-
-impl Default for EndpointConfig {
-    fn default() -> Self {
-        Self {
-            min_speech_duration_ms: 300,
-            max_speech_duration_ms: 30000,
-            trailing_silence_ms: RECOMMENDED_TRAILING_SILENCE_MS,
-            force_timeout_ms: 60000,
-            vad_assisted: true,
-            vad_silence_confirm_frames: RECOMMENDED_VAD_SILENCE_CONFIRM_FRAMES,
-        }
-    }
-}
+fn load_from(path: &Path, example_path: &Path) -> Result<VInputConfig, ConfigError> {
+    if !path.exists() {
+        tracing::info!("配置文件不存在，尝试从示例文件创建: {:?}", path);
 
-impl Default for VInputConfig {
-    fn default() -> Self {
-        Self {
-            basic: BasicConfig::default(),
-            hotwords: HotwordsConfig {
-                words: HashMap::new(),
-                global_weight: 2.5,
-                max_words: 10000,
-            },
-            punctuation: PunctuationConfig {
-                style: "Professional".to_string(),
-                pause_ratio: 3.5,
-                min_tokens: 5,
-                allow_exclamation: false,
-                question_strict: true,
-            },
-            vad: VadConfig {
-                start_threshold: 0.7,
-                end_threshold: 0.35,
-                min_speech_duration: 100,
-                min_silence_duration: RECOMMENDED_MIN_SILENCE_DURATION_MS,
-            },
-            asr: AsrConfig {
-                model_dir: "/usr/share/droplet-voice-input/models".to_string(),
-                sample_rate: 16000,
-                hotwords_file: None,
-                hotwords_score: 1.5,
-            },
-            endpoint: EndpointConfig::default(),
-        }
-    }
-}
-
-impl VInputConfig {
-    /// 获取配置文件路径
-    pub fn config_path() -> PathBuf {
-        if let Some(config_dir) = dirs::config_dir() {
-            config_dir.join("vinput").join("config.toml")
-        } else {
-            PathBuf::from(".vinput-config.toml")
-        }
-    }
-
-    /// 加载配置
-    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let path = Self::config_path();
-
-        if !path.exists() {
-            tracing::info!("配置文件不存在，尝试从示例文件创建: {:?}", path);
-
-            // 尝试从系统示例文件复制
-            let example_path = PathBuf::from("/usr/share/droplet-voice-input/config.toml.example");
-            if example_path.exists() {
-                tracing::info!("从系统示例文件复制: {:?}", example_path);
-
-                // 确保目录存在
-                if let Some(parent) = path.parent() {
-                    fs::create_dir_all(parent)?;
-                }
-
-                // 复制示例文件
-                fs::copy(&example_path, &path)?;
-                tracing::info!("配置文件创建成功: {:?}", path);
-            } else {
-                tracing::info!("示例文件不存在，使用默认配置并保存");
-
-                // 使用默认配置并保存
-                let default_config = Self::default();
-                default_config.save()?;
-                tracing::info!("默认配置已保存: {:?}", path);
-
-                return Ok(default_config);
+        if example_path.exists() {
+            tracing::info!("从系统示例文件复制: {:?}", example_path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
             }
+            fs::copy(example_path, path)?;
+            tracing::info!("配置文件创建成功: {:?}", path);
+        } else {
+            tracing::info!("示例文件不存在，使用默认配置并保存");
+            let default_config = VInputConfig::default();
+            default_config.save_to(path)?;
+            return Ok(default_config);
         }
-
-        // 读取配置文件
-        let content = fs::read_to_string(&path)?;
-        let config: VInputConfig = toml::from_str(&content)?;
-        let (normalized, updated) = config.normalize_legacy_config();
-
-        if updated {
-            tracing::info!("检测到旧版配置，已升级");
-            normalized.save()?;
-        }
-
-        tracing::info!("配置加载成功: {:?}", path);
-        Ok(normalized)
     }
 
-    /// 保存配置
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let path = Self::config_path();
+    let content = fs::read_to_string(path)?;
+    let (config, migrated_from) = VInputConfig::parse(&content)?;
 
-        // 确保目录存在
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
+    if let Some(version) = migrated_from {
+        tracing::info!("检测到旧版配置 (v{})，已升级", version);
+        // 旧文件中的值未通过校验时保留原文件，由用户在界面中修正后再保存
+        if let Err(e) = config.save_to(path) {
+            tracing::warn!("升级后的配置未写回: {}", e);
         }
-
-        let content = toml::to_string_pretty(self)?;
-        fs::write(path, content)?;
-        Ok(())
     }
 
-    fn normalize_legacy_config(self) -> (Self, bool) {
-        (self, false)
-    }
+    tracing::info!("配置加载成功: {:?}", path);
+    Ok(config)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::basic_settings_panel::BasicSettingsPanel;
+    use crate::endpoint_panel::EndpointPanel;
+    use crate::hotwords_editor::HotwordsEditor;
+    use crate::punctuation_panel::PunctuationPanel;
+    use std::path::PathBuf;
+
+    /// 测试用临时目录（测试结束时删除）
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("vinput-gui-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
 
     #[test]
-    fn test_default_config_creation() {
+    fn test_panels_write_loadable_config() {
         let config = VInputConfig::default();
+        let mut saved = config.clone();
+        BasicSettingsPanel::new(&config).apply_to_config(&mut saved);
+        HotwordsEditor::new(&config).apply_to_config(&mut saved);
+        PunctuationPanel::new(&config).apply_to_config(&mut saved);
+        EndpointPanel::new(&config).apply_to_config(&mut saved);
 
-        // 验证默认值
-        assert_eq!(config.asr.model_dir, "/usr/share/droplet-voice-input/models");
-        assert_eq!(config.asr.sample_rate, 16000);
-        assert_eq!(config.vad.start_threshold, 0.7);
-        assert_eq!(config.vad.min_silence_duration, RECOMMENDED_MIN_SILENCE_DURATION_MS);
-        assert_eq!(config.endpoint.trailing_silence_ms, RECOMMENDED_TRAILING_SILENCE_MS);
-        assert_eq!(config.endpoint.vad_silence_confirm_frames, RECOMMENDED_VAD_SILENCE_CONFIRM_FRAMES);
+        let toml_str = saved.to_toml_string().expect("设置界面写出的配置必须通过校验");
+        let (loaded, migrated_from) = VInputConfig::parse(&toml_str).expect("核心库必须能加载");
+        assert_eq!(migrated_from, None);
+        assert_eq!(loaded.vad.hysteresis.start_threshold, config.vad.hysteresis.start_threshold);
+        assert_eq!(loaded.punctuation.style, config.punctuation.style);
+        assert_eq!(loaded.shortcuts, config.shortcuts);
+        assert_eq!(loaded.endpoint.trailing_silence_ms, config.endpoint.trailing_silence_ms);
     }
 
     #[test]
-    fn test_config_serialization() {
-        let config = VInputConfig::default();
+    fn test_load_creates_default_config() {
+        let dir = TempDir::new("default");
+        let path = dir.0.join("vinput").join("config.toml");
 
-        // 序列化为 TOML
-        let toml_str = toml::to_string_pretty(&config).expect("Failed to serialize");
+        let config = load_from(&path, &dir.0.join("missing.example")).unwrap();
+        assert!(path.exists());
+        assert_eq!(config.asr.model_dir, VInputConfig::default().asr.model_dir);
+        assert_eq!(VInputConfig::parse(&fs::read_to_string(&path).unwrap()).unwrap().1, None);
+    }
 
-        // 验证包含关键字段
-        assert!(toml_str.contains("model_dir"));
-        assert!(toml_str.contains("start_threshold"));
-        assert!(toml_str.contains("trailing_silence_ms"));
+    #[test]
+    fn test_load_copies_example_config() {
+        let dir = TempDir::new("example");
+        let path = dir.0.join("config.toml");
+        let example = Path::new(env!("CARGO_MANIFEST_DIR")).join("../config.toml.example");
+
+        let config = load_from(&path, &example).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), fs::read_to_string(&example).unwrap());
+        assert_eq!(config.endpoint.vad_silence_confirm_frames, 8);
+        assert_eq!(config.vad.hysteresis.min_silence_duration_ms, 700);
     }
 
     #[test]
-    fn test_config_deserialization() {
-        let toml_str = r#"
-[hotwords]
-words = {}
-global_weight = 2.5
-max_words = 10000
+    fn test_legacy_config_upgraded_on_load() {
+        let dir = TempDir::new("legacy");
+        let path = dir.0.join("config.toml");
+        fs::write(
+            &path,
+            r#"
+[basic]
+hotkey = "Ctrl+Alt+V"
 
 [punctuation]
 style = "Professional"
@@ -283,97 +143,31 @@ start_threshold = 0.7
 end_threshold = 0.35
 min_speech_duration = 100
 min_silence_duration = 700
+"#,
+        )
+        .unwrap();
 
-[asr]
-model_dir = "/usr/share/droplet-voice-input/models"
-sample_rate = 16000
-hotwords_score = 1.5
-
-[endpoint]
-min_speech_duration_ms = 300
-max_speech_duration_ms = 30000
-trailing_silence_ms = 1000
-force_timeout_ms = 60000
-vad_assisted = true
-vad_silence_confirm_frames = 8
-"#;
+        let config = load_from(&path, Path::new("/nonexistent")).unwrap();
+        assert_eq!(config.shortcuts.trigger, "Control+Alt+v");
+        assert_eq!(config.vad.hysteresis.min_silence_duration_ms, 700);
+        assert_eq!(config.punctuation.streaming_pause_ratio, 3.5);
 
-        let config: VInputConfig = toml::from_str(toml_str).expect("Failed to deserialize");
-
-        // 验证反序列化的值
-        assert_eq!(config.asr.model_dir, "/usr/share/droplet-voice-input/models");
-        assert_eq!(config.vad.start_threshold, 0.7);
-        assert_eq!(config.vad.min_silence_duration, 700);
-        assert_eq!(config.endpoint.trailing_silence_ms, 1000);
-    }
-
-    #[test]
-    fn test_config_roundtrip() {
-        let original = VInputConfig::default();
-
-        // 序列化
-        let toml_str = toml::to_string_pretty(&original).expect("Failed to serialize");
-
-        // 反序列化
-        let deserialized: VInputConfig = toml::from_str(&toml_str).expect("Failed to deserialize");
-
-        // 验证关键字段一致
-        assert_eq!(original.asr.model_dir, deserialized.asr.model_dir);
-        assert_eq!(original.vad.start_threshold, deserialized.vad.start_threshold);
-        assert_eq!(original.endpoint.trailing_silence_ms, deserialized.endpoint.trailing_silence_ms);
+        // 写回的文件为当前版本，不含旧字段
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("hotkey"));
+        assert!(!content.contains("\npause_ratio"));
+        assert_eq!(VInputConfig::parse(&content).unwrap().1, None);
     }
 
     #[test]
-    fn test_vad_config_values() {
-        let config = VInputConfig::default();
-
-        // 验证 VAD 参数在合理范围内
-        assert!(config.vad.start_threshold >= 0.0 && config.vad.start_threshold <= 1.0);
-        assert!(config.vad.end_threshold >= 0.0 && config.vad.end_threshold <= 1.0);
-        assert!(config.vad.start_threshold > config.vad.end_threshold);
-        assert!(config.vad.min_silence_duration > 0);
-    }
-
-    #[test]
-    fn test_endpoint_config_values() {
-        let config = VInputConfig::default();
-
-        // 验证端点检测参数在合理范围内
-        assert!(config.endpoint.min_speech_duration_ms > 0);
-        assert!(config.endpoint.max_speech_duration_ms > config.endpoint.min_speech_duration_ms);
-        assert!(config.endpoint.trailing_silence_ms > 0);
-        assert!(config.endpoint.vad_silence_confirm_frames > 0);
-    }
-
-    #[test]
-    fn test_hotwords_config() {
-        let config = VInputConfig::default();
-
-        // 验证热词配置
-        assert!(config.hotwords.words.is_empty());
-        assert!(config.hotwords.global_weight > 0.0);
-        assert!(config.hotwords.max_words > 0);
-    }
-
-    #[test]
-    fn test_punctuation_config() {
-        let config = VInputConfig::default();
-
-        // 验证标点配置
-        assert_eq!(config.punctuation.style, "Professional");
-        assert!(config.punctuation.pause_ratio > 0.0);
-        assert!(config.punctuation.min_tokens > 0);
-    }
-
-    #[test]
-    fn test_normalize_legacy_config_no_change_for_partial_legacy_values() {
-        let base = VInputConfig::default();
-
-        let (normalized, updated) = base.normalize_legacy_config();
-
-        assert!(!updated);
-        assert_eq!(normalized.vad.min_silence_duration, RECOMMENDED_MIN_SILENCE_DURATION_MS);
-        assert_eq!(normalized.endpoint.trailing_silence_ms, RECOMMENDED_TRAILING_SILENCE_MS);
-        assert_eq!(normalized.endpoint.vad_silence_confirm_frames, RECOMMENDED_VAD_SILENCE_CONFIRM_FRAMES);
+    fn test_invalid_legacy_config_left_untouched() {
+        let dir = TempDir::new("invalid");
+        let path = dir.0.join("config.toml");
+        let legacy = "[vad]\nstart_threshold = 0.3\nend_threshold = 0.5\nmin_speech_duration = 100\nmin_silence_duration = 700\n";
+        fs::write(&path, legacy).unwrap();
+
+        let config = load_from(&path, Path::new("/nonexistent")).unwrap();
+        assert!(config.validate().is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), legacy);
     }
 }
@@ -14,6 +14,8 @@ pub struct EndpointPanel {
     vad_end_threshold: f32,
     vad_min_speech_duration: u64,
     vad_min_silence_duration: u64,
+    streaming_commit_enabled: bool,
+    streaming_commit_stable_updates: u32,
 }
 
 impl EndpointPanel {
@@ -25,10 +27,12 @@ impl EndpointPanel {
             force_timeout_ms: config.endpoint.force_timeout_ms,
             vad_assisted: config.endpoint.vad_assisted,
             vad_silence_confirm_frames: config.endpoint.vad_silence_confirm_frames,
-            vad_start_threshold: config.vad.start_threshold,
-            vad_end_threshold: config.vad.end_threshold,
-            vad_min_speech_duration: config.vad.min_speech_duration,
-            vad_min_silence_duration: config.vad.min_silence_duration,
+            vad_start_threshold: config.vad.hysteresis.start_threshold,
+            vad_end_threshold: config.vad.hysteresis.end_threshold,
+            vad_min_speech_duration: config.vad.hysteresis.min_speech_duration_ms,
+            vad_min_silence_duration: config.vad.hysteresis.min_silence_duration_ms,
+            streaming_commit_enabled: config.streaming_commit.enabled,
+            streaming_commit_stable_updates: config.streaming_commit.stable_updates,
         }
     }
 
@@ -39,10 +43,12 @@ impl EndpointPanel {
         config.endpoint.force_timeout_ms = self.force_timeout_ms;
         config.endpoint.vad_assisted = self.vad_assisted;
         config.endpoint.vad_silence_confirm_frames = self.vad_silence_confirm_frames;
-        config.vad.start_threshold = self.vad_start_threshold;
-        config.vad.end_threshold = self.vad_end_threshold;
-        config.vad.min_speech_duration = self.vad_min_speech_duration;
-        config.vad.min_silence_duration = self.vad_min_silence_duration;
+        config.vad.hysteresis.start_threshold = self.vad_start_threshold;
+        config.vad.hysteresis.end_threshold = self.vad_end_threshold;
+        config.vad.hysteresis.min_speech_duration_ms = self.vad_min_speech_duration;
+        config.vad.hysteresis.min_silence_duration_ms = self.vad_min_silence_duration;
+        config.streaming_commit.enabled = self.streaming_commit_enabled;
+        config.streaming_commit.stable_updates = self.streaming_commit_stable_updates;
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
@@ -86,6 +92,31 @@ impl EndpointPanel {
 
             ui.add_space(12.0);
 
+            // 长句流式上屏
+            ui.label(egui::RichText::new("长句流式上屏").size(13.0).strong());
+            ui.add_space(6.0);
+            ui.group(|ui| {
+                if ui.checkbox(&mut self.streaming_commit_enabled,
+                    egui::RichText::new("连续说话时提前上屏已稳定的分句").size(13.0)).changed() {
+                    modified = true;
+                }
+                ui.add_enabled_ui(self.streaming_commit_enabled, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new("稳定等待").size(13.0));
+                        let mut v = self.streaming_commit_stable_updates as f32;
+                        if ui.add(egui::Slider::new(&mut v, 2.0..=15.0)
+                            .suffix(format!(" 次 ≈{}ms", self.streaming_commit_stable_updates * 160))).changed() {
+                            self.streaming_commit_stable_updates = v as u32;
+                            modified = true;
+                        }
+                    });
+                });
+                ui.label(egui::RichText::new("只在逗号等标点处切分；含数字的句子仍在结束时整体上屏，撤销时整句撤销")
+                    .size(11.0).color(egui::Color32::GRAY));
+            });
+
+            ui.add_space(12.0);
+
             // 噪声过滤
             ui.label(egui::RichText::new("噪声过滤").size(13.0).strong());
             ui.add_space(6.0);
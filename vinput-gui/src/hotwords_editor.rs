@@ -1,64 +1,133 @@
 //! 热词编辑器
+//!
+//! 热词按分组管理（未分组热词始终启用，分组可整组启用 / 停用）；
+//! 每个词按 `HotwordEntry::validate` 检查权重，并对照当前模型的 `tokens.txt`
+//! 标出模型无法输出的字符。导入先显示预览与冲突，确认后才写入。
+//! 自动学习的热词单独列出，可保留到配置、忽略或删除。
 
 use crate::config::VInputConfig;
 use eframe::egui;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use vinput_core::hotwords::learning::unix_now;
+use vinput_core::hotwords::transfer::{self, DEFAULT_WEIGHT};
+use vinput_core::hotwords::{
+    HotwordEntry, HotwordFileFormat, HotwordGroup, HotwordsConfig, ImportPreview, LearnSource,
+    LearnedHotwords, TokenVocabulary,
+};
+
+/// 「保留」的学习热词移入的分组
+const LEARNED_GROUP: &str = "自动学习";
+
+const WARN_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 150, 50);
+const OK_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 160, 80);
+
+/// 待确认的导入
+struct PendingImport {
+    preview: ImportPreview,
+    /// 文件未指定分组的热词导入到的分组（None 为未分组）
+    target: Option<String>,
+    /// 同一分组内已有的词是否改写权重
+    overwrite: bool,
+}
 
 pub struct HotwordsEditor {
-    hotwords: HashMap<String, f32>,
-    global_weight: f32,
+    hotwords: HotwordsConfig,
+    /// 当前编辑的分组（None 为未分组）
+    selected: Option<String>,
     new_word: String,
     new_weight: f32,
-    to_delete: Option<String>,
+    new_group: String,
+    /// 当前模型词表（模型目录无 tokens.txt 时为 None）
+    vocab: Option<TokenVocabulary>,
     import_file_path: String,
+    pending_import: Option<PendingImport>,
+    export_format: HotwordFileFormat,
     status_msg: String,
+    /// 学习文件路径（无法获取数据目录时为 None）
+    learned_path: Option<PathBuf>,
+    learned: LearnedHotwords,
+}
+
+/// 对学到的词的操作
+enum LearnedAction {
+    Keep(String, f32),
+    Ignore(String),
+    Delete(String),
 }
 
 impl HotwordsEditor {
     pub fn new(config: &VInputConfig) -> Self {
+        let vocab = match TokenVocabulary::load(Path::new(&config.asr.model_dir)) {
+            Ok(vocab) => Some(vocab),
+            Err(e) => {
+                tracing::warn!("无法加载模型词表，跳过词表检查: {}", e);
+                None
+            }
+        };
+        let learned_path = config.hotwords.learning.file_path();
+        let learned = learned_path.as_deref()
+            .map(LearnedHotwords::load)
+            .transpose()
+            .unwrap_or_else(|e| {
+                tracing::warn!("无法读取学习的热词: {}", e);
+                None
+            })
+            .unwrap_or_default();
         Self {
-            hotwords: config.hotwords.words.clone(),
-            global_weight: config.hotwords.global_weight,
+            hotwords: config.hotwords.clone(),
+            selected: None,
             new_word: String::new(),
-            new_weight: 2.5,
-            to_delete: None,
+            new_weight: DEFAULT_WEIGHT,
+            new_group: String::new(),
+            vocab,
             import_file_path: String::new(),
+            pending_import: None,
+            export_format: HotwordFileFormat::Txt,
             status_msg: String::new(),
+            learned_path,
+            learned,
         }
     }
 
     pub fn apply_to_config(&self, config: &mut VInputConfig) {
-        config.hotwords.words = self.hotwords.clone();
-        config.hotwords.global_weight = self.global_weight;
+        config.hotwords.words = self.hotwords.words.clone();
+        config.hotwords.groups = self.hotwords.groups.clone();
+        config.hotwords.global_weight = self.hotwords.global_weight;
+        config.hotwords.learning.enabled = self.hotwords.learning.enabled;
+    }
+
+    fn problems(&self, word: &str, weight: f32) -> Vec<String> {
+        HotwordEntry::new(word.to_string(), weight).validate(self.vocab.as_ref())
     }
 
-    fn import_from_file(&mut self, path: &str) -> Result<usize, String> {
-        let content = std::fs::read_to_string(path).map_err(|e| format!("无法读取文件: {}", e))?;
-        let mut count = 0;
-        for line in content.lines() {
-            let line = line.trim();
-            if line.is_empty() || line.starts_with('#') { continue; }
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.is_empty() { continue; }
-            let word = parts[0].to_string();
-            let weight = parts.get(1).and_then(|s| s.parse::<f32>().ok()).unwrap_or(2.5).clamp(1.0, 5.0);
-            self.hotwords.insert(word, weight);
-            count += 1;
+    fn group_label(group: Option<&str>) -> &str {
+        group.unwrap_or("未分组")
+    }
+
+    fn preview_import(&mut self) {
+        let path = self.import_file_path.trim();
+        match ImportPreview::load(Path::new(path)) {
+            Ok(preview) => {
+                self.status_msg.clear();
+                self.pending_import = Some(PendingImport {
+                    preview,
+                    target: self.selected.clone(),
+                    overwrite: false,
+                });
+            }
+            Err(e) => self.status_msg = format!("导入失败：{}", e),
         }
-        Ok(count)
     }
 
-    fn export_to_file(&self) -> Result<String, String> {
-        use std::io::Write;
+    fn export_path(&self) -> String {
         let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        let path = format!("{}/vinput-hotwords-export.txt", home);
-        let mut file = std::fs::File::create(&path).map_err(|e| format!("无法创建文件: {}", e))?;
-        writeln!(file, "# V-Input 热词导出  格式: 词 权重").map_err(|e| format!("{}", e))?;
-        let mut words: Vec<_> = self.hotwords.iter().collect();
-        words.sort_by(|a, b| a.0.cmp(b.0));
-        for (word, weight) in words {
-            writeln!(file, "{} {:.1}", word, weight).map_err(|e| format!("{}", e))?;
-        }
+        format!("{}/vinput-hotwords-export.{}", home, self.export_format.extension())
+    }
+
+    fn export_to_file(&self) -> Result<String, String> {
+        let path = self.export_path();
+        std::fs::write(&path, transfer::export(&self.hotwords, self.export_format))
+            .map_err(|e| format!("无法写入文件: {}", e))?;
         Ok(path)
     }
 
@@ -71,22 +140,104 @@ impl HotwordsEditor {
         ui.separator();
         ui.add_space(8.0);
 
-        // 全局权重
-        ui.label(egui::RichText::new("全局权重").size(13.0).strong());
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            // 全局权重
+            ui.label(egui::RichText::new("全局权重").size(13.0).strong());
+            ui.add_space(6.0);
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("权重倍率：").size(13.0));
+                    if ui.add(egui::Slider::new(&mut self.hotwords.global_weight, 1.0..=5.0)
+                        .fixed_decimals(1)).changed() { modified = true; }
+                });
+                ui.label(egui::RichText::new("所有热词的基础权重乘数").size(11.0).color(egui::Color32::GRAY));
+            });
+
+            ui.add_space(12.0);
+            modified |= self.ui_groups(ui);
+            ui.add_space(12.0);
+            modified |= self.ui_words(ui);
+            ui.add_space(12.0);
+            modified |= self.ui_learned(ui);
+            ui.add_space(12.0);
+            modified |= self.ui_transfer(ui);
+        });
+
+        modified
+    }
+
+    /// 分组列表：选择、启用 / 停用、新建、删除
+    fn ui_groups(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut modified = false;
+        let active = self.hotwords.active_words().len();
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("热词分组").size(13.0).strong());
+            ui.add_space(8.0);
+            let color = if active > self.hotwords.max_words { WARN_COLOR } else { egui::Color32::GRAY };
+            ui.label(egui::RichText::new(format!("已启用 {} / 上限 {} 个热词", active, self.hotwords.max_words))
+                .size(11.0).color(color));
+        });
         ui.add_space(6.0);
         ui.group(|ui| {
+            ui.set_min_width(ui.available_width());
+            ui.horizontal_wrapped(|ui| {
+                let label = format!("未分组（{}）", self.hotwords.words.len());
+                if ui.selectable_label(self.selected.is_none(), egui::RichText::new(label).size(13.0)).clicked() {
+                    self.selected = None;
+                }
+                for group in &mut self.hotwords.groups {
+                    ui.add_space(6.0);
+                    if ui.checkbox(&mut group.enabled, "").on_hover_text("启用此分组").changed() {
+                        modified = true;
+                    }
+                    let selected = self.selected.as_deref() == Some(group.name.as_str());
+                    let label = format!("{}（{}）", group.name, group.words.len());
+                    let mut text = egui::RichText::new(label).size(13.0);
+                    if !group.enabled {
+                        text = text.color(egui::Color32::GRAY);
+                    }
+                    if ui.selectable_label(selected, text).clicked() {
+                        self.selected = Some(group.name.clone());
+                    }
+                }
+            });
+            ui.add_space(6.0);
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("权重倍率：").size(13.0));
-                if ui.add(egui::Slider::new(&mut self.global_weight, 1.0..=5.0)
-                    .fixed_decimals(1)).changed() { modified = true; }
+                ui.add(egui::TextEdit::singleline(&mut self.new_group)
+                    .desired_width(160.0)
+                    .font(egui::TextStyle::Body)
+                    .hint_text("新分组名，如「项目名称」"));
+                let name = self.new_group.trim().to_string();
+                let can_add = !name.is_empty() && self.hotwords.group(&name).is_none();
+                if ui.add_enabled(can_add, egui::Button::new(egui::RichText::new("新建分组").size(13.0))).clicked() {
+                    self.hotwords.groups.push(HotwordGroup::new(name.clone()));
+                    self.selected = Some(name);
+                    self.new_group.clear();
+                    modified = true;
+                }
+                if let Some(name) = self.selected.clone() {
+                    ui.add_space(8.0);
+                    if ui.button(egui::RichText::new(format!("删除分组「{}」", name)).size(13.0)).clicked() {
+                        self.hotwords.groups.retain(|g| g.name != name);
+                        self.selected = None;
+                        self.status_msg = format!("已删除分组「{}」", name);
+                        modified = true;
+                    }
+                }
             });
-            ui.label(egui::RichText::new("所有热词的基础权重乘数").size(11.0).color(egui::Color32::GRAY));
+            ui.label(egui::RichText::new("未勾选的分组保留在配置中但不加载；未分组热词始终生效").size(11.0)
+                .color(egui::Color32::GRAY));
         });
+        modified
+    }
 
-        ui.add_space(12.0);
+    /// 当前分组的热词：添加、调整权重、删除，并显示检查结果
+    fn ui_words(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut modified = false;
+        let group_label = Self::group_label(self.selected.as_deref()).to_string();
 
         // 添加热词
-        ui.label(egui::RichText::new("添加热词").size(13.0).strong());
+        ui.label(egui::RichText::new(format!("添加热词到「{}」", group_label)).size(13.0).strong());
         ui.add_space(6.0);
         ui.group(|ui| {
             ui.horizontal(|ui| {
@@ -100,32 +251,58 @@ impl HotwordsEditor {
                 ui.add(egui::Slider::new(&mut self.new_weight, 1.0..=5.0)
                     .fixed_decimals(1));
                 ui.add_space(8.0);
-                let can_add = !self.new_word.is_empty();
+                let word = self.new_word.trim().to_string();
+                let can_add = !word.is_empty();
                 if ui.add_enabled(can_add, egui::Button::new(egui::RichText::new("添加").size(13.0))
                     .min_size([50.0, 0.0].into())).clicked() {
-                    self.hotwords.insert(self.new_word.clone(), self.new_weight);
-                    self.new_word.clear();
-                    self.new_weight = 2.5;
-                    modified = true;
+                    if let Some(words) = self.hotwords.words_mut(self.selected.as_deref()) {
+                        words.insert(word, self.new_weight);
+                        self.new_word.clear();
+                        self.new_weight = DEFAULT_WEIGHT;
+                        modified = true;
+                    }
                 }
             });
+            let word = self.new_word.trim();
+            if !word.is_empty() {
+                for problem in self.problems(word, self.new_weight) {
+                    ui.label(egui::RichText::new(format!("⚠ {}", problem)).size(11.0).color(WARN_COLOR));
+                }
+            }
         });
 
         ui.add_space(12.0);
 
+        let Some(words) = self.hotwords.words_mut(self.selected.as_deref()) else {
+            self.selected = None;
+            return modified;
+        };
+        let mut sorted: Vec<(String, f32)> = words.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        let checks: Vec<Vec<String>> = sorted.iter().map(|(w, weight)| self.problems(w, *weight)).collect();
+        let flagged = checks.iter().filter(|p| !p.is_empty()).count();
+
         // 热词列表
+        let mut clear = false;
         ui.horizontal(|ui| {
-            ui.label(egui::RichText::new(format!("热词列表（{} 个）", self.hotwords.len())).size(13.0).strong());
+            ui.label(egui::RichText::new(format!("「{}」热词列表（{} 个）", group_label, sorted.len())).size(13.0).strong());
+            if flagged > 0 {
+                ui.label(egui::RichText::new(format!("⚠ {} 个有问题", flagged)).size(12.0).color(WARN_COLOR));
+            }
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 if ui.button(egui::RichText::new("清空全部").size(12.0)).clicked() {
-                    self.hotwords.clear();
-                    modified = true;
-                    self.status_msg = "已清空所有热词".to_string();
+                    clear = true;
                 }
             });
         });
+        if self.vocab.is_none() {
+            ui.label(egui::RichText::new("未找到模型词表（tokens.txt），不检查词表外字符").size(11.0)
+                .color(egui::Color32::GRAY));
+        }
         ui.add_space(6.0);
 
+        let mut updates: Vec<(String, f32)> = Vec::new();
+        let mut to_delete = None;
         egui::Frame::new()
             .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(100, 100, 100)))
             .corner_radius(4.0)
@@ -133,96 +310,331 @@ impl HotwordsEditor {
             .show(ui, |ui| {
                 // 表头
                 egui::Grid::new("hw_header")
-                    .num_columns(3)
-                    .min_col_width(160.0)
+                    .num_columns(4)
+                    .min_col_width(120.0)
                     .spacing([8.0, 4.0])
                     .show(ui, |ui| {
                         ui.label(egui::RichText::new("词汇").size(12.0).strong().color(egui::Color32::GRAY));
                         ui.label(egui::RichText::new("权重").size(12.0).strong().color(egui::Color32::GRAY));
+                        ui.label(egui::RichText::new("检查").size(12.0).strong().color(egui::Color32::GRAY));
                         ui.label(egui::RichText::new("操作").size(12.0).strong().color(egui::Color32::GRAY));
                         ui.end_row();
                     });
                 ui.separator();
 
                 egui::ScrollArea::vertical()
-                    .max_height(180.0)
+                    .id_salt("hw_list_scroll")
+                    .max_height(220.0)
                     .show(ui, |ui| {
-                        let mut words: Vec<_> = self.hotwords.iter().map(|(k, v)| (k.clone(), *v)).collect();
-                        words.sort_by(|a, b| a.0.cmp(&b.0));
-                        let mut updates: Vec<(String, f32)> = Vec::new();
-
                         egui::Grid::new("hw_list")
-                            .num_columns(3)
-                            .min_col_width(160.0)
+                            .num_columns(4)
+                            .min_col_width(120.0)
                             .spacing([8.0, 6.0])
                             .striped(true)
                             .show(ui, |ui| {
-                                for (word, weight) in &words {
+                                for ((word, weight), problems) in sorted.iter().zip(&checks) {
                                     ui.label(egui::RichText::new(word).size(13.0));
                                     let mut w = *weight;
                                     if ui.add(egui::Slider::new(&mut w, 1.0..=5.0)
                                         .fixed_decimals(1)).changed() {
                                         updates.push((word.clone(), w));
                                     }
+                                    if problems.is_empty() {
+                                        ui.label(egui::RichText::new("✓").size(12.0).color(OK_COLOR));
+                                    } else {
+                                        ui.label(egui::RichText::new("⚠").size(12.0).color(WARN_COLOR))
+                                            .on_hover_text(problems.join("\n"));
+                                    }
                                     if ui.button(egui::RichText::new("删除").size(12.0)).clicked() {
-                                        self.to_delete = Some(word.clone());
-                                        modified = true;
+                                        to_delete = Some(word.clone());
                                     }
                                     ui.end_row();
                                 }
                             });
-
-                        for (word, weight) in updates {
-                            if let Some(entry) = self.hotwords.get_mut(&word) {
-                                *entry = weight;
-                                modified = true;
-                            }
-                        }
                     });
             });
 
-        if let Some(word) = self.to_delete.take() {
-            self.hotwords.remove(&word);
+        let Some(words) = self.hotwords.words_mut(self.selected.as_deref()) else {
+            return modified;
+        };
+        for (word, weight) in updates {
+            if let Some(entry) = words.get_mut(&word) {
+                *entry = weight;
+                modified = true;
+            }
+        }
+        if let Some(word) = to_delete {
+            words.remove(&word);
+            modified = true;
         }
+        if clear {
+            words.clear();
+            modified = true;
+            self.status_msg = format!("已清空「{}」中的热词", group_label);
+        }
+        modified
+    }
 
-        ui.add_space(12.0);
+    /// 自动学习的热词：来源、次数、衰减后的权重，可保留 / 忽略 / 删除
+    fn ui_learned(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut modified = false;
+        let mut action = None;
+        let learning = self.hotwords.learning.clone();
+        let now = unix_now();
+
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("自动学习的热词").size(13.0).strong());
+            ui.add_space(8.0);
+            if ui.checkbox(&mut self.hotwords.learning.enabled, egui::RichText::new("启用自动学习").size(13.0))
+                .changed() { modified = true; }
+        });
+        ui.add_space(6.0);
+        ui.group(|ui| {
+            ui.set_min_width(ui.available_width());
+            if self.learned.entries.is_empty() {
+                ui.label(egui::RichText::new("暂无。撤销后重新输入、从候选中替换或按 Ctrl+数字加为热词的词会出现在这里")
+                    .size(12.0).color(egui::Color32::GRAY));
+            } else {
+                egui::Grid::new("learned_hotwords_grid").num_columns(6).spacing([12.0, 6.0]).striped(true).show(ui, |ui| {
+                    for header in ["词汇", "来源", "次数", "当前权重", "状态", ""] {
+                        ui.label(egui::RichText::new(header).size(12.0).strong());
+                    }
+                    ui.end_row();
+
+                    for entry in &self.learned.entries {
+                        let weight = entry.effective_weight(&learning, now);
+                        ui.label(egui::RichText::new(&entry.word).size(13.0));
+                        let source = match entry.source {
+                            LearnSource::Correction => "纠错",
+                            LearnSource::Explicit => "手动",
+                        };
+                        ui.label(egui::RichText::new(source).size(12.0));
+                        ui.label(egui::RichText::new(entry.hits.to_string()).size(12.0));
+                        ui.label(egui::RichText::new(format!("{:.1}", weight)).size(12.0));
+                        if entry.is_active(&learning) {
+                            ui.label(egui::RichText::new("生效中").size(12.0).color(OK_COLOR));
+                        } else {
+                            let remaining = learning.min_hits.saturating_sub(entry.hits);
+                            ui.label(egui::RichText::new(format!("还需 {} 次", remaining)).size(12.0)
+                                .color(egui::Color32::GRAY));
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.small_button("保留").on_hover_text(format!("移入「{}」分组", LEARNED_GROUP)).clicked() {
+                                action = Some(LearnedAction::Keep(entry.word.clone(), weight));
+                            }
+                            if ui.small_button("忽略").on_hover_text("删除并不再学习这个词").clicked() {
+                                action = Some(LearnedAction::Ignore(entry.word.clone()));
+                            }
+                            if ui.small_button("删除").clicked() {
+                                action = Some(LearnedAction::Delete(entry.word.clone()));
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            }
+            ui.label(egui::RichText::new(format!(
+                "纠错学到的词出现 {} 次后生效，权重每 {} 天减半；保留的词写入配置，不再衰减",
+                learning.min_hits, learning.half_life_days,
+            )).size(11.0).color(egui::Color32::GRAY));
+        });
+
+        if let Some(action) = action {
+            modified |= self.apply_learned_action(action);
+        }
+        modified
+    }
 
-        // 导入导出
+    /// 执行操作并立即写回学习文件；返回配置是否被修改
+    fn apply_learned_action(&mut self, action: LearnedAction) -> bool {
+        let mut modified = false;
+        match action {
+            LearnedAction::Keep(word, weight) => {
+                if self.hotwords.group(LEARNED_GROUP).is_none() {
+                    self.hotwords.groups.push(HotwordGroup::new(LEARNED_GROUP));
+                }
+                if let Some(words) = self.hotwords.words_mut(Some(LEARNED_GROUP)) {
+                    words.insert(word.clone(), (weight * 10.0).round().clamp(10.0, 50.0) / 10.0);
+                }
+                self.learned.remove(&word);
+                self.status_msg = format!("已将「{}」保留到「{}」分组", word, LEARNED_GROUP);
+                modified = true;
+            }
+            LearnedAction::Ignore(word) => {
+                self.learned.reject(&word);
+                self.status_msg = format!("已忽略「{}」，不再学习", word);
+            }
+            LearnedAction::Delete(word) => {
+                self.learned.remove(&word);
+                self.status_msg = format!("已删除「{}」", word);
+            }
+        }
+
+        if let Some(path) = &self.learned_path {
+            if let Err(e) = self.learned.save(path) {
+                self.status_msg = format!("保存学习文件失败：{}", e);
+            }
+        }
+        modified
+    }
+
+    /// 导入（预览 + 冲突报告）与导出
+    fn ui_transfer(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut modified = false;
         ui.label(egui::RichText::new("导入 / 导出").size(13.0).strong());
         ui.add_space(6.0);
         ui.group(|ui| {
+            ui.set_min_width(ui.available_width());
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("文件路径：").size(13.0));
+                ui.label(egui::RichText::new("导入文件：").size(13.0));
                 ui.add(egui::TextEdit::singleline(&mut self.import_file_path)
                     .desired_width(260.0)
                     .font(egui::TextStyle::Body)
                     .hint_text("/path/to/hotwords.txt"));
                 ui.add_space(8.0);
-                if ui.button(egui::RichText::new("导入").size(13.0)).clicked() {
-                    let path = self.import_file_path.clone();
-                    match self.import_from_file(&path) {
-                        Ok(n)  => { self.status_msg = format!("已导入 {} 个热词", n); modified = true; }
-                        Err(e) => { self.status_msg = format!("导入失败：{}", e); }
-                    }
+                let can_preview = !self.import_file_path.trim().is_empty();
+                if ui.add_enabled(can_preview, egui::Button::new(egui::RichText::new("预览导入").size(13.0))).clicked() {
+                    self.preview_import();
                 }
-                ui.add_space(4.0);
-                if ui.button(egui::RichText::new("导出").size(13.0)).clicked() {
+            });
+            ui.label(egui::RichText::new("支持 txt（每行「词 权重」）、csv（词汇,权重,分组）与按分组的 toml，按扩展名识别")
+                .size(11.0).color(egui::Color32::GRAY));
+
+            if self.pending_import.is_some() {
+                ui.add_space(6.0);
+                modified |= self.ui_pending_import(ui);
+            }
+
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("导出格式：").size(13.0));
+                egui::ComboBox::from_id_salt("hotwords_export_format")
+                    .width(80.0)
+                    .selected_text(egui::RichText::new(self.export_format.extension()).size(13.0))
+                    .show_ui(ui, |ui| {
+                        for format in HotwordFileFormat::ALL {
+                            ui.selectable_value(&mut self.export_format, format,
+                                egui::RichText::new(format.extension()).size(13.0));
+                        }
+                    });
+                ui.add_space(8.0);
+                if ui.button(egui::RichText::new("导出全部").size(13.0))
+                    .on_hover_text(self.export_path())
+                    .clicked()
+                {
                     match self.export_to_file() {
                         Ok(p)  => { self.status_msg = format!("已导出到：{}", p); }
                         Err(e) => { self.status_msg = format!("导出失败：{}", e); }
                     }
                 }
             });
+            if self.export_format == HotwordFileFormat::Txt {
+                ui.label(egui::RichText::new("txt 不保存分组，导入时全部进入所选分组").size(11.0)
+                    .color(egui::Color32::GRAY));
+            }
+
             if !self.status_msg.is_empty() {
                 ui.add_space(4.0);
-                ui.label(egui::RichText::new(&self.status_msg).size(12.0)
-                    .color(egui::Color32::from_rgb(80, 160, 80)));
+                ui.label(egui::RichText::new(&self.status_msg).size(12.0).color(OK_COLOR));
             }
-            ui.add_space(2.0);
-            ui.label(egui::RichText::new("文件格式：每行一个词，可选权重，如「深度操作系统 3.0」").size(11.0)
-                .color(egui::Color32::GRAY));
         });
-
         modified
     }
+
+    /// 导入预览：条目数、无法导入的行、冲突与词表检查，确认后写入
+    fn ui_pending_import(&mut self, ui: &mut egui::Ui) -> bool {
+        let Some(pending) = &mut self.pending_import else { return false };
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        let preview = &pending.preview;
+        let conflicts = preview.conflicts(&self.hotwords, pending.target.as_deref());
+        let flagged: Vec<(String, Vec<String>)> = preview.hotwords.iter()
+            .map(|h| (h.entry.word.clone(), h.entry.validate(self.vocab.as_ref())))
+            .filter(|(_, problems)| !problems.is_empty())
+            .collect();
+        let file_groups: Vec<String> = preview.groups().into_iter().flatten().map(str::to_string).collect();
+
+        egui::Frame::new()
+            .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(100, 100, 100)))
+            .corner_radius(4.0)
+            .inner_margin(egui::Margin::same(8))
+            .show(ui, |ui| {
+                ui.label(egui::RichText::new(format!(
+                    "可导入 {} 个热词，{} 行无法导入，{} 处冲突",
+                    preview.hotwords.len(), preview.issues.len(), conflicts.len()
+                )).size(13.0).strong());
+                if !file_groups.is_empty() {
+                    ui.label(egui::RichText::new(format!("文件中的分组：{}", file_groups.join("、")))
+                        .size(11.0).color(egui::Color32::GRAY));
+                }
+                ui.add_space(4.0);
+
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new("未指定分组的热词导入到：").size(12.0));
+                    egui::ComboBox::from_id_salt("hotwords_import_target")
+                        .width(140.0)
+                        .selected_text(egui::RichText::new(Self::group_label(pending.target.as_deref())).size(12.0))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut pending.target, None, egui::RichText::new("未分组").size(12.0));
+                            for group in &self.hotwords.groups {
+                                ui.selectable_value(&mut pending.target, Some(group.name.clone()),
+                                    egui::RichText::new(&group.name).size(12.0));
+                            }
+                        });
+                });
+                ui.checkbox(&mut pending.overwrite, egui::RichText::new("同一分组中已有的词使用导入的权重").size(12.0));
+
+                egui::ScrollArea::vertical()
+                    .id_salt("hw_import_report")
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        for issue in &preview.issues {
+                            ui.label(egui::RichText::new(format!("✗ 第 {} 行「{}」：{}", issue.line, issue.text, issue.reason))
+                                .size(11.0).color(egui::Color32::from_rgb(200, 80, 80)));
+                        }
+                        for conflict in &conflicts {
+                            let text = if conflict.same_group() {
+                                format!("↔ 「{}」已在「{}」中，权重 {} → {}",
+                                    conflict.word, Self::group_label(conflict.existing_group.as_deref()),
+                                    conflict.existing_weight, conflict.weight)
+                            } else {
+                                format!("↔ 「{}」已在「{}」中（权重 {}），将同时加入「{}」",
+                                    conflict.word, Self::group_label(conflict.existing_group.as_deref()),
+                                    conflict.existing_weight, Self::group_label(conflict.group.as_deref()))
+                            };
+                            ui.label(egui::RichText::new(text).size(11.0).color(WARN_COLOR));
+                        }
+                        for (word, problems) in &flagged {
+                            ui.label(egui::RichText::new(format!("⚠ 「{}」：{}", word, problems.join("；")))
+                                .size(11.0).color(WARN_COLOR));
+                        }
+                    });
+
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!preview.hotwords.is_empty(),
+                        egui::Button::new(egui::RichText::new("确认导入").size(13.0))).clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button(egui::RichText::new("取消").size(13.0)).clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+
+        if confirmed {
+            let summary = pending.preview.apply(&mut self.hotwords, pending.target.as_deref(), pending.overwrite);
+            self.status_msg = format!(
+                "已导入：新增 {} 个，更新权重 {} 个，保留原权重 {} 个",
+                summary.added, summary.updated, summary.skipped
+            );
+            self.pending_import = None;
+            return summary.added + summary.updated > 0;
+        }
+        if cancelled {
+            self.pending_import = None;
+        }
+        false
+    }
 }
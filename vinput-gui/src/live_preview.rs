@@ -0,0 +1,236 @@
+//! 效果测试：在设置界面进程内运行识别管道
+//!
+//! 用未保存的设置创建 `StreamingPipeline`，从 `[audio]` 配置的输入源采集，
+//! 在后台线程逐帧处理，记录波形、VAD 状态、语音概率、Preedit 与最终结果，
+//! 以及逗号、端点、句尾标点的触发位置，供测试面板绘制。
+
+use crate::config::VInputConfig;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use vinput_core::audio::{open_audio_source, AudioRingBuffer, AudioRingBufferConfig, DeviceEventListener, PipeWireStreamConfig};
+use vinput_core::itn::{ITNEngine, ITNMode};
+use vinput_core::streaming::{EndpointTrigger, PipelineState, StreamingConfig, StreamingPipeline};
+use vinput_core::vad::VadState;
+
+/// 每帧样本数（32ms @ 16kHz，与核心库音频线程一致）
+const FRAME_SIZE: usize = 512;
+/// 时间轴保留的帧数（约 10 秒）
+pub const TIMELINE_FRAMES: usize = 312;
+/// Preedit 刷新间隔（帧，与核心库一致）
+const PREEDIT_INTERVAL: u64 = 5;
+/// 保留的最终结果条数
+const MAX_SENTENCES: usize = 20;
+
+/// 时间轴标记
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Marker {
+    /// VAD 停顿达到逗号阈值
+    Comma,
+    /// 句子结束或丢弃
+    Endpoint(EndpointTrigger),
+    /// 最终结果的句尾标点
+    SentenceEnd(char),
+}
+
+/// 一帧的测试数据
+#[derive(Debug, Clone)]
+pub struct FrameInfo {
+    /// 原始输入峰值（0.0 ~ 1.0）
+    pub peak: f32,
+    /// 语音概率
+    pub speech_prob: f32,
+    pub vad_state: VadState,
+    pub markers: Vec<Marker>,
+}
+
+/// 一句最终结果
+#[derive(Debug, Clone)]
+pub struct SentenceResult {
+    /// 带标点的识别结果（ITN 之前）
+    pub raw: String,
+    /// ITN 之后上屏的文本
+    pub text: String,
+    /// 结束原因
+    pub trigger: Option<EndpointTrigger>,
+}
+
+/// 测试状态快照
+#[derive(Debug, Clone)]
+pub struct PreviewSnapshot {
+    /// 正在加载模型
+    pub loading: bool,
+    /// 启动或运行出错
+    pub error: Option<String>,
+    /// 最近的帧（旧 → 新）
+    pub frames: VecDeque<FrameInfo>,
+    pub vad_state: VadState,
+    pub speech_prob: f32,
+    /// 当前 Preedit（带实时逗号）
+    pub preedit: String,
+    /// 最终结果（旧 → 新）
+    pub sentences: Vec<SentenceResult>,
+}
+
+impl Default for PreviewSnapshot {
+    fn default() -> Self {
+        Self {
+            loading: true,
+            error: None,
+            frames: VecDeque::with_capacity(TIMELINE_FRAMES),
+            vad_state: VadState::Silence,
+            speech_prob: 0.0,
+            preedit: String::new(),
+            sentences: Vec::new(),
+        }
+    }
+}
+
+/// 后台识别测试
+pub struct LivePreview {
+    running: Arc<AtomicBool>,
+    state: Arc<Mutex<PreviewSnapshot>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl LivePreview {
+    /// 用给定配置开始测试（模型在后台线程加载）
+    pub fn start(config: VInputConfig) -> Self {
+        let running = Arc::new(AtomicBool::new(true));
+        let state = Arc::new(Mutex::new(PreviewSnapshot::default()));
+
+        let worker = {
+            let running = Arc::clone(&running);
+            let state = Arc::clone(&state);
+            std::thread::spawn(move || {
+                if let Err(e) = run(&config, &running, &state) {
+                    tracing::error!("效果测试出错: {}", e);
+                    if let Ok(mut state) = state.lock() {
+                        state.loading = false;
+                        state.error = Some(e.user_message());
+                    }
+                }
+            })
+        };
+
+        Self {
+            running,
+            state,
+            worker: Some(worker),
+        }
+    }
+
+    /// 当前状态
+    pub fn snapshot(&self) -> PreviewSnapshot {
+        self.state.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+}
+
+impl Drop for LivePreview {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// 采集并逐帧处理，直到停止
+fn run(
+    config: &VInputConfig,
+    running: &AtomicBool,
+    state: &Mutex<PreviewSnapshot>,
+) -> vinput_core::VInputResult<()> {
+    let mut pipeline = StreamingPipeline::new(StreamingConfig::from_config(config))?;
+    let itn = ITNEngine::new(ITNMode::Auto);
+
+    let ring_buffer = AudioRingBuffer::new(AudioRingBufferConfig { capacity: 16000 });
+    let (producer, mut consumer) = ring_buffer.split();
+    let device_listener: DeviceEventListener = Arc::new(|_| {});
+    let source = open_audio_source(
+        &config.audio.source,
+        PipeWireStreamConfig::from_capture_config(&config.audio),
+        device_listener,
+        producer,
+    )?;
+    if let Ok(mut state) = state.lock() {
+        state.loading = false;
+    }
+
+    let mut frame = vec![0.0f32; FRAME_SIZE];
+    let mut frame_counter: u64 = 0;
+    let result = loop {
+        if !running.load(Ordering::Relaxed) {
+            break Ok(());
+        }
+        if consumer.read(&mut frame) < FRAME_SIZE {
+            if !source.is_running() {
+                break source.failure().map_or(Ok(()), Err);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+            continue;
+        }
+        frame_counter += 1;
+
+        let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        let result = pipeline.process(&frame)?;
+
+        let mut markers = Vec::new();
+        if result.should_add_comma {
+            markers.push(Marker::Comma);
+        }
+        if let Some(trigger) = result.endpoint {
+            markers.push(Marker::Endpoint(trigger));
+        }
+
+        let preedit = (result.pipeline_state == PipelineState::Recognizing && frame_counter.is_multiple_of(PREEDIT_INTERVAL))
+            .then(|| pipeline.get_partial_result_with_punctuation());
+
+        let mut sentence = None;
+        if result.pipeline_state == PipelineState::Completed {
+            let raw = pipeline.get_final_result_with_punctuation();
+            pipeline.take_alternative_hypotheses();
+            if let Some(ending) = raw.chars().last().filter(|c| matches!(c, '。' | '？' | '！')) {
+                markers.push(Marker::SentenceEnd(ending));
+            }
+            if !raw.is_empty() {
+                let text = itn.process(&raw).text;
+                sentence = Some(SentenceResult {
+                    raw,
+                    text,
+                    trigger: result.endpoint,
+                });
+            }
+        }
+
+        let Ok(mut state) = state.lock() else { break Ok(()) };
+        if state.frames.len() == TIMELINE_FRAMES {
+            state.frames.pop_front();
+        }
+        state.frames.push_back(FrameInfo {
+            peak,
+            speech_prob: result.speech_prob,
+            vad_state: result.vad_state,
+            markers,
+        });
+        state.vad_state = result.vad_state;
+        state.speech_prob = result.speech_prob;
+        if let Some(preedit) = preedit {
+            state.preedit = preedit;
+        }
+        if result.pipeline_state != PipelineState::Recognizing {
+            state.preedit.clear();
+        }
+        if let Some(sentence) = sentence {
+            if state.sentences.len() == MAX_SENTENCES {
+                state.sentences.remove(0);
+            }
+            state.sentences.push(sentence);
+        }
+    };
+
+    source.stop();
+    result
+}
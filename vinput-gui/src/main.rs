@@ -8,13 +8,20 @@ mod basic_settings_panel;
 mod about_panel;
 mod endpoint_panel;
 mod hotwords_editor;
+mod live_preview;
+mod mic_meter;
+mod model_manager_panel;
+mod preview_panel;
 mod punctuation_panel;
+mod shortcuts;
 
 use config::VInputConfig;
 use basic_settings_panel::BasicSettingsPanel;
 use about_panel::AboutPanel;
 use endpoint_panel::EndpointPanel;
 use hotwords_editor::HotwordsEditor;
+use model_manager_panel::ModelManagerPanel;
+use preview_panel::PreviewPanel;
 use punctuation_panel::PunctuationPanel;
 
 fn main() -> eframe::Result {
@@ -45,7 +52,11 @@ struct VInputApp {
     hotwords_editor: HotwordsEditor,
     punctuation_panel: PunctuationPanel,
     endpoint_panel: EndpointPanel,
+    model_manager_panel: ModelManagerPanel,
+    preview_panel: PreviewPanel,
     config_modified: bool,
+    /// 上次保存失败的原因（校验未通过等）
+    save_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,6 +65,8 @@ enum Tab {
     Hotwords,
     Punctuation,
     Endpoint,
+    Models,
+    Preview,
     About,
 }
 
@@ -64,6 +77,8 @@ impl Tab {
             Tab::Hotwords => "热词管理",
             Tab::Punctuation => "标点控制",
             Tab::Endpoint => "端点检测",
+            Tab::Models => "模型管理",
+            Tab::Preview => "效果测试",
             Tab::About => "关于",
         }
     }
@@ -73,7 +88,7 @@ impl VInputApp {
     fn new(cc: &eframe::CreationContext<'_>) -> Self {
         Self::setup_custom_fonts(&cc.egui_ctx);
 
-        let config = match VInputConfig::load() {
+        let config = match config::load() {
             Ok(cfg) => { tracing::info!("✓ Config loaded"); cfg }
             Err(e) => { tracing::error!("✗ Load failed: {}", e); VInputConfig::default() }
         };
@@ -85,8 +100,11 @@ impl VInputApp {
             hotwords_editor: HotwordsEditor::new(&config),
             punctuation_panel: PunctuationPanel::new(&config),
             endpoint_panel: EndpointPanel::new(&config),
+            model_manager_panel: ModelManagerPanel::new(&config),
+            preview_panel: PreviewPanel::new(),
             config,
             config_modified: false,
+            save_error: None,
         }
     }
 
@@ -113,14 +131,29 @@ impl VInputApp {
         ctx.set_fonts(fonts);
     }
 
+    /// 各面板当前（未保存）的设置
+    fn draft_config(&self) -> VInputConfig {
+        let mut config = self.config.clone();
+        self.basic_settings_panel.apply_to_config(&mut config);
+        self.hotwords_editor.apply_to_config(&mut config);
+        self.punctuation_panel.apply_to_config(&mut config);
+        self.endpoint_panel.apply_to_config(&mut config);
+        self.model_manager_panel.apply_to_config(&mut config);
+        config
+    }
+
     fn save_config(&mut self) {
-        self.basic_settings_panel.apply_to_config(&mut self.config);
-        self.hotwords_editor.apply_to_config(&mut self.config);
-        self.punctuation_panel.apply_to_config(&mut self.config);
-        self.endpoint_panel.apply_to_config(&mut self.config);
+        self.config = self.draft_config();
         match self.config.save() {
-            Ok(_) => { self.config_modified = false; tracing::info!("Config saved"); }
-            Err(e) => tracing::error!("Save failed: {}", e),
+            Ok(_) => {
+                self.config_modified = false;
+                self.save_error = None;
+                tracing::info!("Config saved");
+            }
+            Err(e) => {
+                tracing::error!("Save failed: {}", e);
+                self.save_error = Some(e.to_string());
+            }
         }
     }
 
@@ -130,12 +163,19 @@ impl VInputApp {
         self.hotwords_editor = HotwordsEditor::new(&self.config);
         self.punctuation_panel = PunctuationPanel::new(&self.config);
         self.endpoint_panel = EndpointPanel::new(&self.config);
+        self.model_manager_panel = ModelManagerPanel::new(&self.config);
+        self.preview_panel.settings_changed();
         self.config_modified = true;
     }
 }
 
 impl eframe::App for VInputApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // 离开效果测试页时停止测试，释放麦克风
+        if self.active_tab != Tab::Preview {
+            self.preview_panel.stop();
+        }
+
         // 底部状态栏
         egui::TopBottomPanel::bottom("bottom_panel")
             .exact_height(40.0)
@@ -143,7 +183,10 @@ impl eframe::App for VInputApp {
                 ui.add_space(4.0);
                 ui.horizontal(|ui| {
                     ui.add_space(8.0);
-                    if self.config_modified {
+                    if let Some(error) = &self.save_error {
+                        ui.label(egui::RichText::new(format!("● 保存失败: {}", error)).size(12.0).color(egui::Color32::from_rgb(220, 80, 80)))
+                            .on_hover_text(error);
+                    } else if self.config_modified {
                         ui.label(egui::RichText::new("● 配置已修改").size(12.0).color(egui::Color32::from_rgb(220, 150, 50)));
                     } else {
                         ui.label(egui::RichText::new("● 已保存").size(12.0).color(egui::Color32::from_rgb(80, 180, 80)));
@@ -167,7 +210,7 @@ impl eframe::App for VInputApp {
             .show(ctx, |ui| {
                 ui.add_space(16.0);
 
-                let main_tabs = [Tab::Basic, Tab::Hotwords, Tab::Punctuation, Tab::Endpoint];
+                let main_tabs = [Tab::Basic, Tab::Hotwords, Tab::Punctuation, Tab::Endpoint, Tab::Models, Tab::Preview];
                 for tab in main_tabs {
                     let is_active = self.active_tab == tab;
                     let text = egui::RichText::new(tab.label()).size(14.0);
@@ -192,12 +235,22 @@ impl eframe::App for VInputApp {
         // 中央内容区
         egui::CentralPanel::default().show(ctx, |ui| {
             let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                match self.active_tab {
-                    Tab::Basic => { if self.basic_settings_panel.ui(ui) { self.config_modified = true; } }
-                    Tab::Hotwords => { if self.hotwords_editor.ui(ui) { self.config_modified = true; } }
-                    Tab::Punctuation => { if self.punctuation_panel.ui(ui) { self.config_modified = true; } }
-                    Tab::Endpoint => { if self.endpoint_panel.ui(ui) { self.config_modified = true; } }
-                    Tab::About => { self.about_panel.ui(ui); }
+                let modified = match self.active_tab {
+                    Tab::Basic => self.basic_settings_panel.ui(ui),
+                    Tab::Hotwords => self.hotwords_editor.ui(ui),
+                    Tab::Punctuation => self.punctuation_panel.ui(ui),
+                    Tab::Endpoint => self.endpoint_panel.ui(ui),
+                    Tab::Models => self.model_manager_panel.ui(ui),
+                    Tab::Preview => {
+                        let draft = self.draft_config();
+                        self.preview_panel.ui(ui, &draft);
+                        false
+                    }
+                    Tab::About => { self.about_panel.ui(ui); false }
+                };
+                if modified {
+                    self.config_modified = true;
+                    self.preview_panel.settings_changed();
                 }
             }));
 
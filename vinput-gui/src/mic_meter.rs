@@ -0,0 +1,151 @@
+//! 麦克风实时电平表
+//!
+//! 与设备枚举一致通过 PulseAudio 命令行工具（`parec`）读取所选音频源，
+//! 电平计算与削波 / 无信号 / 增益过低诊断复用 vinput-core 的 [`LevelMeter`]，
+//! 与录音时的提示保持一致。
+
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use vinput_core::audio::{LevelMeter, LevelMeterConfig, LevelWarning};
+
+/// 采样率（与 vinput-core 一致）
+const SAMPLE_RATE: u32 = 16000;
+/// 每次读取的样本数（50ms）
+const CHUNK_SAMPLES: usize = SAMPLE_RATE as usize / 20;
+
+/// 电平快照
+#[derive(Debug, Clone, Copy)]
+pub struct MicLevel {
+    /// RMS (dBFS)
+    pub rms_dbfs: f32,
+    /// 峰值 (dBFS)
+    pub peak_dbfs: f32,
+    /// 近期出现过削波
+    pub clipping: bool,
+    /// 长时间无信号
+    pub no_signal: bool,
+    /// 说话音量过低
+    pub low_gain: bool,
+}
+
+impl Default for MicLevel {
+    fn default() -> Self {
+        Self {
+            rms_dbfs: -120.0,
+            peak_dbfs: -120.0,
+            clipping: false,
+            no_signal: false,
+            low_gain: false,
+        }
+    }
+}
+
+/// 后台电平监听
+pub struct MicMeter {
+    child: Child,
+    running: Arc<AtomicBool>,
+    level: Arc<Mutex<MicLevel>>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl MicMeter {
+    /// 开始监听指定音频源（"default" 表示默认源）
+    ///
+    /// 诊断阈值取自配置的 `[audio.level_meter]`；测试时始终启用电平表
+    pub fn start(device: &str, config: &LevelMeterConfig) -> std::io::Result<Self> {
+        let mut command = Command::new("parec");
+        command.args([
+            "--raw",
+            "--format=float32le",
+            "--channels=1",
+            "--latency-msec=50",
+        ]);
+        command.arg(format!("--rate={}", SAMPLE_RATE));
+        if device != "default" {
+            command.arg(format!("--device={}", device));
+        }
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let mut stdout = child.stdout.take().expect("stdout 已设置为 piped");
+        let running = Arc::new(AtomicBool::new(true));
+        let level = Arc::new(Mutex::new(MicLevel::default()));
+        let config = LevelMeterConfig {
+            enabled: true,
+            ..config.clone()
+        };
+
+        let reader = {
+            let running = Arc::clone(&running);
+            let level = Arc::clone(&level);
+            std::thread::spawn(move || {
+                let mut buf = vec![0u8; CHUNK_SAMPLES * 4];
+                let (silence_dbfs, low_gain_dbfs) = (config.silence_dbfs, config.low_gain_dbfs);
+                let mut meter = LevelMeter::new(config, SAMPLE_RATE);
+
+                while running.load(Ordering::Relaxed) {
+                    if stdout.read_exact(&mut buf).is_err() {
+                        break;
+                    }
+                    let samples: Vec<f32> = buf
+                        .chunks_exact(4)
+                        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                        .collect();
+
+                    // 测试时没有运行 Energy Gate，增益按配置的下限判定
+                    let update = meter.process(&samples, None);
+                    let Ok(mut level) = level.lock() else {
+                        continue;
+                    };
+                    for warning in &update.warnings {
+                        match warning {
+                            LevelWarning::NoSignal { .. } => level.no_signal = true,
+                            LevelWarning::LowGain { .. } => level.low_gain = true,
+                            LevelWarning::Clipping { .. } => {}
+                        }
+                    }
+                    if let Some(reading) = update.reading {
+                        level.rms_dbfs = reading.rms_dbfs;
+                        level.peak_dbfs = reading.peak_dbfs;
+                        level.clipping = reading.clipping;
+                        if reading.rms_dbfs >= silence_dbfs {
+                            level.no_signal = false;
+                        }
+                        if reading.rms_dbfs >= low_gain_dbfs {
+                            level.low_gain = false;
+                        }
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            child,
+            running,
+            level,
+            reader: Some(reader),
+        })
+    }
+
+    /// 当前电平
+    pub fn level(&self) -> MicLevel {
+        self.level.lock().map(|l| *l).unwrap_or_default()
+    }
+}
+
+impl Drop for MicMeter {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
@@ -1,212 +1,268 @@
 //! 模型管理面板
+//!
+//! 模型列表来自 vinput-core 的模型目录，下载、校验与安装在后台线程进行，
+//! 「使用此模型」切换 `~/.local/share/vinput/models/active` 并让 `asr.model_dir` 指向它。
 
-use eframe::egui;
 use crate::config::VInputConfig;
-use std::path::PathBuf;
-
-/// 模型信息
-#[derive(Debug, Clone)]
-struct ModelInfo {
-    name: String,
-    path: String,
-    size_mb: f64,
-    language: String,
-    is_installed: bool,
+use eframe::egui;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::time::Duration;
+use vinput_core::models::{DownloadProgress, ModelEntry, ModelError, ModelManager};
+
+/// 后台安装任务消息
+enum InstallEvent {
+    Progress(DownloadProgress),
+    Done(Result<(), String>),
+}
+
+/// 进行中的安装任务
+struct InstallJob {
+    id: String,
+    events: Receiver<InstallEvent>,
+    cancel: Arc<AtomicBool>,
+    progress: Option<DownloadProgress>,
 }
 
 /// 模型管理面板
 pub struct ModelManagerPanel {
-    /// 当前模型目录
+    /// 当前模型目录（asr.model_dir）
     model_dir: String,
-    /// 可用模型列表
-    available_models: Vec<ModelInfo>,
+    manager: Option<Arc<ModelManager>>,
+    job: Option<InstallJob>,
     /// 状态消息
     status_message: String,
 }
 
 impl ModelManagerPanel {
     pub fn new(config: &VInputConfig) -> Self {
-        let mut panel = Self {
-            model_dir: config.asr.model_dir.clone(),
-            available_models: Vec::new(),
-            status_message: String::new(),
+        let (manager, status_message) = match ModelManager::open_default() {
+            Ok(manager) => (Some(Arc::new(manager)), String::new()),
+            Err(e) => (None, format!("模型管理不可用: {}", e)),
         };
+        Self {
+            model_dir: config.asr.model_dir.clone(),
+            manager,
+            job: None,
+            status_message,
+        }
+    }
 
-        panel.scan_models();
-        panel
+    /// 应用到配置
+    pub fn apply_to_config(&self, config: &mut VInputConfig) {
+        config.asr.model_dir = self.model_dir.clone();
+    }
+
+    fn start_install(&mut self, manager: &Arc<ModelManager>, entry: &ModelEntry) {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let id = entry.id.clone();
+        {
+            let manager = Arc::clone(manager);
+            let cancel = Arc::clone(&cancel);
+            let id = id.clone();
+            std::thread::spawn(move || {
+                let result = manager.install(&id, &mut |progress| {
+                    let _ = tx.send(InstallEvent::Progress(progress));
+                    if cancel.load(Ordering::Relaxed) {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                });
+                let result = match result {
+                    Ok(_) => Ok(()),
+                    Err(ModelError::Cancelled) => Err("已取消，再次下载将从断点继续".to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                let _ = tx.send(InstallEvent::Done(result));
+            });
+        }
+        self.status_message = format!("正在下载: {}", entry.name);
+        self.job = Some(InstallJob {
+            id,
+            events: rx,
+            cancel,
+            progress: None,
+        });
     }
 
-    /// 扫描模型目录
-    fn scan_models(&mut self) {
-        self.available_models.clear();
-
-        // 预定义的模型列表
-        let predefined_models = vec![
-            ModelInfo {
-                name: "Zipformer 中英双语 (推荐)".to_string(),
-                path: "models/zipformer/sherpa-onnx-streaming-zipformer-bilingual-zh-en-2023-02-20".to_string(),
-                size_mb: 180.0,
-                language: "中文+英文".to_string(),
-                is_installed: false,
-            },
-            ModelInfo {
-                name: "Zipformer 中文".to_string(),
-                path: "models/zipformer/sherpa-onnx-streaming-zipformer-zh-2023-02-20".to_string(),
-                size_mb: 150.0,
-                language: "中文".to_string(),
-                is_installed: false,
-            },
-            ModelInfo {
-                name: "Paraformer 中文".to_string(),
-                path: "models/paraformer/sherpa-onnx-paraformer-zh-2023-03-28".to_string(),
-                size_mb: 120.0,
-                language: "中文".to_string(),
-                is_installed: false,
-            },
-        ];
-
-        // 检查哪些模型已安装
-        for mut model in predefined_models {
-            let full_path = PathBuf::from(&model.path);
-            model.is_installed = full_path.exists();
-            self.available_models.push(model);
+    /// 处理后台任务消息
+    fn poll_job(&mut self) {
+        let Some(job) = &mut self.job else { return };
+        loop {
+            match job.events.try_recv() {
+                Ok(InstallEvent::Progress(progress)) => job.progress = Some(progress),
+                Ok(InstallEvent::Done(result)) => {
+                    self.status_message = match result {
+                        Ok(()) => format!("安装完成: {}", job.id),
+                        Err(e) => format!("安装失败: {}", e),
+                    };
+                    self.job = None;
+                    return;
+                }
+                Err(TryRecvError::Empty) => return,
+                Err(TryRecvError::Disconnected) => {
+                    self.job = None;
+                    return;
+                }
+            }
         }
     }
 
     /// 渲染 UI
     pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
         let mut modified = false;
+        self.poll_job();
 
-        ui.heading("模型管理");
-        ui.add_space(10.0);
+        ui.add_space(4.0);
+        ui.heading(egui::RichText::new("模型管理").size(18.0).strong());
+        ui.add_space(2.0);
+        ui.separator();
+        ui.add_space(8.0);
 
         egui::ScrollArea::vertical().show(ui, |ui| {
             // 当前模型
+            ui.label(egui::RichText::new("当前模型目录").size(13.0).strong());
+            ui.add_space(6.0);
             ui.group(|ui| {
-                ui.label("当前使用的模型");
-                ui.add_space(5.0);
-
-                ui.horizontal(|ui| {
-                    ui.label("模型目录:");
-                    if ui.text_edit_singleline(&mut self.model_dir).changed() {
-                        modified = true;
-                    }
-                });
-
-                ui.add_space(5.0);
-                if ui.button("📁 选择目录...").clicked() {
-                    // TODO: File dialog
-                    self.status_message = "文件选择对话框功能待实现".to_string();
-                }
-
-                ui.add_space(5.0);
-                if ui.button("🔄 刷新模型列表").clicked() {
-                    self.scan_models();
-                    self.status_message = "已刷新模型列表".to_string();
+                ui.set_min_width(ui.available_width());
+                if ui.add(egui::TextEdit::singleline(&mut self.model_dir).desired_width(f32::INFINITY)).changed() {
+                    modified = true;
                 }
+                ui.label(egui::RichText::new("可手动填写已解压的模型目录；使用下方安装的模型时自动填写").size(11.0).color(egui::Color32::GRAY));
             });
 
-            ui.add_space(10.0);
+            ui.add_space(12.0);
 
             // 可用模型列表
-            ui.group(|ui| {
-                ui.label("可用模型");
-                ui.add_space(5.0);
-
-                for model in &self.available_models {
-                    ui.group(|ui| {
-                        ui.horizontal(|ui| {
-                            // 模型名称
-                            ui.label(egui::RichText::new(&model.name).strong());
+            ui.label(egui::RichText::new("可用模型").size(13.0).strong());
+            ui.add_space(6.0);
 
-                            ui.add_space(10.0);
+            let Some(manager) = self.manager.clone() else {
+                ui.label(egui::RichText::new(&self.status_message).size(12.0).color(egui::Color32::from_rgb(200, 80, 80)));
+                return;
+            };
+            let store = manager.store();
+            let active = store.active();
 
-                            // 安装状态
-                            if model.is_installed {
-                                ui.label(egui::RichText::new("✅ 已安装").color(egui::Color32::GREEN));
-                            } else {
-                                ui.label(egui::RichText::new("❌ 未安装").color(egui::Color32::RED));
-                            }
-                        });
+            for entry in &manager.catalog().models {
+                let installed = store.is_installed(entry);
+                let is_active = installed && active.as_deref() == Some(entry.id.as_str());
 
-                        ui.add_space(5.0);
+                ui.group(|ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(&entry.name).size(13.0).strong());
+                        ui.add_space(10.0);
+                        if is_active {
+                            ui.label(egui::RichText::new("● 使用中").size(12.0).color(egui::Color32::from_rgb(80, 180, 80)));
+                        } else if installed {
+                            ui.label(egui::RichText::new("● 已安装").size(12.0).color(egui::Color32::from_rgb(80, 130, 220)));
+                        } else {
+                            ui.label(egui::RichText::new("● 未安装").size(12.0).color(egui::Color32::GRAY));
+                        }
+                    });
 
+                    let size = if installed {
+                        format_size(store.installed_size(&entry.id))
+                    } else if entry.size_bytes > 0 {
+                        format!("下载 {}", format_size(entry.size_bytes))
+                    } else {
+                        "大小未知".to_string()
+                    };
+                    ui.label(egui::RichText::new(format!(
+                        "{}  ·  {}  ·  {}",
+                        entry.language_label(),
+                        entry.model_type.label(),
+                        size
+                    )).size(12.0).color(egui::Color32::GRAY));
+                    ui.add_space(4.0);
+
+                    let installing = self.job.as_ref().filter(|job| job.id == entry.id);
+                    if let Some(job) = installing {
                         ui.horizontal(|ui| {
-                            ui.label(format!("语言: {}", model.language));
-                            ui.add_space(20.0);
-                            ui.label(format!("大小: {:.1} MB", model.size_mb));
+                            let (fraction, text) = match job.progress {
+                                Some(p) => match p.fraction() {
+                                    Some(f) => (f, format!("{} / {}", format_size(p.downloaded), format_size(p.total.unwrap_or(0)))),
+                                    None => (0.0, format_size(p.downloaded)),
+                                },
+                                None => (0.0, "连接中…".to_string()),
+                            };
+                            ui.add(egui::ProgressBar::new(fraction)
+                                .desired_width(300.0)
+                                .text(egui::RichText::new(text).size(11.0)));
+                            if ui.button(egui::RichText::new("取消").size(13.0)).clicked() {
+                                job.cancel.store(true, Ordering::Relaxed);
+                            }
                         });
+                        return;
+                    }
 
-                        ui.add_space(5.0);
-                        ui.label(format!("路径: {}", model.path));
-
-                        ui.add_space(5.0);
-
-                        ui.horizontal(|ui| {
-                            if model.is_installed {
-                                if ui.button("使用此模型").clicked() {
-                                    self.model_dir = model.path.clone();
-                                    modified = true;
-                                    self.status_message = format!("已切换到模型: {}", model.name);
-                                }
-
-                                if ui.button("🗑 删除").clicked() {
-                                    self.status_message = format!("删除模型功能待实现: {}", model.name);
+                    ui.horizontal(|ui| {
+                        if installed {
+                            if ui.add_enabled(!is_active || self.model_dir != store.active_dir().to_string_lossy(),
+                                egui::Button::new(egui::RichText::new("使用此模型").size(13.0))).clicked()
+                            {
+                                match manager.activate(&entry.id) {
+                                    Ok(dir) => {
+                                        self.model_dir = dir.to_string_lossy().into_owned();
+                                        modified = true;
+                                        self.status_message = format!("已切换到模型: {}，点击「应用」后重新激活输入法生效", entry.name);
+                                    }
+                                    Err(e) => self.status_message = format!("切换失败: {}", e),
                                 }
+                            }
+                            if ui.add_enabled(!is_active, egui::Button::new(egui::RichText::new("删除").size(13.0)))
+                                .on_disabled_hover_text("正在使用的模型不能删除")
+                                .clicked()
+                            {
+                                self.status_message = match store.remove(&entry.id) {
+                                    Ok(()) => format!("已删除: {}", entry.name),
+                                    Err(e) => format!("删除失败: {}", e),
+                                };
+                            }
+                        } else {
+                            let can_install = self.job.is_none() && !entry.sha256.is_empty();
+                            let button = ui.add_enabled(can_install, egui::Button::new(egui::RichText::new("下载安装").size(13.0)));
+                            let button = if entry.sha256.is_empty() {
+                                button.on_disabled_hover_text("模型目录未提供校验值，无法安装")
                             } else {
-                                if ui.button("📥 下载安装").clicked() {
-                                    self.status_message = format!("下载功能待实现: {}", model.name);
-                                }
+                                button.on_disabled_hover_text("请等待当前下载完成")
+                            };
+                            if button.clicked() {
+                                self.start_install(&manager, entry);
                             }
-                        });
+                        }
                     });
+                });
+                ui.add_space(6.0);
+            }
 
-                    ui.add_space(5.0);
-                }
-            });
-
-            ui.add_space(10.0);
-
-            // 模型下载说明
-            ui.group(|ui| {
-                ui.label("模型下载");
-                ui.add_space(5.0);
-
-                ui.label("您可以从以下地址下载模型:");
-                ui.hyperlink_to(
-                    "sherpa-onnx 模型仓库",
-                    "https://github.com/k2-fsa/sherpa-onnx/releases/tag/asr-models"
-                );
-
-                ui.add_space(5.0);
-
-                ui.label("下载后解压到以下目录:");
-                ui.code("/usr/share/vinput/models/");
-                ui.label("或");
-                ui.code("~/.local/share/vinput/models/");
-
-                ui.add_space(5.0);
-
-                ui.label("推荐模型:");
-                ui.label("• sherpa-onnx-streaming-zipformer-bilingual-zh-en-2023-02-20 (中英双语)");
-                ui.label("• sherpa-onnx-streaming-zipformer-zh-2023-02-20 (中文)");
-            });
-
-            ui.add_space(10.0);
+            ui.label(egui::RichText::new(format!("模型安装在 {}", store.root().display())).size(11.0).color(egui::Color32::GRAY));
 
-            // 状态消息
             if !self.status_message.is_empty() {
-                ui.group(|ui| {
-                    ui.label(&self.status_message);
-                });
+                ui.add_space(8.0);
+                ui.label(egui::RichText::new(&self.status_message).size(12.0));
             }
         });
 
+        // 下载中持续刷新进度
+        if self.job.is_some() {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+
         modified
     }
+}
 
-    /// 应用到配置
-    pub fn apply_to_config(&self, config: &mut VInputConfig) {
-        config.asr.model_dir = self.model_dir.clone();
+/// 字节数显示为 MB / KB
+fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{:.0} KB", bytes as f64 / 1024.0)
     }
 }
@@ -0,0 +1,286 @@
+//! 效果测试面板
+//!
+//! 用未保存的设置运行识别，实时显示波形、VAD 状态、语音概率、Preedit 与最终结果，
+//! 并在时间轴上标出逗号、端点与句尾标点的触发位置，调参后无需保存即可对比效果。
+
+use crate::config::{StopMode, VInputConfig};
+use crate::live_preview::{FrameInfo, LivePreview, Marker, PreviewSnapshot, TIMELINE_FRAMES};
+use eframe::egui;
+use std::time::Duration;
+use vinput_core::streaming::EndpointTrigger;
+use vinput_core::vad::VadState;
+
+const COMMA_COLOR: egui::Color32 = egui::Color32::from_rgb(80, 160, 240);
+const ENDPOINT_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 80, 80);
+const ENDING_COLOR: egui::Color32 = egui::Color32::from_rgb(180, 110, 230);
+const PROB_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 170, 50);
+
+pub struct PreviewPanel {
+    preview: Option<LivePreview>,
+    /// 测试开始后设置是否又被修改
+    settings_changed: bool,
+    /// 测试使用的 VAD 阈值（绘制参考线）
+    thresholds: (f32, f32),
+}
+
+impl PreviewPanel {
+    pub fn new() -> Self {
+        Self {
+            preview: None,
+            settings_changed: false,
+            thresholds: (0.0, 0.0),
+        }
+    }
+
+    /// 其他面板修改了设置
+    pub fn settings_changed(&mut self) {
+        if self.preview.is_some() {
+            self.settings_changed = true;
+        }
+    }
+
+    /// 停止测试（切换到其他页面时调用，释放麦克风）
+    pub fn stop(&mut self) {
+        self.preview = None;
+    }
+
+    fn start(&mut self, draft: &VInputConfig) {
+        // 先释放旧管道与麦克风
+        self.preview = None;
+        let vad = if draft.activation.stop_mode == StopMode::HandsFree {
+            &draft.hands_free_vad
+        } else {
+            &draft.vad
+        };
+        self.thresholds = (vad.hysteresis.start_threshold, vad.hysteresis.end_threshold);
+        self.preview = Some(LivePreview::start(draft.clone()));
+        self.settings_changed = false;
+    }
+
+    /// `draft` 为各面板当前（未保存）的设置
+    pub fn ui(&mut self, ui: &mut egui::Ui, draft: &VInputConfig) {
+        ui.add_space(4.0);
+        ui.heading(egui::RichText::new("效果测试").size(18.0).strong());
+        ui.add_space(2.0);
+        ui.separator();
+        ui.add_space(8.0);
+
+        ui.horizontal(|ui| {
+            if self.preview.is_none() {
+                if ui.add_sized([90.0, 28.0], egui::Button::new(egui::RichText::new("开始测试").size(13.0))).clicked() {
+                    self.start(draft);
+                }
+            } else {
+                if ui.add_sized([90.0, 28.0], egui::Button::new(egui::RichText::new("停止测试").size(13.0))).clicked() {
+                    self.stop();
+                }
+                let restart = egui::Button::new(egui::RichText::new("用当前设置重新开始").size(13.0));
+                let restart = if self.settings_changed {
+                    restart.fill(egui::Color32::from_rgb(160, 110, 30))
+                } else {
+                    restart
+                };
+                if ui.add_sized([150.0, 28.0], restart).clicked() {
+                    self.start(draft);
+                }
+            }
+            ui.label(egui::RichText::new("使用尚未保存的设置，测试结果不会上屏").size(11.0).color(egui::Color32::GRAY));
+        });
+        if self.settings_changed {
+            ui.label(egui::RichText::new("设置已修改，重新开始后生效").size(11.0).color(egui::Color32::from_rgb(220, 150, 50)));
+        }
+        ui.add_space(8.0);
+
+        let Some(preview) = &self.preview else {
+            ui.label(egui::RichText::new("对着麦克风说几句话，观察 VAD、断句与标点在哪里触发；\n在「端点检测」「标点控制」等页面调整参数后回到此页重新开始测试。")
+                .size(12.0).color(egui::Color32::GRAY));
+            return;
+        };
+        let snapshot = preview.snapshot();
+        // 测试进行中持续刷新
+        ui.ctx().request_repaint_after(Duration::from_millis(50));
+
+        if let Some(error) = &snapshot.error {
+            ui.label(egui::RichText::new(format!("测试失败：{}", error)).size(12.0).color(ENDPOINT_COLOR));
+            return;
+        }
+        if snapshot.loading {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(egui::RichText::new("正在加载模型…").size(12.0));
+            });
+            return;
+        }
+
+        // 状态
+        ui.horizontal(|ui| {
+            let (label, color) = vad_state_style(snapshot.vad_state);
+            ui.label(egui::RichText::new(format!("● {}", label)).size(13.0).color(color));
+            ui.add_space(12.0);
+            ui.label(egui::RichText::new("语音概率").size(12.0));
+            ui.add(egui::ProgressBar::new(snapshot.speech_prob.clamp(0.0, 1.0))
+                .desired_width(160.0)
+                .fill(PROB_COLOR)
+                .text(egui::RichText::new(format!("{:.2}", snapshot.speech_prob)).size(11.0)));
+        });
+        ui.add_space(6.0);
+
+        self.ui_timeline(ui, &snapshot);
+        ui_legend(ui);
+        ui.add_space(10.0);
+
+        ui_results(ui, &snapshot);
+    }
+
+    /// 时间轴：VAD 状态底色、波形、语音概率曲线、阈值参考线与触发标记
+    fn ui_timeline(&self, ui: &mut egui::Ui, snapshot: &PreviewSnapshot) {
+        let width = ui.available_width();
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 150.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 4.0, egui::Color32::from_rgb(30, 33, 40));
+
+        let step = rect.width() / TIMELINE_FRAMES as f32;
+        // 最新的帧在最右侧
+        let x_of = |i: usize| rect.right() - (snapshot.frames.len() - i) as f32 * step;
+        let y_of = |v: f32| rect.bottom() - v.clamp(0.0, 1.0) * rect.height();
+
+        for (i, frame) in snapshot.frames.iter().enumerate() {
+            let x = x_of(i);
+            if let Some(fill) = vad_background(frame.vad_state) {
+                painter.rect_filled(egui::Rect::from_x_y_ranges(x..=x + step, rect.y_range()), 0.0, fill);
+            }
+            let half = frame.peak.clamp(0.0, 1.0) * rect.height() * 0.45;
+            painter.line_segment(
+                [egui::pos2(x + step / 2.0, rect.center().y - half), egui::pos2(x + step / 2.0, rect.center().y + half)],
+                egui::Stroke::new(step.max(1.0), egui::Color32::from_rgb(120, 140, 170)),
+            );
+        }
+
+        // 启动 / 结束阈值
+        for threshold in [self.thresholds.0, self.thresholds.1] {
+            painter.hline(rect.x_range(), y_of(threshold), egui::Stroke::new(1.0, PROB_COLOR.gamma_multiply(0.4)));
+        }
+
+        let points: Vec<egui::Pos2> = snapshot.frames.iter().enumerate()
+            .map(|(i, f)| egui::pos2(x_of(i) + step / 2.0, y_of(f.speech_prob)))
+            .collect();
+        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, PROB_COLOR)));
+
+        for (i, frame) in snapshot.frames.iter().enumerate() {
+            draw_markers(&painter, rect, x_of(i) + step / 2.0, frame);
+        }
+    }
+}
+
+/// 绘制一帧的触发标记（竖线 + 文字）
+fn draw_markers(painter: &egui::Painter, rect: egui::Rect, x: f32, frame: &FrameInfo) {
+    let font = egui::FontId::proportional(11.0);
+    for (row, marker) in frame.markers.iter().enumerate() {
+        let (text, color) = match marker {
+            Marker::Comma => ("，".to_string(), COMMA_COLOR),
+            Marker::Endpoint(trigger) => (trigger_label(*trigger).to_string(), ENDPOINT_COLOR),
+            Marker::SentenceEnd(c) => (c.to_string(), ENDING_COLOR),
+        };
+        painter.vline(x, rect.y_range(), egui::Stroke::new(1.5, color));
+        painter.text(
+            egui::pos2(x - 2.0, rect.top() + 2.0 + row as f32 * 14.0),
+            egui::Align2::RIGHT_TOP,
+            text,
+            font.clone(),
+            color,
+        );
+    }
+}
+
+fn ui_legend(ui: &mut egui::Ui) {
+    ui.horizontal(|ui| {
+        for (text, color) in [
+            ("语音", egui::Color32::from_rgb(60, 180, 100)),
+            ("候选", egui::Color32::from_rgb(200, 170, 60)),
+            ("语音概率", PROB_COLOR),
+            ("停顿逗号", COMMA_COLOR),
+            ("断句", ENDPOINT_COLOR),
+            ("句尾标点", ENDING_COLOR),
+        ] {
+            ui.label(egui::RichText::new("■").size(11.0).color(color));
+            ui.label(egui::RichText::new(text).size(11.0).color(egui::Color32::GRAY));
+            ui.add_space(6.0);
+        }
+    });
+}
+
+/// Preedit 与最终结果（新 → 旧）
+fn ui_results(ui: &mut egui::Ui, snapshot: &PreviewSnapshot) {
+    ui.label(egui::RichText::new("Preedit").size(13.0).strong());
+    ui.add_space(4.0);
+    ui.group(|ui| {
+        ui.set_min_width(ui.available_width());
+        let text = if snapshot.preedit.is_empty() { " " } else { snapshot.preedit.as_str() };
+        ui.label(egui::RichText::new(text).size(15.0));
+    });
+
+    ui.add_space(8.0);
+    ui.label(egui::RichText::new("识别结果").size(13.0).strong());
+    ui.add_space(4.0);
+    egui::ScrollArea::vertical().max_height(ui.available_height()).show(ui, |ui| {
+        ui.set_min_width(ui.available_width());
+        if snapshot.sentences.is_empty() {
+            ui.label(egui::RichText::new("暂无结果").size(12.0).color(egui::Color32::GRAY));
+        }
+        for sentence in snapshot.sentences.iter().rev() {
+            ui.group(|ui| {
+                ui.set_min_width(ui.available_width());
+                ui.horizontal_wrapped(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+                    for c in sentence.text.chars() {
+                        let color = match c {
+                            '，' | '、' => COMMA_COLOR,
+                            '。' | '？' | '！' => ENDING_COLOR,
+                            _ => ui.visuals().text_color(),
+                        };
+                        ui.label(egui::RichText::new(c.to_string()).size(15.0).color(color));
+                    }
+                });
+                let mut detail = match sentence.trigger {
+                    Some(trigger) => format!("断句：{}", trigger_label(trigger)),
+                    None => String::new(),
+                };
+                if sentence.raw != sentence.text {
+                    detail.push_str(&format!("    ITN 前：{}", sentence.raw));
+                }
+                if !detail.is_empty() {
+                    ui.label(egui::RichText::new(detail).size(11.0).color(egui::Color32::GRAY));
+                }
+            });
+        }
+    });
+}
+
+fn trigger_label(trigger: EndpointTrigger) -> &'static str {
+    match trigger {
+        EndpointTrigger::VadSilence => "静音断句",
+        EndpointTrigger::AsrEndpoint => "ASR 端点",
+        EndpointTrigger::ForcedSegmentation => "过长分段",
+        EndpointTrigger::Timeout => "超时",
+        EndpointTrigger::TooShort => "过短忽略",
+    }
+}
+
+fn vad_state_style(state: VadState) -> (&'static str, egui::Color32) {
+    match state {
+        VadState::Silence => ("静音", egui::Color32::GRAY),
+        VadState::SpeechCandidate => ("语音候选", egui::Color32::from_rgb(200, 170, 60)),
+        VadState::Speech => ("语音", egui::Color32::from_rgb(60, 180, 100)),
+        VadState::SilenceCandidate => ("静音候选", egui::Color32::from_rgb(200, 170, 60)),
+    }
+}
+
+fn vad_background(state: VadState) -> Option<egui::Color32> {
+    match state {
+        VadState::Silence => None,
+        VadState::Speech => Some(egui::Color32::from_rgba_unmultiplied(60, 180, 100, 40)),
+        VadState::SpeechCandidate | VadState::SilenceCandidate => {
+            Some(egui::Color32::from_rgba_unmultiplied(200, 170, 60, 30))
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! 标点控制面板
 
-use crate::config::VInputConfig;
+use crate::config::{StyleProfile, VInputConfig};
 use eframe::egui;
 
 pub struct PunctuationPanel {
@@ -15,19 +15,23 @@ impl PunctuationPanel {
     pub fn new(config: &VInputConfig) -> Self {
         Self {
             style: config.punctuation.style.clone(),
-            pause_ratio: config.punctuation.pause_ratio,
-            min_tokens: config.punctuation.min_tokens,
+            pause_ratio: config.punctuation.streaming_pause_ratio,
+            min_tokens: config.punctuation.streaming_min_tokens,
             allow_exclamation: config.punctuation.allow_exclamation,
-            question_strict: config.punctuation.question_strict,
+            question_strict: config.punctuation.question_strict_mode,
         }
     }
 
     pub fn apply_to_config(&self, config: &mut VInputConfig) {
+        // 预设风格同时写入面板未展示的参数（逗号间隔、逻辑连接词等）
+        if self.style != "Custom" {
+            config.punctuation = StyleProfile::from_preset(&self.style);
+        }
         config.punctuation.style = self.style.clone();
-        config.punctuation.pause_ratio = self.pause_ratio;
-        config.punctuation.min_tokens = self.min_tokens;
+        config.punctuation.streaming_pause_ratio = self.pause_ratio;
+        config.punctuation.streaming_min_tokens = self.min_tokens;
         config.punctuation.allow_exclamation = self.allow_exclamation;
-        config.punctuation.question_strict = self.question_strict;
+        config.punctuation.question_strict_mode = self.question_strict;
     }
 
     pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
@@ -46,19 +50,21 @@ impl PunctuationPanel {
             ui.group(|ui| {
                 ui.horizontal(|ui| {
                     let presets = [
-                        ("Professional", "正式", 3.5_f32, 5_usize, false, true),
-                        ("Balanced",     "均衡", 2.5,     3,       true,  false),
-                        ("Expressive",   "表达", 1.8,     2,       true,  false),
+                        ("Professional", "正式"),
+                        ("Balanced",     "均衡"),
+                        ("Expressive",   "表达"),
                     ];
-                    for (id, label, ratio, tokens, excl, strict) in presets {
+                    for (id, label) in presets {
                         let active = self.style == id;
                         if ui.add_sized([90.0, 30.0], egui::SelectableLabel::new(active,
                             egui::RichText::new(label).size(13.0))).clicked() && !active {
-                            self.style = id.to_string();
-                            self.pause_ratio = ratio;
-                            self.min_tokens = tokens;
-                            self.allow_exclamation = excl;
-                            self.question_strict = strict;
+                            // 预设参数与 vinput-core 共用同一份定义
+                            let preset = StyleProfile::from_preset(id);
+                            self.style = preset.style;
+                            self.pause_ratio = preset.streaming_pause_ratio;
+                            self.min_tokens = preset.streaming_min_tokens;
+                            self.allow_exclamation = preset.allow_exclamation;
+                            self.question_strict = preset.question_strict_mode;
                             modified = true;
                         }
                         ui.add_space(4.0);
@@ -71,7 +77,7 @@ impl PunctuationPanel {
                 ui.add_space(4.0);
                 let desc = match self.style.as_str() {
                     "Professional" => "适合正式文档：逗号稀少，严格问号，不用感叹号",
-                    "Balanced"     => "适合日常对话：标点适中，允许感叹号",
+                    "Balanced"     => "适合日常对话：标点适中，问号检测宽松",
                     "Expressive"   => "适合口语输出：逗号较多，宽松的问号与感叹号",
                     _              => "已手动调整参数",
                 };
@@ -27,10 +27,10 @@ impl RecognitionSettingsPanel {
             sample_rate: config.asr.sample_rate,
             model_dir: config.asr.model_dir.clone(),
             hotwords_score: config.asr.hotwords_score,
-            vad_start_threshold: config.vad.start_threshold,
-            vad_end_threshold: config.vad.end_threshold,
-            min_speech_duration: config.vad.min_speech_duration,
-            min_silence_duration: config.vad.min_silence_duration,
+            vad_start_threshold: config.vad.hysteresis.start_threshold,
+            vad_end_threshold: config.vad.hysteresis.end_threshold,
+            min_speech_duration: config.vad.hysteresis.min_speech_duration_ms,
+            min_silence_duration: config.vad.hysteresis.min_silence_duration_ms,
         }
     }
 
@@ -210,9 +210,9 @@ impl RecognitionSettingsPanel {
         config.asr.sample_rate = self.sample_rate;
         config.asr.model_dir = self.model_dir.clone();
         config.asr.hotwords_score = self.hotwords_score;
-        config.vad.start_threshold = self.vad_start_threshold;
-        config.vad.end_threshold = self.vad_end_threshold;
-        config.vad.min_speech_duration = self.min_speech_duration;
-        config.vad.min_silence_duration = self.min_silence_duration;
+        config.vad.hysteresis.start_threshold = self.vad_start_threshold;
+        config.vad.hysteresis.end_threshold = self.vad_end_threshold;
+        config.vad.hysteresis.min_speech_duration_ms = self.min_speech_duration;
+        config.vad.hysteresis.min_silence_duration_ms = self.min_silence_duration;
     }
 }
@@ -0,0 +1,202 @@
+//! 快捷键写法、显示与冲突检测
+//!
+//! 写法与 vinput-core 的 `[shortcuts]` 段一致：`修饰键+键名`，键名为 X11 keysym
+//! （如 `Control_R`、`Control+z`），修饰键为 Control / Shift / Alt / Super。
+//! 解析使用 vinput-config 的 `KeyCombo`，与核心库完全一致。
+
+use crate::config::{ActivationMode, KeyCombo, ShortcutConfig};
+
+/// 常见的系统 / 应用快捷键（规范写法，键名小写）
+const COMMON_SHORTCUTS: &[(&str, &str)] = &[
+    ("Control+c", "复制"),
+    ("Control+v", "粘贴"),
+    ("Control+x", "剪切"),
+    ("Control+a", "全选"),
+    ("Control+s", "保存"),
+    ("Control+f", "查找"),
+    ("Control+n", "新建"),
+    ("Control+w", "关闭标签页"),
+    ("Control+t", "新建标签页"),
+    ("Control+q", "退出程序"),
+    ("Control+space", "切换输入法（Fcitx5 默认）"),
+    ("Super+space", "切换输入法（IBus / GNOME 默认）"),
+    ("Control+Shift+z", "重做"),
+    ("Alt+Tab", "切换窗口"),
+    ("Alt+F4", "关闭窗口"),
+    ("Shift_L", "切换中英文（多数输入法默认）"),
+    ("Shift_R", "切换中英文（多数输入法默认）"),
+];
+
+/// 不带修饰键时会妨碍正常输入的编辑键
+const EDITING_KEYS: [&str; 8] = [
+    "space", "return", "tab", "backspace", "delete", "escape", "left", "right",
+];
+
+/// 快捷键用途
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutTarget {
+    /// 开始 / 停止录音
+    Trigger,
+    /// 撤销
+    Undo,
+    /// 重试
+    Redo,
+}
+
+impl ShortcutTarget {
+    pub const ALL: [ShortcutTarget; 3] = [Self::Trigger, Self::Undo, Self::Redo];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Trigger => "触发键",
+            Self::Undo => "撤销",
+            Self::Redo => "重试",
+        }
+    }
+
+    pub fn get(self, shortcuts: &ShortcutConfig) -> &str {
+        match self {
+            Self::Trigger => &shortcuts.trigger,
+            Self::Undo => &shortcuts.undo,
+            Self::Redo => &shortcuts.redo,
+        }
+    }
+
+    pub fn get_mut(self, shortcuts: &mut ShortcutConfig) -> &mut String {
+        match self {
+            Self::Trigger => &mut shortcuts.trigger,
+            Self::Undo => &mut shortcuts.undo,
+            Self::Redo => &mut shortcuts.redo,
+        }
+    }
+}
+
+/// 规范化快捷键写法（修饰键别名、顺序与大小写），用于比较；无效时返回 None
+pub fn normalize(spec: &str) -> Option<String> {
+    let mut combo = KeyCombo::parse(spec).ok()?;
+    // 单字符键名（字母）统一小写，其余 keysym 保留原样
+    if combo.key.chars().count() == 1 {
+        combo.key = combo.key.to_lowercase();
+    }
+    Some(combo.to_string())
+}
+
+/// 友好显示名（如 `Control_R` → `右 Ctrl`，`Control+z` → `Ctrl+Z`）
+pub fn display_name(spec: &str) -> String {
+    let Some(normalized) = normalize(spec) else {
+        return spec.to_string();
+    };
+    normalized
+        .split('+')
+        .map(|part| match part {
+            "Control_R" => "右 Ctrl".to_string(),
+            "Control_L" => "左 Ctrl".to_string(),
+            "Alt_R" => "右 Alt".to_string(),
+            "Alt_L" => "左 Alt".to_string(),
+            "Shift_R" => "右 Shift".to_string(),
+            "Shift_L" => "左 Shift".to_string(),
+            "Control" => "Ctrl".to_string(),
+            "space" => "Space".to_string(),
+            other if other.chars().count() == 1 => other.to_uppercase(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// 检测快捷键冲突，返回提示文本（为空表示无冲突）
+pub fn conflicts(shortcuts: &ShortcutConfig, mode: ActivationMode) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let normalized: Vec<(ShortcutTarget, Option<String>)> = ShortcutTarget::ALL
+        .iter()
+        .map(|target| (*target, normalize(target.get(shortcuts))))
+        .collect();
+
+    for (i, (target, spec)) in normalized.iter().enumerate() {
+        let Some(spec) = spec else {
+            warnings.push(format!("{}快捷键无效: '{}'", target.label(), target.get(shortcuts)));
+            continue;
+        };
+
+        // 彼此重复
+        for (other, other_spec) in &normalized[..i] {
+            if other_spec.as_deref() == Some(spec.as_str()) {
+                warnings.push(format!("{}与{}使用了同一个快捷键 {}", other.label(), target.label(), display_name(spec)));
+            }
+        }
+
+        // 常见系统 / 应用快捷键
+        if let Some((_, usage)) = COMMON_SHORTCUTS
+            .iter()
+            .find(|(common, _)| normalize(common).as_deref() == Some(spec.as_str()))
+        {
+            warnings.push(format!("{} {} 与常用快捷键冲突（{}）", target.label(), display_name(spec), usage));
+        }
+
+        // 不带修饰键的字符键 / 编辑键会妨碍正常输入
+        if !spec.contains('+') {
+            let typing = spec.chars().count() == 1 || EDITING_KEYS.contains(&spec.to_ascii_lowercase().as_str());
+            if typing {
+                warnings.push(format!("{} {} 不带修饰键，会影响正常打字", target.label(), display_name(spec)));
+            }
+        }
+    }
+
+    // 按住说话需要能检测到松开：带修饰键的组合在先松开修饰键时可能收不到主键释放
+    if mode == ActivationMode::Hold {
+        if let Some(Some(trigger)) = normalized.first().map(|(_, spec)| spec) {
+            if trigger.contains('+') {
+                warnings.push(format!("按住说话建议使用单个按键作为触发键，当前为 {}", display_name(trigger)));
+            }
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shortcuts(trigger: &str, undo: &str, redo: &str) -> ShortcutConfig {
+        ShortcutConfig {
+            trigger: trigger.to_string(),
+            undo: undo.to_string(),
+            redo: redo.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_and_display() {
+        assert_eq!(normalize("shift+ctrl+Z").as_deref(), Some("Control+Shift+z"));
+        assert_eq!(normalize("Control_R").as_deref(), Some("Control_R"));
+        assert_eq!(normalize("Hyper+z"), None);
+        assert_eq!(display_name("Control+z"), "Ctrl+Z");
+        assert_eq!(display_name("Control_R"), "右 Ctrl");
+    }
+
+    #[test]
+    fn test_default_shortcuts_have_no_conflicts() {
+        let defaults = ShortcutConfig::default();
+        assert!(conflicts(&defaults, ActivationMode::Toggle).is_empty());
+        assert!(conflicts(&defaults, ActivationMode::Hold).is_empty());
+    }
+
+    #[test]
+    fn test_conflict_detection() {
+        // 与常用快捷键冲突
+        let warnings = conflicts(&shortcuts("Ctrl+C", "Control+z", "Control+y"), ActivationMode::Toggle);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("复制"));
+
+        // 彼此重复
+        let warnings = conflicts(&shortcuts("F9", "Control+z", "ctrl+Z"), ActivationMode::Toggle);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("同一个快捷键"));
+
+        // 不带修饰键的字符键，以及按住说话使用组合键
+        assert_eq!(conflicts(&shortcuts("a", "Control+z", "Control+y"), ActivationMode::Toggle).len(), 1);
+        assert_eq!(conflicts(&shortcuts("Control+F9", "Control+z", "Control+y"), ActivationMode::Hold).len(), 1);
+    }
+}
@@ -20,10 +20,10 @@ pub struct VadAsrPanel {
 impl VadAsrPanel {
     pub fn new(config: &VInputConfig) -> Self {
         Self {
-            start_threshold: config.vad.start_threshold,
-            end_threshold: config.vad.end_threshold,
-            min_speech_duration: config.vad.min_speech_duration,
-            min_silence_duration: config.vad.min_silence_duration,
+            start_threshold: config.vad.hysteresis.start_threshold,
+            end_threshold: config.vad.hysteresis.end_threshold,
+            min_speech_duration: config.vad.hysteresis.min_speech_duration_ms,
+            min_silence_duration: config.vad.hysteresis.min_silence_duration_ms,
             model_dir: config.asr.model_dir.clone(),
             sample_rate: config.asr.sample_rate,
             hotwords_file: config.asr.hotwords_file.clone().unwrap_or_default(),
@@ -32,10 +32,10 @@ impl VadAsrPanel {
     }
 
     pub fn apply_to_config(&self, config: &mut VInputConfig) {
-        config.vad.start_threshold = self.start_threshold;
-        config.vad.end_threshold = self.end_threshold;
-        config.vad.min_speech_duration = self.min_speech_duration;
-        config.vad.min_silence_duration = self.min_silence_duration;
+        config.vad.hysteresis.start_threshold = self.start_threshold;
+        config.vad.hysteresis.end_threshold = self.end_threshold;
+        config.vad.hysteresis.min_speech_duration_ms = self.min_speech_duration;
+        config.vad.hysteresis.min_silence_duration_ms = self.min_silence_duration;
 
         config.asr.model_dir = self.model_dir.clone();
         config.asr.sample_rate = self.sample_rate;